@@ -0,0 +1,184 @@
+//! Showing and hiding the tab bars as apps are activated
+//!
+//! The observer passes every app-activated payload through `on_activation`
+//! before emitting it. An editor brings the bars back and puts them in place;
+//! another app whose window covers the editor (already debounced by the
+//! observer) hides them. Doing this from Rust instead of each webview avoids
+//! the flicker, and a busy webview can no longer leave a bar stuck on screen.
+//!
+//! While the bars are hidden the Claude status watcher is paused, unless
+//! notifications are on: those are most useful exactly when another app is in
+//! front.
+
+use crate::editor_model::AppActivationPayload;
+use crate::settings_store::{StoredSettings, STORE_FILE};
+use crate::{claude_status, notification, settings_window, tab_bars};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+pub(crate) const AUTOHIDE_KEY: &str = "settings:autohide";
+
+/// Hide the bars when another app covers the editor (off = always visible)
+static AUTOHIDE: AtomicBool = AtomicBool::new(true);
+/// The main bar has finished onboarding and handed visibility over
+static READY: AtomicBool = AtomicBool::new(false);
+static HIDDEN: AtomicBool = AtomicBool::new(false);
+/// The watcher was paused by hiding, not by the user
+static WATCHER_PAUSED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visibility {
+    /// Show the bars; `position` puts them back at the top of their display
+    Show { position: bool },
+    Hide,
+    Keep,
+}
+
+fn decide(payload: &AppActivationPayload, autohide: bool, settings_open: bool) -> Visibility {
+    match payload.app_type.as_str() {
+        "editor" => Visibility::Show { position: true },
+        // クリックでアクティブになったときはサイズを保ち、クリックを最後まで通す
+        "tab_manager" => Visibility::Show { position: false },
+        _ if autohide
+            && !settings_open
+            && payload.is_on_primary_screen
+            && payload.covers_editor =>
+        {
+            Visibility::Hide
+        }
+        _ => Visibility::Keep,
+    }
+}
+
+fn notifications_enabled(app: &AppHandle) -> bool {
+    StoredSettings::from_app(app)
+        .get(notification::ENABLED_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true)
+}
+
+fn show(app: &AppHandle, position: bool) {
+    for label in tab_bars::tab_bar_labels() {
+        if let Some(window) = app.get_webview_window(&label) {
+            if position {
+                tab_bars::position_tab_bar(&window);
+            }
+            let _ = window.show();
+        }
+    }
+    if HIDDEN.swap(false, Ordering::SeqCst) {
+        tab_bars::emit_to_tab_bars(app, "tab-bar-visibility-changed", true);
+    }
+    if WATCHER_PAUSED.swap(false, Ordering::SeqCst) {
+        claude_status::start_claude_status_watcher(app.clone());
+    }
+}
+
+fn hide(app: &AppHandle) {
+    for label in tab_bars::tab_bar_labels() {
+        if let Some(window) = app.get_webview_window(&label) {
+            let _ = window.hide();
+        }
+    }
+    if !HIDDEN.swap(true, Ordering::SeqCst) {
+        tab_bars::emit_to_tab_bars(app, "tab-bar-visibility-changed", false);
+    }
+    if claude_status::is_status_watcher_running() && !notifications_enabled(app) {
+        claude_status::stop_claude_status_watcher();
+        WATCHER_PAUSED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Show or hide the bars for an activation (main thread, from the observer)
+pub fn on_activation(app: &AppHandle, payload: &AppActivationPayload) {
+    if !READY.load(Ordering::SeqCst) {
+        return;
+    }
+    let autohide = AUTOHIDE.load(Ordering::SeqCst);
+    match decide(payload, autohide, settings_window::is_open(app)) {
+        Visibility::Show { position } => show(app, position),
+        Visibility::Hide => hide(app),
+        Visibility::Keep => {}
+    }
+}
+
+/// The main bar is past onboarding and permission checks; until then it sizes
+/// and shows itself
+pub fn frontend_ready() {
+    READY.store(true, Ordering::SeqCst);
+}
+
+/// Load the saved setting (setup, settings import)
+pub fn load_settings(stored: &StoredSettings) {
+    let enabled = stored
+        .get(AUTOHIDE_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(true);
+    AUTOHIDE.store(enabled, Ordering::SeqCst);
+}
+
+/// Tauri command: whether the bars hide when another app covers the editor
+#[tauri::command]
+pub fn get_autohide() -> bool {
+    AUTOHIDE.load(Ordering::SeqCst)
+}
+
+/// Tauri command: persist the setting; turning it off brings hidden bars back
+#[tauri::command]
+pub fn set_autohide(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(AUTOHIDE_KEY, enabled);
+    store.save().map_err(|e| e.to_string())?;
+
+    AUTOHIDE.store(enabled, Ordering::SeqCst);
+    if !enabled && HIDDEN.load(Ordering::SeqCst) {
+        show(&app, true);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(app_type: &str, covers_editor: bool) -> AppActivationPayload {
+        AppActivationPayload {
+            app_type: app_type.to_string(),
+            bundle_id: None,
+            is_on_primary_screen: true,
+            covers_editor,
+        }
+    }
+
+    #[test]
+    fn editors_show_and_covering_apps_hide() {
+        assert_eq!(
+            decide(&payload("editor", false), true, false),
+            Visibility::Show { position: true }
+        );
+        assert_eq!(
+            decide(&payload("tab_manager", false), true, false),
+            Visibility::Show { position: false }
+        );
+        assert_eq!(decide(&payload("other", true), true, false), Visibility::Hide);
+        // 小さなウィンドウのアプリではタブバーを残す
+        assert_eq!(decide(&payload("other", false), true, false), Visibility::Keep);
+
+        let secondary = AppActivationPayload {
+            is_on_primary_screen: false,
+            ..payload("other", true)
+        };
+        assert_eq!(decide(&secondary, true, false), Visibility::Keep);
+    }
+
+    #[test]
+    fn bars_stay_up_without_autohide_or_with_settings_open() {
+        assert_eq!(decide(&payload("other", true), false, false), Visibility::Keep);
+        assert_eq!(decide(&payload("other", true), true, true), Visibility::Keep);
+        assert_eq!(
+            decide(&payload("editor", false), false, true),
+            Visibility::Show { position: true }
+        );
+    }
+}
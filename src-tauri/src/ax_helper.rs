@@ -1,7 +1,8 @@
 //! Accessibility API helper module for macOS window management
 //!
 //! Provides fast, direct access to window information using the macOS Accessibility API
-//! instead of slower AppleScript calls.
+//! instead of slower AppleScript calls. Calls that change windows (focus, close,
+//! move/resize, minimize) run on the main thread via `main_thread::run`.
 
 use accessibility::{AXUIElement, AXUIElementActions, AXUIElementAttributes};
 use accessibility_sys::{AXUIElementGetPid, AXUIElementRef};
@@ -9,11 +10,15 @@ use core_foundation::boolean::CFBoolean;
 use core_foundation::base::TCFType;
 use core_foundation::string::CFString;
 use core_graphics::window::CGWindowID;
-use objc2_app_kit::NSRunningApplication;
-use objc2_foundation::NSString;
+use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+use objc2_foundation::{NSBundle, NSString};
 use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
-use crate::editor_model::NativeEditorWindow;
+use crate::editor_config::{editor_for_app, get_editor_by_bundle_id, EditorConfig};
+use crate::editor_model::{FocusOutcome, NativeEditorWindow};
+use crate::main_thread;
 
 // Private API declaration for getting CGWindowID from AXUIElement
 #[link(name = "ApplicationServices", kind = "framework")]
@@ -36,19 +41,115 @@ pub fn get_window_id(window: &AXUIElement) -> Option<u32> {
     }
 }
 
-/// Get the process ID (PID) for an application by its bundle identifier
-pub fn get_pid_by_bundle_id(bundle_id: &str) -> Option<i32> {
+/// Get the process IDs of every running instance of an application.
+/// An editor can briefly run twice while it updates, or be launched twice on purpose.
+/// Editors are also found under their other bundle IDs, or by name.
+pub fn get_pids_by_bundle_id(bundle_id: &str) -> Vec<i32> {
+    let Some(editor) = get_editor_by_bundle_id(bundle_id) else {
+        return running_pids(bundle_id);
+    };
+    let pids: Vec<i32> = editor.bundle_ids.iter().flat_map(|id| running_pids(id)).collect();
+    if !pids.is_empty() {
+        return pids;
+    }
+    // 既知のバンドル ID で動いていなければ、実行ファイル名とアプリ名で探す
+    objc2_app_kit::NSWorkspace::sharedWorkspace()
+        .runningApplications()
+        .iter()
+        .filter(|app| app_editor(app).is_some_and(|found| found.id == editor.id))
+        .map(|app| app.processIdentifier())
+        .filter(|pid| *pid > 0)
+        .collect()
+}
+
+fn running_pids(bundle_id: &str) -> Vec<i32> {
     let bundle_id_ns = NSString::from_str(bundle_id);
     let apps = NSRunningApplication::runningApplicationsWithBundleIdentifier(&bundle_id_ns);
 
-    if apps.count() > 0 {
-        let app = apps.objectAtIndex(0);
-        let pid = app.processIdentifier();
-        if pid > 0 {
-            return Some(pid);
+    (0..apps.count())
+        .map(|index| apps.objectAtIndex(index).processIdentifier())
+        .filter(|pid| *pid > 0)
+        .collect()
+}
+
+/// The editor a running app is, by bundle ID or else by executable and name
+fn app_editor(app: &NSRunningApplication) -> Option<&'static EditorConfig> {
+    let bundle_id = app.bundleIdentifier().map(|id| id.to_string());
+    let executable = app
+        .executableURL()
+        .and_then(|url| url.lastPathComponent())
+        .map(|name| name.to_string());
+    let name = app.localizedName().map(|name| name.to_string());
+    editor_for_app(bundle_id.as_deref(), executable.as_deref(), name.as_deref())
+}
+
+/// Bundle ID of a running app. Editors are reported under their configured
+/// `bundle_id`, whichever ID the running version has.
+pub fn app_bundle_id(app: &NSRunningApplication) -> Option<String> {
+    match app_editor(app) {
+        Some(editor) => Some(editor.bundle_id.to_string()),
+        None => app.bundleIdentifier().map(|id| id.to_string()),
+    }
+}
+
+/// Get the process ID (PID) of the first running instance of an application
+pub fn get_pid_by_bundle_id(bundle_id: &str) -> Option<i32> {
+    get_pids_by_bundle_id(bundle_id).into_iter().next()
+}
+
+/// Find which of `pids` owns the window with the given CGWindowID
+pub fn find_window_pid(pids: &[i32], window_id: u32) -> Option<i32> {
+    pids.iter().copied().find(|pid| {
+        get_all_window_frames(*pid)
+            .map(|frames| frames.iter().any(|frame| frame.0 == window_id))
+            .unwrap_or(false)
+    })
+}
+
+/// Whether any running instance of the application is hidden (Cmd+H)
+pub fn is_app_hidden(bundle_id: &str) -> bool {
+    get_pids_by_bundle_id(bundle_id).into_iter().any(|pid| {
+        NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+            .is_some_and(|app| app.isHidden())
+    })
+}
+
+/// Whether the process is still running (an editor may quit when its last window closes)
+pub fn is_process_running(pid: i32) -> bool {
+    NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+        .is_some_and(|app| !app.isTerminated())
+}
+
+/// Unhide the application owning `pid`. Returns true if it was hidden.
+pub fn unhide_app(pid: i32) -> bool {
+    let Some(app) = NSRunningApplication::runningApplicationWithProcessIdentifier(pid) else {
+        return false;
+    };
+    app.isHidden() && app.unhide()
+}
+
+/// Cap every AX call this process makes at `seconds` (0 restores the system
+/// default of about 6s). Used on quit so an unresponsive editor can't hold up exit.
+pub fn set_global_messaging_timeout(seconds: f32) {
+    use accessibility_sys::{AXUIElementCreateSystemWide, AXUIElementSetMessagingTimeout};
+    use core_foundation::base::CFRelease;
+
+    unsafe {
+        let system_wide = AXUIElementCreateSystemWide();
+        let err = AXUIElementSetMessagingTimeout(system_wide, seconds);
+        CFRelease(system_wide as core_foundation::base::CFTypeRef);
+        if err != 0 {
+            log::debug!("Failed to set the AX messaging timeout: AXError {}", err);
         }
     }
-    None
+}
+
+/// PID of the frontmost application
+pub fn get_frontmost_pid() -> Option<i32> {
+    let workspace = objc2_app_kit::NSWorkspace::sharedWorkspace();
+    workspace
+        .frontmostApplication()
+        .map(|app| app.processIdentifier())
 }
 
 pub fn get_native_windows_ax(
@@ -77,6 +178,15 @@ pub fn get_native_windows_ax(
             continue;
         }
 
+        // Skip dialogs and popovers (file pickers, confirmation prompts, palettes)
+        let subrole = window.subrole().ok().map(|s| s.to_string());
+        if matches!(
+            subrole.as_deref(),
+            Some("AXDialog" | "AXSystemDialog" | "AXFloatingWindow")
+        ) {
+            continue;
+        }
+
         // Get CGWindowID - skip windows without valid ID
         let window_id = match get_window_id(&window) {
             Some(id) => id,
@@ -158,6 +268,10 @@ fn descendant_process_ids(window: &AXUIElement, editor_pid: i32) -> Vec<i32> {
 /// Close a specific window by CGWindowID
 /// Uses CGWindowID for reliable window identification regardless of title changes
 pub fn close_window_by_id(pid: i32, target_window_id: u32) -> Result<(), String> {
+    main_thread::run(move || close_window(pid, target_window_id))
+}
+
+fn close_window(pid: i32, target_window_id: u32) -> Result<(), String> {
     let app = AXUIElement::application(pid);
 
     let windows = app
@@ -188,6 +302,47 @@ pub fn close_window_by_id(pid: i32, target_window_id: u32) -> Result<(), String>
     Ok(())
 }
 
+/// Text of a sheet attached to a window, such as an editor's "Do you want to
+/// save the changes?" prompt. None when the window has no sheet; a sheet with
+/// no readable text gives an empty string.
+pub fn attached_sheet_text(pid: i32, target_window_id: u32) -> Option<String> {
+    let app = AXUIElement::application(pid);
+    let window = app.windows().ok()?.into_iter().find(|w| {
+        let role = w.role().ok().map(|s| s.to_string());
+        role.as_deref() == Some("AXWindow") && get_window_id(w) == Some(target_window_id)
+    })?;
+    fn has_role(element: &AXUIElement, wanted: &str) -> bool {
+        element.role().ok().map(|s| s.to_string()).as_deref() == Some(wanted)
+    }
+    let sheet = window
+        .children()
+        .ok()?
+        .iter()
+        .find(|child| has_role(child, "AXSheet"))
+        .map(|child| AXUIElement::clone(&child))?;
+
+    // シートのタイトルは空のことが多いので、最初の静的テキストで代用する
+    let title = sheet
+        .title()
+        .map(|s| s.to_string())
+        .ok()
+        .filter(|title| !title.is_empty());
+    let static_text = || {
+        sheet.children().ok()?.iter().find_map(|child| {
+            if !has_role(&child, "AXStaticText") {
+                return None;
+            }
+            child
+                .value()
+                .ok()?
+                .downcast::<CFString>()
+                .map(|text| text.to_string())
+                .filter(|text| !text.is_empty())
+        })
+    };
+    Some(title.or_else(static_text).unwrap_or_default())
+}
+
 /// Get the close button from a window element
 fn get_close_button(window: &AXUIElement) -> Result<AXUIElement, String> {
     use accessibility_sys::AXUIElementCopyAttributeValue;
@@ -219,28 +374,60 @@ fn get_close_button(window: &AXUIElement) -> Result<AXUIElement, String> {
     }
 }
 
-/// Open a new window in the application
-/// Uses keyboard shortcut Cmd+Shift+N via osascript
-pub fn open_new_window_ax(pid: i32) -> Result<(), String> {
-    // First activate the app
-    activate_app_by_pid(pid)?;
+/// How long an activated app gets to become frontmost before the shortcut is sent
+const ACTIVATION_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long the new window gets to show up after the shortcut
+const NEW_WINDOW_TIMEOUT: Duration = Duration::from_secs(3);
+const NEW_WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The shortcut is sent once more when no window appeared
+const NEW_WINDOW_ATTEMPTS: u32 = 2;
 
-    // Small delay to ensure app is active
-    std::thread::sleep(std::time::Duration::from_millis(100));
+fn window_ids(pid: i32) -> Result<HashSet<u32>, String> {
+    Ok(get_all_window_frames(pid)?
+        .into_iter()
+        .map(|(id, ..)| id)
+        .collect())
+}
 
-    // Send Cmd+Shift+N keyboard shortcut using osascript
-    send_keyboard_shortcut("n", true, true)?;
+/// Open a new window in the application
+/// Uses keyboard shortcut Cmd+Shift+N via osascript, sent only once the app is
+/// frontmost. Returns the new window's CGWindowID, or None when no new window
+/// appeared (the app never came to front, or ignored the shortcut).
+pub fn open_new_window_ax(pid: i32) -> Result<Option<u32>, String> {
+    let before = window_ids(pid)?;
+    for _ in 0..NEW_WINDOW_ATTEMPTS {
+        main_thread::run(move || activate_app_by_pid(pid))?;
+        // 前面になる前に送るとショートカットが別のアプリに届いてしまう
+        let frontmost = poll_until(ACTIVATION_TIMEOUT, FOCUS_POLL_INTERVAL, || {
+            get_frontmost_pid() == Some(pid)
+        });
+        if !frontmost {
+            log::debug!("App {} did not come to front; not sending Cmd+Shift+N", pid);
+            continue;
+        }
 
-    Ok(())
+        // Send Cmd+Shift+N keyboard shortcut using osascript
+        send_keyboard_shortcut("n", true, true)?;
+
+        let mut new_window = None;
+        poll_until(NEW_WINDOW_TIMEOUT, NEW_WINDOW_POLL_INTERVAL, || {
+            new_window = window_ids(pid)
+                .ok()
+                .and_then(|ids| ids.into_iter().find(|id| !before.contains(id)));
+            new_window.is_some()
+        });
+        if new_window.is_some() {
+            return Ok(new_window);
+        }
+    }
+    Ok(None)
 }
 
 /// Activate an application by its PID
 fn activate_app_by_pid(pid: i32) -> Result<(), String> {
     if let Some(app) = NSRunningApplication::runningApplicationWithProcessIdentifier(pid) {
         #[allow(deprecated)]
-        let _ = app.activateWithOptions(
-            objc2_app_kit::NSApplicationActivationOptions::ActivateIgnoringOtherApps,
-        );
+        let _ = app.activateWithOptions(NSApplicationActivationOptions::ActivateIgnoringOtherApps);
         return Ok(());
     }
 
@@ -251,8 +438,7 @@ fn activate_app_by_pid(pid: i32) -> Result<(), String> {
 pub fn get_frontmost_editor_bundle_id(editor_bundle_ids: &[&str]) -> Option<String> {
     let workspace = objc2_app_kit::NSWorkspace::sharedWorkspace();
     let frontmost = workspace.frontmostApplication()?;
-    let bundle_id = frontmost.bundleIdentifier()?;
-    let bundle_str = bundle_id.to_string();
+    let bundle_str = app_bundle_id(&frontmost)?;
 
     for editor_bundle in editor_bundle_ids {
         if bundle_str == *editor_bundle {
@@ -262,27 +448,38 @@ pub fn get_frontmost_editor_bundle_id(editor_bundle_ids: &[&str]) -> Option<Stri
     None
 }
 
+/// Our own bundle identifier, read once from the main bundle.
+/// None when running unbundled (`tauri dev`, tests).
+pub fn own_bundle_id() -> Option<&'static str> {
+    static OWN_BUNDLE_ID: OnceLock<Option<String>> = OnceLock::new();
+    OWN_BUNDLE_ID
+        .get_or_init(|| {
+            NSBundle::mainBundle()
+                .bundleIdentifier()
+                .map(|id| id.to_string())
+        })
+        .as_deref()
+}
+
+fn is_own_bundle_id(bundle_id: &str, own_bundle_id: Option<&str>) -> bool {
+    own_bundle_id == Some(bundle_id)
+}
+
 /// Check if any supported editor or the Tab Manager is the frontmost application
 pub fn is_editor_frontmost(editor_bundle_ids: &[&str]) -> bool {
     let workspace = objc2_app_kit::NSWorkspace::sharedWorkspace();
-    if let Some(frontmost) = workspace.frontmostApplication() {
-        if let Some(bundle_id) = frontmost.bundleIdentifier() {
-            let bundle_str = bundle_id.to_string();
-
-            // Check if it's our tab manager
-            if bundle_str == "com.884js.editor-tab-manager" {
-                return true;
-            }
-
-            // Check if it's any supported editor
-            for editor_bundle in editor_bundle_ids {
-                if bundle_str == *editor_bundle {
-                    return true;
-                }
-            }
-        }
+    let Some(frontmost) = workspace.frontmostApplication() else {
+        return false;
+    };
+    // バンドル ID が無い・違う開発ビルドでも自分自身は pid で分かる
+    if frontmost.processIdentifier() == std::process::id() as i32 {
+        return true;
     }
-    false
+    let Some(bundle_str) = app_bundle_id(&frontmost) else {
+        return false;
+    };
+    is_own_bundle_id(&bundle_str, own_bundle_id())
+        || editor_bundle_ids.contains(&bundle_str.as_str())
 }
 
 /// Send a keyboard shortcut using osascript
@@ -317,11 +514,29 @@ fn send_keyboard_shortcut(key: &str, cmd: bool, shift: bool) -> Result<(), Strin
     Ok(())
 }
 
-/// Compare two AXUIElements for equality by comparing their CGWindowID
-fn windows_equal(a: &AXUIElement, b: &AXUIElement) -> bool {
-    match (get_window_id(a), get_window_id(b)) {
+/// The parts of a window element focus matching needs (mocked in tests)
+trait WindowIdentity {
+    fn window_id(&self) -> Option<u32>;
+    /// Element identity (CFEqual), used when the private window-id call fails
+    fn same_element(&self, other: &Self) -> bool;
+}
+
+impl WindowIdentity for AXUIElement {
+    fn window_id(&self) -> Option<u32> {
+        get_window_id(self)
+    }
+
+    fn same_element(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Compare two windows by CGWindowID, falling back to element identity.
+/// Titles are never compared: two windows on the same project share one.
+fn windows_equal<W: WindowIdentity>(a: &W, b: &W) -> bool {
+    match (a.window_id(), b.window_id()) {
         (Some(id_a), Some(id_b)) => id_a == id_b,
-        _ => false,
+        _ => a.same_element(b),
     }
 }
 
@@ -494,6 +709,17 @@ pub fn set_window_frame_by_id(
     y: f64,
     width: f64,
     height: f64,
+) -> Result<(), String> {
+    main_thread::run(move || set_window_frame(pid, target_window_id, x, y, width, height))
+}
+
+fn set_window_frame(
+    pid: i32,
+    target_window_id: u32,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
 ) -> Result<(), String> {
     use accessibility_sys::AXUIElementSetAttributeValue;
     use core_foundation::base::CFRelease;
@@ -555,9 +781,103 @@ pub fn set_window_frame_by_id(
     Ok(())
 }
 
+/// How long a raised window gets to report itself as the app's focused window
+const FOCUS_VERIFY_TIMEOUT: Duration = Duration::from_millis(500);
+/// A fullscreen window first needs the Space switch animation to finish
+const FULLSCREEN_VERIFY_TIMEOUT: Duration = Duration::from_millis(800);
+const FOCUS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Raise/activate attempts before giving up. Editors ignore the first one
+/// right after wake or while a sheet is up.
+const FOCUS_ATTEMPTS: u32 = 3;
+const FOCUS_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// CGWindowID of the app's focused window
+fn focused_window_id(pid: i32) -> Option<u32> {
+    let app = AXUIElement::application(pid);
+    let window = app.focused_window().ok()?;
+    get_window_id(&window)
+}
+
+/// Call `check` every `interval` until it passes or `timeout` runs out
+fn poll_until(timeout: Duration, interval: Duration, mut check: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if check() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Poll until the app is frontmost with `target_window_id` as its focused
+/// window, or `timeout` passes. Reads only, so it runs on the caller's thread.
+fn wait_for_focused_window(pid: i32, target_window_id: u32, timeout: Duration) -> bool {
+    poll_until(timeout, FOCUS_POLL_INTERVAL, || {
+        get_frontmost_pid() == Some(pid) && focused_window_id(pid) == Some(target_window_id)
+    })
+}
+
+/// Run `attempt` until `verify` confirms it landed, at most `attempts` times
+fn focus_with_retries(
+    attempts: u32,
+    mut attempt: impl FnMut() -> Result<(), String>,
+    mut verify: impl FnMut() -> bool,
+) -> Result<bool, String> {
+    for n in 0..attempts {
+        if n > 0 {
+            std::thread::sleep(FOCUS_RETRY_DELAY);
+        }
+        attempt()?;
+        if verify() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Focus a specific window by CGWindowID
-/// Uses CGWindowID for reliable window identification regardless of title changes
-pub fn focus_window_by_id(pid: i32, target_window_id: u32) -> Result<(), String> {
+/// Uses CGWindowID for reliable window identification regardless of title changes.
+/// Succeeds only once the app is frontmost with the window focused; otherwise
+/// reports what is in front instead. A fullscreen window on another Space
+/// stays unfocused when macOS refuses to switch Spaces.
+pub fn focus_window_by_id(pid: i32, target_window_id: u32) -> Result<FocusOutcome, String> {
+    let fullscreen = is_window_fullscreen_by_id(pid, target_window_id).unwrap_or(false);
+    let timeout = if fullscreen {
+        FULLSCREEN_VERIFY_TIMEOUT
+    } else {
+        FOCUS_VERIFY_TIMEOUT
+    };
+    let focused = focus_with_retries(
+        FOCUS_ATTEMPTS,
+        || {
+            if fullscreen {
+                // フルスクリーンのウィンドウは先にアプリをアクティブにして Space を切り替えさせる
+                main_thread::run(move || activate_app_by_pid(pid))?;
+                main_thread::run(move || raise_window(pid, target_window_id))
+            } else {
+                main_thread::run(move || focus_window(pid, target_window_id))
+            }
+        },
+        || wait_for_focused_window(pid, target_window_id, timeout),
+    )?;
+    if focused {
+        return Ok(FocusOutcome::Focused);
+    }
+
+    let frontmost_bundle_id = objc2_app_kit::NSWorkspace::sharedWorkspace()
+        .frontmostApplication()
+        .and_then(|app| app_bundle_id(&app));
+    Ok(FocusOutcome::Unverified {
+        frontmost_bundle_id,
+        focused_window_id: focused_window_id(pid),
+    })
+}
+
+/// The AXWindow of `pid` with the given CGWindowID
+fn find_window(pid: i32, target_window_id: u32) -> Result<AXUIElement, String> {
     let app = AXUIElement::application(pid);
 
     let windows = app
@@ -565,7 +885,7 @@ pub fn focus_window_by_id(pid: i32, target_window_id: u32) -> Result<(), String>
         .map_err(|e| format!("Failed to get windows: {:?}", e))?;
 
     // Find the window with matching CGWindowID
-    let window = windows
+    windows
         .into_iter()
         .find(|w| {
             // Check role is AXWindow
@@ -576,7 +896,12 @@ pub fn focus_window_by_id(pid: i32, target_window_id: u32) -> Result<(), String>
             // Check window ID matches
             get_window_id(w) == Some(target_window_id)
         })
-        .ok_or_else(|| format!("Window with ID {} not found", target_window_id))?;
+        .ok_or_else(|| format!("Window with ID {} not found", target_window_id))
+}
+
+/// Raise the window and make it the app's main window
+fn raise_window(pid: i32, target_window_id: u32) -> Result<(), String> {
+    let window = find_window(pid, target_window_id)?;
 
     // Raise the window (bring to front)
     window
@@ -588,6 +913,12 @@ pub fn focus_window_by_id(pid: i32, target_window_id: u32) -> Result<(), String>
         .set_main(CFBoolean::true_value())
         .map_err(|e| format!("Failed to set main window: {:?}", e))?;
 
+    Ok(())
+}
+
+fn focus_window(pid: i32, target_window_id: u32) -> Result<(), String> {
+    raise_window(pid, target_window_id)?;
+
     // Activate the application
     activate_app_by_pid(pid)?;
 
@@ -637,6 +968,54 @@ pub fn is_window_minimized_by_id(pid: i32, target_window_id: u32) -> Result<bool
     }
 }
 
+/// Minimize or restore a specific window by CGWindowID
+pub fn set_window_minimized_by_id(
+    pid: i32,
+    target_window_id: u32,
+    minimized: bool,
+) -> Result<(), String> {
+    main_thread::run(move || set_window_minimized(pid, target_window_id, minimized))
+}
+
+fn set_window_minimized(pid: i32, target_window_id: u32, minimized: bool) -> Result<(), String> {
+    use accessibility_sys::AXUIElementSetAttributeValue;
+
+    let app = AXUIElement::application(pid);
+
+    let windows = app
+        .windows()
+        .map_err(|e| format!("Failed to get windows: {:?}", e))?;
+
+    let window = windows
+        .into_iter()
+        .find(|w| {
+            let role = w.role().ok().map(|s| s.to_string());
+            if role.as_deref() != Some("AXWindow") {
+                return false;
+            }
+            get_window_id(w) == Some(target_window_id)
+        })
+        .ok_or_else(|| format!("Window with ID {} not found", target_window_id))?;
+
+    let value = if minimized {
+        CFBoolean::true_value()
+    } else {
+        CFBoolean::false_value()
+    };
+    let err = unsafe {
+        let attr_name = CFString::from_static_string("AXMinimized");
+        AXUIElementSetAttributeValue(
+            window.as_concrete_TypeRef(),
+            attr_name.as_concrete_TypeRef(),
+            value.as_CFTypeRef(),
+        )
+    };
+    if err != 0 {
+        return Err(format!("Failed to set window minimized: AXError {}", err));
+    }
+    Ok(())
+}
+
 /// Get the document file path from a window by CGWindowID
 /// AXDocument returns a "file:///path/to/file" URL string
 pub fn get_document_path(pid: i32, target_window_id: u32) -> Option<String> {
@@ -676,35 +1055,9 @@ pub fn get_document_path(pid: i32, target_window_id: u32) -> Option<String> {
         );
         let url_str = cf_str.to_string();
 
-        // Remove "file://" prefix and percent-decode the path
-        let path = url_str.strip_prefix("file://")?;
-        Some(percent_decode(path))
-    }
-}
-
-/// Percent-decode a URL-encoded path string (handles multi-byte UTF-8)
-fn percent_decode(input: &str) -> String {
-    let mut bytes = Vec::with_capacity(input.len());
-    let mut chars = input.bytes();
-    while let Some(b) = chars.next() {
-        if b == b'%' {
-            let hi = chars.next();
-            let lo = chars.next();
-            if let (Some(hi), Some(lo)) = (hi, lo) {
-                let hex = [hi, lo];
-                if let Ok(s) = std::str::from_utf8(&hex) {
-                    if let Ok(val) = u8::from_str_radix(s, 16) {
-                        bytes.push(val);
-                        continue;
-                    }
-                }
-            }
-            bytes.push(b'%');
-        } else {
-            bytes.push(b);
-        }
+        let path = crate::workspace_uri::to_local_path(&url_str)?;
+        Some(path.to_string_lossy().to_string())
     }
-    String::from_utf8(bytes).unwrap_or_else(|_| input.to_string())
 }
 
 /// Check if a window is fullscreen by CGWindowID
@@ -749,3 +1102,100 @@ pub fn is_window_fullscreen_by_id(pid: i32, target_window_id: u32) -> Result<boo
         Ok(cf_bool == CFBoolean::true_value())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// Stand-in for an AX window element: `element` plays the role of the CF pointer
+    struct MockWindow {
+        id: Option<u32>,
+        element: usize,
+    }
+
+    impl WindowIdentity for MockWindow {
+        fn window_id(&self) -> Option<u32> {
+            self.id
+        }
+
+        fn same_element(&self, other: &Self) -> bool {
+            self.element == other.element
+        }
+    }
+
+    fn window(id: Option<u32>, element: usize) -> MockWindow {
+        MockWindow { id, element }
+    }
+
+    fn frontmost_flags(windows: &[MockWindow], focused: &MockWindow) -> Vec<bool> {
+        windows.iter().map(|w| windows_equal(w, focused)).collect()
+    }
+
+    #[test]
+    fn duplicate_titles_match_only_the_focused_window() {
+        // Two windows titled "api"; focused_window() returns a distinct element for the second
+        let windows = [window(Some(10), 1), window(Some(11), 2)];
+        let focused = window(Some(11), 99);
+        assert_eq!(frontmost_flags(&windows, &focused), vec![false, true]);
+    }
+
+    #[test]
+    fn falls_back_to_element_identity_without_window_ids() {
+        // Windows still starting up: empty titles and no CGWindowID yet
+        let windows = [window(None, 1), window(None, 2)];
+        let focused = window(None, 1);
+        assert_eq!(frontmost_flags(&windows, &focused), vec![true, false]);
+    }
+
+    #[test]
+    fn own_bundle_id_is_never_hardcoded() {
+        // 識別子が読めないときに既定のバンドル ID を仮定しない
+        assert!(!is_own_bundle_id("com.884js.editor-tab-manager", None));
+        assert!(is_own_bundle_id("com.example.fork", Some("com.example.fork")));
+        assert!(!is_own_bundle_id("com.884js.editor-tab-manager", Some("com.example.fork")));
+    }
+
+    #[test]
+    fn focus_is_retried_until_it_lands() {
+        let attempts = Cell::new(0);
+        let attempt = || {
+            attempts.set(attempts.get() + 1);
+            Ok(())
+        };
+        assert_eq!(focus_with_retries(3, attempt, || attempts.get() == 2), Ok(true));
+        assert_eq!(attempts.get(), 2);
+
+        attempts.set(0);
+        assert_eq!(focus_with_retries(3, attempt, || false), Ok(false));
+        assert_eq!(attempts.get(), 3);
+
+        // ウィンドウが見つからないなどのエラーはやり直さない
+        attempts.set(0);
+        let failing = || {
+            attempts.set(attempts.get() + 1);
+            Err("Window with ID 7 not found".to_string())
+        };
+        assert!(focus_with_retries(3, failing, || true).is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn focus_polling_stops_once_the_window_is_focused() {
+        let mut reads = 0;
+        let focused = poll_until(Duration::from_secs(1), Duration::ZERO, || {
+            reads += 1;
+            reads == 3
+        });
+        assert!(focused);
+        assert_eq!(reads, 3);
+
+        // 期限切れでも最低一度は確認する
+        let mut reads = 0;
+        assert!(!poll_until(Duration::ZERO, Duration::ZERO, || {
+            reads += 1;
+            false
+        }));
+        assert_eq!(reads, 1);
+    }
+}
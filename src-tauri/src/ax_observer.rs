@@ -1,18 +1,25 @@
+use crate::ax_helper;
+use crate::debounce::{self, DebounceKey};
+use crate::diagnostics::ObserverStatus;
 use crate::editor_config::is_supported_editor;
 use crate::observer;
+use crate::tab_bars;
+use accessibility::{AXUIElement, AXUIElementAttributes};
 use core_foundation::base::{CFRelease, TCFType};
 use core_foundation::runloop::{
-    kCFRunLoopCommonModes, CFRunLoopAddSource, CFRunLoopGetMain, CFRunLoopSourceRef,
+    kCFRunLoopCommonModes, CFRunLoopAddSource, CFRunLoopGetMain, CFRunLoopRemoveSource,
+    CFRunLoopSourceRef,
 };
 use core_foundation::string::{CFString, CFStringRef};
-use objc2_app_kit::NSWorkspace;
+use objc2_app_kit::{NSRunningApplication, NSWorkspace};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::ffi::c_void;
 use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::AppHandle;
 
 // Accessibility framework types
 type AXObserverRef = *mut c_void;
@@ -48,16 +55,40 @@ const K_AX_WINDOW_CREATED: &str = "AXWindowCreated";
 const K_AX_UI_ELEMENT_DESTROYED: &str = "AXUIElementDestroyed";
 const K_AX_TITLE_CHANGED: &str = "AXTitleChanged";
 
+/// Every notification an editor observer subscribes to
+const NOTIFICATIONS: [&str; 4] = [
+    K_AX_FOCUSED_WINDOW_CHANGED,
+    K_AX_WINDOW_CREATED,
+    K_AX_UI_ELEMENT_DESTROYED,
+    K_AX_TITLE_CHANGED,
+];
+/// Without these the tab list never learns about opened or closed windows
+const CRITICAL_NOTIFICATIONS: [&str; 2] = [K_AX_WINDOW_CREATED, K_AX_UI_ELEMENT_DESTROYED];
+/// Backoff between retries of notifications that failed to register. Editors
+/// sometimes reject them right after launch, before their AX tree exists.
+const REGISTRATION_RETRY_DELAYS: [Duration; 4] = [
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(4),
+];
+/// Slowest refresh while an editor runs without window notifications
+const DEGRADED_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
 // Error codes
 const K_AX_ERROR_SUCCESS: i32 = 0;
 const WORKSPACE_STATE_REFRESH_DELAY_MS: u64 = 200;
+/// Trailing delay for title changes. Electron editors retitle on nearly every
+/// keystroke (the dirty dot), so bursts are coalesced into one emission per pid.
+const TITLE_CHANGE_DEBOUNCE_MS: u64 = 200;
 
 fn request_registry_refresh(source: &'static str) {
     crate::window_registry::request_refresh(source);
-    thread::spawn(|| {
-        thread::sleep(Duration::from_millis(WORKSPACE_STATE_REFRESH_DELAY_MS));
-        crate::window_registry::request_refresh("ax-event-retry");
-    });
+    debounce::schedule(
+        DebounceKey::RegistryRetry,
+        Duration::from_millis(WORKSPACE_STATE_REFRESH_DELAY_MS),
+        || crate::window_registry::request_refresh("ax-event-retry"),
+    );
 }
 
 // Wrapper types that implement Send + Sync for raw pointers
@@ -76,26 +107,113 @@ impl SendablePtr {
     }
 }
 
+/// Identity of the editor an observer belongs to, passed to the callback as its refcon
+struct ObserverContext {
+    pid: i32,
+    bundle_id: String,
+}
+
+#[derive(Clone, Serialize)]
+struct WindowFocusChangedPayload {
+    bundle_id: String,
+    window_id: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum WindowChangeKind {
+    Created,
+    Destroyed,
+    Title,
+}
+
+impl WindowChangeKind {
+    fn from_notification(notification: &str) -> Option<Self> {
+        match notification {
+            K_AX_WINDOW_CREATED => Some(Self::Created),
+            K_AX_UI_ELEMENT_DESTROYED => Some(Self::Destroyed),
+            K_AX_TITLE_CHANGED => Some(Self::Title),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct WindowsChangedPayload {
+    bundle_id: String,
+    kind: WindowChangeKind,
+    window_id: Option<u32>,
+    title: Option<String>,
+}
+
+fn emit_windows_changed(app_handle: &AppHandle, payload: WindowsChangedPayload) {
+    // A retitled window is re-resolved alone; the other windows keep their
+    // cached path and branch
+    let retitled = match (payload.kind, payload.window_id) {
+        (WindowChangeKind::Title, Some(window_id)) => Some(window_id),
+        _ => None,
+    };
+    let bundle_id = payload.bundle_id.clone();
+    if crate::window_registry::legacy_events_enabled() {
+        tab_bars::emit_to_tab_bars(app_handle, "windows-changed", payload);
+    }
+    // Delegate to the registry — it debounces via snapshot diff
+    // and only emits "windows-diff" when something actually changed.
+    match retitled {
+        Some(window_id) => crate::window_registry::request_window_refresh(&bundle_id, window_id),
+        None => request_registry_refresh("ax-event"),
+    }
+}
+
 /// State for a registered editor observer
 struct EditorObserver {
     observer: SendablePtr,
     app_element: SendablePtr,
+    /// Box<ObserverContext> handed to AXObserverAddNotification as refcon
+    context: SendablePtr,
     #[allow(dead_code)]
     pid: i32,
+    bundle_id: String,
+    /// Notifications that did not register (yet)
+    failed: Vec<&'static str>,
+    /// Critical notifications never registered; refreshed by polling instead
+    degraded: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct DegradedModePayload {
+    bundle_id: String,
+}
+
+/// What to do after registering an observer's notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RetryStep {
+    Done,
+    RetryAfter(Duration),
+    /// Retries ran out with a critical notification still missing
+    Degraded,
+}
+
+/// `attempt` is the number of retries already made
+fn next_retry_step(failed: &[&str], attempt: usize) -> RetryStep {
+    if failed.is_empty() {
+        return RetryStep::Done;
+    }
+    if let Some(delay) = REGISTRATION_RETRY_DELAYS.get(attempt) {
+        return RetryStep::RetryAfter(*delay);
+    }
+    if failed.iter().any(|name| CRITICAL_NOTIFICATIONS.contains(name)) {
+        RetryStep::Degraded
+    } else {
+        RetryStep::Done
+    }
 }
 
 impl Drop for EditorObserver {
     fn drop(&mut self) {
         unsafe {
             // Remove all notifications before releasing
-            let notifications = [
-                K_AX_FOCUSED_WINDOW_CHANGED,
-                K_AX_WINDOW_CREATED,
-                K_AX_UI_ELEMENT_DESTROYED,
-                K_AX_TITLE_CHANGED,
-            ];
-
-            for notification_name in &notifications {
+            for notification_name in &NOTIFICATIONS {
                 let notification = CFString::new(notification_name);
                 AXObserverRemoveNotification(
                     self.observer.get(),
@@ -103,9 +221,14 @@ impl Drop for EditorObserver {
                     notification.as_concrete_TypeRef(),
                 );
             }
+            // Detach from the run loop so a dead process's observer is really gone
+            let run_loop_source = AXObserverGetRunLoopSource(self.observer.get());
+            CFRunLoopRemoveSource(CFRunLoopGetMain(), run_loop_source, kCFRunLoopCommonModes);
             // Release resources
             CFRelease(self.observer.get() as *const c_void);
             CFRelease(self.app_element.get() as *const c_void);
+            // No callback can receive the refcon once the source is off the run loop
+            drop(Box::from_raw(self.context.get() as *mut ObserverContext));
         }
     }
 }
@@ -124,44 +247,89 @@ lazy_static::lazy_static! {
     static ref CALLBACK_REFCON: Arc<Mutex<Option<AppHandle>>> = Arc::new(Mutex::new(None));
 }
 
+/// A polling thread is refreshing editors in degraded mode.
+/// Only set or cleared while holding `AX_STATE`, so the thread cannot stop
+/// just as another editor enters degraded mode.
+static DEGRADED_POLLING: AtomicBool = AtomicBool::new(false);
+
 /// Callback when AX notification is received
 extern "C" fn ax_observer_callback(
     _observer: AXObserverRef,
-    _element: AXUIElementRef,
+    element: AXUIElementRef,
     notification: CFStringRef,
-    _refcon: *mut c_void,
+    refcon: *mut c_void,
 ) {
+    if refcon.is_null() || element.is_null() {
+        return;
+    }
+    // refcon is owned by the EditorObserver and outlives its run loop source
+    let context = unsafe { &*(refcon as *const ObserverContext) };
+
     // Get app handle from global state
     if let Some(app_handle) = CALLBACK_REFCON.lock().unwrap().as_ref() {
-        if let Some(window) = app_handle.get_webview_window("main") {
-            // Convert notification to string to determine event type
-            let notification_str = unsafe {
-                let cf_str = CFString::wrap_under_get_rule(notification);
-                cf_str.to_string()
-            };
-
-            match notification_str.as_str() {
-                K_AX_FOCUSED_WINDOW_CHANGED => {
-                    // Guard: Only process if an editor is actually in the foreground.
-                    // macOS can fire AXFocusedWindowChanged for background apps
-                    // (e.g., after sleep wake, window server state changes), which
-                    // would incorrectly re-show the tab bar.
-                    if get_frontmost_editor_pid().is_some() {
-                        // Approach 2: Cancel any pending "other" debounce event.
-                        // AX Observer only monitors editor processes, so this event
-                        // confirms an editor is active — cancel stale "other" events.
-                        observer::cancel_pending_other_event();
-                        // Emit window-focus-changed event
-                        let _ = window.emit("window-focus-changed", ());
-                        request_registry_refresh("ax-focus-event");
+        // Convert notification to string to determine event type
+        let notification_str = unsafe {
+            let cf_str = CFString::wrap_under_get_rule(notification);
+            cf_str.to_string()
+        };
+        let element = unsafe {
+            AXUIElement::wrap_under_get_rule(element as accessibility_sys::AXUIElementRef)
+        };
+
+        match notification_str.as_str() {
+            K_AX_FOCUSED_WINDOW_CHANGED => {
+                // Guard: Only process if an editor is actually in the foreground.
+                // macOS can fire AXFocusedWindowChanged for background apps
+                // (e.g., after sleep wake, window server state changes), which
+                // would incorrectly re-show the tab bar.
+                if get_frontmost_editor_pid().is_some() {
+                    // Approach 2: Cancel any pending "other" debounce event.
+                    // AX Observer only monitors editor processes, so this event
+                    // confirms an editor is active — cancel stale "other" events.
+                    observer::cancel_pending_other_event();
+                    let window_id = ax_helper::get_window_id(&element);
+                    if let Some(window_id) = window_id {
+                        crate::focus_history::record_focus(&context.bundle_id, window_id);
                     }
+                    // Emit window-focus-changed event to every tab bar
+                    tab_bars::emit_to_tab_bars(
+                        app_handle,
+                        "window-focus-changed",
+                        WindowFocusChangedPayload {
+                            bundle_id: context.bundle_id.clone(),
+                            window_id,
+                        },
+                    );
+                    request_registry_refresh("ax-focus-event");
+                    crate::tray::update_title(app_handle);
                 }
-                K_AX_WINDOW_CREATED | K_AX_UI_ELEMENT_DESTROYED | K_AX_TITLE_CHANGED => {
-                    // Delegate to the registry — it debounces via snapshot diff
-                    // and only emits "windows:snapshot" when something actually changed.
-                    request_registry_refresh("ax-event");
+            }
+            name => {
+                let Some(kind) = WindowChangeKind::from_notification(name) else {
+                    return;
+                };
+                // 破棄済みの要素からはタイトルを取れない（ID も取れなければ None）
+                let title = match kind {
+                    WindowChangeKind::Destroyed => None,
+                    _ => element.title().ok().map(|title| title.to_string()),
+                };
+                let payload = WindowsChangedPayload {
+                    bundle_id: context.bundle_id.clone(),
+                    kind,
+                    window_id: ax_helper::get_window_id(&element),
+                    title,
+                };
+                // Window creation/destruction changes the tab list, so only titles wait
+                if kind == WindowChangeKind::Title {
+                    let app_handle = app_handle.clone();
+                    debounce::schedule(
+                        DebounceKey::WindowTitle(context.pid),
+                        Duration::from_millis(TITLE_CHANGE_DEBOUNCE_MS),
+                        move || emit_windows_changed(&app_handle, payload),
+                    );
+                } else {
+                    emit_windows_changed(app_handle, payload);
                 }
-                _ => {}
             }
         }
     }
@@ -174,20 +342,15 @@ pub fn init(app_handle: AppHandle) {
     state.app_handle = Some(app_handle);
 }
 
-/// Register AX observer for an editor process.
-#[allow(dead_code)]
+/// Register AX observers for every running instance of an editor.
 pub fn register_for_editor(bundle_id: &str) {
-    // Find the running editor process
+    // Find the running editor processes
     let workspace = NSWorkspace::sharedWorkspace();
     let apps = workspace.runningApplications();
 
     for app in apps {
-        if let Some(bid) = app.bundleIdentifier() {
-            if bid.to_string() == bundle_id {
-                let pid = app.processIdentifier();
-                register_for_pid(pid);
-                break;
-            }
+        if ax_helper::app_bundle_id(&app).as_deref() == Some(bundle_id) {
+            register_for_pid(app.processIdentifier());
         }
     }
 }
@@ -204,8 +367,8 @@ pub fn register_all_editors() {
     let apps = workspace.runningApplications();
 
     for app in apps {
-        if let Some(bid) = app.bundleIdentifier() {
-            if is_supported_editor(&bid.to_string()) {
+        if let Some(bid) = ax_helper::app_bundle_id(&app) {
+            if is_supported_editor(&bid) {
                 let pid = app.processIdentifier();
                 if !already_registered.contains(&pid) {
                     register_for_pid(pid);
@@ -216,7 +379,7 @@ pub fn register_all_editors() {
 }
 
 /// Register AX observer for a specific PID
-fn register_for_pid(pid: i32) {
+pub fn register_for_pid(pid: i32) {
     let mut state = AX_STATE.lock().unwrap();
 
     // Check if already registered
@@ -224,11 +387,18 @@ fn register_for_pid(pid: i32) {
         return;
     }
 
-    unsafe {
+    let Some(bundle_id) = NSRunningApplication::runningApplicationWithProcessIdentifier(pid)
+        .and_then(|app| ax_helper::app_bundle_id(&app))
+    else {
+        log::error!("No running application for pid {}", pid);
+        return;
+    };
+
+    let step = unsafe {
         // Create AXUIElement for the application
         let app_element = AXUIElementCreateApplication(pid);
         if app_element.is_null() {
-            eprintln!("Failed to create AXUIElement for pid {}", pid);
+            log::error!("Failed to create AXUIElement for pid {}", pid);
             return;
         }
 
@@ -236,7 +406,7 @@ fn register_for_pid(pid: i32) {
         let mut observer: AXObserverRef = ptr::null_mut();
         let result = AXObserverCreate(pid, ax_observer_callback, &mut observer);
         if result != K_AX_ERROR_SUCCESS {
-            eprintln!(
+            log::error!(
                 "Failed to create AXObserver for pid {}: error {}",
                 pid, result
             );
@@ -244,85 +414,221 @@ fn register_for_pid(pid: i32) {
             return;
         }
 
+        let context = Box::into_raw(Box::new(ObserverContext {
+            pid,
+            bundle_id: bundle_id.clone(),
+        })) as *mut c_void;
+
         // Add notifications for all events we want to observe
-        let notifications = [
-            K_AX_FOCUSED_WINDOW_CHANGED,
-            K_AX_WINDOW_CREATED,
-            K_AX_UI_ELEMENT_DESTROYED,
-            K_AX_TITLE_CHANGED,
-        ];
-
-        for notification_name in &notifications {
-            let notification = CFString::new(notification_name);
-            let result = AXObserverAddNotification(
-                observer,
-                app_element,
-                notification.as_concrete_TypeRef(),
-                ptr::null_mut(),
-            );
-            if result != K_AX_ERROR_SUCCESS {
-                eprintln!(
-                    "Failed to add notification {} for pid {}: error {}",
-                    notification_name, pid, result
-                );
-                // Continue trying to add other notifications even if one fails
-            }
-        }
+        let failed = add_notifications(observer, app_element, context, pid, &NOTIFICATIONS);
 
         // Add to run loop
         let run_loop_source = AXObserverGetRunLoopSource(observer);
         CFRunLoopAddSource(CFRunLoopGetMain(), run_loop_source, kCFRunLoopCommonModes);
 
+        let step = next_retry_step(&failed, 0);
         // Store observer
         state.observers.insert(
             pid,
             EditorObserver {
                 observer: SendablePtr::new(observer),
                 app_element: SendablePtr::new(app_element),
+                context: SendablePtr::new(context),
                 pid,
+                bundle_id,
+                failed,
+                degraded: false,
             },
         );
+        step
+    };
+    drop(state);
+    follow_up(pid, 0, step);
+}
+
+/// Subscribe to `names`; returns the ones AX refused
+unsafe fn add_notifications(
+    observer: AXObserverRef,
+    app_element: AXUIElementRef,
+    context: *mut c_void,
+    pid: i32,
+    names: &[&'static str],
+) -> Vec<&'static str> {
+    let mut failed = Vec::new();
+    for &notification_name in names {
+        let notification = CFString::new(notification_name);
+        let result = AXObserverAddNotification(
+            observer,
+            app_element,
+            notification.as_concrete_TypeRef(),
+            context,
+        );
+        if result != K_AX_ERROR_SUCCESS {
+            log::warn!(
+                "Failed to add notification {} for pid {}: error {}",
+                notification_name,
+                pid,
+                result
+            );
+            failed.push(notification_name);
+        }
+    }
+    failed
+}
+
+/// Schedule the next retry, or switch the editor to polling when retries ran out
+fn follow_up(pid: i32, attempt: usize, step: RetryStep) {
+    match step {
+        RetryStep::Done => {}
+        RetryStep::RetryAfter(delay) => {
+            debounce::schedule(DebounceKey::ObserverRetry(pid), delay, move || {
+                let app_handle = AX_STATE.lock().unwrap().app_handle.clone();
+                if let Some(app_handle) = app_handle {
+                    let _ = app_handle
+                        .run_on_main_thread(move || retry_failed_notifications(pid, attempt + 1));
+                }
+            });
+        }
+        RetryStep::Degraded => enter_degraded_mode(pid),
+    }
+}
+
+/// Try the notifications that failed again (main thread). `attempt` counts
+/// this retry.
+fn retry_failed_notifications(pid: i32, attempt: usize) {
+    let step = {
+        let mut state = AX_STATE.lock().unwrap();
+        // エディタが終了していれば何もしない
+        let Some(editor) = state.observers.get_mut(&pid) else {
+            return;
+        };
+        let failed = unsafe {
+            add_notifications(
+                editor.observer.get(),
+                editor.app_element.get(),
+                editor.context.get(),
+                pid,
+                &editor.failed,
+            )
+        };
+        if failed.is_empty() {
+            log::info!("AX notifications for pid {} registered after {} retries", pid, attempt);
+        }
+        editor.failed = failed;
+        next_retry_step(&editor.failed, attempt)
+    };
+    follow_up(pid, attempt, step);
+}
+
+/// Refresh the editor by polling and tell the tab bars its updates may lag
+fn enter_degraded_mode(pid: i32) {
+    let (bundle_id, app_handle, start_polling) = {
+        let mut state = AX_STATE.lock().unwrap();
+        let app_handle = state.app_handle.clone();
+        let Some(editor) = state.observers.get_mut(&pid) else {
+            return;
+        };
+        editor.degraded = true;
+        log::error!(
+            "AX notifications {:?} never registered for {} (pid {}); polling instead",
+            editor.failed,
+            editor.bundle_id,
+            pid
+        );
+        let start_polling = !DEGRADED_POLLING.swap(true, Ordering::SeqCst);
+        (editor.bundle_id.clone(), app_handle, start_polling)
+    };
+    if let Some(app_handle) = app_handle {
+        tab_bars::emit_to_tab_bars(&app_handle, "degraded-mode", DegradedModePayload { bundle_id });
+    }
+    if start_polling {
+        spawn_degraded_polling();
     }
 }
 
-/// Unregister AX observer for an editor
-#[allow(dead_code)]
+/// Refresh the registry on the polling interval (at most every
+/// `DEGRADED_POLL_INTERVAL`) while any editor is in degraded mode
+fn spawn_degraded_polling() {
+    std::thread::spawn(|| loop {
+        let interval = crate::polling::current_tier().interval().max(DEGRADED_POLL_INTERVAL);
+        std::thread::sleep(interval);
+        // Decide to stop under the same lock enter_degraded_mode holds when it checks the flag
+        let any_degraded = AX_STATE
+            .lock()
+            .map(|state| {
+                let any_degraded = state.observers.values().any(|editor| editor.degraded);
+                if !any_degraded {
+                    DEGRADED_POLLING.store(false, Ordering::SeqCst);
+                }
+                any_degraded
+            })
+            .unwrap_or(false);
+        if !any_degraded {
+            break;
+        }
+        crate::window_registry::request_refresh("degraded-poll");
+    });
+}
+
+/// Registration state of every observer, for diagnostics
+pub fn observer_statuses() -> Vec<ObserverStatus> {
+    let state = AX_STATE.lock().unwrap();
+    let mut statuses: Vec<ObserverStatus> = state
+        .observers
+        .iter()
+        .map(|(pid, editor)| ObserverStatus {
+            pid: *pid,
+            bundle_id: editor.bundle_id.clone(),
+            registered: NOTIFICATIONS
+                .iter()
+                .filter(|name| !editor.failed.contains(name))
+                .map(|name| name.to_string())
+                .collect(),
+            failed: editor.failed.iter().map(|name| name.to_string()).collect(),
+            degraded: editor.degraded,
+        })
+        .collect();
+    statuses.sort_by_key(|status| status.pid);
+    statuses
+}
+
+/// Unregister AX observers for every running instance of an editor
 pub fn unregister_for_editor(bundle_id: &str) {
-    // Find the running editor process
+    // Find the running editor processes
     let workspace = NSWorkspace::sharedWorkspace();
     let apps = workspace.runningApplications();
 
     for app in apps {
-        if let Some(bid) = app.bundleIdentifier() {
-            if bid.to_string() == bundle_id {
-                let pid = app.processIdentifier();
-                unregister_for_pid(pid);
-                break;
-            }
+        if ax_helper::app_bundle_id(&app).as_deref() == Some(bundle_id) {
+            unregister_for_pid(app.processIdentifier());
         }
     }
 }
 
-/// Unregister AX observer for a specific PID
-fn unregister_for_pid(pid: i32) {
+/// Unregister AX observer for a specific PID (the editor quit)
+pub fn unregister_for_pid(pid: i32) {
+    debounce::cancel(DebounceKey::ObserverRetry(pid));
     let mut state = AX_STATE.lock().unwrap();
     // Drop will clean up resources
     state.observers.remove(&pid);
 }
 
 /// Unregister all observers
-#[allow(dead_code)]
 pub fn unregister_all() {
-    let mut state = AX_STATE.lock().unwrap();
-    state.observers.clear();
+    let observers: Vec<EditorObserver> = {
+        let mut state = AX_STATE.lock().unwrap();
+        state.observers.drain().map(|(_, observer)| observer).collect()
+    };
+    // Dropping removes each observer's notifications and its run loop source.
+    // Done outside the lock since AX calls into a hung editor can block.
+    drop(observers);
 }
 
 /// Get the PID of the frontmost supported editor
 fn get_frontmost_editor_pid() -> Option<(i32, String)> {
     let workspace = NSWorkspace::sharedWorkspace();
     if let Some(app) = workspace.frontmostApplication() {
-        if let Some(bid) = app.bundleIdentifier() {
-            let bundle_id = bid.to_string();
+        if let Some(bundle_id) = ax_helper::app_bundle_id(&app) {
             if is_supported_editor(&bundle_id) {
                 return Some((app.processIdentifier(), bundle_id));
             }
@@ -330,3 +636,47 @@ fn get_frontmost_editor_pid() -> Option<(i32, String)> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_kinds_map_from_notifications() {
+        assert_eq!(
+            WindowChangeKind::from_notification(K_AX_WINDOW_CREATED),
+            Some(WindowChangeKind::Created)
+        );
+        assert_eq!(
+            WindowChangeKind::from_notification(K_AX_UI_ELEMENT_DESTROYED),
+            Some(WindowChangeKind::Destroyed)
+        );
+        assert_eq!(
+            WindowChangeKind::from_notification(K_AX_TITLE_CHANGED),
+            Some(WindowChangeKind::Title)
+        );
+        assert_eq!(WindowChangeKind::from_notification(K_AX_FOCUSED_WINDOW_CHANGED), None);
+    }
+
+    #[test]
+    fn change_kind_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&WindowChangeKind::Destroyed).unwrap(),
+            "\"destroyed\""
+        );
+    }
+    #[test]
+    fn failed_registrations_retry_then_degrade_without_critical_events() {
+        assert_eq!(next_retry_step(&[], 0), RetryStep::Done);
+        assert_eq!(
+            next_retry_step(&[K_AX_TITLE_CHANGED], 0),
+            RetryStep::RetryAfter(Duration::from_millis(500))
+        );
+        let retries = REGISTRATION_RETRY_DELAYS.len();
+        assert_eq!(next_retry_step(&[K_AX_TITLE_CHANGED], retries), RetryStep::Done);
+        assert_eq!(
+            next_retry_step(&[K_AX_WINDOW_CREATED, K_AX_TITLE_CHANGED], retries),
+            RetryStep::Degraded
+        );
+    }
+}
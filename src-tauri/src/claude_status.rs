@@ -1,16 +1,76 @@
-use serde::Serialize;
-use std::collections::HashMap;
+use crate::i18n;
+use crate::notification;
+use crate::now_millis;
+use crate::polling;
+use crate::power;
+use crate::settings_store::{StoredSettings, STORE_FILE};
+use crate::tab_bars;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
-use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
 use std::thread;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
 
 static STATUS_WATCHER_RUNNING: AtomicBool = AtomicBool::new(false);
 
-const CLAUDE_EVENTS_FILE: &str = "/tmp/claude-code-events";
+/// 再起動のたびに増える世代番号。古い世代のスレッドは次のループで終了する
+static WATCHER_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+const DEFAULT_EVENTS_FILE: &str = "/tmp/claude-code-events";
+
+/// ペイロードのエントリに付けるエージェント名
+const CLAUDE_AGENT: &str = "claude";
+const CODEX_AGENT: &str = "codex";
+
+/// Store keys overriding the watched file paths
+pub(crate) const EVENTS_FILE_KEY: &str = "settings:claudeEventsFile";
+pub(crate) const GENERIC_STATUS_FILE_KEY: &str = "settings:genericAgentStatusFile";
+
+/// ウォッチャーが最後に計算したペイロード（get_claude_statuses 用）
+static LATEST_PAYLOAD: LazyLock<Mutex<ClaudeStatusPayload>> =
+    LazyLock::new(|| Mutex::new(ClaudeStatusPayload::default()));
+
+/// 現在の世代のウォッチャースレッドへの送信側
+static WATCHER_TX: LazyLock<Mutex<Option<mpsc::Sender<WatcherMessage>>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// refresh_claude_statuses が再計算を待つ上限
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 現在監視しているイベントファイル
+static EVENTS_FILE: LazyLock<Mutex<PathBuf>> =
+    LazyLock::new(|| Mutex::new(PathBuf::from(DEFAULT_EVENTS_FILE)));
+
+/// サブエージェントのセッションを「稼働中」とみなす期間。
+/// サブエージェントは終了行を書かずに消えることがあるので、更新が途絶えたら破棄する
+const SUBAGENT_ACTIVE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Codex CLI のセッションファイルを「稼働中」とみなす期間。
+/// Codex は終了を示す行を書かないので、更新が途絶えたセッションは破棄する
+const CODEX_ACTIVE_WINDOW: Duration = Duration::from_secs(30 * 60);
+
+/// 追跡していない古いセッションファイルも stat し直す間隔
+const CODEX_FULL_STAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 改行のない最終行を「書き込み完了」とみなすまでの待ち時間
+const UNTERMINATED_LINE_GRACE: Duration = Duration::from_secs(1);
+
+/// 改行のない行をこれ以上溜めない（行を終えない書き手でメモリが際限なく伸びないように）
+const MAX_PENDING_LINE: usize = 4 * 1024 * 1024;
+
+/// 追記分を一度に読む大きさ
+const READ_CHUNK_SIZE: usize = 256 * 1024;
+
+/// ファイル監視を開始できなかった場合のポーリング間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
 
 /// Claude Code の状態
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
@@ -18,119 +78,1238 @@ const CLAUDE_EVENTS_FILE: &str = "/tmp/claude-code-events";
 pub enum ClaudeStatus {
     Waiting,
     Generating,
+    /// API エラーや中断（Escape）でターンが終了した
+    Error,
 }
 
+/// 1セッション分の状態
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SessionStatus {
+    /// 状態を報告したエージェント CLI（"claude", "codex" など）
+    pub agent: String,
+    pub project_path: String,
+    /// セッションIDを含まない旧形式の行では空文字列
+    pub session_id: String,
+    pub status: ClaudeStatus,
+    /// 何を待っているか（JSON 形式の行で渡された場合のみ）
+    pub message: Option<String>,
+    /// サブエージェント（Task ツール）のセッション
+    pub is_subagent: bool,
+    /// 現在の状態に入った時刻（epoch millis）。同じ状態の再送ではリセットしない
+    pub since: u64,
+    /// 最後にイベント行を受け取った時刻（epoch millis）
+    pub last_update: u64,
+}
+
+/// (project_path, session_id)
+type SessionKey = (String, String);
+
 /// フロントエンドに送信するペイロード
-#[derive(Clone, Serialize)]
+#[derive(Clone, Default, Serialize)]
 pub struct ClaudeStatusPayload {
+    /// プロジェクト単位に集約した状態（既存UI向け）
     pub statuses: HashMap<String, ClaudeStatus>,
+    /// 集約した状態に入った時刻（epoch millis）。経過時間の表示用
+    pub since: HashMap<String, u64>,
+    /// 集約した状態を報告したエージェント（バッジの出し分け用）
+    pub agents: HashMap<String, String>,
+    /// セッション単位の状態
+    pub sessions: Vec<SessionStatus>,
 }
 
-/// 1行をパースして状態マップを更新する。状態が変化した場合は true を返す。
-fn apply_line(line: &str, statuses: &mut HashMap<String, ClaudeStatus>) -> bool {
-    let trimmed = line.trim();
+impl ClaudeStatusPayload {
+    fn from_sessions(mut sessions: Vec<SessionStatus>) -> Self {
+        sessions.sort_by(|a, b| {
+            (&a.project_path, &a.agent, &a.session_id).cmp(&(&b.project_path, &b.agent, &b.session_id))
+        });
+        let statuses = aggregate(&sessions);
+        let since = aggregate_since(&sessions, &statuses);
+        let agents = aggregate_agents(&sessions, &statuses);
+        Self {
+            statuses,
+            since,
+            agents,
+            sessions,
+        }
+    }
+
+    /// すべての取得元のセッションをまとめる
+    fn from_providers(providers: &[Box<dyn AgentStatusProvider>]) -> Self {
+        Self::from_sessions(providers.iter().flat_map(|p| p.statuses()).collect())
+    }
+}
+
+/// 集約時の優先度（タブのバッジと同じく Generating を優先）
+fn aggregate_priority(status: &ClaudeStatus) -> u8 {
+    match status {
+        ClaudeStatus::Generating => 2,
+        ClaudeStatus::Waiting => 1,
+        ClaudeStatus::Error => 0,
+    }
+}
+
+/// セッション単位の状態をプロジェクト単位に集約する
+fn aggregate(sessions: &[SessionStatus]) -> HashMap<String, ClaudeStatus> {
+    let mut statuses: HashMap<String, ClaudeStatus> = HashMap::new();
+    for session in sessions {
+        let replace = statuses
+            .get(&session.project_path)
+            .is_none_or(|current| aggregate_priority(&session.status) > aggregate_priority(current));
+        if replace {
+            statuses.insert(session.project_path.clone(), session.status.clone());
+        }
+    }
+    statuses
+}
+
+/// 集約した状態ごとに、その状態のセッションのうち最も早い開始時刻を返す
+fn aggregate_since(
+    sessions: &[SessionStatus],
+    statuses: &HashMap<String, ClaudeStatus>,
+) -> HashMap<String, u64> {
+    let mut since: HashMap<String, u64> = HashMap::new();
+    for session in sessions {
+        if statuses.get(&session.project_path) != Some(&session.status) {
+            continue;
+        }
+        since
+            .entry(session.project_path.clone())
+            .and_modify(|t| *t = (*t).min(session.since))
+            .or_insert(session.since);
+    }
+    since
+}
+
+/// 集約した状態ごとに、その状態を報告したエージェントを返す（ソート済みの先頭を優先）
+fn aggregate_agents(
+    sessions: &[SessionStatus],
+    statuses: &HashMap<String, ClaudeStatus>,
+) -> HashMap<String, String> {
+    let mut agents: HashMap<String, String> = HashMap::new();
+    for session in sessions {
+        if statuses.get(&session.project_path) == Some(&session.status) {
+            agents
+                .entry(session.project_path.clone())
+                .or_insert_with(|| session.agent.clone());
+        }
+    }
+    agents
+}
+
+/// イベント行の種類
+#[derive(Debug, PartialEq, Eq)]
+enum LineAction {
+    Set(ClaudeStatus),
+    Clear,
+}
+
+/// パース済みのイベント行
+#[derive(Debug, PartialEq, Eq)]
+struct EventLine {
+    action: LineAction,
+    project: String,
+    session_id: String,
+    message: Option<String>,
+    /// フック側で記録した時刻（epoch millis）
+    ts: Option<u64>,
+    is_subagent: bool,
+}
+
+/// JSON Lines 形式のイベント行
+#[derive(Deserialize)]
+struct JsonEventLine {
+    status: String,
+    cwd: String,
+    #[serde(default)]
+    session_id: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    ts: Option<u64>,
+    /// サブエージェントからの行
+    #[serde(default)]
+    subagent: bool,
+    #[serde(default)]
+    agent_id: Option<String>,
+}
+
+/// 旧形式 `<prefix> <project_path>[\t<session_id>]` をパースする
+fn parse_plain_line(trimmed: &str) -> Option<EventLine> {
     if trimmed.len() < 3 {
-        return false;
+        return None;
     }
 
     let prefix = &trimmed[..1];
-    let project = trimmed[2..].trim_end_matches('/');
+    let (project, session_id) = trimmed[2..]
+        .split_once('\t')
+        .map(|(project, session_id)| (project, session_id.trim()))
+        .unwrap_or((&trimmed[2..], ""));
+
+    let action = match prefix {
+        "g" => LineAction::Set(ClaudeStatus::Generating),
+        "w" => LineAction::Set(ClaudeStatus::Waiting),
+        "e" => LineAction::Set(ClaudeStatus::Error),
+        "c" => LineAction::Clear,
+        _ => return None,
+    };
+    Some(EventLine {
+        action,
+        project: project.to_string(),
+        session_id: session_id.to_string(),
+        message: None,
+        ts: None,
+        is_subagent: false,
+    })
+}
+
+/// JSON 形式 `{"status": "waiting", "cwd": ..., "session_id": ..., "message": ..., "ts": ...}` をパースする
+fn parse_json_line(trimmed: &str) -> Option<EventLine> {
+    let line: JsonEventLine = serde_json::from_str(trimmed).ok()?;
+    let action = match line.status.as_str() {
+        "generating" => LineAction::Set(ClaudeStatus::Generating),
+        "waiting" => LineAction::Set(ClaudeStatus::Waiting),
+        "error" => LineAction::Set(ClaudeStatus::Error),
+        "clear" => LineAction::Clear,
+        _ => return None,
+    };
+    let mut session_id = line.session_id.unwrap_or_default();
+    if line.subagent {
+        // 親セッションの状態を上書きしないよう別セッションとして扱う
+        let agent_id = line.agent_id.as_deref().unwrap_or("subagent");
+        session_id = format!("{}/{}", session_id, agent_id);
+    }
+    Some(EventLine {
+        action,
+        project: line.cwd,
+        session_id,
+        message: line.message.filter(|m| !m.is_empty()),
+        ts: line.ts,
+        is_subagent: line.subagent,
+    })
+}
+
+/// 1行をパースする（旧形式と JSON 形式が混在していてもよい）
+fn parse_line(line: &str) -> Option<EventLine> {
+    let trimmed = line.trim();
+    let mut event = if trimmed.starts_with('{') {
+        parse_json_line(trimmed)?
+    } else {
+        parse_plain_line(trimmed)?
+    };
+    event.project = event.project.trim_end_matches('/').to_string();
+    if event.project.is_empty() {
+        return None;
+    }
+    Some(event)
+}
 
-    if project.is_empty() {
+/// 1行をパースしてセッション状態を更新する。状態が変化した場合は true を返す。
+///
+/// セッションIDのないクリア行はそのプロジェクトの全セッションをクリアする。
+fn apply_line(line: &str, sessions: &mut HashMap<SessionKey, SessionStatus>) -> bool {
+    let Some(event) = parse_line(line) else {
         return false;
+    };
+    let project = event.project.as_str();
+    let session_id = event.session_id.as_str();
+
+    let status = match event.action {
+        LineAction::Set(status) => status,
+        LineAction::Clear => {
+            let before = sessions.len();
+            if session_id.is_empty() {
+                sessions.retain(|(path, _), _| path != project);
+            } else {
+                sessions.remove(&(project.to_string(), session_id.to_string()));
+            }
+            return sessions.len() != before;
+        }
+    };
+
+    // 状態とメッセージが同じなら last_update だけ更新し、変化なしとして扱う
+    // (タイムスタンプの差分で毎回 emit しないように status のみで比較する)
+    let now = event.ts.unwrap_or_else(now_millis);
+    let key = (project.to_string(), session_id.to_string());
+    let prev = sessions.get(&key);
+    let status_changed = prev.map(|prev| &prev.status) != Some(&status);
+    let message_changed = prev.map(|prev| &prev.message) != Some(&event.message);
+    let since = match prev {
+        Some(prev) if !status_changed => prev.since,
+        _ => now,
+    };
+    sessions.insert(
+        key,
+        SessionStatus {
+            // エージェント名は取得元（EventsReader）が statuses() で付ける
+            agent: String::new(),
+            project_path: project.to_string(),
+            session_id: session_id.to_string(),
+            status,
+            message: event.message,
+            is_subagent: event.is_subagent,
+            since,
+            last_update: now,
+        },
+    );
+    status_changed || message_changed
+}
+
+/// 更新が途絶えたサブエージェントのセッションを破棄する。破棄した場合は true を返す
+fn expire_stale_subagents(sessions: &mut HashMap<SessionKey, SessionStatus>, now: u64) -> bool {
+    let window = SUBAGENT_ACTIVE_WINDOW.as_millis() as u64;
+    let before = sessions.len();
+    sessions.retain(|_, session| {
+        !session.is_subagent || now.saturating_sub(session.last_update) < window
+    });
+    sessions.len() != before
+}
+
+/// エージェント CLI ごとの状態の取得元。ウォッチャーはすべての取得元の状態をまとめて emit する
+trait AgentStatusProvider {
+    /// 取得元を読み直す。状態が変化した場合は true を返す
+    fn refresh(&mut self) -> bool;
+    /// 現在のセッション状態（エージェント名付き）
+    fn statuses(&self) -> Vec<SessionStatus>;
+    /// FSEvents で監視するディレクトリ
+    fn watch_dir(&self) -> Option<(PathBuf, RecursiveMode)>;
+    /// FSEvents の通知がこの取得元に関係するかどうか
+    fn is_relevant(&self, event: &notify::Event) -> bool;
+}
+
+/// 追記分を `pending` に足し、完結した行（最後の改行まで）を取り出す。残りは次回に持ち越す。
+/// 持ち越す行が `MAX_PENDING_LINE` を超えたら捨てる
+fn take_complete_lines(pending: &mut Vec<u8>, chunk: &[u8]) -> Option<Vec<u8>> {
+    pending.extend_from_slice(chunk);
+    let complete = pending.iter().rposition(|&b| b == b'\n').map(|last_newline| {
+        let rest = pending.split_off(last_newline + 1);
+        std::mem::replace(pending, rest)
+    });
+    if pending.len() > MAX_PENDING_LINE {
+        log::warn!("Dropping an unterminated line longer than {} bytes", MAX_PENDING_LINE);
+        *pending = Vec::new();
     }
+    complete
+}
 
-    match prefix {
-        "g" => {
-            let prev = statuses.insert(project.to_string(), ClaudeStatus::Generating);
-            prev.as_ref() != Some(&ClaudeStatus::Generating)
+/// `offset` から `size` までに追記されたバイト列を `READ_CHUNK_SIZE` ずつ `feed` に渡し、
+/// 読めたバイト数を返す
+fn read_appended(
+    path: &Path,
+    offset: u64,
+    size: u64,
+    mut feed: impl FnMut(&[u8]),
+) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    let mut appended = file.take(size.saturating_sub(offset));
+    let mut buf = vec![0; READ_CHUNK_SIZE];
+    let mut read = 0;
+    loop {
+        match appended.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                feed(&buf[..n]);
+                read += n as u64;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                log::debug!("Failed to read {}: {}", path.display(), e);
+                break;
+            }
         }
-        "w" => {
-            let prev = statuses.insert(project.to_string(), ClaudeStatus::Waiting);
-            prev.as_ref() != Some(&ClaudeStatus::Waiting)
+    }
+    Some(read)
+}
+
+/// `g/w/e/c <path>` 形式（または JSON 形式）のイベントファイルを差分で読み取る取得元。
+/// Claude Code のフックが書くファイルと、ユーザー指定の汎用ステータスファイルで使う
+#[derive(Default)]
+struct EventsReader {
+    agent: String,
+    path: PathBuf,
+    sessions: HashMap<SessionKey, SessionStatus>,
+    last_offset: u64,
+    /// 書き込み途中の（改行で終わっていない）行
+    pending: Vec<u8>,
+}
+
+impl EventsReader {
+    fn new(agent: &str, path: PathBuf) -> Self {
+        Self {
+            agent: agent.to_string(),
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// 既存の内容は読まず、以降の追記だけを読む（ユーザーのファイルは削除しないため）
+    fn tail(agent: &str, path: PathBuf) -> Self {
+        let last_offset = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            last_offset,
+            ..Self::new(agent, path)
+        }
+    }
+
+    /// 既存の内容（起動前の古いイベント）を切り詰めてから先頭から読む。ファイルは削除しない
+    /// ので追記中のフックはそのまま書き続けられ、先頭から読む CLI とも状態が一致する。
+    /// 切り詰められなければ末尾から読む
+    fn truncate(agent: &str, path: PathBuf) -> Self {
+        match fs::OpenOptions::new().write(true).open(&path) {
+            Ok(file) => match file.set_len(0) {
+                Ok(()) => Self::new(agent, path),
+                Err(e) => {
+                    log::warn!("Failed to truncate {}: {}", path.display(), e);
+                    Self::tail(agent, path)
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::new(agent, path),
+            Err(e) => {
+                log::warn!("Failed to open {}: {}", path.display(), e);
+                Self::tail(agent, path)
+            }
+        }
+    }
+
+    /// 追記されたバイト列を適用する。完結した行のみ処理し、残りは次回に持ち越す。
+    /// いずれかの行で状態が変化した場合は true を返す。
+    fn feed(&mut self, chunk: &[u8]) -> bool {
+        let Some(complete) = take_complete_lines(&mut self.pending, chunk) else {
+            return false;
+        };
+        let mut changed = false;
+        for line in String::from_utf8_lossy(&complete).lines() {
+            changed |= apply_line(line, &mut self.sessions);
+        }
+        changed
+    }
+
+    /// 改行で終わっていない最終行を完結した行として適用する
+    fn flush_pending(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return false;
+        }
+        let line = std::mem::take(&mut self.pending);
+        apply_line(&String::from_utf8_lossy(&line), &mut self.sessions)
+    }
+
+    fn reset(&mut self) {
+        self.sessions.clear();
+        self.last_offset = 0;
+        self.pending.clear();
+    }
+}
+
+impl AgentStatusProvider for EventsReader {
+    /// イベントファイルの追記分だけを読み取る（差分読み取り方式）
+    fn refresh(&mut self) -> bool {
+        // サブエージェントの稼働中扱いは一定時間で切れる（フォールバックの tick でも評価される）
+        let mut changed = expire_stale_subagents(&mut self.sessions, now_millis());
+
+        let Ok(metadata) = fs::metadata(&self.path) else {
+            // ファイルが消えた場合
+            changed |= !self.sessions.is_empty();
+            self.reset();
+            return changed;
+        };
+        let file_size = metadata.len();
+
+        // ファイルが切り詰められた（作り直された）場合は最初から読み直す
+        if file_size < self.last_offset {
+            changed |= !self.sessions.is_empty();
+            self.reset();
+        }
+
+        // 新しいデータがない場合、改行なしで書き終えた最終行があれば適用する
+        if file_size <= self.last_offset {
+            let settled = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|elapsed| elapsed >= UNTERMINATED_LINE_GRACE);
+            if settled {
+                changed |= self.flush_pending();
+            }
+            return changed;
+        }
+        let path = self.path.clone();
+        let Some(read) = read_appended(&path, self.last_offset, file_size, |chunk| {
+            changed |= self.feed(chunk);
+        }) else {
+            return changed;
+        };
+        self.last_offset += read;
+        changed
+    }
+
+    fn statuses(&self) -> Vec<SessionStatus> {
+        self.sessions
+            .values()
+            .map(|session| SessionStatus {
+                agent: self.agent.clone(),
+                ..session.clone()
+            })
+            .collect()
+    }
+
+    fn watch_dir(&self) -> Option<(PathBuf, RecursiveMode)> {
+        // ファイルはまだ無いか、後から作り直されることがあるので、ディレクトリごと監視する
+        let dir = self.path.parent()?;
+        Some((dir.to_path_buf(), RecursiveMode::NonRecursive))
+    }
+
+    fn is_relevant(&self, event: &notify::Event) -> bool {
+        is_events_file_event(event, &self.path)
+    }
+}
+
+/// イベントファイルに関係する通知かどうか
+/// (/tmp は /private/tmp へのシンボリックリンクなのでファイル名で比較する)
+fn is_events_file_event(event: &notify::Event, events_file: &Path) -> bool {
+    let file_name = events_file.file_name();
+    event.paths.iter().any(|path| path.file_name() == file_name)
+}
+
+/// Codex CLI のセッションファイル1つ分の読み取り状態
+#[derive(Default)]
+struct CodexSessionFile {
+    last_offset: u64,
+    pending: Vec<u8>,
+    /// session_meta 行から取得する
+    session_id: String,
+    cwd: Option<String>,
+    status: Option<ClaudeStatus>,
+    since: u64,
+    last_update: u64,
+}
+
+/// Codex CLI のセッションファイル（rollout-*.jsonl）の1行
+#[derive(Deserialize)]
+struct CodexLine {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+}
+
+impl CodexSessionFile {
+    /// 1行を適用する。状態が変化した場合は true を返す
+    fn apply_line(&mut self, line: &str, now: u64) -> bool {
+        let Ok(line) = serde_json::from_str::<CodexLine>(line.trim()) else {
+            return false;
+        };
+        let field = |name: &str| line.payload.get(name).and_then(|v| v.as_str());
+        let status = match (line.kind.as_str(), field("type")) {
+            ("session_meta", _) => {
+                let cwd = field("cwd").map(|cwd| cwd.trim_end_matches('/').to_string());
+                self.session_id = field("id").unwrap_or_default().to_string();
+                let changed = cwd != self.cwd && self.status.is_some();
+                self.cwd = cwd;
+                return changed;
+            }
+            ("event_msg", Some("task_started")) => ClaudeStatus::Generating,
+            ("event_msg", Some("task_complete")) => ClaudeStatus::Waiting,
+            ("event_msg", Some("turn_aborted")) => ClaudeStatus::Error,
+            _ => return false,
+        };
+        self.last_update = now;
+        if self.status.as_ref() == Some(&status) {
+            return false;
+        }
+        self.status = Some(status);
+        self.since = now;
+        self.cwd.is_some()
+    }
+
+    fn feed(&mut self, chunk: &[u8], now: u64) -> bool {
+        let Some(complete) = take_complete_lines(&mut self.pending, chunk) else {
+            return false;
+        };
+        let mut changed = false;
+        for line in String::from_utf8_lossy(&complete).lines() {
+            changed |= self.apply_line(line, now);
+        }
+        changed
+    }
+
+    fn session(&self) -> Option<SessionStatus> {
+        Some(SessionStatus {
+            agent: CODEX_AGENT.to_string(),
+            project_path: self.cwd.clone().filter(|cwd| !cwd.is_empty())?,
+            session_id: self.session_id.clone(),
+            status: self.status.clone()?,
+            message: None,
+            is_subagent: false,
+            since: self.since,
+            last_update: self.last_update,
+        })
+    }
+}
+
+/// Codex CLI のセッションディレクトリ（$CODEX_HOME/sessions、既定は ~/.codex/sessions）
+fn codex_sessions_dir() -> Option<PathBuf> {
+    let codex_home = std::env::var_os("CODEX_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".codex")))?;
+    Some(codex_home.join("sessions"))
+}
+
+/// 前回読み取ったときのディレクトリの mtime と中身（再帰しない）
+struct DirSnapshot {
+    modified: SystemTime,
+    subdirs: Vec<PathBuf>,
+    /// *.jsonl のみ
+    files: Vec<PathBuf>,
+}
+
+/// ディレクトリを1階層だけ読み取る
+fn scan_dir(dir: &Path, modified: SystemTime) -> DirSnapshot {
+    let mut snapshot = DirSnapshot {
+        modified,
+        subdirs: Vec::new(),
+        files: Vec::new(),
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return snapshot;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // file_type() はディレクトリエントリの情報だけで判定できる（stat しない）
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            snapshot.subdirs.push(path);
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            snapshot.files.push(path);
         }
-        "c" => statuses.remove(project).is_some(),
-        _ => false,
     }
+    snapshot
+}
+
+fn is_recent(metadata: &fs::Metadata, window: Duration) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|elapsed| elapsed < window)
+}
+
+/// OpenAI Codex CLI のセッションファイルから状態を読み取る取得元
+struct CodexProvider {
+    sessions_dir: PathBuf,
+    files: HashMap<PathBuf, CodexSessionFile>,
+    /// ディレクトリごとのスナップショット。mtime が変わっていなければ読み直さない
+    dirs: HashMap<PathBuf, DirSnapshot>,
+    /// 追跡していないファイルも含めてすべて stat した時刻
+    last_full_stat: Option<Instant>,
+    #[cfg(test)]
+    dir_scans: usize,
+    #[cfg(test)]
+    file_opens: usize,
 }
 
-/// 状態監視ウォッチャーを開始（差分読み取り方式）
+impl CodexProvider {
+    fn new(sessions_dir: PathBuf) -> Self {
+        Self {
+            sessions_dir,
+            files: HashMap::new(),
+            dirs: HashMap::new(),
+            last_full_stat: None,
+            #[cfg(test)]
+            dir_scans: 0,
+            #[cfg(test)]
+            file_opens: 0,
+        }
+    }
+
+    /// セッションディレクトリ以下（YYYY/MM/DD/rollout-*.jsonl）で `window` 以内に更新された
+    /// ファイルとそのサイズを返す。
+    ///
+    /// mtime が変わっていないディレクトリはエントリが増減していないので読み直さず、
+    /// 追跡中（稼働中）のファイルだけを stat する。再開されたセッションは古いファイルに
+    /// 追記されディレクトリの mtime は変わらないので、一定間隔ですべてのファイルを stat する。
+    fn recent_files(&mut self, window: Duration) -> HashMap<PathBuf, u64> {
+        let full_stat = self
+            .last_full_stat
+            .is_none_or(|at| at.elapsed() >= CODEX_FULL_STAT_INTERVAL);
+        if full_stat {
+            self.last_full_stat = Some(Instant::now());
+        }
+        let mut recent = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.sessions_dir.clone()];
+        while let Some(dir) = stack.pop() {
+            let Ok(modified) = fs::metadata(&dir).and_then(|m| m.modified()) else {
+                continue;
+            };
+            let unchanged = self
+                .dirs
+                .get(&dir)
+                .is_some_and(|snapshot| snapshot.modified == modified);
+            if !unchanged {
+                self.dirs.insert(dir.clone(), scan_dir(&dir, modified));
+                #[cfg(test)]
+                {
+                    self.dir_scans += 1;
+                }
+            }
+
+            let snapshot = &self.dirs[&dir];
+            stack.extend(snapshot.subdirs.iter().cloned());
+            for path in &snapshot.files {
+                if unchanged && !full_stat && !self.files.contains_key(path) {
+                    continue;
+                }
+                if let Ok(metadata) = fs::metadata(path) {
+                    if is_recent(&metadata, window) {
+                        recent.insert(path.clone(), metadata.len());
+                    }
+                }
+            }
+            visited.insert(dir);
+        }
+        // 消えたディレクトリのスナップショットを捨てる
+        self.dirs.retain(|dir, _| visited.contains(dir));
+        recent
+    }
+}
+
+impl AgentStatusProvider for CodexProvider {
+    fn refresh(&mut self) -> bool {
+        let recent = self.recent_files(CODEX_ACTIVE_WINDOW);
+        let now = now_millis();
+
+        // 更新が途絶えたセッションを破棄する
+        let mut changed = false;
+        self.files.retain(|path, file| {
+            let keep = recent.contains_key(path);
+            changed |= !keep && file.session().is_some();
+            keep
+        });
+
+        for (path, size) in recent {
+            let file = self.files.entry(path.clone()).or_default();
+            if size < file.last_offset {
+                changed |= file.session().is_some();
+                *file = CodexSessionFile::default();
+            }
+            if size == file.last_offset {
+                continue;
+            }
+            let offset = file.last_offset;
+            let Some(read) = read_appended(&path, offset, size, |chunk| {
+                changed |= file.feed(chunk, now);
+            }) else {
+                continue;
+            };
+            #[cfg(test)]
+            {
+                self.file_opens += 1;
+            }
+            file.last_offset += read;
+        }
+        changed
+    }
+
+    fn statuses(&self) -> Vec<SessionStatus> {
+        self.files.values().filter_map(CodexSessionFile::session).collect()
+    }
+
+    fn watch_dir(&self) -> Option<(PathBuf, RecursiveMode)> {
+        // Codex を使っていない場合はディレクトリがない（フォールバックの tick で拾う）
+        self.sessions_dir
+            .is_dir()
+            .then(|| (self.sessions_dir.clone(), RecursiveMode::Recursive))
+    }
+
+    fn is_relevant(&self, event: &notify::Event) -> bool {
+        event.paths.iter().any(|path| {
+            path.starts_with(&self.sessions_dir) && path.extension().is_some_and(|ext| ext == "jsonl")
+        })
+    }
+}
+
+/// ウォッチャースレッドが受け取るメッセージ
+enum WatcherMessage {
+    /// FSEvents の通知
+    Fs(notify::Result<notify::Event>),
+    /// refresh_claude_statuses からの再計算要求。計算結果を送り返す
+    Refresh(mpsc::Sender<ClaudeStatusPayload>),
+}
+
+/// 取得元のディレクトリを監視する。1つも監視できなかった場合は None（ポーリングに戻る）
+fn watch_dirs(
+    dirs: &[(PathBuf, RecursiveMode)],
+    tx: mpsc::Sender<WatcherMessage>,
+) -> Option<RecommendedWatcher> {
+    let handler = move |event: notify::Result<notify::Event>| {
+        let _ = tx.send(WatcherMessage::Fs(event));
+    };
+    let mut watcher = match notify::recommended_watcher(handler) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::error!("Failed to create agent status watcher: {}", e);
+            return None;
+        }
+    };
+    let mut watching = false;
+    for (dir, mode) in dirs {
+        match watcher.watch(dir, *mode) {
+            Ok(()) => watching = true,
+            Err(e) => log::error!("Failed to watch {}: {}", dir.display(), e),
+        }
+    }
+    watching.then_some(watcher)
+}
+
+/// 長時間の生成が終わったときの通知設定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LongGenerationSettings {
+    pub enabled: bool,
+    /// この秒数を超えた生成のみ通知する
+    pub threshold_secs: u64,
+}
+
+impl Default for LongGenerationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_secs: 120,
+        }
+    }
+}
+
+pub(crate) const LONG_GENERATION_KEY: &str = "settings:longGenerationNotification";
+
+static LONG_GENERATION_SETTINGS: LazyLock<Mutex<LongGenerationSettings>> =
+    LazyLock::new(|| Mutex::new(LongGenerationSettings::default()));
+
+pub fn long_generation_settings() -> LongGenerationSettings {
+    LONG_GENERATION_SETTINGS
+        .lock()
+        .map(|settings| *settings)
+        .unwrap_or_default()
+}
+
+/// 長時間生成の通知設定を変更して保存する
+pub fn set_long_generation_settings(
+    app_handle: &AppHandle,
+    settings: LongGenerationSettings,
+) -> Result<(), String> {
+    let value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(LONG_GENERATION_KEY, value);
+    store.save().map_err(|e| e.to_string())?;
+
+    *LONG_GENERATION_SETTINGS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))? = settings;
+    Ok(())
+}
+
+fn load_long_generation_settings(stored: &StoredSettings) -> LongGenerationSettings {
+    stored
+        .get(LONG_GENERATION_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Generating から抜けた（消えた、または Waiting になった）プロジェクトと生成時間を返す
+fn finished_generations(
+    before: &ClaudeStatusPayload,
+    after: &ClaudeStatusPayload,
+    now: u64,
+) -> Vec<(String, Duration)> {
+    let mut finished: Vec<(String, Duration)> = before
+        .statuses
+        .iter()
+        .filter(|(_, status)| **status == ClaudeStatus::Generating)
+        .filter(|(path, _)| match after.statuses.get(*path) {
+            None | Some(ClaudeStatus::Waiting) => true,
+            Some(ClaudeStatus::Generating) | Some(ClaudeStatus::Error) => false,
+        })
+        .filter_map(|(path, _)| {
+            let since = *before.since.get(path)?;
+            Some((path.clone(), Duration::from_millis(now.saturating_sub(since))))
+        })
+        .collect();
+    finished.sort();
+    finished
+}
+
+/// Waiting から抜けた（消えた、または別の状態になった）プロジェクトを返す
+fn left_waiting(before: &ClaudeStatusPayload, after: &ClaudeStatusPayload) -> Vec<String> {
+    let mut paths: Vec<String> = before
+        .statuses
+        .iter()
+        .filter(|(path, status)| {
+            **status == ClaudeStatus::Waiting && after.statuses.get(*path) != Some(&ClaudeStatus::Waiting)
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// 経過時間を "2m 14s" 形式にする
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, _) => format!("{}h {}m", h, m),
+    }
+}
+
+/// 通知に表示するエージェント名
+fn agent_label(agent: &str) -> &str {
+    match agent {
+        CLAUDE_AGENT => "Claude Code",
+        CODEX_AGENT => "Codex",
+        other => other,
+    }
+}
+
+fn notify_long_generations(before: &ClaudeStatusPayload, after: &ClaudeStatusPayload) {
+    let settings = long_generation_settings();
+    if !settings.enabled {
+        return;
+    }
+    let threshold = Duration::from_secs(settings.threshold_secs);
+    for (path, elapsed) in finished_generations(before, after, now_millis()) {
+        if elapsed < threshold {
+            continue;
+        }
+        let project_name = Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        let agent = before.agents.get(&path).map(String::as_str).unwrap_or(CLAUDE_AGENT);
+        // project_path を埋め込むので notification-clicked で該当ウィンドウにフォーカスできる
+        if let Err(e) = notification::send_notification(
+            project_name,
+            agent_label(agent).to_string(),
+            i18n::t_with("notification.finishedAfter", &[("elapsed", &format_elapsed(elapsed))]),
+            path,
+            None,
+        ) {
+            log::debug!("Long generation notification not sent: {}", e);
+        }
+    }
+}
+
+/// 現在のイベントファイルのパス
+pub fn events_file() -> PathBuf {
+    EVENTS_FILE
+        .lock()
+        .map(|path| path.clone())
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_EVENTS_FILE))
+}
+
+/// イベントファイルのパスを検証する（絶対パスで、親ディレクトリが存在すること）
+pub(crate) fn validate_events_file(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!("Events file must be an absolute path: {}", path.display()));
+    }
+    if path.is_dir() {
+        return Err(format!("Events file is a directory: {}", path.display()));
+    }
+    match path.parent() {
+        Some(dir) if dir.is_dir() => Ok(()),
+        _ => Err(format!("Directory does not exist for: {}", path.display())),
+    }
+}
+
+/// ストアに保存された設定を読み込む（未設定・不正な場合はデフォルト）
+fn load_events_file_setting(stored: &StoredSettings) -> PathBuf {
+    let configured = stored
+        .get(EVENTS_FILE_KEY)
+        .and_then(|value| value.as_str().map(PathBuf::from));
+    match configured {
+        Some(path) if validate_events_file(&path).is_ok() => path,
+        Some(path) => {
+            log::warn!("Ignoring invalid Claude events file setting: {}", path.display());
+            PathBuf::from(DEFAULT_EVENTS_FILE)
+        }
+        None => PathBuf::from(DEFAULT_EVENTS_FILE),
+    }
+}
+
+/// イベントファイルのパスを変更して保存し、ウォッチャーを再起動する。
+/// None でデフォルトに戻す。変更後のパスを返す。
+pub fn set_events_file(app_handle: &AppHandle, path: Option<String>) -> Result<String, String> {
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(p) => {
+            let path = PathBuf::from(p.trim());
+            validate_events_file(&path)?;
+            path
+        }
+        None => PathBuf::from(DEFAULT_EVENTS_FILE),
+    };
+
+    let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
+    if path == Path::new(DEFAULT_EVENTS_FILE) {
+        store.delete(EVENTS_FILE_KEY);
+    } else {
+        store.set(EVENTS_FILE_KEY, path.to_string_lossy().to_string());
+    }
+    store.save().map_err(|e| e.to_string())?;
+
+    *EVENTS_FILE.lock().map_err(|e| format!("Lock error: {}", e))? = path.clone();
+    if STATUS_WATCHER_RUNNING.load(Ordering::SeqCst) {
+        spawn_watcher(app_handle.clone());
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// `g/w/c <path>` 形式で状態を書き出す任意のエージェント CLI（Aider など）のステータスファイル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenericStatusFile {
+    /// ペイロードのエントリに付けるエージェント名
+    pub agent: String,
+    pub path: String,
+}
+
+static GENERIC_STATUS_FILE: LazyLock<Mutex<Option<GenericStatusFile>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// 現在の汎用ステータスファイルの設定
+pub fn generic_status_file() -> Option<GenericStatusFile> {
+    GENERIC_STATUS_FILE.lock().ok().and_then(|file| file.clone())
+}
+
+pub(crate) fn validate_generic_status_file(file: &GenericStatusFile) -> Result<(), String> {
+    if file.agent.trim().is_empty() {
+        return Err(i18n::t("error.emptyAgentName"));
+    }
+    validate_events_file(Path::new(&file.path))
+}
+
+fn load_generic_status_file_setting(stored: &StoredSettings) -> Option<GenericStatusFile> {
+    let file: GenericStatusFile = stored
+        .get(GENERIC_STATUS_FILE_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())?;
+    match validate_generic_status_file(&file) {
+        Ok(()) => Some(file),
+        Err(e) => {
+            log::warn!("Ignoring invalid generic agent status file setting: {}", e);
+            None
+        }
+    }
+}
+
+/// 汎用ステータスファイルを変更して保存し、ウォッチャーを再起動する。None で無効にする
+pub fn set_generic_status_file(
+    app_handle: &AppHandle,
+    file: Option<GenericStatusFile>,
+) -> Result<(), String> {
+    let file = file.map(|file| GenericStatusFile {
+        agent: file.agent.trim().to_string(),
+        path: file.path.trim().to_string(),
+    });
+    let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
+    match &file {
+        Some(file) => {
+            validate_generic_status_file(file)?;
+            let value = serde_json::to_value(file).map_err(|e| e.to_string())?;
+            store.set(GENERIC_STATUS_FILE_KEY, value);
+        }
+        None => {
+            store.delete(GENERIC_STATUS_FILE_KEY);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+
+    *GENERIC_STATUS_FILE
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))? = file;
+    if STATUS_WATCHER_RUNNING.load(Ordering::SeqCst) {
+        spawn_watcher(app_handle.clone());
+    }
+    Ok(())
+}
+
+/// 状態監視ウォッチャーを開始（FSEvents で変更時のみ読み取り）
 pub fn start_claude_status_watcher(app_handle: AppHandle) {
     if STATUS_WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
         return;
     }
 
-    // ウォッチャー開始前に古いイベントログをクリア
-    let _ = fs::remove_file(CLAUDE_EVENTS_FILE);
+    load_settings(&StoredSettings::from_app(&app_handle));
+    spawn_watcher(app_handle);
+    power::watcher_started(power::Watcher::Status);
+}
+
+/// 保存された設定を読み込む（ウォッチャー開始時と CLI から）
+pub fn load_settings(stored: &StoredSettings) {
+    if let Ok(mut settings) = LONG_GENERATION_SETTINGS.lock() {
+        *settings = load_long_generation_settings(stored);
+    }
+
+    let path = load_events_file_setting(stored);
+    if let Ok(mut events_file) = EVENTS_FILE.lock() {
+        *events_file = path;
+    }
+    if let Ok(mut generic) = GENERIC_STATUS_FILE.lock() {
+        *generic = load_generic_status_file_setting(stored);
+    }
+}
+
+/// 監視するファイルを決める設定キー（変わったらウォッチャーを再起動する）
+const WATCHER_KEYS: &[&str] = &[EVENTS_FILE_KEY, GENERIC_STATUS_FILE_KEY];
+
+fn affects_watcher<'a>(mut keys: impl Iterator<Item = &'a str>) -> bool {
+    keys.any(|key| WATCHER_KEYS.contains(&key))
+}
+
+/// 設定を読み直し、監視するファイルの設定がインポートされていれば
+/// ウォッチャーを新しい設定で再起動する（設定のインポート後）
+pub fn reload_settings<'a>(app_handle: &AppHandle, imported_keys: impl Iterator<Item = &'a str>) {
+    load_settings(&StoredSettings::from_app(app_handle));
+    if STATUS_WATCHER_RUNNING.load(Ordering::SeqCst) && affects_watcher(imported_keys) {
+        spawn_watcher(app_handle.clone());
+    }
+}
+
+/// ウォッチャーを使わずに取得元を一度だけ読んで状態を返す（CLI 用）
+pub fn collect_statuses() -> ClaudeStatusPayload {
+    let mut providers = build_providers(EventsReader::new(CLAUDE_AGENT, events_file()));
+    for provider in providers.iter_mut() {
+        provider.refresh();
+    }
+    ClaudeStatusPayload::from_providers(&providers)
+}
+
+/// 現在の設定から取得元を作る。`claude` は Claude Code のイベントファイルの読み手
+fn build_providers(claude: EventsReader) -> Vec<Box<dyn AgentStatusProvider>> {
+    let mut providers: Vec<Box<dyn AgentStatusProvider>> = vec![Box::new(claude)];
+    if let Some(dir) = codex_sessions_dir() {
+        providers.push(Box::new(CodexProvider::new(dir)));
+    }
+    if let Some(file) = generic_status_file() {
+        providers.push(Box::new(EventsReader::tail(&file.agent, PathBuf::from(file.path))));
+    }
+    providers
+}
+
+/// ウォッチャーが最後に計算した状態
+pub fn latest_statuses() -> ClaudeStatusPayload {
+    LATEST_PAYLOAD
+        .lock()
+        .map(|payload| payload.clone())
+        .unwrap_or_default()
+}
+
+/// ウォッチャーに取得元を読み直させ、再計算した状態を返す
+pub fn refresh_statuses() -> Result<ClaudeStatusPayload, String> {
+    let tx = WATCHER_TX
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .clone()
+        .ok_or_else(|| i18n::t("error.watcherNotRunning"))?;
+    let (reply_tx, reply_rx) = mpsc::channel();
+    tx.send(WatcherMessage::Refresh(reply_tx))
+        .map_err(|_| i18n::t("error.watcherStopped"))?;
+    reply_rx
+        .recv_timeout(REFRESH_TIMEOUT)
+        .map_err(|e| format!("Failed to refresh Claude statuses: {}", e))
+}
+
+/// 計算した状態を共有状態に保存し、タブバーに emit する
+fn publish(app_handle: &AppHandle, payload: &ClaudeStatusPayload) {
+    if let Ok(mut latest) = LATEST_PAYLOAD.lock() {
+        *latest = payload.clone();
+    }
+    tab_bars::emit_to_tab_bars(app_handle, "claude-status", payload.clone());
+    crate::tray::apply_claude_status(app_handle, payload);
+}
+
+/// 新しい世代のウォッチャースレッドを起動する（既存のスレッドは終了させる）
+fn spawn_watcher(app_handle: AppHandle) {
+    let events_file = events_file();
+    let generation = WATCHER_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let is_current = move || {
+        STATUS_WATCHER_RUNNING.load(Ordering::SeqCst)
+            && WATCHER_GENERATION.load(Ordering::SeqCst) == generation
+    };
+
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut watcher_tx) = WATCHER_TX.lock() {
+        *watcher_tx = Some(tx.clone());
+    }
 
     thread::spawn(move || {
-        let mut current_statuses: HashMap<String, ClaudeStatus> = HashMap::new();
-        let mut last_offset: u64 = 0;
-
-        while STATUS_WATCHER_RUNNING.load(Ordering::SeqCst) {
-            let path = Path::new(CLAUDE_EVENTS_FILE);
-
-            if let Ok(metadata) = fs::metadata(path) {
-                let file_size = metadata.len();
-
-                // ファイルが切り詰められた場合はリセット
-                if file_size < last_offset {
-                    last_offset = 0;
-                    current_statuses.clear();
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let payload = ClaudeStatusPayload {
-                            statuses: current_statuses.clone(),
-                        };
-                        let _ = window.emit("claude-status", payload);
-                    }
+        // ユーザーが指定したファイルでもあるので削除せず、切り詰めて際限なく伸びないようにする
+        let mut providers = build_providers(EventsReader::truncate(CLAUDE_AGENT, events_file));
+        for provider in providers.iter_mut() {
+            provider.refresh();
+        }
+        // 再起動時は前の設定での状態を置き換える
+        let mut last = ClaudeStatusPayload::from_providers(&providers);
+        publish(&app_handle, &last);
+
+        let dirs: Vec<_> = providers.iter().filter_map(|p| p.watch_dir()).collect();
+        log::debug!("Watching agent status directories: {:?}", dirs);
+        // watcher は drop すると監視が止まるのでループ中は保持する
+        let watcher = watch_dirs(&dirs, tx);
+        // FSEvents の取りこぼしに備えた再読込の間隔は活動状況に合わせる
+        let tick = || {
+            if watcher.is_some() {
+                polling::interval()
+            } else {
+                POLL_INTERVAL
+            }
+        };
+
+        while is_current() {
+            // 通知に関係する取得元だけを読み直す（tick と再計算要求ではすべて）
+            let (event, reply) = match rx.recv_timeout(tick()) {
+                Ok(WatcherMessage::Fs(Ok(event))) => (Some(event), None),
+                Ok(WatcherMessage::Fs(Err(_))) | Err(RecvTimeoutError::Timeout) => (None, None),
+                Ok(WatcherMessage::Refresh(reply)) => (None, Some(reply)),
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(tick());
+                    (None, None)
                 }
+            };
 
-                // 新しいデータがある場合のみ処理
-                if file_size > last_offset {
-                    if let Ok(mut file) = File::open(path) {
-                        if file.seek(SeekFrom::Start(last_offset)).is_ok() {
-                            let reader = BufReader::new(file);
-
-                            for line in reader.lines().map_while(Result::ok) {
-                                let changed = apply_line(&line, &mut current_statuses);
-                                if changed {
-                                    if let Some(window) =
-                                        app_handle.get_webview_window("main")
-                                    {
-                                        let payload = ClaudeStatusPayload {
-                                            statuses: current_statuses.clone(),
-                                        };
-                                        let _ = window.emit("claude-status", &payload);
-                                    }
-                                }
-                            }
-                        }
-                        last_offset = file_size;
-                    }
+            if !is_current() {
+                break;
+            }
+            let mut changed = false;
+            for provider in providers.iter_mut() {
+                if event.as_ref().is_none_or(|event| provider.is_relevant(event)) {
+                    changed |= provider.refresh();
                 }
-            } else {
-                // ファイルが消えた場合
-                if !current_statuses.is_empty() {
-                    current_statuses.clear();
-                    if let Some(window) = app_handle.get_webview_window("main") {
-                        let payload = ClaudeStatusPayload {
-                            statuses: current_statuses.clone(),
-                        };
-                        let _ = window.emit("claude-status", payload);
-                    }
+            }
+            if changed {
+                let payload = ClaudeStatusPayload::from_providers(&providers);
+                log::debug!("Agent statuses changed: {:?}", payload.statuses);
+                notify_long_generations(&last, &payload);
+                // 応答済みになった「待機中」の通知を通知センターに残さない
+                for path in left_waiting(&last, &payload) {
+                    notification::clear_notifications_for_project(path);
                 }
-                last_offset = 0;
+                publish(&app_handle, &payload);
+                last = payload;
+            }
+            if let Some(reply) = reply {
+                let _ = reply.send(last.clone());
             }
-
-            thread::sleep(Duration::from_millis(300));
         }
     });
 }
 
+/// ウォッチャーが動いているか
+pub fn is_status_watcher_running() -> bool {
+    STATUS_WATCHER_RUNNING.load(Ordering::SeqCst)
+}
+
 /// 状態監視ウォッチャーを停止
-#[allow(dead_code)]
 pub fn stop_claude_status_watcher() {
     STATUS_WATCHER_RUNNING.store(false, Ordering::SeqCst);
+    if let Ok(mut watcher_tx) = WATCHER_TX.lock() {
+        *watcher_tx = None;
+    }
+    power::watcher_stopped(power::Watcher::Status);
 }
 
 #[cfg(test)]
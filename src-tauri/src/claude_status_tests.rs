@@ -1,12 +1,17 @@
 use super::*;
 
+fn status_of(statuses: &HashMap<SessionKey, SessionStatus>, project: &str) -> Option<ClaudeStatus> {
+    let sessions: Vec<SessionStatus> = statuses.values().cloned().collect();
+    aggregate(&sessions).get(project).cloned()
+}
+
 #[test]
 fn generating_status() {
     let mut statuses = HashMap::new();
     assert!(apply_line("g /path/to/project", &mut statuses));
     assert_eq!(
-        statuses.get("/path/to/project"),
-        Some(&ClaudeStatus::Generating)
+        status_of(&statuses, "/path/to/project"),
+        Some(ClaudeStatus::Generating)
     );
 }
 
@@ -15,11 +20,35 @@ fn waiting_status() {
     let mut statuses = HashMap::new();
     assert!(apply_line("w /path/to/project", &mut statuses));
     assert_eq!(
-        statuses.get("/path/to/project"),
-        Some(&ClaudeStatus::Waiting)
+        status_of(&statuses, "/path/to/project"),
+        Some(ClaudeStatus::Waiting)
     );
 }
 
+#[test]
+fn error_status() {
+    let mut statuses = HashMap::new();
+    apply_line("g /path/to/project", &mut statuses);
+    assert!(apply_line("e /path/to/project", &mut statuses));
+    assert_eq!(status_of(&statuses, "/path/to/project"), Some(ClaudeStatus::Error));
+    // 同じ状態の再送は変化なし
+    assert!(!apply_line("e /path/to/project", &mut statuses));
+}
+
+#[test]
+fn complete_clears_error() {
+    let mut statuses = HashMap::new();
+    apply_line("e /path/to/project", &mut statuses);
+    assert!(apply_line("c /path/to/project", &mut statuses));
+    assert!(statuses.is_empty());
+}
+
+#[test]
+fn error_serializes_distinctly_from_waiting() {
+    assert_eq!(serde_json::to_string(&ClaudeStatus::Error).unwrap(), "\"error\"");
+    assert_eq!(serde_json::to_string(&ClaudeStatus::Waiting).unwrap(), "\"waiting\"");
+}
+
 #[test]
 fn complete_removes_entry() {
     let mut statuses = HashMap::new();
@@ -47,8 +76,8 @@ fn status_transition_generating_to_waiting() {
     apply_line("g /path/to/project", &mut statuses);
     assert!(apply_line("w /path/to/project", &mut statuses));
     assert_eq!(
-        statuses.get("/path/to/project"),
-        Some(&ClaudeStatus::Waiting)
+        status_of(&statuses, "/path/to/project"),
+        Some(ClaudeStatus::Waiting)
     );
 }
 
@@ -59,10 +88,10 @@ fn multiple_projects() {
     apply_line("w /project-b", &mut statuses);
     assert_eq!(statuses.len(), 2);
     assert_eq!(
-        statuses.get("/project-a"),
-        Some(&ClaudeStatus::Generating)
+        status_of(&statuses, "/project-a"),
+        Some(ClaudeStatus::Generating)
     );
-    assert_eq!(statuses.get("/project-b"), Some(&ClaudeStatus::Waiting));
+    assert_eq!(status_of(&statuses, "/project-b"), Some(ClaudeStatus::Waiting));
 }
 
 #[test]
@@ -70,8 +99,8 @@ fn trailing_slash_stripped() {
     let mut statuses = HashMap::new();
     apply_line("g /path/to/project/", &mut statuses);
     assert_eq!(
-        statuses.get("/path/to/project"),
-        Some(&ClaudeStatus::Generating)
+        status_of(&statuses, "/path/to/project"),
+        Some(ClaudeStatus::Generating)
     );
 }
 
@@ -94,3 +123,568 @@ fn empty_project_ignored() {
     let mut statuses = HashMap::new();
     assert!(!apply_line("g ", &mut statuses));
 }
+
+#[test]
+fn sessions_in_same_project_are_tracked_separately() {
+    let mut statuses = HashMap::new();
+    assert!(apply_line("g /repo\tsession-a", &mut statuses));
+    assert!(apply_line("w /repo\tsession-b", &mut statuses));
+    assert_eq!(statuses.len(), 2);
+
+    // 集約マップでは Generating が Waiting に隠れない
+    assert_eq!(status_of(&statuses, "/repo"), Some(ClaudeStatus::Generating));
+
+    let payload = ClaudeStatusPayload::from_sessions(statuses.values().cloned().collect());
+    let summary: Vec<_> = payload
+        .sessions
+        .iter()
+        .map(|s| (s.session_id.as_str(), s.status.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("session-a", ClaudeStatus::Generating),
+            ("session-b", ClaudeStatus::Waiting),
+        ]
+    );
+}
+
+#[test]
+fn complete_with_session_clears_only_that_session() {
+    let mut statuses = HashMap::new();
+    apply_line("g /repo\tsession-a", &mut statuses);
+    apply_line("g /repo\tsession-b", &mut statuses);
+    assert!(apply_line("c /repo\tsession-a", &mut statuses));
+    assert_eq!(statuses.len(), 1);
+    assert!(statuses.contains_key(&("/repo".to_string(), "session-b".to_string())));
+}
+
+#[test]
+fn complete_without_session_clears_whole_project() {
+    let mut statuses = HashMap::new();
+    apply_line("g /repo\tsession-a", &mut statuses);
+    apply_line("w /repo", &mut statuses);
+    apply_line("g /other\tsession-c", &mut statuses);
+    assert!(apply_line("c /repo", &mut statuses));
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(status_of(&statuses, "/other"), Some(ClaudeStatus::Generating));
+}
+
+#[test]
+fn path_with_spaces_and_session() {
+    let mut statuses = HashMap::new();
+    apply_line("g /Users/me/My Project/\tabc", &mut statuses);
+    let session = &statuses[&("/Users/me/My Project".to_string(), "abc".to_string())];
+    assert_eq!(session.status, ClaudeStatus::Generating);
+    assert!(session.last_update > 0);
+}
+
+#[test]
+fn since_is_kept_for_repeated_status_and_reset_on_transition() {
+    let mut statuses = HashMap::new();
+    apply_line("g /repo\ts", &mut statuses);
+    let key = ("/repo".to_string(), "s".to_string());
+    let started = statuses[&key].since;
+
+    // PostToolUse などで g が繰り返し届いても開始時刻は変わらない
+    statuses.get_mut(&key).unwrap().since = started - 5_000;
+    assert!(!apply_line("g /repo\ts", &mut statuses));
+    assert_eq!(statuses[&key].since, started - 5_000);
+    assert!(statuses[&key].last_update >= started);
+
+    assert!(apply_line("w /repo\ts", &mut statuses));
+    assert!(statuses[&key].since >= started);
+}
+
+#[test]
+fn aggregated_since_uses_earliest_matching_session() {
+    let mut statuses = HashMap::new();
+    apply_line("g /repo\ta", &mut statuses);
+    apply_line("g /repo\tb", &mut statuses);
+    apply_line("w /repo\tc", &mut statuses);
+    statuses.get_mut(&("/repo".to_string(), "a".to_string())).unwrap().since = 1_000;
+    statuses.get_mut(&("/repo".to_string(), "b".to_string())).unwrap().since = 2_000;
+    statuses.get_mut(&("/repo".to_string(), "c".to_string())).unwrap().since = 500;
+
+    let payload = ClaudeStatusPayload::from_sessions(statuses.values().cloned().collect());
+    // 集約状態は Generating なので、Waiting のセッション c は無視する
+    assert_eq!(payload.since.get("/repo"), Some(&1_000));
+}
+
+#[test]
+fn events_file_changes_are_detected_through_private_tmp() {
+    let event = notify::Event::new(notify::EventKind::Any)
+        .add_path("/private/tmp/claude-code-events".into());
+    assert!(is_events_file_event(&event, Path::new(DEFAULT_EVENTS_FILE)));
+
+    let other = notify::Event::new(notify::EventKind::Any).add_path("/private/tmp/other".into());
+    assert!(!is_events_file_event(&other, Path::new(DEFAULT_EVENTS_FILE)));
+}
+
+#[test]
+fn reader_applies_complete_lines_only() {
+    let mut reader = EventsReader::default();
+    assert!(!reader.feed(b"g /repo"));
+    assert!(reader.sessions.is_empty());
+
+    // 行の残りが届いた時点で適用される
+    assert!(reader.feed(b"-a\nw /repo-b\n"));
+    assert_eq!(status_of(&reader.sessions, "/repo-a"), Some(ClaudeStatus::Generating));
+    assert_eq!(status_of(&reader.sessions, "/repo-b"), Some(ClaudeStatus::Waiting));
+    assert!(reader.pending.is_empty());
+}
+
+#[test]
+fn reader_handles_chunked_appends() {
+    let log = b"g /repo\tsession-a\ng /repo\tsession-b\nw /repo\tsession-a\nc /repo\tsession-b\n";
+
+    for chunk_size in [1, 3, 7, 64] {
+        let mut reader = EventsReader::default();
+        for chunk in log.chunks(chunk_size) {
+            reader.feed(chunk);
+        }
+        assert_eq!(reader.sessions.len(), 1, "chunk size {}", chunk_size);
+        assert_eq!(status_of(&reader.sessions, "/repo"), Some(ClaudeStatus::Waiting));
+    }
+}
+
+#[test]
+fn reader_reports_no_change_for_repeated_lines() {
+    let mut reader = EventsReader::default();
+    assert!(reader.feed(b"g /repo\n"));
+    assert!(!reader.feed(b"g /repo\ng /repo\n"));
+}
+
+#[test]
+fn reader_reset_drops_pending_line() {
+    let mut reader = EventsReader::default();
+    reader.feed(b"g /repo\nw /re");
+    reader.last_offset = 14;
+    reader.reset();
+    assert!(reader.sessions.is_empty());
+    assert!(reader.pending.is_empty());
+    assert_eq!(reader.last_offset, 0);
+}
+
+#[test]
+fn reader_handles_lines_longer_than_a_read_chunk() {
+    let long_session = "x".repeat(100 * 1024);
+    let log = format!("g /repo\t{}\nw /other\n", long_session);
+
+    let mut reader = EventsReader::default();
+    for chunk in log.as_bytes().chunks(64 * 1024) {
+        reader.feed(chunk);
+    }
+    assert_eq!(reader.sessions.len(), 2);
+    assert!(reader
+        .sessions
+        .contains_key(&("/repo".to_string(), long_session.clone())));
+}
+
+#[test]
+fn reader_reads_appended_data_in_chunks() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("status");
+    let long_session = "x".repeat(READ_CHUNK_SIZE * 2 + 10);
+    fs::write(&path, format!("g /repo\t{}\nw /other\n", long_session)).unwrap();
+
+    let mut reader = EventsReader::new("aider", path.clone());
+    assert!(reader.refresh());
+    assert_eq!(reader.sessions.len(), 2);
+    assert_eq!(reader.last_offset, fs::metadata(&path).unwrap().len());
+}
+
+#[test]
+fn reader_drops_unterminated_lines_past_the_cap() {
+    let mut reader = EventsReader::default();
+    let endless = vec![b'x'; 1024 * 1024];
+    for _ in 0..5 {
+        reader.feed(&endless);
+    }
+    assert!(reader.pending.len() <= MAX_PENDING_LINE);
+
+    // 捨てた行の残りは読み飛ばされ、次の行から読める
+    assert!(reader.feed(b"xxx\ng /repo\n"));
+    assert_eq!(status_of(&reader.sessions, "/repo"), Some(ClaudeStatus::Generating));
+}
+
+#[test]
+fn reader_flushes_final_line_without_newline() {
+    let mut reader = EventsReader::default();
+    assert!(reader.feed(b"g /repo\nw /repo"));
+    assert_eq!(status_of(&reader.sessions, "/repo"), Some(ClaudeStatus::Generating));
+
+    assert!(reader.flush_pending());
+    assert_eq!(status_of(&reader.sessions, "/repo"), Some(ClaudeStatus::Waiting));
+    assert!(!reader.flush_pending());
+}
+
+#[test]
+fn events_file_setting_is_validated() {
+    let tmp = tempfile::tempdir().unwrap();
+
+    assert!(validate_events_file(&tmp.path().join("claude-events")).is_ok());
+    assert!(validate_events_file(Path::new("relative/claude-events")).is_err());
+    // ディレクトリそのものは指定できない
+    assert!(validate_events_file(tmp.path()).is_err());
+    // 親ディレクトリが存在しない
+    assert!(validate_events_file(&tmp.path().join("missing/claude-events")).is_err());
+}
+
+#[test]
+fn only_watched_file_settings_restart_the_watcher() {
+    assert!(affects_watcher(["settings:debugLogging", EVENTS_FILE_KEY].into_iter()));
+    assert!(affects_watcher([GENERIC_STATUS_FILE_KEY].into_iter()));
+    // 長時間生成の通知設定はウォッチャーを止めずに反映される
+    assert!(!affects_watcher([LONG_GENERATION_KEY, "settings:autohide"].into_iter()));
+}
+
+#[test]
+fn json_line_carries_message() {
+    let mut statuses = HashMap::new();
+    let line = r#"{"status":"waiting","cwd":"/repo/","session_id":"abc","message":"Claude needs your permission to use Bash","ts":1700000000000}"#;
+    assert!(apply_line(line, &mut statuses));
+
+    let session = &statuses[&("/repo".to_string(), "abc".to_string())];
+    assert_eq!(session.status, ClaudeStatus::Waiting);
+    assert_eq!(
+        session.message.as_deref(),
+        Some("Claude needs your permission to use Bash")
+    );
+    assert_eq!(session.last_update, 1_700_000_000_000);
+}
+
+#[test]
+fn message_change_is_reported_without_resetting_since() {
+    let mut statuses = HashMap::new();
+    apply_line(r#"{"status":"waiting","cwd":"/repo","message":"first"}"#, &mut statuses);
+    let since = statuses[&("/repo".to_string(), String::new())].since;
+
+    assert!(apply_line(r#"{"status":"waiting","cwd":"/repo","message":"second"}"#, &mut statuses));
+    let session = &statuses[&("/repo".to_string(), String::new())];
+    assert_eq!(session.message.as_deref(), Some("second"));
+    assert_eq!(session.since, since);
+}
+
+#[test]
+fn mixed_plain_and_json_lines() {
+    let mut reader = EventsReader::default();
+    reader.feed(
+        b"g /repo-a\n{\"status\":\"generating\",\"cwd\":\"/repo-b\",\"session_id\":\"s\"}\n{\"status\":\"clear\",\"cwd\":\"/repo-a\"}\n",
+    );
+    assert_eq!(status_of(&reader.sessions, "/repo-a"), None);
+    assert_eq!(
+        status_of(&reader.sessions, "/repo-b"),
+        Some(ClaudeStatus::Generating)
+    );
+}
+
+#[test]
+fn invalid_json_line_ignored() {
+    let mut statuses = HashMap::new();
+    assert!(!apply_line(r#"{"status":"waiting"}"#, &mut statuses));
+    assert!(!apply_line(r#"{"status":"unknown","cwd":"/repo"}"#, &mut statuses));
+    assert!(!apply_line("{not json", &mut statuses));
+    assert!(statuses.is_empty());
+}
+
+fn payload(entries: &[(&str, ClaudeStatus, u64)]) -> ClaudeStatusPayload {
+    let sessions = entries
+        .iter()
+        .map(|(path, status, since)| SessionStatus {
+            agent: CLAUDE_AGENT.to_string(),
+            project_path: path.to_string(),
+            session_id: String::new(),
+            status: status.clone(),
+            message: None,
+            is_subagent: false,
+            since: *since,
+            last_update: *since,
+        })
+        .collect();
+    ClaudeStatusPayload::from_sessions(sessions)
+}
+
+#[test]
+fn finished_generations_detects_waiting_and_cleared_projects() {
+    let before = payload(&[
+        ("/a", ClaudeStatus::Generating, 1_000),
+        ("/b", ClaudeStatus::Generating, 2_000),
+        ("/c", ClaudeStatus::Generating, 3_000),
+        ("/d", ClaudeStatus::Waiting, 0),
+    ]);
+    // /a は Waiting に、/b は消え、/c はまだ生成中
+    let after = payload(&[
+        ("/a", ClaudeStatus::Waiting, 61_000),
+        ("/c", ClaudeStatus::Generating, 3_000),
+    ]);
+
+    assert_eq!(
+        finished_generations(&before, &after, 61_000),
+        vec![
+            ("/a".to_string(), Duration::from_secs(60)),
+            ("/b".to_string(), Duration::from_secs(59)),
+        ]
+    );
+}
+
+#[test]
+fn finished_generations_ignores_errors() {
+    let before = payload(&[("/a", ClaudeStatus::Generating, 0)]);
+    let after = payload(&[("/a", ClaudeStatus::Error, 10_000)]);
+    assert!(finished_generations(&before, &after, 10_000).is_empty());
+}
+
+#[test]
+fn left_waiting_lists_answered_projects() {
+    let before = payload(&[
+        ("/a", ClaudeStatus::Waiting, 0),
+        ("/b", ClaudeStatus::Waiting, 0),
+        ("/c", ClaudeStatus::Waiting, 0),
+    ]);
+    // /a は再び生成中、/b は消え、/c はまだ待機中
+    let after = payload(&[
+        ("/a", ClaudeStatus::Generating, 1_000),
+        ("/c", ClaudeStatus::Waiting, 0),
+    ]);
+    assert_eq!(left_waiting(&before, &after), vec!["/a".to_string(), "/b".to_string()]);
+}
+
+#[test]
+fn elapsed_is_formatted_compactly() {
+    assert_eq!(format_elapsed(Duration::from_secs(42)), "42s");
+    assert_eq!(format_elapsed(Duration::from_secs(134)), "2m 14s");
+    assert_eq!(format_elapsed(Duration::from_secs(3_720)), "1h 2m");
+}
+
+#[test]
+fn active_subagent_keeps_project_generating() {
+    let mut statuses = HashMap::new();
+    // 親セッションが Task ツールを呼んだ後に待機状態になっても、サブエージェントが動いていれば Generating
+    apply_line(r#"{"status":"waiting","cwd":"/repo","session_id":"parent"}"#, &mut statuses);
+    apply_line(
+        r#"{"status":"generating","cwd":"/repo","session_id":"parent","subagent":true,"agent_id":"task-1"}"#,
+        &mut statuses,
+    );
+
+    assert_eq!(statuses.len(), 2);
+    let subagent = &statuses[&("/repo".to_string(), "parent/task-1".to_string())];
+    assert!(subagent.is_subagent);
+    assert_eq!(status_of(&statuses, "/repo"), Some(ClaudeStatus::Generating));
+    // 親セッションの状態は上書きされない
+    assert_eq!(
+        statuses[&("/repo".to_string(), "parent".to_string())].status,
+        ClaudeStatus::Waiting
+    );
+}
+
+#[test]
+fn stale_subagent_expires() {
+    let mut statuses = HashMap::new();
+    apply_line(r#"{"status":"waiting","cwd":"/repo","session_id":"parent","ts":1000}"#, &mut statuses);
+    apply_line(
+        r#"{"status":"generating","cwd":"/repo","session_id":"parent","subagent":true,"ts":1000}"#,
+        &mut statuses,
+    );
+
+    // まだ稼働中
+    assert!(!expire_stale_subagents(&mut statuses, 30_000));
+    assert_eq!(status_of(&statuses, "/repo"), Some(ClaudeStatus::Generating));
+
+    // 更新が途絶えたら破棄される（親セッションは残る）
+    assert!(expire_stale_subagents(&mut statuses, 1_000 + 60_000));
+    assert_eq!(status_of(&statuses, "/repo"), Some(ClaudeStatus::Waiting));
+}
+
+#[test]
+fn reader_tags_sessions_with_its_agent() {
+    let mut reader = EventsReader::new("aider", PathBuf::from("/tmp/aider-status"));
+    assert!(reader.feed(b"g /repo\n"));
+    let statuses = reader.statuses();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].agent, "aider");
+}
+
+#[test]
+fn generic_provider_tails_only_new_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("status");
+    fs::write(&path, "g /stale\n").unwrap();
+
+    let mut reader = EventsReader::tail("aider", path.clone());
+    assert!(!reader.refresh());
+
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    std::io::Write::write_all(&mut file, b"w /repo\n").unwrap();
+    assert!(reader.refresh());
+    let statuses = reader.statuses();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].project_path, "/repo");
+    assert_eq!(statuses[0].status, ClaudeStatus::Waiting);
+}
+
+#[test]
+fn events_written_before_the_watcher_started_are_not_replayed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("claude-code-events");
+    // 生成中に終了したセッションの古い行
+    fs::write(&path, "g /stale\n").unwrap();
+
+    let mut watcher = EventsReader::truncate(CLAUDE_AGENT, path.clone());
+    assert!(!watcher.refresh());
+    assert!(path.exists());
+
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    std::io::Write::write_all(&mut file, b"w /repo\n").unwrap();
+    assert!(watcher.refresh());
+    assert_eq!(watcher.statuses().len(), 1);
+
+    // CLI は先頭から読むが、アプリと同じ状態になる
+    let mut cli = EventsReader::new(CLAUDE_AGENT, path);
+    cli.refresh();
+    let statuses = cli.statuses();
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].project_path, "/repo");
+    assert_eq!(statuses[0].status, ClaudeStatus::Waiting);
+}
+
+const CODEX_META: &str = r#"{"timestamp":"2025-01-01T00:00:00Z","type":"session_meta","payload":{"id":"abc","cwd":"/repo/"}}"#;
+
+#[test]
+fn codex_lines_drive_session_status() {
+    let mut file = CodexSessionFile::default();
+    assert!(!file.apply_line(CODEX_META, 1_000));
+    assert_eq!(file.session(), None);
+
+    assert!(file.apply_line(r#"{"type":"event_msg","payload":{"type":"task_started"}}"#, 2_000));
+    let session = file.session().unwrap();
+    assert_eq!(session.agent, CODEX_AGENT);
+    assert_eq!(session.project_path, "/repo");
+    assert_eq!(session.session_id, "abc");
+    assert_eq!(session.status, ClaudeStatus::Generating);
+    assert_eq!(session.since, 2_000);
+
+    // 状態に関係しない行は無視する
+    assert!(!file.apply_line(r#"{"type":"event_msg","payload":{"type":"token_count"}}"#, 3_000));
+    assert!(!file.apply_line(r#"{"type":"response_item","payload":{"type":"message"}}"#, 3_000));
+
+    assert!(file.apply_line(r#"{"type":"event_msg","payload":{"type":"task_complete"}}"#, 4_000));
+    assert_eq!(file.session().unwrap().status, ClaudeStatus::Waiting);
+    assert!(file.apply_line(r#"{"type":"event_msg","payload":{"type":"turn_aborted"}}"#, 5_000));
+    assert_eq!(file.session().unwrap().status, ClaudeStatus::Error);
+}
+
+#[test]
+fn codex_provider_reads_recent_session_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let day = dir.path().join("2025/01/01");
+    fs::create_dir_all(&day).unwrap();
+    fs::write(
+        day.join("rollout-abc.jsonl"),
+        format!("{}\n{}\n", CODEX_META, r#"{"type":"event_msg","payload":{"type":"task_started"}}"#),
+    )
+    .unwrap();
+    fs::write(day.join("notes.txt"), "ignored").unwrap();
+
+    let mut provider = CodexProvider::new(dir.path().to_path_buf());
+    assert!(provider.refresh());
+    assert!(!provider.refresh());
+
+    let payload = ClaudeStatusPayload::from_sessions(provider.statuses());
+    assert_eq!(payload.statuses.get("/repo"), Some(&ClaudeStatus::Generating));
+    assert_eq!(payload.agents.get("/repo").map(String::as_str), Some(CODEX_AGENT));
+}
+
+#[test]
+fn codex_provider_skips_unchanged_tree() {
+    let dir = tempfile::tempdir().unwrap();
+    let today = dir.path().join("2025/01/02");
+    let old_day = dir.path().join("2025/01/01");
+    fs::create_dir_all(&today).unwrap();
+    fs::create_dir_all(&old_day).unwrap();
+    let active = today.join("rollout-active.jsonl");
+    fs::write(&active, format!("{}\n", CODEX_META)).unwrap();
+    let old = old_day.join("rollout-old.jsonl");
+    fs::write(&old, format!("{}\n", CODEX_META)).unwrap();
+    File::options()
+        .write(true)
+        .open(&old)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(24 * 60 * 60))
+        .unwrap();
+
+    let mut provider = CodexProvider::new(dir.path().to_path_buf());
+    provider.refresh();
+    // sessions, 2025, 01, 01, 02
+    assert_eq!(provider.dir_scans, 5);
+    // 古いセッションファイルは開かない
+    assert_eq!(provider.file_opens, 1);
+
+    // 何も変わっていなければディレクトリもファイルも読み直さない
+    assert!(!provider.refresh());
+    assert_eq!(provider.dir_scans, 5);
+    assert_eq!(provider.file_opens, 1);
+
+    // 追記されたファイルだけを開く（ディレクトリの mtime は変わらない）
+    let mut file = fs::OpenOptions::new().append(true).open(&active).unwrap();
+    std::io::Write::write_all(&mut file, b"{\"type\":\"event_msg\",\"payload\":{\"type\":\"task_started\"}}\n").unwrap();
+    assert!(provider.refresh());
+    assert_eq!(provider.dir_scans, 5);
+    assert_eq!(provider.file_opens, 2);
+
+    // 新しいセッションファイルができたディレクトリだけを読み直す
+    fs::write(today.join("rollout-new.jsonl"), format!("{}\n", CODEX_META)).unwrap();
+    provider.refresh();
+    assert_eq!(provider.dir_scans, 6);
+    assert_eq!(provider.file_opens, 3);
+}
+
+#[test]
+fn codex_provider_finds_resumed_sessions_on_the_full_stat() {
+    let dir = tempfile::tempdir().unwrap();
+    let old_day = dir.path().join("2025/01/01");
+    fs::create_dir_all(&old_day).unwrap();
+    let old = old_day.join("rollout-old.jsonl");
+    fs::write(&old, format!("{}\n", CODEX_META)).unwrap();
+    File::options()
+        .write(true)
+        .open(&old)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(24 * 60 * 60))
+        .unwrap();
+
+    let mut provider = CodexProvider::new(dir.path().to_path_buf());
+    provider.refresh();
+    assert_eq!(provider.file_opens, 0);
+
+    // 再開されたセッションは古いファイルに追記する（ディレクトリの mtime は変わらない）
+    let mut file = fs::OpenOptions::new().append(true).open(&old).unwrap();
+    std::io::Write::write_all(&mut file, b"{\"type\":\"event_msg\",\"payload\":{\"type\":\"task_started\"}}\n").unwrap();
+    assert!(!provider.refresh());
+
+    // 定期的な全件 stat で見つかる
+    provider.last_full_stat = None;
+    assert!(provider.refresh());
+    let payload = ClaudeStatusPayload::from_sessions(provider.statuses());
+    assert_eq!(payload.statuses.get("/repo"), Some(&ClaudeStatus::Generating));
+}
+
+#[test]
+fn merged_payload_reports_agent_of_aggregated_status() {
+    let mut claude = EventsReader::new(CLAUDE_AGENT, PathBuf::new());
+    claude.feed(b"w /repo\n");
+    let mut aider = EventsReader::new("aider", PathBuf::new());
+    aider.feed(b"g /repo\n");
+
+    let providers: Vec<Box<dyn AgentStatusProvider>> = vec![Box::new(claude), Box::new(aider)];
+    let payload = ClaudeStatusPayload::from_providers(&providers);
+    assert_eq!(payload.sessions.len(), 2);
+    assert_eq!(payload.statuses.get("/repo"), Some(&ClaudeStatus::Generating));
+    assert_eq!(payload.agents.get("/repo").map(String::as_str), Some("aider"));
+}
+
+#[test]
+fn refresh_requires_running_watcher() {
+    assert!(refresh_statuses().is_err());
+    assert!(latest_statuses().statuses.is_empty());
+}
@@ -0,0 +1,225 @@
+//! Command-line interface
+//!
+//! `list` and `status` run in the launching process: they print to stdout and
+//! exit before Tauri starts, so they work from scripts whether or not the app
+//! is running. Other commands are for the running app. The single-instance
+//! plugin forwards them there (see instance.rs); when no instance is running,
+//! the launching process becomes the app and handles them after setup.
+
+use crate::settings_store::StoredSettings;
+use crate::{claude_status, editor, project_appearance, window_filter};
+
+pub const USAGE: &str = "\
+Usage: editor-tab-manager [COMMAND]
+
+Commands:
+  list [--json]    Print the open editor windows
+  status [--json]  Print Claude Code statuses by project
+  focus <path>     Focus the project's window, opening it if needed
+  show             Show the settings window
+  help             Print this message
+
+Without a command the app starts (or the running app shows its settings).";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    Help,
+    Show,
+    List { json: bool },
+    Status { json: bool },
+    Focus { path: String },
+}
+
+impl CliCommand {
+    /// Runs in the launching process without starting the app
+    pub fn is_standalone(&self) -> bool {
+        matches!(
+            self,
+            CliCommand::Help | CliCommand::List { .. } | CliCommand::Status { .. }
+        )
+    }
+}
+
+/// Arguments after the executable name that were meant for us
+pub fn user_args(argv: impl IntoIterator<Item = String>) -> Vec<String> {
+    argv.into_iter()
+        .skip(1)
+        // Finder から起動すると古い macOS では -psn_ 引数が付く
+        .filter(|arg| !arg.is_empty() && !arg.starts_with("-psn_"))
+        .collect()
+}
+
+/// Parse user arguments. Ok(None) means no command: just start the app.
+pub fn parse(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Ok(None);
+    };
+    let command = match command.as_str() {
+        "help" | "-h" | "--help" => {
+            no_arguments(command, rest)?;
+            CliCommand::Help
+        }
+        "show" => {
+            no_arguments(command, rest)?;
+            CliCommand::Show
+        }
+        "list" => CliCommand::List {
+            json: json_flag(command, rest)?,
+        },
+        "status" => CliCommand::Status {
+            json: json_flag(command, rest)?,
+        },
+        "focus" => match rest {
+            [path] if !path.starts_with('-') => CliCommand::Focus { path: path.clone() },
+            [] => return Err("focus needs a project path".to_string()),
+            _ => return Err(format!("focus takes one path, got: {}", rest.join(" "))),
+        },
+        other => return Err(format!("Unknown command: {}", other)),
+    };
+    Ok(Some(command))
+}
+
+fn no_arguments(command: &str, rest: &[String]) -> Result<(), String> {
+    match rest {
+        [] => Ok(()),
+        _ => Err(format!("{} takes no arguments, got: {}", command, rest.join(" "))),
+    }
+}
+
+fn json_flag(command: &str, rest: &[String]) -> Result<bool, String> {
+    match rest {
+        [] => Ok(false),
+        [flag] if flag == "--json" => Ok(true),
+        _ => Err(format!("Unexpected arguments for {}: {}", command, rest.join(" "))),
+    }
+}
+
+/// Run a standalone command and return the process exit code
+pub fn run_standalone(command: &CliCommand) -> i32 {
+    // アプリと同じ設定（無効にしたエディタ、タブの色、イベントファイル）で出力する
+    let stored = StoredSettings::from_file(&StoredSettings::app_store_path());
+    let output = match command {
+        CliCommand::Help => Ok(USAGE.to_string()),
+        CliCommand::List { json } => {
+            window_filter::load_settings(&stored);
+            project_appearance::load_settings(&stored);
+            let windows = editor::get_all_editor_windows();
+            if *json {
+                serde_json::to_string_pretty(&windows).map_err(|e| e.to_string())
+            } else {
+                Ok(windows
+                    .iter()
+                    .map(|window| {
+                        format!("{}\t{}\t{}", window.editor_name, window.name, window.path)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+        }
+        CliCommand::Status { json } => {
+            claude_status::load_settings(&stored);
+            let payload = claude_status::collect_statuses();
+            if *json {
+                serde_json::to_string_pretty(&payload).map_err(|e| e.to_string())
+            } else {
+                let mut lines: Vec<String> = payload
+                    .statuses
+                    .iter()
+                    .map(|(path, status)| format!("{}\t{}", status_label(status), path))
+                    .collect();
+                lines.sort();
+                Ok(lines.join("\n"))
+            }
+        }
+        CliCommand::Show | CliCommand::Focus { .. } => {
+            Err("This command is handled by the running app".to_string())
+        }
+    };
+    match output {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("editor-tab-manager: {}", e);
+            1
+        }
+    }
+}
+
+fn status_label(status: &claude_status::ClaudeStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn no_arguments_starts_the_app() {
+        assert_eq!(parse(&[]), Ok(None));
+    }
+
+    #[test]
+    fn commands_and_flags_are_parsed() {
+        assert_eq!(
+            parse(&args(&["list"])),
+            Ok(Some(CliCommand::List { json: false }))
+        );
+        assert_eq!(
+            parse(&args(&["list", "--json"])),
+            Ok(Some(CliCommand::List { json: true }))
+        );
+        assert_eq!(
+            parse(&args(&["status", "--json"])),
+            Ok(Some(CliCommand::Status { json: true }))
+        );
+        assert_eq!(
+            parse(&args(&["focus", "/Users/me/work/api"])),
+            Ok(Some(CliCommand::Focus {
+                path: "/Users/me/work/api".to_string()
+            }))
+        );
+        assert_eq!(parse(&args(&["--help"])), Ok(Some(CliCommand::Help)));
+    }
+
+    #[test]
+    fn bad_arguments_are_rejected() {
+        assert!(parse(&args(&["focus"])).is_err());
+        assert!(parse(&args(&["focus", "a", "b"])).is_err());
+        assert!(parse(&args(&["focus", "--json"])).is_err());
+        assert!(parse(&args(&["list", "--yaml"])).is_err());
+        assert!(parse(&args(&["show", "now"])).is_err());
+        assert_eq!(
+            parse(&args(&["frobnicate"])),
+            Err("Unknown command: frobnicate".to_string())
+        );
+    }
+
+    #[test]
+    fn only_list_status_and_help_skip_the_app() {
+        assert!(CliCommand::List { json: true }.is_standalone());
+        assert!(CliCommand::Status { json: false }.is_standalone());
+        assert!(CliCommand::Help.is_standalone());
+        assert!(!CliCommand::Show.is_standalone());
+        assert!(!CliCommand::Focus {
+            path: "/tmp".to_string()
+        }
+        .is_standalone());
+    }
+
+    #[test]
+    fn executable_name_and_finder_arguments_are_dropped() {
+        let argv = args(&["/Applications/x.app/Contents/MacOS/x", "-psn_0_1", "", "list"]);
+        assert_eq!(user_args(argv), vec!["list".to_string()]);
+    }
+}
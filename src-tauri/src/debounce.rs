@@ -0,0 +1,225 @@
+//! Shared debounce timer
+//!
+//! Debounced handlers (app activation, display and Space changes, AX title
+//! changes, tray rebuilds) used to spawn an OS thread per event just to sleep
+//! for the delay. They now hand their work to one long-lived worker instead.
+//! Scheduling under a key replaces that key's pending task, which keeps the
+//! semantics of the old version counters: only the last event of a burst runs.
+
+use std::collections::HashMap;
+use std::sync::{Condvar, LazyLock, Mutex, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// One independent debouncer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DebounceKey {
+    /// A non-editor app was activated, including its cold-start rechecks
+    OtherApp,
+    /// Display configuration or menu bar auto-hide changed
+    Display,
+    /// The active Space changed
+    Space,
+    /// The tray menu needs rebuilding
+    TrayRebuild,
+    /// Follow-up registry refresh after AX events
+    RegistryRetry,
+    /// Editor storage.json or workspaceStorage changed
+    WorkspaceStorage,
+    /// Window title changes of one editor process (pid)
+    WindowTitle(i32),
+    /// Retry of AX notifications that failed to register for one editor process (pid)
+    ObserverRetry(i32),
+}
+
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Pending tasks, at most one per key
+#[derive(Default)]
+struct Queue {
+    pending: HashMap<DebounceKey, (Instant, Task)>,
+}
+
+impl Queue {
+    fn schedule(&mut self, key: DebounceKey, due: Instant, task: Task) {
+        self.pending.insert(key, (due, task));
+    }
+
+    fn cancel(&mut self, key: DebounceKey) {
+        self.pending.remove(&key);
+    }
+
+    fn next_due(&self) -> Option<Instant> {
+        self.pending.values().map(|(due, _)| *due).min()
+    }
+
+    /// Remove every task due at `now`, earliest deadline first
+    fn take_due(&mut self, now: Instant) -> Vec<Task> {
+        let mut due_keys: Vec<(Instant, DebounceKey)> = self
+            .pending
+            .iter()
+            .filter(|(_, (due, _))| *due <= now)
+            .map(|(key, (due, _))| (*due, *key))
+            .collect();
+        due_keys.sort_by_key(|(due, _)| *due);
+        due_keys
+            .into_iter()
+            .filter_map(|(_, key)| self.pending.remove(&key))
+            .map(|(_, task)| task)
+            .collect()
+    }
+}
+
+struct Timer {
+    queue: Mutex<Queue>,
+    wake: Condvar,
+}
+
+static TIMER: LazyLock<Timer> = LazyLock::new(|| Timer {
+    queue: Mutex::new(Queue::default()),
+    wake: Condvar::new(),
+});
+
+static WORKER: Once = Once::new();
+
+/// Run `task` after `delay` unless the same key is scheduled or cancelled again first.
+/// Tasks run on the shared worker thread, so anything slow or main-thread bound
+/// should hop to the main thread itself.
+pub fn schedule(key: DebounceKey, delay: Duration, task: impl FnOnce() + Send + 'static) {
+    WORKER.call_once(|| {
+        thread::spawn(run_worker);
+    });
+    if let Ok(mut queue) = TIMER.queue.lock() {
+        queue.schedule(key, Instant::now() + delay, Box::new(task));
+        TIMER.wake.notify_one();
+    }
+}
+
+/// Drop the key's pending task, if any
+pub fn cancel(key: DebounceKey) {
+    if let Ok(mut queue) = TIMER.queue.lock() {
+        queue.cancel(key);
+    }
+}
+
+fn run_worker() {
+    let Ok(mut queue) = TIMER.queue.lock() else {
+        return;
+    };
+    loop {
+        let now = Instant::now();
+        let due = queue.take_due(now);
+        if !due.is_empty() {
+            // タスクはロックを外して実行する（タスク内から schedule できるように）
+            drop(queue);
+            for task in due {
+                task();
+            }
+            queue = match TIMER.queue.lock() {
+                Ok(queue) => queue,
+                Err(_) => return,
+            };
+            continue;
+        }
+        queue = match queue.next_due() {
+            Some(next) => {
+                let timeout = next.saturating_duration_since(now);
+                match TIMER.wake.wait_timeout(queue, timeout) {
+                    Ok((queue, _)) => queue,
+                    Err(_) => return,
+                }
+            }
+            None => match TIMER.wake.wait(queue) {
+                Ok(queue) => queue,
+                Err(_) => return,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Arc};
+
+    fn recorder() -> (Arc<Mutex<Vec<&'static str>>>, impl Fn(&'static str) -> Task) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let log_for_tasks = Arc::clone(&log);
+        let task = move |name: &'static str| -> Task {
+            let log = Arc::clone(&log_for_tasks);
+            Box::new(move || log.lock().unwrap().push(name))
+        };
+        (log, task)
+    }
+
+    fn run(tasks: Vec<Task>) {
+        for task in tasks {
+            task();
+        }
+    }
+
+    #[test]
+    fn rapid_schedules_run_only_the_last() {
+        let (log, task) = recorder();
+        let start = Instant::now();
+        let mut queue = Queue::default();
+        queue.schedule(DebounceKey::OtherApp, start + ms(150), task("first"));
+        queue.schedule(DebounceKey::OtherApp, start + ms(200), task("second"));
+        queue.schedule(DebounceKey::OtherApp, start + ms(250), task("third"));
+
+        // The first deadline has passed, but it was superseded
+        run(queue.take_due(start + ms(160)));
+        assert!(log.lock().unwrap().is_empty());
+
+        run(queue.take_due(start + ms(250)));
+        assert_eq!(*log.lock().unwrap(), vec!["third"]);
+        assert!(queue.next_due().is_none());
+    }
+
+    #[test]
+    fn cancel_drops_pending_and_later_schedule_still_runs() {
+        let (log, task) = recorder();
+        let start = Instant::now();
+        let mut queue = Queue::default();
+        queue.schedule(DebounceKey::OtherApp, start + ms(150), task("stale"));
+        queue.cancel(DebounceKey::OtherApp);
+        run(queue.take_due(start + ms(200)));
+        assert!(log.lock().unwrap().is_empty());
+
+        queue.schedule(DebounceKey::OtherApp, start + ms(300), task("fresh"));
+        run(queue.take_due(start + ms(300)));
+        assert_eq!(*log.lock().unwrap(), vec!["fresh"]);
+    }
+
+    #[test]
+    fn keys_are_independent_and_run_in_deadline_order() {
+        let (log, task) = recorder();
+        let start = Instant::now();
+        let mut queue = Queue::default();
+        queue.schedule(DebounceKey::Display, start + ms(300), task("display"));
+        queue.schedule(DebounceKey::WindowTitle(1), start + ms(200), task("title 1"));
+        queue.schedule(DebounceKey::WindowTitle(2), start + ms(100), task("title 2"));
+        queue.cancel(DebounceKey::WindowTitle(1));
+
+        assert_eq!(queue.next_due(), Some(start + ms(100)));
+        run(queue.take_due(start + ms(300)));
+        assert_eq!(*log.lock().unwrap(), vec!["title 2", "display"]);
+    }
+
+    #[test]
+    fn worker_runs_the_last_of_a_burst() {
+        let (tx, rx) = mpsc::channel();
+        for i in 0..5 {
+            let tx = tx.clone();
+            schedule(DebounceKey::WindowTitle(-1), ms(20), move || {
+                let _ = tx.send(i);
+            });
+        }
+        assert_eq!(rx.recv_timeout(Duration::from_secs(2)), Ok(4));
+        assert!(rx.recv_timeout(ms(100)).is_err());
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+}
@@ -0,0 +1,97 @@
+//! `editortabs://` URLs
+//!
+//! Other apps can link to a project with
+//! `editortabs://focus?path=/Users/me/work/api&editor=cursor`, or open the
+//! settings with `editortabs://settings`. The scheme is registered through
+//! tauri-plugin-deep-link (the bundler writes it to Info.plist from
+//! tauri.conf.json). URLs become the same `InstanceMessage`s that later
+//! launches forward, so both paths share one handler.
+
+use crate::editor_config::get_editor_by_id;
+use crate::instance::{self, InstanceMessage};
+use std::path::Path;
+use tauri::{AppHandle, Url};
+
+pub const SCHEME: &str = "editortabs";
+
+/// Parse and validate an `editortabs://` URL
+pub fn parse_url(url: &Url) -> Result<InstanceMessage, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Not an {} URL", SCHEME));
+    }
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    match url.host_str().unwrap_or_default() {
+        "settings" => Ok(InstanceMessage::Show),
+        "focus" => {
+            let path = query("path")
+                .filter(|path| !path.is_empty())
+                .ok_or_else(|| "focus needs a path parameter".to_string())?;
+            if !Path::new(&path).is_absolute() {
+                return Err(format!("Project path must be absolute: {}", path));
+            }
+            let editor = query("editor").filter(|editor| !editor.is_empty());
+            if let Some(editor) = &editor {
+                get_editor_by_id(editor).ok_or_else(|| format!("Unknown editor: {}", editor))?;
+            }
+            Ok(InstanceMessage::Focus { path, editor })
+        }
+        "" => Err("Missing action".to_string()),
+        other => Err(format!("Unknown action: {}", other)),
+    }
+}
+
+/// Handle URLs the app was opened with. Bad URLs are logged and skipped.
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        match parse_url(&url) {
+            Ok(message) => instance::dispatch(app, message),
+            Err(e) => log::warn!("Ignoring URL {}: {}", url, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<InstanceMessage, String> {
+        parse_url(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn focus_urls_carry_the_path_and_editor() {
+        assert_eq!(
+            parse("editortabs://focus?path=/Users/me/work/api&editor=cursor"),
+            Ok(InstanceMessage::Focus {
+                path: "/Users/me/work/api".to_string(),
+                editor: Some("cursor".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("editortabs://focus?path=%2FUsers%2Fme%2Fmy%20app"),
+            Ok(InstanceMessage::Focus {
+                path: "/Users/me/my app".to_string(),
+                editor: None,
+            })
+        );
+    }
+
+    #[test]
+    fn settings_url_shows_the_settings() {
+        assert_eq!(parse("editortabs://settings"), Ok(InstanceMessage::Show));
+    }
+
+    #[test]
+    fn malformed_urls_are_rejected() {
+        assert!(parse("editortabs://focus").is_err());
+        assert!(parse("editortabs://focus?path=work/api").is_err());
+        assert!(parse("editortabs://focus?path=/work/api&editor=emacs").is_err());
+        assert!(parse("editortabs://delete?path=/work/api").is_err());
+        assert!(parse("editortabs:focus").is_err());
+        assert!(parse("https://focus?path=/work/api").is_err());
+    }
+}
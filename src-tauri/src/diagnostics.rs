@@ -0,0 +1,49 @@
+//! Runtime state for troubleshooting
+//!
+//! `get_diagnostics` reports what the background machinery is doing right
+//! now, so it can be pasted into a bug report. Each subsystem contributes its
+//! own section.
+
+use crate::polling::{self, PollingTier};
+use crate::power::{self, PowerState};
+use crate::settings_window::{self, ActivationPolicy};
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// AX notification registration of one editor process
+#[derive(Debug, Clone, Serialize)]
+pub struct ObserverStatus {
+    pub pid: i32,
+    pub bundle_id: String,
+    pub registered: Vec<String>,
+    /// Refused by the editor; retried with backoff
+    pub failed: Vec<String>,
+    /// Window created/destroyed never registered, so the editor is polled
+    pub degraded: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub app_version: String,
+    /// App Nap prevention
+    pub power: PowerState,
+    pub polling_tier: PollingTier,
+    pub polling_interval_ms: u64,
+    pub ax_observers: Vec<ObserverStatus>,
+    /// `regular` while the settings window is open
+    pub activation_policy: ActivationPolicy,
+}
+
+/// Tauri command: current runtime state of the watchers
+#[tauri::command]
+pub fn get_diagnostics(app: AppHandle) -> Diagnostics {
+    let tier = polling::current_tier();
+    Diagnostics {
+        app_version: app.package_info().version.to_string(),
+        power: power::state(),
+        polling_tier: tier,
+        polling_interval_ms: tier.interval().as_millis() as u64,
+        ax_observers: crate::ax_observer::observer_statuses(),
+        activation_policy: settings_window::activation_policy(),
+    }
+}
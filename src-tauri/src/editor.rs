@@ -1,21 +1,55 @@
 use crate::ax_helper;
-use crate::editor_config::{EditorConfig, EDITORS};
-use crate::editor_model::{EditorSession, NativeEditorWindow};
+use crate::debounce::{self, DebounceKey};
+use crate::editor_config::{enabled_editors, EditorConfig, EDITORS};
+use crate::editor_model::{EditorSession, FocusOutcome, NativeEditorWindow};
+use crate::window_filter;
+use crate::workspace_history;
+use crate::workspace_uri;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant, SystemTime};
 
-pub use crate::editor_model::{EditorState, EditorWindow, WorkspaceResolution};
+pub use crate::editor_model::{
+    EditorState, EditorWindow, PathSource, ProjectAppearance, RunningEditor, WorkspaceKind,
+    WorkspaceResolution,
+};
 
 type WindowPathCacheKey = (String, u32, String);
 type WorkspacePathOwnerKey = (String, PathBuf);
+type WorkspaceStateCache = HashMap<String, (SystemTime, OpenWorkspaceState)>;
 
 lazy_static::lazy_static! {
     /// Editor ID + window ID + project name -> full path
-    static ref WINDOW_PATH_CACHE: std::sync::Mutex<HashMap<WindowPathCacheKey, PathBuf>> =
+    /// Read-mostly and shared by the window resolver threads
+    static ref WINDOW_PATH_CACHE: std::sync::RwLock<HashMap<WindowPathCacheKey, PathBuf>> =
+        std::sync::RwLock::new(HashMap::new());
+    /// Cache entries learned from AXDocument; kept across workspace re-scans
+    static ref DOCUMENT_PATH_KEYS: std::sync::Mutex<HashSet<WindowPathCacheKey>> =
+        std::sync::Mutex::new(HashSet::new());
+    /// Editor ID -> parsed storage.json, reused until the file's mtime changes
+    static ref WORKSPACE_STATE_CACHE: std::sync::Mutex<WorkspaceStateCache> =
         std::sync::Mutex::new(HashMap::new());
+    /// Keeps the workspace storage watcher alive
+    static ref WORKSPACE_STORAGE_WATCHER: std::sync::Mutex<Option<notify::RecommendedWatcher>> =
+        std::sync::Mutex::new(None);
 }
 
-#[derive(Default)]
+/// Storage writes come in bursts when windows open or close
+const WORKSPACE_STORAGE_DEBOUNCE_MS: u64 = 500;
+
+/// Windows resolved in parallel (path lookup, git branch and repository)
+const RESOLVE_WORKERS: usize = 8;
+
+/// Windows not resolved by then are listed without a path
+const RESOLVE_DEADLINE: Duration = Duration::from_millis(1500);
+
+/// The startup pre-warm is still parsing workspace state
+static WORKSPACE_PREWARM_PENDING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Default, Clone)]
 struct OpenWorkspaceState {
     is_available: bool,
     active_path: Option<PathBuf>,
@@ -26,7 +60,7 @@ struct OpenWorkspaceState {
 /// Get editor state for any running editor
 /// Prioritizes the frontmost editor application
 pub fn get_any_editor_state() -> EditorState {
-    let editor_bundle_ids: Vec<&str> = EDITORS.iter().map(|e| e.bundle_id).collect();
+    let editor_bundle_ids: Vec<&str> = enabled_editors().map(|e| e.bundle_id).collect();
 
     // 最前面のエディタを特定
     if let Some(frontmost_bid) = ax_helper::get_frontmost_editor_bundle_id(&editor_bundle_ids) {
@@ -39,24 +73,24 @@ pub fn get_any_editor_state() -> EditorState {
     }
 
     // フォールバック: 最前面がエディタでない場合（Tab Managerやその他アプリ）
-    // ウィンドウを持つ最初のエディタを返す
-    for editor in EDITORS {
+    // ウィンドウを持つ最初のエディタを返す（なければウィンドウ0枚で起動中のエディタ）
+    let mut running_without_windows = None;
+    for editor in enabled_editors() {
         let state = get_editor_state_with_config(editor);
         if !state.windows.is_empty() {
             return state;
         }
+        if state.is_running && running_without_windows.is_none() {
+            running_without_windows = Some(state);
+        }
     }
 
-    EditorState {
-        is_active: false,
-        windows: vec![],
-        active_index: None,
-    }
+    running_without_windows.unwrap_or_else(|| EditorState::not_running(false))
 }
 
 /// Get windows from any running editor (tries each editor in order)
 pub fn get_any_editor_windows() -> Vec<EditorWindow> {
-    for editor in EDITORS {
+    for editor in enabled_editors() {
         let windows = get_editor_windows_with_config(editor);
         if !windows.is_empty() {
             return windows;
@@ -71,7 +105,7 @@ pub fn get_editor_state(bundle_id: &str) -> EditorState {
 
     let config = match config {
         Some(c) => c,
-        None => return EditorState { is_active: false, windows: vec![], active_index: None },
+        None => return EditorState::not_running(false),
     };
 
     get_editor_state_with_config(config)
@@ -81,25 +115,29 @@ pub fn get_editor_state(bundle_id: &str) -> EditorState {
 pub fn get_editor_state_with_config(config: &EditorConfig) -> EditorState {
     let is_active = is_editor_active();
 
-    let pid = match ax_helper::get_pid_by_bundle_id(config.bundle_id) {
-        Some(pid) => pid,
-        None => return EditorState { is_active, windows: vec![], active_index: None },
+    let pids = ax_helper::get_pids_by_bundle_id(config.bundle_id);
+    let Some(&pid) = pids.first() else {
+        return EditorState::not_running(is_active);
     };
 
-    let (windows, active_id) = match collect_editor_windows(config, pid) {
-        Ok(result) => result,
-        Err(_) => return EditorState { is_active, windows: vec![], active_index: None },
-    };
+    // Running but windowless (or AX failed) is still reported as running
+    let (windows, active_id) = collect_editor_windows(config, &pids).unwrap_or_default();
     let active_index = active_id.and_then(|active_id| {
         windows.iter().position(|window| window.id == active_id)
     });
 
-    EditorState { is_active, windows, active_index }
+    EditorState {
+        is_active,
+        windows,
+        active_index,
+        is_running: true,
+        pid: Some(pid),
+    }
 }
 
 /// Check if any supported editor or Tab Manager is the frontmost application
 pub fn is_editor_active() -> bool {
-    let editor_bundle_ids: Vec<&str> = EDITORS.iter().map(|e| e.bundle_id).collect();
+    let editor_bundle_ids: Vec<&str> = enabled_editors().map(|e| e.bundle_id).collect();
     ax_helper::is_editor_frontmost(&editor_bundle_ids)
 }
 
@@ -115,31 +153,91 @@ pub fn get_editor_windows(bundle_id: &str) -> Vec<EditorWindow> {
 
 /// Get windows using a specific EditorConfig
 pub fn get_editor_windows_with_config(config: &EditorConfig) -> Vec<EditorWindow> {
-    let pid = match ax_helper::get_pid_by_bundle_id(config.bundle_id) {
-        Some(pid) => pid,
-        None => return vec![],
-    };
+    let pids = ax_helper::get_pids_by_bundle_id(config.bundle_id);
+    if pids.is_empty() {
+        return vec![];
+    }
 
-    collect_editor_windows(config, pid)
+    collect_editor_windows(config, &pids)
         .map(|(windows, _)| windows)
         .unwrap_or_default()
 }
 
+/// Enumerate the windows of every running instance of an editor.
+/// Fails only if no instance could be queried.
+fn collect_native_windows(
+    config: &EditorConfig,
+    pids: &[i32],
+) -> Result<Vec<NativeEditorWindow>, String> {
+    let mut per_pid = Vec::new();
+    let mut last_error = None;
+    for &pid in pids {
+        match ax_helper::get_native_windows_ax(pid, config.bundle_id, config.id == "cursor") {
+            Ok(windows) => per_pid.push((pid, windows)),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) if per_pid.is_empty() => Err(e),
+        _ => Ok(merge_instance_windows(per_pid, ax_helper::get_frontmost_pid())),
+    }
+}
+
+/// Merge window lists from several instances. Each process reports its own
+/// focused window, so only the frontmost instance (or else the first) keeps
+/// its frontmost flag. CGWindowIDs are unique across processes.
+fn merge_instance_windows(
+    per_pid: Vec<(i32, Vec<NativeEditorWindow>)>,
+    frontmost_pid: Option<i32>,
+) -> Vec<NativeEditorWindow> {
+    let active_pid = frontmost_pid
+        .filter(|pid| per_pid.iter().any(|(candidate, _)| candidate == pid))
+        .or_else(|| per_pid.first().map(|(pid, _)| *pid));
+    per_pid
+        .into_iter()
+        .flat_map(|(pid, windows)| {
+            windows.into_iter().map(move |mut window| {
+                window.is_frontmost &= Some(pid) == active_pid;
+                window
+            })
+        })
+        .collect()
+}
+
 fn collect_editor_windows(
     config: &EditorConfig,
-    pid: i32,
+    pids: &[i32],
 ) -> Result<(Vec<EditorWindow>, Option<u32>), String> {
-    let native_windows = ax_helper::get_native_windows_ax(
-        pid,
-        config.bundle_id,
-        config.id == "cursor",
-    )?;
+    let native_windows = collect_native_windows(config, pids)?;
+    let active_id = active_window_id(&native_windows);
+    let context = Arc::new(resolution_context(config, &native_windows));
+    let worker_context = Arc::clone(&context);
+    let windows = resolve_concurrently(
+        native_windows,
+        RESOLVE_WORKERS,
+        Instant::now() + RESOLVE_DEADLINE,
+        move |window| resolve_window(window, &worker_context),
+        |window| unresolved_window(window, &context),
+    )
+    .into_iter()
+    .flatten()
+    .collect();
+
+    Ok((windows, active_id))
+}
+
+/// Sessions, workspace state and title counts for resolving `native_windows`.
+/// Reads no git state; that happens per window in `resolve_window`.
+fn resolution_context(
+    config: &EditorConfig,
+    native_windows: &[NativeEditorWindow],
+) -> WindowResolutionContext {
     let sessions = if config.id == "cursor" {
         crate::cursor_ipc::discover_sessions().unwrap_or_default()
     } else {
         Vec::new()
     };
-    let session_resolutions = resolve_sessions(&native_windows, &sessions);
+    let session_resolutions = resolve_sessions(native_windows, &sessions);
     let workspace_state = if sessions.is_empty() {
         load_open_workspace_state(config.id)
     } else {
@@ -154,61 +252,243 @@ fn collect_editor_windows(
 
     for (window_id, (path, _)) in &session_resolutions {
         if let Some(window) = native_windows.iter().find(|window| window.id == *window_id) {
-            let name = extract_project_name(&window.title, config);
-            cache_window_path(config.id, *window_id, &name, path);
+            let parsed = parse_window_title(&window.title, config);
+            if parsed.remote.is_none() {
+                cache_window_path(config.id, *window_id, &parsed.name, path);
+            }
         }
     }
 
-    let active_id = native_windows
-        .iter()
-        .find(|window| window.is_frontmost)
-        .map(|window| window.id);
-    let windows = native_windows
-        .iter()
-        .filter_map(|window| {
-            if window.title.is_empty() || window.title == "Untitled" {
-                return None;
-            }
-            let name = extract_project_name(&window.title, config);
-            let session_resolution = session_resolutions.get(&window.id);
-            let resolved_path = session_resolution
-                .map(|(path, _)| path.clone())
-                .or_else(|| {
-                    resolve_project_path(
-                        &name,
-                        config.id,
-                        pid,
-                        window.id,
-                        project_window_counts.get(&name).copied().unwrap_or(1),
-                        &workspace_state,
-                    )
-                });
-            let resolution = session_resolution
-                .map(|(_, resolution)| *resolution)
-                .or_else(|| resolved_path.as_ref().map(|_| WorkspaceResolution::Inferred))
-                .unwrap_or(WorkspaceResolution::Unresolved);
-            let git_root = resolved_path.as_ref().and_then(|path| find_git_root(path));
-            let branch = git_root.as_ref().and_then(|root| get_git_branch(root));
-            let repository = git_root.as_ref().and_then(|root| get_repository_info(root));
-
-            Some(EditorWindow {
-                runtime_id: window.runtime_id.clone(),
-                id: window.id,
+    WindowResolutionContext {
+        config: config.clone(),
+        session_resolutions,
+        workspace_state,
+        project_window_counts,
+    }
+}
+
+/// Re-resolve one window from its current title. The editor's other windows
+/// are listed but not resolved, so no git state is read for them. None when
+/// the window is gone, excluded or in an ignored folder.
+pub fn resolve_editor_window(bundle_id: &str, window_id: u32) -> Option<EditorWindow> {
+    let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)?;
+    let pids = ax_helper::get_pids_by_bundle_id(config.bundle_id);
+    let native_windows = collect_native_windows(config, &pids).ok()?;
+    let window = native_windows.iter().find(|window| window.id == window_id)?;
+    let context = resolution_context(config, &native_windows);
+    resolve_window(window, &context)
+}
+
+/// Per-editor state shared by the window resolver threads
+struct WindowResolutionContext {
+    config: EditorConfig,
+    session_resolutions: HashMap<u32, (PathBuf, WorkspaceResolution)>,
+    workspace_state: OpenWorkspaceState,
+    project_window_counts: HashMap<String, usize>,
+}
+
+/// Resolve one window's project path, branch and repository.
+/// `None` when the window is excluded or its project is ignored.
+fn resolve_window(
+    window: &NativeEditorWindow,
+    context: &WindowResolutionContext,
+) -> Option<EditorWindow> {
+    let config = &context.config;
+    if window_filter::is_excluded(&window.title, config) {
+        return None;
+    }
+    let ParsedTitle {
+        name,
+        remote,
+        has_unsaved,
+        is_multi_root,
+        file_name,
+    } = parse_window_title(&window.title, config);
+    // リモート/コンテナのウィンドウはローカルのパスや git 情報を解決しない
+    let session_resolution = context
+        .session_resolutions
+        .get(&window.id)
+        .filter(|_| remote.is_none());
+    let resolved = match (session_resolution, &remote) {
+        (Some((path, _)), _) => Ok((path.clone(), PathSource::Session)),
+        (None, Some(remote)) => Err(format!("remote window ({})", remote)),
+        (None, None) => resolve_project_path(
+            &name,
+            file_name.as_deref(),
+            config.id,
+            window.pid,
+            window.id,
+            context.project_window_counts.get(&name).copied().unwrap_or(1),
+            &context.workspace_state,
+        ),
+    };
+    let (resolved_path, path_source) = match resolved {
+        Ok((path, source)) => (Some(path), source),
+        Err(reason) => {
+            log::debug!(
+                "Path unresolved for {} window {} ({}): {}",
+                config.id,
+                window.id,
                 name,
-                path: resolved_path
-                    .map(|path| path.to_string_lossy().to_string())
-                    .unwrap_or_default(),
-                branch,
-                repository_id: repository.as_ref().map(|(id, _)| id.clone()),
-                repository_name: repository.map(|(_, name)| name),
-                bundle_id: config.bundle_id.to_string(),
-                editor_name: config.display_name.to_string(),
-                resolution,
-            })
-        })
-        .collect();
+                reason
+            );
+            (None, PathSource::Unresolved)
+        }
+    };
+    // 無視リストのフォルダ配下のプロジェクトはタブに出さない
+    if resolved_path.as_deref().is_some_and(window_filter::is_ignored_path) {
+        return None;
+    }
+    let resolution = session_resolution
+        .map(|(_, resolution)| *resolution)
+        .or_else(|| resolved_path.as_ref().map(|_| WorkspaceResolution::Inferred))
+        .unwrap_or(WorkspaceResolution::Unresolved);
+    let workspace_kind = resolved_path.as_ref().map(|path| {
+        if is_multi_root {
+            WorkspaceKind::MultiRoot
+        } else if path.is_file() {
+            WorkspaceKind::File
+        } else {
+            WorkspaceKind::Folder
+        }
+    });
+    let git_root = resolved_path.as_ref().and_then(|path| find_git_root(path));
+    let branch = git_root.as_ref().and_then(|root| get_git_branch(root));
+    let repository = git_root.as_ref().and_then(|root| get_repository_info(root));
 
-    Ok((windows, active_id))
+    let path = resolved_path
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let appearance = crate::project_appearance::appearance_for(&path);
+
+    Some(EditorWindow {
+        runtime_id: window.runtime_id.clone(),
+        id: window.id,
+        name,
+        path,
+        branch,
+        repository_id: repository.as_ref().map(|(id, _)| id.clone()),
+        repository_name: repository.map(|(_, name)| name),
+        bundle_id: config.bundle_id.to_string(),
+        editor_name: config.display_name.to_string(),
+        resolution,
+        hidden: false,
+        remote,
+        has_unsaved,
+        workspace_kind,
+        path_source,
+        appearance,
+    })
+}
+
+/// A window whose resolution missed the deadline: listed by title only
+fn unresolved_window(
+    window: &NativeEditorWindow,
+    context: &WindowResolutionContext,
+) -> Option<EditorWindow> {
+    let config = &context.config;
+    if window_filter::is_excluded(&window.title, config) {
+        return None;
+    }
+    let ParsedTitle {
+        name,
+        remote,
+        has_unsaved,
+        ..
+    } = parse_window_title(&window.title, config);
+    log::debug!(
+        "Path resolution timed out for {} window {} ({})",
+        config.id,
+        window.id,
+        name
+    );
+    Some(EditorWindow {
+        runtime_id: window.runtime_id.clone(),
+        id: window.id,
+        name,
+        path: String::new(),
+        branch: None,
+        repository_id: None,
+        repository_name: None,
+        bundle_id: config.bundle_id.to_string(),
+        editor_name: config.display_name.to_string(),
+        resolution: WorkspaceResolution::Unresolved,
+        hidden: false,
+        remote,
+        has_unsaved,
+        workspace_kind: None,
+        path_source: PathSource::Unresolved,
+        appearance: None,
+    })
+}
+
+/// Map `items` through `resolve` on up to `workers` tasks of the blocking pool, keeping
+/// their order. Items still pending at `deadline` get `fallback` instead. Their workers
+/// are left to finish in the background, so one hung lookup cannot stall the caller.
+fn resolve_concurrently<T, R, F>(
+    items: Vec<T>,
+    workers: usize,
+    deadline: Instant,
+    resolve: F,
+    fallback: impl Fn(&T) -> R,
+) -> Vec<R>
+where
+    T: Send + Sync + 'static,
+    R: Send + 'static,
+    F: Fn(&T) -> R + Send + Sync + 'static,
+{
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let items = Arc::new(items);
+    let resolve = Arc::new(resolve);
+    let next_index = Arc::new(AtomicUsize::new(0));
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+    for _ in 0..workers.clamp(1, items.len()) {
+        let items = Arc::clone(&items);
+        let resolve = Arc::clone(&resolve);
+        let next_index = Arc::clone(&next_index);
+        let cancelled = Arc::clone(&cancelled);
+        let tx = tx.clone();
+        // 更新のたびにスレッドを作らないよう、使い回されるブロッキングプールで動かす
+        tauri::async_runtime::spawn_blocking(move || {
+            // 期限切れ後は新しい項目を取らない（処理中のものは結果が捨てられる）
+            while !cancelled.load(Ordering::Relaxed) {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if tx.send((index, resolve(item))).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    drop(tx);
+
+    let mut results: Vec<Option<R>> = std::iter::repeat_with(|| None).take(items.len()).collect();
+    let mut remaining = items.len();
+    while remaining > 0 {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(timeout) {
+            Ok((index, result)) => {
+                results[index] = Some(result);
+                remaining -= 1;
+            }
+            Err(_) => break,
+        }
+    }
+    cancelled.store(true, Ordering::Relaxed);
+    if remaining > 0 {
+        log::warn!("{} of {} windows missed the resolve deadline", remaining, items.len());
+    }
+
+    results
+        .into_iter()
+        .zip(items.iter())
+        .map(|(result, item)| result.unwrap_or_else(|| fallback(item)))
+        .collect()
 }
 
 /// Get windows from ALL running editors
@@ -217,15 +497,16 @@ pub fn get_all_editor_windows() -> Vec<EditorWindow> {
 }
 
 pub fn get_all_editor_window_snapshot() -> (Vec<EditorWindow>, Option<u32>) {
-    let editor_bundle_ids = EDITORS.iter().map(|editor| editor.bundle_id).collect::<Vec<_>>();
+    let editor_bundle_ids = enabled_editors().map(|editor| editor.bundle_id).collect::<Vec<_>>();
     let frontmost_bundle_id = ax_helper::get_frontmost_editor_bundle_id(&editor_bundle_ids);
     let mut all_windows = Vec::new();
     let mut active_id = None;
-    for editor in EDITORS {
-        let Some(pid) = ax_helper::get_pid_by_bundle_id(editor.bundle_id) else {
+    for editor in enabled_editors() {
+        let pids = ax_helper::get_pids_by_bundle_id(editor.bundle_id);
+        if pids.is_empty() {
             continue;
-        };
-        let Ok((windows, editor_active_id)) = collect_editor_windows(editor, pid) else {
+        }
+        let Ok((windows, editor_active_id)) = collect_editor_windows(editor, &pids) else {
             continue;
         };
         if frontmost_bundle_id.as_deref() == Some(editor.bundle_id) {
@@ -236,11 +517,182 @@ pub fn get_all_editor_window_snapshot() -> (Vec<EditorWindow>, Option<u32>) {
     (all_windows, active_id)
 }
 
+/// Keep only windows located on the given display.
+/// Windows whose frame can't be read are kept so a flaky AX query doesn't empty the bar.
+pub fn filter_windows_by_display(
+    windows: Vec<EditorWindow>,
+    display: &crate::window_offset::DisplayFrame,
+) -> Vec<EditorWindow> {
+    let mut frames_by_bundle: HashMap<String, HashMap<u32, ax_helper::WindowFrameInfo>> =
+        HashMap::new();
+    windows
+        .into_iter()
+        .filter(|window| {
+            let frames = frames_by_bundle
+                .entry(window.bundle_id.clone())
+                .or_insert_with(|| {
+                    ax_helper::get_pids_by_bundle_id(&window.bundle_id)
+                        .into_iter()
+                        .filter_map(|pid| ax_helper::get_all_window_frames(pid).ok())
+                        .flatten()
+                        .map(|f| (f.0, f))
+                        .collect()
+                });
+            match frames.get(&window.id) {
+                Some((_, x, y, width, height)) => display.contains_window(*x, *y, *width, *height),
+                None => true,
+            }
+        })
+        .collect()
+}
+
+/// Filter an editor state to a display, recomputing the active index
+pub fn filter_state_by_display(
+    state: EditorState,
+    display: &crate::window_offset::DisplayFrame,
+) -> EditorState {
+    let active_id = state
+        .active_index
+        .and_then(|index| state.windows.get(index))
+        .map(|window| window.id);
+    let windows = filter_windows_by_display(state.windows, display);
+    let active_index =
+        active_id.and_then(|active_id| windows.iter().position(|window| window.id == active_id));
+    EditorState {
+        is_active: state.is_active,
+        windows,
+        active_index,
+        is_running: state.is_running,
+        pid: state.pid,
+    }
+}
+
+/// Every supported editor with its running state and window count.
+/// Window counts come from the registry's current snapshot.
+pub fn get_running_editors() -> Vec<RunningEditor> {
+    let snapshot = crate::window_registry::snapshot();
+    EDITORS
+        .iter()
+        .map(|editor| {
+            let pids = ax_helper::get_pids_by_bundle_id(editor.bundle_id);
+            running_editor(editor, &pids, &snapshot.windows)
+        })
+        .collect()
+}
+
+fn running_editor(config: &EditorConfig, pids: &[i32], windows: &[EditorWindow]) -> RunningEditor {
+    RunningEditor {
+        bundle_id: config.bundle_id.to_string(),
+        editor_name: config.display_name.to_string(),
+        is_running: !pids.is_empty(),
+        pid: pids.first().copied(),
+        window_count: windows
+            .iter()
+            .filter(|window| window.bundle_id == config.bundle_id)
+            .count(),
+    }
+}
+
 /// Invalidate window path assignments when an editor process changes.
 pub fn invalidate_path_cache_for_editor(editor_id: &str) {
-    if let Ok(mut cache) = WINDOW_PATH_CACHE.lock() {
+    if let Ok(mut cache) = WINDOW_PATH_CACHE.write() {
         cache.retain(|(cached_editor_id, _, _), _| cached_editor_id != editor_id);
     }
+    if let Ok(mut keys) = DOCUMENT_PATH_KEYS.lock() {
+        keys.retain(|(cached_editor_id, _, _)| cached_editor_id != editor_id);
+    }
+}
+
+/// Forget every resolved project path so the next refresh resolves from scratch
+pub fn clear_project_path_cache() {
+    if let Ok(mut cache) = WINDOW_PATH_CACHE.write() {
+        cache.clear();
+    }
+    if let Ok(mut keys) = DOCUMENT_PATH_KEYS.lock() {
+        keys.clear();
+    }
+    if let Ok(mut states) = WORKSPACE_STATE_CACHE.lock() {
+        states.clear();
+    }
+    workspace_history::invalidate();
+}
+
+/// Parse every editor's workspace state on a background thread at startup.
+/// Until it finishes, queries for a cold editor return unresolved paths instead
+/// of blocking, and a refresh follows once the cache is warm.
+pub fn prewarm_workspace_state() {
+    WORKSPACE_PREWARM_PENDING.store(true, Ordering::SeqCst);
+    std::thread::spawn(|| {
+        let started = std::time::Instant::now();
+        for editor in EDITORS {
+            if get_global_storage_file(editor.id).is_some() {
+                let _ = read_and_cache_workspace_state(editor.id);
+                workspace_history::warm(editor.id);
+            }
+        }
+        WORKSPACE_PREWARM_PENDING.store(false, Ordering::SeqCst);
+        log::debug!("Workspace state warmed in {:?}", started.elapsed());
+        crate::window_registry::request_refresh("workspace-warm");
+    });
+}
+
+/// Watch the editors' storage.json and workspaceStorage so projects opened
+/// after launch are resolved without waiting for an unrelated refresh
+pub fn start_workspace_storage_watcher() {
+    use notify::{RecursiveMode, Watcher};
+
+    let user_dirs: Vec<PathBuf> = EDITORS
+        .iter()
+        .filter_map(|editor| get_editor_user_dir(editor.id))
+        .collect();
+    let watched_dirs: Vec<PathBuf> = user_dirs
+        .iter()
+        .flat_map(|dir| [dir.join("globalStorage"), dir.join("workspaceStorage")])
+        .filter(|dir| dir.is_dir())
+        .collect();
+    if watched_dirs.is_empty() {
+        return;
+    }
+
+    let handler = move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if event.paths.iter().any(|path| is_workspace_storage_change(path)) {
+            workspace_history::invalidate();
+            debounce::schedule(
+                DebounceKey::WorkspaceStorage,
+                Duration::from_millis(WORKSPACE_STORAGE_DEBOUNCE_MS),
+                || crate::window_registry::request_refresh("workspace-storage"),
+            );
+        }
+    };
+    let mut watcher = match notify::recommended_watcher(handler) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::error!("Failed to create workspace storage watcher: {}", e);
+            return;
+        }
+    };
+    for dir in &watched_dirs {
+        // workspaceStorage は新しいエントリ（サブディレクトリ）の追加だけ分かればよい
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            log::error!("Failed to watch {}: {}", dir.display(), e);
+        }
+    }
+    if let Ok(mut slot) = WORKSPACE_STORAGE_WATCHER.lock() {
+        *slot = Some(watcher);
+    }
+}
+
+/// storage.json itself, or an entry added directly under workspaceStorage
+fn is_workspace_storage_change(path: &Path) -> bool {
+    if path.file_name().is_some_and(|name| name == "storage.json") {
+        return true;
+    }
+    path.parent()
+        .and_then(|parent| parent.file_name())
+        .is_some_and(|name| name == "workspaceStorage")
 }
 
 fn resolve_sessions(
@@ -352,7 +804,7 @@ fn open_workspace_state_from_sessions(sessions: &[EditorSession]) -> OpenWorkspa
     }
 }
 
-fn get_editor_user_dir(editor_id: &str) -> Option<PathBuf> {
+pub(crate) fn get_editor_user_dir(editor_id: &str) -> Option<PathBuf> {
     let home = dirs::home_dir()?;
     let subdir = match editor_id {
         "cursor" => "Cursor",
@@ -383,10 +835,137 @@ fn add_named_workspace_path(map: &mut HashMap<String, Vec<PathBuf>>, name: &str,
     }
 }
 
+/// Folder of a workspaceStorage entry; multi-root entries resolve to their first folder
+pub(crate) fn workspace_entry_folder(value: &serde_json::Value) -> Option<PathBuf> {
+    let path = folder_path(value)?;
+    if is_code_workspace_file(&path) {
+        return code_workspace_root(&path).map(|(_, folder)| folder);
+    }
+    Some(path)
+}
+
+/// The folder of a window entry, or the .code-workspace file of a multi-root window
 fn folder_path(value: &serde_json::Value) -> Option<PathBuf> {
-    let folder = value.get("folder")?.as_str()?;
-    let path = folder.strip_prefix("file://").unwrap_or(folder);
-    Some(PathBuf::from(percent_decode(path)))
+    // リモート（vscode-remote:// など）はローカルのフォルダとして扱わない
+    if let Some(folder) = value.get("folder").and_then(|folder| folder.as_str()) {
+        return workspace_uri::to_local_path(folder);
+    }
+    // workspace.json: "workspace": "file:///...", storage.json: "workspaceIdentifier"
+    let workspace = value
+        .get("workspace")
+        .and_then(|workspace| workspace.as_str().or_else(|| workspace.get("configPath")?.as_str()))
+        .or_else(|| value.get("workspaceIdentifier")?.get("configURIPath")?.as_str())?;
+    workspace_uri::to_local_path(workspace)
+}
+
+fn is_code_workspace_file(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "code-workspace")
+}
+
+/// Folders listed in a .code-workspace file; relative paths are resolved
+/// against the file's directory
+fn parse_code_workspace_folders(content: &str, base_dir: &Path) -> Vec<PathBuf> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(&strip_jsonc(content)) else {
+        return Vec::new();
+    };
+    let Some(folders) = json.get("folders").and_then(|folders| folders.as_array()) else {
+        return Vec::new();
+    };
+    folders
+        .iter()
+        .filter_map(|folder| {
+            if let Some(path) = folder.get("path").and_then(|path| path.as_str()) {
+                let path = Path::new(path);
+                return Some(if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    normalize_relative(&base_dir.join(path))
+                });
+            }
+            workspace_uri::to_local_path(folder.get("uri")?.as_str()?)
+        })
+        .collect()
+}
+
+/// Collapse "." and ".." without touching the file system
+pub(crate) fn normalize_relative(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// .code-workspace は JSONC（コメント・末尾カンマ可）なので serde_json 用に除去する
+fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for skipped in chars.by_ref() {
+                    if skipped == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for skipped in chars.by_ref() {
+                    if previous == '*' && skipped == '/' {
+                        break;
+                    }
+                    previous = skipped;
+                }
+            }
+            (',', _) => {
+                // 閉じ括弧の直前のカンマは捨てる
+                let next = chars.clone().find(|next| !next.is_whitespace());
+                if !matches!(next, Some('}' | ']')) {
+                    output.push(c);
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// Name shown in the title ("name (Workspace)") and first existing folder
+fn code_workspace_root(workspace_file: &Path) -> Option<(String, PathBuf)> {
+    let name = workspace_file.file_stem()?.to_str()?.to_string();
+    let content = std::fs::read_to_string(workspace_file).ok()?;
+    let base_dir = workspace_file.parent()?;
+    let folder = parse_code_workspace_folders(&content, base_dir)
+        .into_iter()
+        .find(|folder| folder.exists())?;
+    Some((name, folder))
 }
 
 fn parse_open_workspace_paths(json: &serde_json::Value) -> (Option<PathBuf>, Vec<PathBuf>) {
@@ -414,6 +993,41 @@ fn load_open_workspace_state(editor_id: &str) -> OpenWorkspaceState {
     let Some(storage_file) = get_global_storage_file(editor_id) else {
         return OpenWorkspaceState::default();
     };
+    let modified = storage_file_modified(&storage_file);
+    if let Ok(states) = WORKSPACE_STATE_CACHE.lock() {
+        match states.get(editor_id) {
+            Some((cached_modified, state)) if Some(*cached_modified) == modified => {
+                return state.clone();
+            }
+            // 起動時の先読みが終わるまではブロックせず未解決のまま返す
+            None if WORKSPACE_PREWARM_PENDING.load(Ordering::SeqCst) => {
+                return OpenWorkspaceState::default();
+            }
+            _ => {}
+        }
+    }
+    read_and_cache_workspace_state(editor_id)
+}
+
+fn storage_file_modified(storage_file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(storage_file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn read_and_cache_workspace_state(editor_id: &str) -> OpenWorkspaceState {
+    let Some(storage_file) = get_global_storage_file(editor_id) else {
+        return OpenWorkspaceState::default();
+    };
+    let modified = storage_file_modified(&storage_file);
+    let state = read_open_workspace_state(&storage_file);
+    if let (Some(modified), Ok(mut states)) = (modified, WORKSPACE_STATE_CACHE.lock()) {
+        states.insert(editor_id.to_string(), (modified, state.clone()));
+    }
+    state
+}
+
+fn read_open_workspace_state(storage_file: &Path) -> OpenWorkspaceState {
     let empty_state = || OpenWorkspaceState {
         is_available: true,
         ..OpenWorkspaceState::default()
@@ -424,11 +1038,31 @@ fn load_open_workspace_state(editor_id: &str) -> OpenWorkspaceState {
     let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
         return empty_state();
     };
-    let (active_path, all_paths) = parse_open_workspace_paths(&json);
-    let all_paths: Vec<PathBuf> = all_paths.into_iter().filter(|path| path.exists()).collect();
-    let active_path = active_path.filter(|path| all_paths.contains(path));
+    let (active_path, opened_paths) = parse_open_workspace_paths(&json);
+    let mut all_paths = Vec::new();
     let mut paths_by_name = HashMap::new();
 
+    for path in opened_paths.into_iter().filter(|path| path.exists()) {
+        if !is_code_workspace_file(&path) {
+            add_unique_path(&mut all_paths, path);
+            continue;
+        }
+        // マルチルートはタイトルのワークスペース名から最初のフォルダへ解決する
+        if let Some((name, folder)) = code_workspace_root(&path) {
+            add_named_workspace_path(&mut paths_by_name, &name, &folder);
+            add_unique_path(&mut all_paths, folder);
+        }
+    }
+    let active_path = active_path
+        .and_then(|path| {
+            if is_code_workspace_file(&path) {
+                code_workspace_root(&path).map(|(_, folder)| folder)
+            } else {
+                Some(path)
+            }
+        })
+        .filter(|path| all_paths.contains(path));
+
     for path in &all_paths {
         if let Some(name) = path.file_name().and_then(|name| name.to_str()) {
             add_named_workspace_path(&mut paths_by_name, name, path);
@@ -449,33 +1083,8 @@ fn load_open_workspace_state(editor_id: &str) -> OpenWorkspaceState {
     }
 }
 
-/// 簡易パーセントデコード（%XX → バイト変換）
-fn percent_decode(input: &str) -> String {
-    let mut bytes = Vec::with_capacity(input.len());
-    let mut chars = input.bytes();
-    while let Some(b) = chars.next() {
-        if b == b'%' {
-            let hi = chars.next();
-            let lo = chars.next();
-            if let (Some(h), Some(l)) = (hi, lo) {
-                if let Ok(decoded) = u8::from_str_radix(
-                    &format!("{}{}", h as char, l as char),
-                    16,
-                ) {
-                    bytes.push(decoded);
-                    continue;
-                }
-            }
-            bytes.push(b);
-        } else {
-            bytes.push(b);
-        }
-    }
-    String::from_utf8(bytes).unwrap_or_else(|_| input.to_string())
-}
-
 fn cache_window_path(editor_id: &str, window_id: u32, project_name: &str, path: &Path) {
-    if let Ok(mut cache) = WINDOW_PATH_CACHE.lock() {
+    if let Ok(mut cache) = WINDOW_PATH_CACHE.write() {
         cache.insert(
             (editor_id.to_string(), window_id, project_name.to_string()),
             path.to_path_buf(),
@@ -483,6 +1092,14 @@ fn cache_window_path(editor_id: &str, window_id: u32, project_name: &str, path:
     }
 }
 
+/// Cache a path learned from AXDocument so workspace re-scans don't drop it
+fn cache_document_path(editor_id: &str, window_id: u32, project_name: &str, path: &Path) {
+    cache_window_path(editor_id, window_id, project_name, path);
+    if let Ok(mut keys) = DOCUMENT_PATH_KEYS.lock() {
+        keys.insert((editor_id.to_string(), window_id, project_name.to_string()));
+    }
+}
+
 fn workspace_path_for_document(candidates: &[PathBuf], document_path: &Path) -> Option<PathBuf> {
     candidates
         .iter()
@@ -497,10 +1114,13 @@ fn count_project_windows(
 ) -> HashMap<String, usize> {
     let mut counts = HashMap::new();
     for (_, title, _) in ax_windows {
-        if title.is_empty() || title == "Untitled" {
+        if window_filter::is_excluded(title, config) {
             continue;
         }
-        *counts.entry(extract_project_name(title, config)).or_default() += 1;
+        let parsed = parse_window_title(title, config);
+        if parsed.remote.is_none() {
+            *counts.entry(parsed.name).or_default() += 1;
+        }
     }
     counts
 }
@@ -520,6 +1140,61 @@ fn single_unassigned_workspace_path(
     unassigned.next().is_none().then_some(path)
 }
 
+/// Directory entries visited per candidate when looking for a title's file
+const FILE_SEARCH_ENTRY_LIMIT: usize = 5000;
+
+/// Directories never searched for a title's file
+const FILE_SEARCH_SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist", "build"];
+
+/// The only candidate that contains a file with this name, if exactly one does
+fn candidate_containing_file(candidates: &[PathBuf], file_name: &str) -> Option<PathBuf> {
+    let mut matching = candidates
+        .iter()
+        .filter(|candidate| directory_contains_file(candidate, file_name));
+    let path = matching.next()?.clone();
+    matching.next().is_none().then_some(path)
+}
+
+/// Breadth-first search for `file_name`, bounded by FILE_SEARCH_ENTRY_LIMIT
+fn directory_contains_file(root: &Path, file_name: &str) -> bool {
+    let mut queue = std::collections::VecDeque::from([root.to_path_buf()]);
+    let mut visited = 0;
+    while let Some(dir) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            visited += 1;
+            if visited > FILE_SEARCH_ENTRY_LIMIT {
+                return false;
+            }
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !name.starts_with('.') && !FILE_SEARCH_SKIPPED_DIRS.contains(&name) {
+                    queue.push_back(entry.path());
+                }
+            } else if name == file_name {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// The focused window, identified by the AX layer's window id rather than by title
+fn active_window_id(windows: &[NativeEditorWindow]) -> Option<u32> {
+    windows
+        .iter()
+        .find(|window| window.is_frontmost)
+        .map(|window| window.id)
+}
+
 fn prepare_window_path_resolution(
     config: &EditorConfig,
     ax_windows: &[(u32, String, bool)],
@@ -530,9 +1205,13 @@ fn prepare_window_path_resolution(
         .as_ref()
         .and_then(|active_path| {
             let (window_id, title, _) = ax_windows.iter().find(|(_, title, is_frontmost)| {
-                *is_frontmost && !title.is_empty() && title != "Untitled"
+                *is_frontmost && !window_filter::is_excluded(title, config)
             })?;
-            let project_name = extract_project_name(title, config);
+            let parsed = parse_window_title(title, config);
+            if parsed.remote.is_some() {
+                return None;
+            }
+            let project_name = parsed.name;
             let is_candidate = workspace_state
                 .paths_by_name
                 .get(&project_name)
@@ -540,22 +1219,28 @@ fn prepare_window_path_resolution(
             is_candidate.then(|| (*window_id, project_name, active_path.clone()))
         });
 
-    let mut cache = match WINDOW_PATH_CACHE.lock() {
+    let mut cache = match WINDOW_PATH_CACHE.write() {
         Ok(cache) => cache,
         Err(_) => return,
     };
-    cache.retain(|(editor_id, window_id, project_name), path| {
+    // AXDocument で得たパスはワークスペース一覧に無くても、ウィンドウがある限り残す
+    let mut document_keys = DOCUMENT_PATH_KEYS.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|key, path| {
+        let (editor_id, window_id, project_name) = key;
         if editor_id != config.id {
             return true;
         }
         let window_exists = ax_windows.iter().any(|(id, _, _)| id == window_id);
         let path_is_open = !workspace_state.is_available
+            || document_keys.contains(key)
             || workspace_state
                 .paths_by_name
                 .get(project_name)
                 .is_some_and(|paths| paths.contains(path));
         window_exists && path_is_open
     });
+    document_keys.retain(|key| cache.contains_key(key));
+    drop(document_keys);
 
     let active_key = active_window.map(|(window_id, project_name, active_path)| {
         let key = (config.id.to_string(), window_id, project_name);
@@ -588,42 +1273,78 @@ fn prepare_window_path_resolution(
 /// Resolve a full path from the project name and window metadata
 fn resolve_project_path(
     project_name: &str,
+    file_name: Option<&str>,
     editor_id: &str,
     pid: i32,
     window_id: u32,
     project_window_count: usize,
     workspace_state: &OpenWorkspaceState,
-) -> Option<PathBuf> {
+) -> Result<(PathBuf, PathSource), String> {
     let document_path = ax_helper::get_document_path(pid, window_id).map(PathBuf::from);
 
-    if let Some(document_path) = document_path {
+    if let Some(document_path) = &document_path {
         // Use the containing workspace to distinguish same-named worktrees and submodules
         if let Some(workspace_path) =
-            workspace_path_for_document(&workspace_state.all_paths, &document_path)
+            workspace_path_for_document(&workspace_state.all_paths, document_path)
         {
-            cache_window_path(editor_id, window_id, project_name, &workspace_path);
-            return Some(workspace_path);
+            cache_document_path(editor_id, window_id, project_name, &workspace_path);
+            return Ok((workspace_path, PathSource::AxDocument));
         }
-        if let Some(git_root) = find_git_root(&document_path) {
-            cache_window_path(editor_id, window_id, project_name, &git_root);
-            return Some(git_root);
+        if let Some(git_root) = find_git_root(document_path) {
+            cache_document_path(editor_id, window_id, project_name, &git_root);
+            return Ok((git_root, PathSource::AxDocument));
         }
     }
 
     let window_cache_key = (editor_id.to_string(), window_id, project_name.to_string());
-    let candidates = workspace_state
+    let mut candidates = workspace_state
         .paths_by_name
         .get(project_name)
         .cloned()
         .unwrap_or_default();
-    let mut cache = WINDOW_PATH_CACHE.lock().ok()?;
-    if let Some(path) = cache.get(&window_cache_key) {
-        if !workspace_state.is_available || candidates.contains(path) {
-            return Some(path.clone());
+    // 開いているウィンドウ一覧に無い名前は workspaceStorage の履歴から探す
+    if candidates.is_empty() && workspace_state.is_available {
+        candidates = workspace_history::lookup(editor_id, project_name)
+            .into_iter()
+            .filter(|path| path.exists())
+            .collect();
+    }
+
+    // 同名プロジェクトが複数ある場合は、タイトルのファイル名を含む候補に絞る
+    if candidates.len() > 1 {
+        if let Some(path) = file_name.and_then(|name| candidate_containing_file(&candidates, name))
+        {
+            cache_window_path(editor_id, window_id, project_name, &path);
+            return Ok((path, PathSource::SubdirScan));
         }
-        cache.remove(&window_cache_key);
     }
 
+    // ほとんどはキャッシュヒットなので、読み取りロックで並列に確認する
+    {
+        let cache = WINDOW_PATH_CACHE
+            .read()
+            .map_err(|_| "path cache lock poisoned".to_string())?;
+        if let Some(path) = cache.get(&window_cache_key) {
+            if !workspace_state.is_available || candidates.contains(path) {
+                let from_document = DOCUMENT_PATH_KEYS
+                    .lock()
+                    .is_ok_and(|keys| keys.contains(&window_cache_key));
+                let source = if from_document {
+                    PathSource::AxDocument
+                } else {
+                    PathSource::WorkspaceStorage
+                };
+                return Ok((path.clone(), source));
+            }
+        }
+    }
+
+    // 同名ウィンドウが同じパスを取り合わないよう、割り当ては書き込みロック下で行う
+    let mut cache = WINDOW_PATH_CACHE
+        .write()
+        .map_err(|_| "path cache lock poisoned".to_string())?;
+    cache.remove(&window_cache_key);
+
     let assigned_paths: Vec<PathBuf> = cache
         .iter()
         .filter(
@@ -637,17 +1358,155 @@ fn resolve_project_path(
         .map(|(_, path)| path.clone())
         .collect();
     let unassigned_window_count = project_window_count.saturating_sub(assigned_paths.len());
-    let path = single_unassigned_workspace_path(
-        &candidates,
-        &assigned_paths,
-        unassigned_window_count,
-    )?;
+    let Some(path) =
+        single_unassigned_workspace_path(&candidates, &assigned_paths, unassigned_window_count)
+    else {
+        return Err(unresolved_reason(
+            workspace_state,
+            &candidates,
+            document_path.is_some(),
+        ));
+    };
     cache.insert(window_cache_key, path.clone());
-    Some(path)
+    Ok((path, PathSource::WorkspaceStorage))
+}
+
+/// Why resolve_project_path gave up, for the debug log
+fn unresolved_reason(
+    workspace_state: &OpenWorkspaceState,
+    candidates: &[PathBuf],
+    had_document: bool,
+) -> String {
+    let document = if had_document {
+        "AXDocument outside any repository"
+    } else {
+        "no AXDocument"
+    };
+    if !workspace_state.is_available {
+        format!("editor has no workspace storage, {}", document)
+    } else if candidates.is_empty() {
+        format!("no open or recent workspace with this name, {}", document)
+    } else {
+        format!(
+            "{} candidate workspaces, none uniquely assignable, {}",
+            candidates.len(),
+            document
+        )
+    }
+}
+
+/// Bracketed suffixes that mark a remote or container window, e.g. "project [SSH: myhost]"
+const REMOTE_PREFIXES: &[&str] = &[
+    "SSH",
+    "WSL",
+    "Dev Container",
+    "Codespaces",
+    "Container",
+    "Tunnel",
+];
+
+/// Unsaved-changes markers editors put in front of the title
+const DIRTY_PREFIXES: &[&str] = &["• ", "● "];
+
+/// Privilege markers appended to the whole title
+const PRIVILEGE_SUFFIXES: &[&str] = &[" [Administrator]", " [Superuser]"];
+
+/// Appended to the folder segment when a .code-workspace file is open
+const WORKSPACE_SUFFIX: &str = " (Workspace)";
+
+/// Project name and decorations parsed from a window title
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParsedTitle {
+    name: String,
+    /// "SSH: myhost", "WSL: Ubuntu", ... for windows whose files are not local
+    remote: Option<String>,
+    /// The title carries the unsaved-changes dot
+    has_unsaved: bool,
+    /// The folder segment ends with "(Workspace)": a .code-workspace is open
+    is_multi_root: bool,
+    /// File name segment ("main.rs"), used to tell same-named projects apart
+    file_name: Option<String>,
+}
+
+fn parse_window_title(title: &str, config: &EditorConfig) -> ParsedTitle {
+    let (title, has_unsaved) = normalize_title(title);
+    let (name, remote) = split_remote_suffix(&extract_raw_project_name(&title, config));
+    let (name, is_multi_root) = match name.strip_suffix(WORKSPACE_SUFFIX) {
+        Some(stripped) => (stripped.trim_end().to_string(), true),
+        None => (name, false),
+    };
+    ParsedTitle {
+        name,
+        remote,
+        has_unsaved,
+        is_multi_root,
+        file_name: title_file_name(&title, config),
+    }
+}
+
+/// The file segment of a title: "main.rs — api — Cursor", "api — main.rs" (Zed)
+fn title_file_name(title: &str, config: &EditorConfig) -> Option<String> {
+    let parts: Vec<&str> = title.split(" — ").map(str::trim).collect();
+    let file_name = match (config.id, parts.as_slice()) {
+        ("zed", [_, file_name]) => *file_name,
+        ("zed", _) => return None,
+        (_, [file_name, _, _]) => *file_name,
+        _ => return None,
+    };
+    (!file_name.is_empty()).then(|| file_name.to_string())
+}
+
+/// Strip zero-width characters, the dirty dot and privilege markers.
+/// Returns the cleaned title and whether the dirty dot was present.
+fn normalize_title(title: &str) -> (String, bool) {
+    let cleaned: String = title
+        .chars()
+        .filter(|c| !matches!(c, '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}'))
+        .collect();
+    let mut cleaned = cleaned.trim();
+    let mut has_unsaved = false;
+    for prefix in DIRTY_PREFIXES {
+        if let Some(rest) = cleaned.strip_prefix(prefix) {
+            cleaned = rest.trim_start();
+            has_unsaved = true;
+            break;
+        }
+    }
+    for suffix in PRIVILEGE_SUFFIXES {
+        if let Some(rest) = cleaned.strip_suffix(suffix) {
+            cleaned = rest.trim_end();
+        }
+    }
+    (cleaned.to_string(), has_unsaved)
 }
 
 /// Extract project name from editor window title
 fn extract_project_name(title: &str, config: &EditorConfig) -> String {
+    parse_window_title(title, config).name
+}
+
+/// "project [SSH: myhost]" -> ("project", Some("SSH: myhost"))
+fn split_remote_suffix(name: &str) -> (String, Option<String>) {
+    let trimmed = name.trim_end();
+    let Some(inner) = trimmed.strip_suffix(']') else {
+        return (name.to_string(), None);
+    };
+    let Some(open) = inner.rfind(" [") else {
+        return (name.to_string(), None);
+    };
+    let label = &inner[open + 2..];
+    let is_remote = REMOTE_PREFIXES.iter().any(|prefix| {
+        label
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+    });
+    if !is_remote {
+        return (name.to_string(), None);
+    }
+    (inner[..open].trim_end().to_string(), Some(label.to_string()))
+}
+
+fn extract_raw_project_name(title: &str, config: &EditorConfig) -> String {
     // Editor title formats vary by editor:
     // VSCode/Cursor: "filename — folder — Editor" or "folder — Editor" or "Editor"
     // Zed: "project — filename" or "project"
@@ -773,16 +1632,97 @@ fn get_git_branch(git_root: &std::path::Path) -> Option<String> {
     }
 }
 
+/// Time for an unhidden editor to bring its windows back before raising one
+const UNHIDE_SETTLE_MS: u64 = 100;
+
+/// The instance of the editor that owns `window_id`.
+/// Falls back to the first instance so a stale id still gets its "not found" error.
+pub(crate) fn owning_pid(config: &EditorConfig, window_id: u32) -> Result<i32, String> {
+    let pids = ax_helper::get_pids_by_bundle_id(config.bundle_id);
+    let first = *pids.first().ok_or_else(|| {
+        crate::i18n::t_with("error.editorNotRunning", &[("name", config.display_name)])
+    })?;
+    if pids.len() == 1 {
+        return Ok(first);
+    }
+    Ok(ax_helper::find_window_pid(&pids, window_id).unwrap_or(first))
+}
+
+/// Why a window could not be focused. Serialized so the tab bar can tell the
+/// user and put the highlight back on the window that is really in front.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FocusError {
+    /// The window never became focused, even after retrying: the editor
+    /// ignored the request (right after wake, or with a sheet up) or macOS
+    /// would not switch to the window's Space. Says what is in front instead.
+    FocusUnverified {
+        window_id: u32,
+        frontmost_bundle_id: Option<String>,
+        focused_window_id: Option<u32>,
+    },
+    Failed { message: String },
+}
+
+impl std::fmt::Display for FocusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FocusUnverified {
+                frontmost_bundle_id,
+                ..
+            } => {
+                let app = frontmost_bundle_id.as_deref().unwrap_or("?");
+                write!(f, "{}", crate::i18n::t_with("error.focusUnverified", &[("app", app)]))
+            }
+            Self::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for FocusError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
 /// Focus a specific editor window by CGWindowID
 /// Uses CGWindowID for reliable window identification regardless of title changes
-pub fn focus_editor_window(bundle_id: &str, window_id: u32) -> Result<(), String> {
+pub fn focus_editor_window(bundle_id: &str, window_id: u32) -> Result<(), FocusError> {
+    let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)
+        .ok_or_else(|| format!("Unknown editor: {}", bundle_id))?;
+
+    let pid = owning_pid(config, window_id)?;
+
+    // A hidden (Cmd+H) editor has to be shown before its windows can be raised
+    if ax_helper::unhide_app(pid) {
+        std::thread::sleep(std::time::Duration::from_millis(UNHIDE_SETTLE_MS));
+    }
+
+    match ax_helper::focus_window_by_id(pid, window_id)? {
+        FocusOutcome::Focused => Ok(()),
+        FocusOutcome::Unverified {
+            frontmost_bundle_id,
+            focused_window_id,
+        } => Err(FocusError::FocusUnverified {
+            window_id,
+            frontmost_bundle_id,
+            focused_window_id,
+        }),
+    }
+}
+
+/// Minimize or restore a specific editor window by CGWindowID
+pub fn set_editor_window_minimized(
+    bundle_id: &str,
+    window_id: u32,
+    minimized: bool,
+) -> Result<(), String> {
     let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)
         .ok_or_else(|| format!("Unknown editor: {}", bundle_id))?;
 
-    let pid = ax_helper::get_pid_by_bundle_id(config.bundle_id)
-        .ok_or_else(|| format!("Editor not running: {}", config.display_name))?;
+    let pid = owning_pid(config, window_id)?;
 
-    ax_helper::focus_window_by_id(pid, window_id)
+    ax_helper::set_window_minimized_by_id(pid, window_id, minimized)
 }
 
 /// Open a new editor window
@@ -790,10 +1730,17 @@ pub fn open_new_editor(bundle_id: &str) -> Result<(), String> {
     let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)
         .ok_or_else(|| format!("Unknown editor: {}", bundle_id))?;
 
-    let pid = ax_helper::get_pid_by_bundle_id(config.bundle_id)
-        .ok_or_else(|| format!("Editor not running: {}", config.display_name))?;
+    let pid = ax_helper::get_pid_by_bundle_id(config.bundle_id).ok_or_else(|| {
+        crate::i18n::t_with("error.editorNotRunning", &[("name", config.display_name)])
+    })?;
 
-    ax_helper::open_new_window_ax(pid)
+    match ax_helper::open_new_window_ax(pid)? {
+        Some(_) => Ok(()),
+        None => Err(crate::i18n::t_with(
+            "error.newWindowNotOpened",
+            &[("name", config.display_name)],
+        )),
+    }
 }
 
 /// Open a project directory in a specific editor
@@ -810,16 +1757,122 @@ pub fn open_project_in_editor(bundle_id: &str, path: &str) -> Result<(), String>
     Ok(())
 }
 
+/// Windows of an editor as (owning pid, CGWindowID), without resolving paths
+pub fn native_window_ids(bundle_id: &str) -> Result<Vec<(i32, u32)>, String> {
+    let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)
+        .ok_or_else(|| format!("Unknown editor: {}", bundle_id))?;
+    let pids = ax_helper::get_pids_by_bundle_id(config.bundle_id);
+    if pids.is_empty() {
+        return Err(crate::i18n::t_with(
+            "error.editorNotRunning",
+            &[("name", config.display_name)],
+        ));
+    }
+    Ok(collect_native_windows(config, &pids)?
+        .into_iter()
+        .map(|window| (window.pid, window.id))
+        .collect())
+}
+
+/// Checks that a closed window went away before looking for a dialog
+const CLOSE_CONFIRM_ATTEMPTS: u32 = 3;
+const CLOSE_CONFIRM_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Why a window did not close. Serialized so the tab bar can keep the tab
+/// while the editor waits for an answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CloseWindowError {
+    /// The window put up a sheet (usually "save changes?") and was brought to
+    /// front. `title` is the sheet's text, empty if it has none.
+    BlockedByDialog { title: String },
+    Failed { message: String },
+}
+
+impl std::fmt::Display for CloseWindowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BlockedByDialog { title } => write!(
+                f,
+                "{}",
+                crate::i18n::t_with("error.closeBlockedByDialog", &[("title", title)])
+            ),
+            Self::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for CloseWindowError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+/// Whether a window is still listed after its close button was pressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowPresence {
+    Gone,
+    Present,
+    /// The window list could not be read
+    Unknown,
+}
+
+fn window_presence(pid: i32, window_id: u32) -> WindowPresence {
+    match ax_helper::get_all_window_frames(pid) {
+        Ok(frames) if frames.iter().any(|(id, ..)| *id == window_id) => WindowPresence::Present,
+        Ok(_) => WindowPresence::Gone,
+        // 最後のウィンドウを閉じてエディタが終了した
+        Err(_) if !ax_helper::is_process_running(pid) => WindowPresence::Gone,
+        Err(e) => {
+            log::debug!("Failed to list windows of pid {}: {}", pid, e);
+            WindowPresence::Unknown
+        }
+    }
+}
+
+/// Error for a window that was not seen closing and has no sheet attached
+fn not_closed_error(presence: WindowPresence) -> CloseWindowError {
+    let key = match presence {
+        WindowPresence::Unknown => "error.windowCloseUnconfirmed",
+        WindowPresence::Gone | WindowPresence::Present => "error.windowNotClosed",
+    };
+    CloseWindowError::Failed {
+        message: crate::i18n::t(key),
+    }
+}
+
+/// Press a window's close button and check that it went away. A window that
+/// is still there with a sheet attached is brought to front and reported as
+/// blocked; any other window that is still listed (or can't be checked) failed.
+pub fn close_window_confirmed(pid: i32, window_id: u32) -> Result<(), CloseWindowError> {
+    ax_helper::close_window_by_id(pid, window_id)?;
+    let mut presence = WindowPresence::Unknown;
+    for _ in 0..CLOSE_CONFIRM_ATTEMPTS {
+        std::thread::sleep(CLOSE_CONFIRM_INTERVAL);
+        presence = window_presence(pid, window_id);
+        if presence == WindowPresence::Gone {
+            return Ok(());
+        }
+    }
+    let Some(title) = ax_helper::attached_sheet_text(pid, window_id) else {
+        return Err(not_closed_error(presence));
+    };
+    // ダイアログに答えられるようウィンドウを前面に出す
+    if let Err(e) = ax_helper::focus_window_by_id(pid, window_id) {
+        log::debug!("Failed to raise window {} with a dialog: {}", window_id, e);
+    }
+    Err(CloseWindowError::BlockedByDialog { title })
+}
+
 /// Close a specific editor window by CGWindowID
 /// Uses CGWindowID for reliable window identification regardless of title changes
-pub fn close_editor_window(bundle_id: &str, window_id: u32) -> Result<(), String> {
+pub fn close_editor_window(bundle_id: &str, window_id: u32) -> Result<(), CloseWindowError> {
     let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)
         .ok_or_else(|| format!("Unknown editor: {}", bundle_id))?;
 
-    let pid = ax_helper::get_pid_by_bundle_id(config.bundle_id)
-        .ok_or_else(|| format!("Editor not running: {}", config.display_name))?;
+    let pid = owning_pid(config, window_id)?;
 
-    ax_helper::close_window_by_id(pid, window_id)
+    close_window_confirmed(pid, window_id)
 }
 
 #[cfg(test)]
@@ -840,6 +1893,49 @@ mod tests {
         }
     }
 
+    fn instance_window(pid: i32, id: u32, is_frontmost: bool) -> NativeEditorWindow {
+        NativeEditorWindow::new("vscode", pid, id, "project".to_string(), is_frontmost, Vec::new())
+    }
+
+    #[test]
+    fn merged_instances_keep_every_window_and_one_active() {
+        let per_pid = vec![
+            (10, vec![instance_window(10, 100, true), instance_window(10, 101, false)]),
+            (20, vec![instance_window(20, 200, true)]),
+        ];
+
+        let windows = merge_instance_windows(per_pid.clone(), Some(20));
+        assert_eq!(windows.iter().map(|w| w.id).collect::<Vec<_>>(), vec![100, 101, 200]);
+        assert_eq!(active_window_id(&windows), Some(200));
+        assert_eq!(windows[0].pid, 10);
+
+        // Neither instance is frontmost: the first one's focused window wins
+        let windows = merge_instance_windows(per_pid, Some(999));
+        assert_eq!(active_window_id(&windows), Some(100));
+        assert_eq!(windows.iter().filter(|w| w.is_frontmost).count(), 1);
+    }
+
+    #[test]
+    fn running_editor_without_windows_is_still_running() {
+        let config = &EDITORS[0];
+        let running = running_editor(config, &[42], &[]);
+        assert!(running.is_running);
+        assert_eq!(running.pid, Some(42));
+        assert_eq!(running.window_count, 0);
+
+        let stopped = running_editor(config, &[], &[]);
+        assert!(!stopped.is_running);
+        assert_eq!(stopped.pid, None);
+    }
+
+    #[test]
+    fn active_window_is_found_among_duplicate_titles() {
+        let mut focused = native_window(200, "project");
+        focused.is_frontmost = true;
+        let windows = vec![native_window(100, "project"), focused, native_window(300, "")];
+        assert_eq!(active_window_id(&windows), Some(200));
+    }
+
     #[test]
     fn cursor_sessions_are_resolved_by_creation_ids_not_input_order() {
         let windows = vec![
@@ -1056,19 +2152,6 @@ mod tests {
         assert_eq!(second_info.1, "second");
     }
 
-    #[test]
-    fn percent_decode_basic() {
-        assert_eq!(percent_decode("hello%20world"), "hello world");
-        assert_eq!(percent_decode("/path/to/no%20encode"), "/path/to/no encode");
-        assert_eq!(percent_decode("no_encoding"), "no_encoding");
-    }
-
-    #[test]
-    fn percent_decode_japanese() {
-        // %E3%83%86%E3%82%B9%E3%83%88 = "テスト" in UTF-8
-        assert_eq!(percent_decode("%E3%83%86%E3%82%B9%E3%83%88"), "テスト");
-    }
-
     #[test]
     fn unsupported_editor_has_no_global_storage_file() {
         assert!(get_global_storage_file("zed").is_none());
@@ -1167,6 +2250,8 @@ mod tests {
             id: "cache-test",
             display_name: "Sample Editor",
             bundle_id: "com.example.editor",
+            bundle_ids: &["com.example.editor"],
+            process_name: "Sample Editor",
             app_name: "Sample Editor",
         };
         let first_path = PathBuf::from("/worktrees/one/project");
@@ -1190,7 +2275,7 @@ mod tests {
 
         prepare_window_path_resolution(&config, &windows, &workspace_state);
 
-        let cache = WINDOW_PATH_CACHE.lock().unwrap();
+        let cache = WINDOW_PATH_CACHE.read().unwrap();
         assert_eq!(
             cache.get(&(config.id.to_string(), 1, "project".to_string())),
             Some(&first_path)
@@ -1214,4 +2299,351 @@ mod tests {
             Some(PathBuf::from("/worktrees/two/project"))
         );
     }
+
+    fn editor_config(id: &str) -> &'static EditorConfig {
+        EDITORS.iter().find(|editor| editor.id == id).unwrap()
+    }
+
+    #[test]
+    fn remote_suffixes_are_split_from_project_names() {
+        let cursor = editor_config("cursor");
+        let vscode = editor_config("vscode");
+        let cases = [
+            ("project [SSH: myhost] — Cursor", cursor, "project", "SSH: myhost"),
+            ("main.rs — api [WSL: Ubuntu] — Cursor", cursor, "api", "WSL: Ubuntu"),
+            (
+                "app.ts — web [Dev Container: Node.js @ desktop-linux] — Visual Studio Code",
+                vscode,
+                "web",
+                "Dev Container: Node.js @ desktop-linux",
+            ),
+            (
+                "README.md — repo [Codespaces: fluffy-space-xyz] — Visual Studio Code",
+                vscode,
+                "repo",
+                "Codespaces: fluffy-space-xyz",
+            ),
+        ];
+        for (title, config, name, remote) in cases {
+            let parsed = parse_window_title(title, config);
+            assert_eq!(parsed.name, name, "{title}");
+            assert_eq!(parsed.remote.as_deref(), Some(remote), "{title}");
+        }
+    }
+
+    #[test]
+    fn local_brackets_are_kept_in_project_names() {
+        let parsed = parse_window_title("notes [draft] — Cursor", editor_config("cursor"));
+        assert_eq!(parsed.name, "notes [draft]");
+        assert_eq!(parsed.remote, None);
+        assert_eq!(extract_project_name("project — Cursor", editor_config("cursor")), "project");
+    }
+
+    #[test]
+    fn title_decorations_are_stripped_from_project_names() {
+        let vscode = editor_config("vscode");
+        let cursor = editor_config("cursor");
+        let zed = editor_config("zed");
+        let cases = [
+            // (title, config, name, has_unsaved)
+            ("• main.rs — api — Visual Studio Code", vscode, "api", true),
+            ("● main.rs — api — Visual Studio Code", vscode, "api", true),
+            ("main.rs — api (Workspace) — Visual Studio Code", vscode, "api", false),
+            ("• api (Workspace) — Visual Studio Code", vscode, "api", true),
+            ("main.rs — api — Visual Studio Code [Administrator]", vscode, "api", false),
+            ("\u{200B}main.rs — api\u{200B} — Visual Studio Code", vscode, "api", false),
+            ("• index.ts — web — Cursor", cursor, "web", true),
+            ("• web (Workspace) [SSH: box] — Cursor", cursor, "web", true),
+            ("web — Cursor [Superuser]", cursor, "web", false),
+            ("zed-project — main.rs", zed, "zed-project", false),
+            ("\u{FEFF}zed-project\u{200D} — main.rs", zed, "zed-project", false),
+            ("• zed-project — main.rs", zed, "zed-project", true),
+        ];
+        for (title, config, name, has_unsaved) in cases {
+            let parsed = parse_window_title(title, config);
+            assert_eq!(parsed.name, name, "{title}");
+            assert_eq!(parsed.has_unsaved, has_unsaved, "{title}");
+        }
+    }
+
+    #[test]
+    fn workspace_entries_point_at_the_code_workspace_file() {
+        let json = serde_json::json!({
+            "windowsState": {
+                "lastActiveWindow": {
+                    "workspaceIdentifier": {
+                        "id": "abc",
+                        "configURIPath": "file:///work/my%20team.code-workspace"
+                    }
+                },
+                "openedWindows": [
+                    { "workspace": "file:///work/my%20team.code-workspace" },
+                    { "folder": "file:///work/plain" }
+                ]
+            }
+        });
+        let (active_path, all_paths) = parse_open_workspace_paths(&json);
+        let workspace_file = PathBuf::from("/work/my team.code-workspace");
+        assert_eq!(active_path, Some(workspace_file.clone()));
+        assert_eq!(all_paths, vec![workspace_file.clone(), PathBuf::from("/work/plain")]);
+        assert!(is_code_workspace_file(&workspace_file));
+    }
+
+    #[test]
+    fn code_workspace_folders_resolve_relative_paths() {
+        let content = r#"{
+            // Folders in this workspace
+            "folders": [
+                { "path": "." },
+                { "path": "../shared/lib", "name": "lib" },
+                { "path": "/abs/tools" },
+                { "uri": "file:///remote%20ok/app" },
+                { "uri": "vscode-remote://ssh-remote+box/home/app" },
+            ],
+            /* trailing comma above is allowed in .code-workspace */
+            "settings": { "url": "http://example.com//path" }
+        }"#;
+        let folders = parse_code_workspace_folders(content, Path::new("/work/team"));
+        assert_eq!(
+            folders,
+            vec![
+                PathBuf::from("/work/team"),
+                PathBuf::from("/work/shared/lib"),
+                PathBuf::from("/abs/tools"),
+                PathBuf::from("/remote ok/app"),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_workspace_root_uses_the_first_existing_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("api")).unwrap();
+        let workspace_file = dir.path().join("team.code-workspace");
+        fs::write(
+            &workspace_file,
+            r#"{ "folders": [{ "path": "missing" }, { "path": "api" }] }"#,
+        )
+        .unwrap();
+
+        let (name, folder) = code_workspace_root(&workspace_file).unwrap();
+        assert_eq!(name, "team");
+        assert_eq!(folder, dir.path().join("api"));
+    }
+
+    #[test]
+    fn workspace_suffix_marks_multi_root_titles() {
+        let vscode = editor_config("vscode");
+        let parsed = parse_window_title("a.rs — team (Workspace) — Visual Studio Code", vscode);
+        assert_eq!(parsed.name, "team");
+        assert!(parsed.is_multi_root);
+        assert!(!parse_window_title("a.rs — team — Visual Studio Code", vscode).is_multi_root);
+    }
+
+    #[test]
+    fn title_file_segment_is_parsed_per_editor() {
+        let cursor = editor_config("cursor");
+        let zed = editor_config("zed");
+        let parsed = parse_window_title("• main.rs — api — Cursor", cursor);
+        assert_eq!(parsed.file_name.as_deref(), Some("main.rs"));
+        assert_eq!(parse_window_title("api — Cursor", cursor).file_name, None);
+        assert_eq!(
+            parse_window_title("api — src/lib.rs", zed).file_name.as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(parse_window_title("api", zed).file_name, None);
+    }
+
+    #[test]
+    fn same_named_projects_are_told_apart_by_the_title_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let work = tmp.path().join("work/api");
+        let personal = tmp.path().join("personal/api");
+        fs::create_dir_all(work.join("src")).unwrap();
+        fs::create_dir_all(personal.join("node_modules/pkg")).unwrap();
+        fs::write(work.join("src/server.rs"), "").unwrap();
+        fs::write(work.join("README.md"), "").unwrap();
+        fs::write(personal.join("README.md"), "").unwrap();
+        // Files under skipped directories don't count
+        fs::write(personal.join("node_modules/pkg/server.rs"), "").unwrap();
+        let candidates = vec![personal.clone(), work.clone()];
+
+        assert_eq!(candidate_containing_file(&candidates, "server.rs"), Some(work));
+        // Both contain README.md, so the title alone can't decide
+        assert_eq!(candidate_containing_file(&candidates, "README.md"), None);
+        assert_eq!(candidate_containing_file(&candidates, "missing.rs"), None);
+    }
+
+    #[test]
+    fn collision_is_resolved_before_the_cache_is_consulted() {
+        let tmp = tempfile::tempdir().unwrap();
+        let work = tmp.path().join("work/collision-api");
+        let personal = tmp.path().join("personal/collision-api");
+        fs::create_dir_all(&work).unwrap();
+        fs::create_dir_all(&personal).unwrap();
+        fs::write(personal.join("notes.md"), "").unwrap();
+        let state = OpenWorkspaceState {
+            is_available: true,
+            active_path: None,
+            all_paths: vec![work.clone(), personal.clone()],
+            paths_by_name: HashMap::from([(
+                "collision-api".to_string(),
+                vec![work.clone(), personal.clone()],
+            )]),
+        };
+        // A stale assignment from an earlier refresh
+        cache_window_path("collision-test", 4242, "collision-api", &work);
+
+        let path = resolve_project_path(
+            "collision-api",
+            Some("notes.md"),
+            "collision-test",
+            -1,
+            4242,
+            2,
+            &state,
+        );
+        assert_eq!(path, Ok((personal.clone(), PathSource::SubdirScan)));
+        let cache = WINDOW_PATH_CACHE.read().unwrap();
+        let key = ("collision-test".to_string(), 4242, "collision-api".to_string());
+        assert_eq!(cache.get(&key), Some(&personal));
+    }
+
+    #[test]
+    fn workspace_storage_changes_are_recognized() {
+        let user_dir = Path::new("/Users/me/Library/Application Support/Code/User");
+        assert!(is_workspace_storage_change(&user_dir.join("globalStorage/storage.json")));
+        assert!(is_workspace_storage_change(&user_dir.join("workspaceStorage/0a1b2c")));
+        assert!(!is_workspace_storage_change(&user_dir.join("globalStorage/state.vscdb")));
+        assert!(!is_workspace_storage_change(
+            &user_dir.join("workspaceStorage/0a1b2c/state.vscdb")
+        ));
+    }
+
+    #[test]
+    fn document_paths_survive_a_workspace_rescan() {
+        let config = editor_config("zed");
+        let document_root = PathBuf::from("/outside/any/workspace");
+        let folder_root = PathBuf::from("/closed/workspace");
+        cache_document_path(config.id, 9001, "doc-project", &document_root);
+        cache_window_path(config.id, 9002, "folder-project", &folder_root);
+        let state = OpenWorkspaceState {
+            is_available: true,
+            ..OpenWorkspaceState::default()
+        };
+        let ax_windows = vec![
+            (9001, "doc-project".to_string(), false),
+            (9002, "folder-project".to_string(), false),
+        ];
+
+        prepare_window_path_resolution(config, &ax_windows, &state);
+
+        let cache = WINDOW_PATH_CACHE.read().unwrap();
+        let document_key = (config.id.to_string(), 9001, "doc-project".to_string());
+        let folder_key = (config.id.to_string(), 9002, "folder-project".to_string());
+        assert_eq!(cache.get(&document_key), Some(&document_root));
+        assert_eq!(cache.get(&folder_key), None);
+    }
+
+    /// Resolver that records how many calls ran at once. Each call waits (up to 2s)
+    /// until `wait_for` calls have started, so parallel workers are sure to overlap.
+    fn counting_resolver(
+        wait_for: usize,
+    ) -> (Arc<AtomicUsize>, impl Fn(&u32) -> u32 + Send + Sync + 'static) {
+        let running = Arc::new(AtomicUsize::new(0));
+        let started = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let peak_for_resolver = Arc::clone(&peak);
+        let resolve = move |id: &u32| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak_for_resolver.fetch_max(now, Ordering::SeqCst);
+            started.fetch_add(1, Ordering::SeqCst);
+            let until = Instant::now() + Duration::from_secs(2);
+            while started.load(Ordering::SeqCst) < wait_for && Instant::now() < until {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            running.fetch_sub(1, Ordering::SeqCst);
+            *id * 2
+        };
+        (peak, resolve)
+    }
+
+    #[test]
+    fn slow_resolvers_run_in_parallel() {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        let (serial_peak, resolve_one) = counting_resolver(1);
+        let serial = resolve_concurrently((0..8).collect(), 1, deadline, resolve_one, |_| 0);
+
+        let (parallel_peak, resolve_one) = counting_resolver(8);
+        let parallel = resolve_concurrently((0..8).collect(), 8, deadline, resolve_one, |_| 0);
+
+        assert_eq!(serial, (0..8).map(|id| id * 2).collect::<Vec<_>>());
+        assert_eq!(parallel, serial);
+        assert_eq!(serial_peak.load(Ordering::SeqCst), 1);
+        assert_eq!(parallel_peak.load(Ordering::SeqCst), 8);
+    }
+
+    #[test]
+    fn items_missing_the_deadline_fall_back() {
+        let started = Instant::now();
+        let results = resolve_concurrently(
+            vec![1u64, 2000, 3],
+            3,
+            Instant::now() + Duration::from_millis(200),
+            |delay: &u64| {
+                std::thread::sleep(Duration::from_millis(*delay));
+                Some(*delay)
+            },
+            |_| None,
+        );
+
+        assert_eq!(results, vec![Some(1), None, Some(3)]);
+        assert!(started.elapsed() < Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn unresolved_windows_keep_their_title_information() {
+        let config = editor_config("vscode").clone();
+        let context = WindowResolutionContext {
+            config,
+            session_resolutions: HashMap::new(),
+            workspace_state: OpenWorkspaceState::default(),
+            project_window_counts: HashMap::new(),
+        };
+        let window = native_window(7, "● main.rs — api — Visual Studio Code");
+
+        let unresolved = unresolved_window(&window, &context).unwrap();
+        assert_eq!(unresolved.name, "api");
+        assert!(unresolved.has_unsaved);
+        assert!(unresolved.path.is_empty());
+        assert_eq!(unresolved.path_source, PathSource::Unresolved);
+        assert_eq!(unresolved.resolution, WorkspaceResolution::Unresolved);
+    }
+
+    #[test]
+    fn blocked_close_is_a_distinct_error_kind() {
+        let blocked = CloseWindowError::BlockedByDialog {
+            title: "Do you want to save the changes?".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&blocked).unwrap(),
+            serde_json::json!({
+                "kind": "blocked_by_dialog",
+                "title": "Do you want to save the changes?",
+            })
+        );
+        let failed = CloseWindowError::from("Window with ID 7 not found".to_string());
+        assert_eq!(failed.to_string(), "Window with ID 7 not found");
+    }
+
+    #[test]
+    fn windows_still_listed_without_a_sheet_fail_to_close() {
+        let present = not_closed_error(WindowPresence::Present);
+        let unknown = not_closed_error(WindowPresence::Unknown);
+        assert!(matches!(present, CloseWindowError::Failed { .. }));
+        assert!(matches!(unknown, CloseWindowError::Failed { .. }));
+        // 読み取りに失敗しただけなら「閉じなかった」とは言わない
+        assert_ne!(present, unknown);
+    }
 }
@@ -1,9 +1,19 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
 /// Editor configuration for multi-editor support
+///
+/// `bundle_id` is the ID the app stores and reports the editor under (tab
+/// order keys, events). A running editor is also recognized by any of
+/// `bundle_ids`, and as a last resort by its executable and app name, so an
+/// update that changes the bundle ID doesn't make the editor disappear.
 #[derive(Debug, Clone)]
 pub struct EditorConfig {
     pub id: &'static str,           // "vscode", "cursor", "zed", "codex", "claude"
     pub display_name: &'static str, // "Visual Studio Code", "Cursor"
     pub bundle_id: &'static str,    // macOS bundle ID
+    pub bundle_ids: &'static [&'static str], // All IDs it has shipped under, bundle_id first
+    pub process_name: &'static str, // Executable name in Contents/MacOS
     pub app_name: &'static str,     // App name for title parsing
 }
 
@@ -13,42 +23,102 @@ pub const EDITORS: &[EditorConfig] = &[
         id: "vscode",
         display_name: "Visual Studio Code",
         bundle_id: "com.microsoft.VSCode",
+        bundle_ids: &["com.microsoft.VSCode"],
+        process_name: "Electron",
         app_name: "Visual Studio Code",
     },
     EditorConfig {
         id: "cursor",
         display_name: "Cursor",
         bundle_id: "com.todesktop.230313mzl4w4u92",
+        bundle_ids: &["com.todesktop.230313mzl4w4u92"],
+        process_name: "Cursor",
         app_name: "Cursor",
     },
     EditorConfig {
         id: "zed",
         display_name: "Zed",
         bundle_id: "dev.zed.Zed",
+        bundle_ids: &["dev.zed.Zed"],
+        process_name: "zed",
         app_name: "Zed",
     },
     EditorConfig {
         id: "codex",
         display_name: "Codex",
         bundle_id: "com.openai.codex",
+        bundle_ids: &["com.openai.codex"],
+        process_name: "Codex",
         app_name: "Codex",
     },
     EditorConfig {
         id: "claude",
         display_name: "Claude",
         bundle_id: "com.anthropic.claudefordesktop",
+        bundle_ids: &["com.anthropic.claudefordesktop"],
+        process_name: "Claude",
         app_name: "Claude",
     },
 ];
 
-/// Get editor config by bundle ID
+/// Bundle IDs already logged by `editor_for_app`
+static UNKNOWN_BUNDLE_IDS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+impl EditorConfig {
+    /// Whether the editor has shipped under this bundle ID
+    pub fn has_bundle_id(&self, bundle_id: &str) -> bool {
+        self.bundle_ids.contains(&bundle_id)
+    }
+}
+
+/// Get editor config by bundle ID (any ID the editor has shipped under)
 pub fn get_editor_by_bundle_id(bundle_id: &str) -> Option<&'static EditorConfig> {
-    EDITORS.iter().find(|e| e.bundle_id == bundle_id)
+    EDITORS.iter().find(|e| e.has_bundle_id(bundle_id))
+}
+
+/// The editor a running app is, from its bundle ID or else from its
+/// executable and localized name. An editor found only by name is logged once
+/// per bundle ID so the new ID can be added to `bundle_ids`.
+pub fn editor_for_app(
+    bundle_id: Option<&str>,
+    executable: Option<&str>,
+    localized_name: Option<&str>,
+) -> Option<&'static EditorConfig> {
+    if let Some(editor) = bundle_id.and_then(get_editor_by_bundle_id) {
+        return Some(editor);
+    }
+    let (executable, localized_name) = (executable?, localized_name?);
+    let editor = EDITORS
+        .iter()
+        .find(|e| e.process_name == executable && e.display_name == localized_name)?;
+    let bundle_id = bundle_id.unwrap_or_default();
+    let mut logged = UNKNOWN_BUNDLE_IDS.lock().unwrap_or_else(|e| e.into_inner());
+    if logged.get_or_insert_with(HashSet::new).insert(bundle_id.to_string()) {
+        log::warn!(
+            "Treating {} ({}) as {}: unknown bundle ID",
+            localized_name,
+            bundle_id,
+            editor.id
+        );
+    }
+    Some(editor)
+}
+
+/// Get editor config by editor ID ("cursor")
+pub fn get_editor_by_id(id: &str) -> Option<&'static EditorConfig> {
+    EDITORS.iter().find(|e| e.id == id)
 }
 
-/// Check if a bundle ID belongs to a supported editor
+/// Editors the user hasn't turned off in Settings
+pub fn enabled_editors() -> impl Iterator<Item = &'static EditorConfig> {
+    EDITORS
+        .iter()
+        .filter(|e| !crate::window_filter::is_editor_disabled(e.id))
+}
+
+/// Check if a bundle ID belongs to a supported editor that is turned on
 pub fn is_supported_editor(bundle_id: &str) -> bool {
-    EDITORS.iter().any(|e| e.bundle_id == bundle_id)
+    enabled_editors().any(|e| e.has_bundle_id(bundle_id))
 }
 
 /// Get all supported editor bundle IDs
@@ -56,3 +126,21 @@ pub fn is_supported_editor(bundle_id: &str) -> bool {
 pub fn get_supported_bundle_ids() -> Vec<&'static str> {
     EDITORS.iter().map(|e| e.bundle_id).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_is_found_under_a_new_bundle_id_by_its_name() {
+        let cursor = "com.todesktop.230313mzl4w4u92";
+        assert_eq!(editor_for_app(Some(cursor), None, None).map(|e| e.id), Some("cursor"));
+
+        let renamed = editor_for_app(Some("com.todesktop.9999"), Some("Cursor"), Some("Cursor"));
+        assert_eq!(renamed.map(|e| e.bundle_id), Some(cursor));
+
+        // 実行ファイル名だけ一致する別のアプリは対象外
+        assert!(editor_for_app(Some("com.example.app"), Some("Cursor"), Some("Other")).is_none());
+        assert!(editor_for_app(Some("com.example.app"), None, Some("Cursor")).is_none());
+    }
+}
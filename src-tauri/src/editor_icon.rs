@@ -0,0 +1,205 @@
+//! App icons for editor tabs
+//!
+//! `get_editor_icon` renders an editor's app icon as a PNG of the requested
+//! size. Rendered icons are cached under Application Support, keyed by the
+//! app bundle's modification time, so a repeat call is a file read and an app
+//! update (which replaces the bundle) renders the new icon.
+
+use crate::i18n;
+#[cfg(target_os = "macos")]
+use objc2::AllocAnyThread;
+#[cfg(target_os = "macos")]
+use objc2_app_kit::{
+    NSBitmapImageFileType, NSBitmapImageRep, NSDeviceRGBColorSpace, NSGraphicsContext,
+    NSWorkspace,
+};
+#[cfg(target_os = "macos")]
+use objc2_foundation::{NSDictionary, NSPoint, NSRect, NSSize, NSString};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::ipc::Response;
+
+/// Smallest and largest icon edge, in pixels
+const MIN_SIZE: u32 = 16;
+const MAX_SIZE: u32 = 1024;
+
+/// Why an icon could not be returned. Serialized so the UI can fall back to
+/// a generic icon for editors that are not installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EditorIconError {
+    /// No app with this bundle ID is installed
+    NotInstalled { bundle_id: String },
+    /// Rendering or the cache failed
+    Failed { message: String },
+}
+
+impl std::fmt::Display for EditorIconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotInstalled { bundle_id } => {
+                write!(f, "{}", i18n::t_with("error.appNotInstalled", &[("app", bundle_id)]))
+            }
+            Self::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+fn failed(message: impl Into<String>) -> EditorIconError {
+    EditorIconError::Failed {
+        message: message.into(),
+    }
+}
+
+/// ~/Library/Application Support/<identifier>/icons
+fn cache_dir() -> PathBuf {
+    crate::settings_store::app_support_dir().join("icons")
+}
+
+/// Cache files of one bundle ID and size share this prefix
+fn cache_prefix(bundle_id: &str, size: u32) -> String {
+    format!("{}-{}-", bundle_id, size)
+}
+
+/// The bundle's mtime is part of the name, so an updated app misses the cache
+fn cache_file_name(bundle_id: &str, size: u32, modified: u64) -> String {
+    format!("{}{}.png", cache_prefix(bundle_id, size), modified)
+}
+
+/// Seconds since the epoch at which the app bundle was last modified
+fn bundle_modified(app_path: &Path) -> Result<u64, EditorIconError> {
+    let modified = fs::metadata(app_path)
+        .and_then(|metadata| metadata.modified())
+        .map_err(|e| failed(format!("Cannot read {}: {}", app_path.display(), e)))?;
+    Ok(modified
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0))
+}
+
+/// Remove icons rendered from earlier versions of the app
+fn remove_stale(dir: &Path, bundle_id: &str, size: u32, current: &str) {
+    let prefix = cache_prefix(bundle_id, size);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(&prefix) && name != current {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Draw the Finder icon of the app bundle into a `size`×`size` bitmap
+#[cfg(target_os = "macos")]
+fn render_png(app_path: &Path, size: u32) -> Result<Vec<u8>, EditorIconError> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let image = workspace.iconForFile(&NSString::from_str(&app_path.to_string_lossy()));
+
+    let pixels = size as isize;
+    // planes を null にすると NSBitmapImageRep がバッファを確保する
+    let rep = unsafe {
+        NSBitmapImageRep::initWithBitmapDataPlanes_pixelsWide_pixelsHigh_bitsPerSample_samplesPerPixel_hasAlpha_isPlanar_colorSpaceName_bytesPerRow_bitsPerPixel(
+            NSBitmapImageRep::alloc(),
+            std::ptr::null_mut(),
+            pixels,
+            pixels,
+            8,
+            4,
+            true,
+            false,
+            NSDeviceRGBColorSpace,
+            0,
+            0,
+        )
+    }
+    .ok_or_else(|| failed("Could not allocate the icon bitmap"))?;
+    let context = NSGraphicsContext::graphicsContextWithBitmapImageRep(&rep)
+        .ok_or_else(|| failed("Could not draw into the icon bitmap"))?;
+
+    NSGraphicsContext::saveGraphicsState_class();
+    NSGraphicsContext::setCurrentContext(Some(&context));
+    let edge = f64::from(size);
+    image.drawInRect(NSRect::new(NSPoint::new(0.0, 0.0), NSSize::new(edge, edge)));
+    context.flushGraphics();
+    NSGraphicsContext::restoreGraphicsState_class();
+
+    let data = unsafe {
+        rep.representationUsingType_properties(NSBitmapImageFileType::PNG, &NSDictionary::new())
+    }
+    .ok_or_else(|| failed("Could not encode the icon as PNG"))?;
+    Ok(data.to_vec())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn render_png(_app_path: &Path, _size: u32) -> Result<Vec<u8>, EditorIconError> {
+    Err(failed("Not supported on this platform"))
+}
+
+/// PNG bytes of the icon, from the cache when the app has not changed
+fn load_icon(bundle_id: &str, size: u32) -> Result<Vec<u8>, EditorIconError> {
+    let app_path = crate::path_actions::application_path(bundle_id).ok_or_else(|| {
+        EditorIconError::NotInstalled {
+            bundle_id: bundle_id.to_string(),
+        }
+    })?;
+    let size = size.clamp(MIN_SIZE, MAX_SIZE);
+    let dir = cache_dir();
+    let file_name = cache_file_name(bundle_id, size, bundle_modified(&app_path)?);
+    let path = dir.join(&file_name);
+    if let Ok(bytes) = fs::read(&path) {
+        return Ok(bytes);
+    }
+
+    let png = render_png(&app_path, size)?;
+    // キャッシュに書けなくてもアイコン自体は返す
+    match fs::create_dir_all(&dir).and_then(|()| fs::write(&path, &png)) {
+        Ok(()) => remove_stale(&dir, bundle_id, size, &file_name),
+        Err(e) => log::debug!("Failed to cache the icon of {}: {}", bundle_id, e),
+    }
+    Ok(png)
+}
+
+/// Tauri command: the app icon of an editor as PNG bytes (an ArrayBuffer in
+/// the frontend). `size` is the edge in pixels, clamped to 16–1024.
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_editor_icon(bundle_id: String, size: u32) -> Result<Response, EditorIconError> {
+    crate::run_blocking(move || load_icon(&bundle_id, size))
+        .await
+        .map_err(failed)?
+        .map(Response::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_names_change_with_the_app_bundle() {
+        let before = cache_file_name("com.microsoft.VSCode", 64, 1_700_000_000);
+        let after = cache_file_name("com.microsoft.VSCode", 64, 1_700_086_400);
+        assert_eq!(before, "com.microsoft.VSCode-64-1700000000.png");
+        assert_ne!(before, after);
+        assert!(after.starts_with(&cache_prefix("com.microsoft.VSCode", 64)));
+    }
+
+    #[test]
+    fn stale_icons_of_the_same_size_are_removed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let old = cache_file_name("dev.zed.Zed", 32, 1);
+        let current = cache_file_name("dev.zed.Zed", 32, 2);
+        let other_size = cache_file_name("dev.zed.Zed", 64, 1);
+        for name in [&old, &current, &other_size] {
+            fs::write(tmp.path().join(name), b"png").unwrap();
+        }
+
+        remove_stale(tmp.path(), "dev.zed.Zed", 32, &current);
+        assert!(!tmp.path().join(&old).exists());
+        assert!(tmp.path().join(&current).exists());
+        assert!(tmp.path().join(&other_size).exists());
+    }
+}
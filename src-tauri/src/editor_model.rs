@@ -4,6 +4,8 @@ use std::path::PathBuf;
 #[derive(Debug, Clone)]
 pub struct NativeEditorWindow {
     pub runtime_id: String,
+    /// Process owning the window (an editor can run more than one instance)
+    pub pid: i32,
     pub id: u32,
     pub title: String,
     pub is_frontmost: bool,
@@ -21,6 +23,7 @@ impl NativeEditorWindow {
     ) -> Self {
         Self {
             runtime_id: format!("{}:{}:{}", bundle_id, editor_pid, id),
+            pid: editor_pid,
             id,
             title,
             is_frontmost,
@@ -45,6 +48,44 @@ pub enum WorkspaceResolution {
     Unresolved,
 }
 
+/// Where a window's path came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathSource {
+    /// Cursor's IPC session for the window
+    Session,
+    /// The editor's storage.json or workspaceStorage history
+    WorkspaceStorage,
+    /// The title's file was found under one of several same-named workspaces
+    SubdirScan,
+    /// The focused document's path (AXDocument)
+    AxDocument,
+    #[default]
+    Unresolved,
+}
+
+/// Tab color and optional emoji for a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectAppearance {
+    /// Tab palette color id such as "blue", or "#rrggbb"
+    pub color: String,
+    #[serde(default)]
+    pub emoji: Option<String>,
+    /// The color was derived from the path; the user hasn't picked one
+    #[serde(default)]
+    pub is_default: bool,
+}
+
+/// What the window has open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceKind {
+    Folder,
+    /// A .code-workspace file; `path` is its first folder
+    MultiRoot,
+    File,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EditorWindow {
     pub runtime_id: String,
@@ -57,6 +98,49 @@ pub struct EditorWindow {
     pub bundle_id: String,
     pub editor_name: String,
     pub resolution: WorkspaceResolution,
+    /// The editor is hidden (Cmd+H); the window is kept from the last snapshot
+    #[serde(default)]
+    pub hidden: bool,
+    /// Remote or container the window is attached to ("SSH: myhost"); path stays empty
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// The title shows the unsaved-changes dot
+    #[serde(default)]
+    pub has_unsaved: bool,
+    /// None while the path is unresolved
+    #[serde(default)]
+    pub workspace_kind: Option<WorkspaceKind>,
+    #[serde(default)]
+    pub path_source: PathSource,
+    /// Custom or path-derived appearance; None while the path is unresolved
+    #[serde(default)]
+    pub appearance: Option<ProjectAppearance>,
+}
+
+#[cfg(test)]
+impl EditorWindow {
+    /// Resolved window of a project named "project", for tests. Other fields are
+    /// set with struct update syntax.
+    pub(crate) fn for_test(bundle_id: &str, id: u32, path: &str) -> Self {
+        Self {
+            runtime_id: String::new(),
+            id,
+            name: "project".to_string(),
+            path: path.to_string(),
+            branch: None,
+            repository_id: None,
+            repository_name: None,
+            bundle_id: bundle_id.to_string(),
+            editor_name: String::new(),
+            resolution: WorkspaceResolution::Exact,
+            hidden: false,
+            remote: None,
+            has_unsaved: false,
+            workspace_kind: None,
+            path_source: PathSource::Session,
+            appearance: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,4 +148,71 @@ pub struct EditorState {
     pub is_active: bool,
     pub windows: Vec<EditorWindow>,
     pub active_index: Option<usize>,
+    /// The editor process is alive, even if it has no windows
+    #[serde(default)]
+    pub is_running: bool,
+    #[serde(default)]
+    pub pid: Option<i32>,
+}
+
+impl EditorState {
+    /// State for an editor that is not running (or unknown)
+    pub fn not_running(is_active: bool) -> Self {
+        Self {
+            is_active,
+            windows: Vec::new(),
+            active_index: None,
+            is_running: false,
+            pid: None,
+        }
+    }
+}
+
+/// Running state of one supported editor, as listed by `get_running_editors`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunningEditor {
+    pub bundle_id: String,
+    pub editor_name: String,
+    pub is_running: bool,
+    pub pid: Option<i32>,
+    pub window_count: usize,
+}
+
+/// Payload for editor-launched events, sent once the editor's first window appears
+#[derive(Clone, Serialize, Debug)]
+pub struct EditorLaunchedPayload {
+    pub bundle_id: String,
+}
+
+/// Payload for app activation events
+#[derive(Clone, Serialize, Debug)]
+pub struct AppActivationPayload {
+    pub app_type: String, // "editor", "tab_manager", or "other"
+    pub bundle_id: Option<String>,
+    pub is_on_primary_screen: bool,
+    pub covers_editor: bool,
+}
+
+/// Frontmost app returned by `get_frontmost_app`
+#[derive(Clone, Serialize, Debug, PartialEq, Eq)]
+pub struct FrontmostApp {
+    pub app_type: String, // "editor", "tab_manager", or "other"
+    pub bundle_id: Option<String>,
+    pub pid: i32,
+    pub is_on_primary_screen: bool,
+    /// An editor or the tab manager itself is frontmost, so the tabs stay relevant
+    pub is_editor_context: bool,
+}
+
+/// Result of a focus request, checked against what actually came to front
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusOutcome {
+    Focused,
+    /// The editor ignored the request (or macOS kept another Space)
+    Unverified {
+        /// App in front instead, None if it could not be read
+        frontmost_bundle_id: Option<String>,
+        /// Window the editor has focused instead, if any
+        focused_window_id: Option<u32>,
+    },
 }
@@ -0,0 +1,195 @@
+//! Focus history across editors, for "back to the previous project"
+//!
+//! Every AXFocusedWindowChanged from an editor moves that window to the front
+//! of a short most-recently-used list. `focus_previous_window` walks the list
+//! past the current window, like Cmd+` but across editors. Entries keep the
+//! project path as well, so a window that came back under a new id after its
+//! editor restarted is still found.
+
+use crate::editor::{self, EditorWindow};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Windows remembered, most recent first
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FocusEntry {
+    bundle_id: String,
+    window_id: u32,
+    /// Empty until the window's path is resolved
+    path: String,
+}
+
+static HISTORY: Mutex<VecDeque<FocusEntry>> = Mutex::new(VecDeque::new());
+
+fn push_entry(history: &mut VecDeque<FocusEntry>, entry: FocusEntry) {
+    history.retain(|existing| {
+        !(existing.bundle_id == entry.bundle_id && existing.window_id == entry.window_id)
+    });
+    history.push_front(entry);
+    history.truncate(MAX_ENTRIES);
+}
+
+/// Record that an editor window got focus (called from the AX observer)
+pub fn record_focus(bundle_id: &str, window_id: u32) {
+    let path = crate::window_registry::window_path(bundle_id, window_id).unwrap_or_default();
+    if let Ok(mut history) = HISTORY.lock() {
+        push_entry(
+            &mut history,
+            FocusEntry {
+                bundle_id: bundle_id.to_string(),
+                window_id,
+                path,
+            },
+        );
+    }
+}
+
+/// Fill in paths resolved after the window was focused (called per snapshot)
+pub fn update_paths(windows: &[EditorWindow]) {
+    let Ok(mut history) = HISTORY.lock() else {
+        return;
+    };
+    for entry in history.iter_mut() {
+        if let Some(window) = windows.iter().find(|window| {
+            window.bundle_id == entry.bundle_id
+                && window.id == entry.window_id
+                && !window.path.is_empty()
+        }) {
+            entry.path.clone_from(&window.path);
+        }
+    }
+}
+
+/// Focused windows as (bundle_id, window_id), most recent first
+pub fn recent_windows() -> Vec<(String, u32)> {
+    let history = HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+    history
+        .iter()
+        .map(|entry| (entry.bundle_id.clone(), entry.window_id))
+        .collect()
+}
+
+/// The most recent entry, other than `current`, that still has a window.
+/// Falls back to a window with the same path when the id is gone.
+/// Entries with no matching window are dropped.
+fn pick_previous(
+    history: &mut VecDeque<FocusEntry>,
+    windows: &[EditorWindow],
+    current: Option<(&str, u32)>,
+) -> Option<(String, u32)> {
+    let is_current = |bundle_id: &str, window_id: u32| current == Some((bundle_id, window_id));
+    let mut found = None;
+    history.retain(|entry| {
+        if found.is_some() || is_current(&entry.bundle_id, entry.window_id) {
+            return true;
+        }
+        let by_id = windows
+            .iter()
+            .find(|window| window.bundle_id == entry.bundle_id && window.id == entry.window_id);
+        // エディタ再起動でウィンドウ ID が変わったら、同じパスのウィンドウを探す
+        let by_path = || {
+            windows.iter().find(|window| {
+                !entry.path.is_empty()
+                    && window.path == entry.path
+                    && !is_current(&window.bundle_id, window.id)
+            })
+        };
+        match by_id.or_else(by_path) {
+            Some(window) => {
+                found = Some((window.bundle_id.clone(), window.id));
+                true
+            }
+            None => false,
+        }
+    });
+    found
+}
+
+/// Focus the window that was in front before the current one
+pub fn focus_previous_window() -> Result<(), String> {
+    let (windows, active_id) = editor::get_all_editor_window_snapshot();
+    let current = active_id.and_then(|id| {
+        windows
+            .iter()
+            .find(|window| window.id == id)
+            .map(|window| (window.bundle_id.clone(), id))
+    });
+    let target = {
+        let mut history = HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+        pick_previous(
+            &mut history,
+            &windows,
+            current.as_ref().map(|(bundle_id, id)| (bundle_id.as_str(), *id)),
+        )
+    };
+    let (bundle_id, window_id) = target.ok_or_else(|| "No previous window".to_string())?;
+    editor::focus_editor_window(&bundle_id, window_id).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(bundle_id: &str, id: u32, path: &str) -> EditorWindow {
+        EditorWindow::for_test(bundle_id, id, path)
+    }
+
+    fn entry(bundle_id: &str, window_id: u32, path: &str) -> FocusEntry {
+        FocusEntry {
+            bundle_id: bundle_id.to_string(),
+            window_id,
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn refocusing_moves_a_window_to_the_front() {
+        let mut history = VecDeque::new();
+        push_entry(&mut history, entry("code", 1, "/a"));
+        push_entry(&mut history, entry("zed", 2, "/b"));
+        push_entry(&mut history, entry("code", 1, "/a"));
+        assert_eq!(history, vec![entry("code", 1, "/a"), entry("zed", 2, "/b")]);
+    }
+
+    #[test]
+    fn previous_window_can_be_in_another_editor() {
+        let mut history = VecDeque::from(vec![entry("code", 1, "/a"), entry("zed", 2, "/b")]);
+        let windows = [window("code", 1, "/a"), window("zed", 2, "/b")];
+
+        let previous = pick_previous(&mut history, &windows, Some(("code", 1)));
+        assert_eq!(previous, Some(("zed".to_string(), 2)));
+    }
+
+    #[test]
+    fn closed_windows_are_skipped_and_forgotten() {
+        let mut history = VecDeque::from(vec![
+            entry("code", 1, "/a"),
+            entry("code", 2, ""),
+            entry("code", 3, "/c"),
+        ]);
+        let windows = [window("code", 1, "/a"), window("code", 3, "/c")];
+
+        let previous = pick_previous(&mut history, &windows, Some(("code", 1)));
+        assert_eq!(previous, Some(("code".to_string(), 3)));
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn restarted_editor_windows_are_matched_by_path() {
+        let mut history = VecDeque::from(vec![entry("zed", 5, "/b"), entry("code", 1, "/a")]);
+        // VSCode を再起動してウィンドウ ID が 1 -> 9 に変わった
+        let windows = [window("zed", 5, "/b"), window("code", 9, "/a")];
+
+        let previous = pick_previous(&mut history, &windows, Some(("zed", 5)));
+        assert_eq!(previous, Some(("code".to_string(), 9)));
+    }
+
+    #[test]
+    fn late_resolved_paths_are_filled_in() {
+        push_entry(&mut HISTORY.lock().unwrap(), entry("history-test", 77, ""));
+        update_paths(&[window("history-test", 77, "/resolved")]);
+        assert_eq!(HISTORY.lock().unwrap()[0].path, "/resolved");
+    }
+}
@@ -0,0 +1,232 @@
+//! Backend string localization
+//!
+//! Strings the backend shows on its own (tray menu, notification actions and
+//! bodies, error messages returned to the UI) are looked up here instead of
+//! being hardcoded. The locale follows the same store key as the frontend's
+//! language setting and falls back to the macOS preferred language.
+
+use crate::settings_store::STORE_FILE;
+#[cfg(target_os = "macos")]
+use objc2_foundation::NSLocale;
+use std::sync::{LazyLock, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store key of the UI language
+pub(crate) const LOCALE_KEY: &str = "language";
+
+/// Used when a key or locale is missing
+const FALLBACK_LOCALE: &str = "en";
+
+/// String tables by locale. Adding a locale only needs a new table here.
+const LOCALES: &[(&str, &[(&str, &str)])] = &[("en", EN), ("ja", JA)];
+
+const EN: &[(&str, &str)] = &[
+    ("tray.settings", "Settings..."),
+    ("tray.quit", "Quit Editor Tab Manager"),
+    ("tray.moreWindows", "More Windows..."),
+    ("tray.pauseShortcuts", "Pause Shortcuts"),
+    ("tray.launchAtLogin", "Launch at Login"),
+    ("tray.tooltip", "Editor Tab Manager"),
+    ("tray.waitingOne", "1 project waiting"),
+    ("tray.waitingMany", "{count} projects waiting"),
+    ("notification.focus", "Focus Window"),
+    ("notification.dismiss", "Dismiss"),
+    ("notification.mute", "Mute This Project"),
+    ("notification.finishedAfter", "Finished after {elapsed}"),
+    ("notification.updateAvailable", "Editor Tab Manager {version} is available"),
+    ("notification.updateCurrentVersion", "You have version {version}"),
+    ("error.notificationPermission", "Notification permission not granted ({status})"),
+    (
+        "error.notificationsUnavailable",
+        "Notifications are unavailable without a bundle identifier",
+    ),
+    ("error.editorNotRunning", "Editor not running: {name}"),
+    ("error.watcherNotRunning", "Claude status watcher is not running"),
+    ("error.watcherStopped", "Claude status watcher has stopped"),
+    ("error.emptyAgentName", "Agent name must not be empty"),
+    ("error.unsupportedLocale", "Unsupported language: {locale}"),
+    ("error.pathNotFound", "Path not found: {path}"),
+    ("error.terminalNotInstalled", "{app} is not installed"),
+    ("error.appNotInstalled", "No app with bundle ID {app} is installed"),
+    ("error.closeBlockedByDialog", "The window is waiting for an answer: {title}"),
+    ("error.focusUnverified", "The window did not come to front ({app} is in front)"),
+    ("error.newWindowNotOpened", "{name} did not open a new window"),
+    ("error.windowNotClosed", "The window did not close"),
+    ("error.windowCloseUnconfirmed", "Could not confirm that the window closed"),
+];
+
+const JA: &[(&str, &str)] = &[
+    ("tray.settings", "設定..."),
+    ("tray.quit", "Editor Tab Managerを終了"),
+    ("tray.moreWindows", "その他のウィンドウ..."),
+    ("tray.pauseShortcuts", "ショートカットを一時停止"),
+    ("tray.launchAtLogin", "ログイン時に起動"),
+    ("tray.tooltip", "Editor Tab Manager"),
+    ("tray.waitingOne", "1件のプロジェクトが入力待ち"),
+    ("tray.waitingMany", "{count}件のプロジェクトが入力待ち"),
+    ("notification.focus", "ウィンドウを表示"),
+    ("notification.dismiss", "閉じる"),
+    ("notification.mute", "このプロジェクトをミュート"),
+    ("notification.finishedAfter", "{elapsed}で完了しました"),
+    ("notification.updateAvailable", "Editor Tab Manager {version} が利用可能です"),
+    ("notification.updateCurrentVersion", "現在のバージョン: {version}"),
+    ("error.notificationPermission", "通知が許可されていません（{status}）"),
+    (
+        "error.notificationsUnavailable",
+        "バンドルIDがないため通知を利用できません",
+    ),
+    ("error.editorNotRunning", "エディタが起動していません: {name}"),
+    ("error.watcherNotRunning", "Claude のステータス監視が動いていません"),
+    ("error.watcherStopped", "Claude のステータス監視が停止しました"),
+    ("error.emptyAgentName", "エージェント名を入力してください"),
+    ("error.unsupportedLocale", "対応していない言語です: {locale}"),
+    ("error.pathNotFound", "パスが見つかりません: {path}"),
+    ("error.terminalNotInstalled", "{app} がインストールされていません"),
+    ("error.appNotInstalled", "バンドル ID {app} のアプリがインストールされていません"),
+    ("error.closeBlockedByDialog", "ウィンドウが応答を待っています: {title}"),
+    ("error.focusUnverified", "ウィンドウを前面に出せませんでした（前面: {app}）"),
+    ("error.newWindowNotOpened", "{name} で新しいウィンドウを開けませんでした"),
+    ("error.windowNotClosed", "ウィンドウが閉じませんでした"),
+    ("error.windowCloseUnconfirmed", "ウィンドウが閉じたか確認できませんでした"),
+];
+
+static LOCALE: LazyLock<Mutex<&'static str>> = LazyLock::new(|| Mutex::new(FALLBACK_LOCALE));
+
+/// Map a language tag ("ja", "ja-JP", "en_US") to a supported locale
+fn supported_locale(tag: &str) -> Option<&'static str> {
+    let language = tag.split(['-', '_']).next()?.to_ascii_lowercase();
+    LOCALES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| *locale == language)
+}
+
+fn lookup(locale: &str, key: &str) -> Option<&'static str> {
+    LOCALES
+        .iter()
+        .find(|(name, _)| *name == locale)
+        .and_then(|(_, table)| table.iter().find(|(k, _)| *k == key))
+        .map(|(_, value)| *value)
+}
+
+/// Resolve `key` in `locale`, then in the fallback locale, then the key itself
+fn translate(locale: &str, key: &str) -> String {
+    lookup(locale, key)
+        .or_else(|| lookup(FALLBACK_LOCALE, key))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// Current locale code
+pub fn locale() -> &'static str {
+    LOCALE.lock().map(|locale| *locale).unwrap_or(FALLBACK_LOCALE)
+}
+
+/// Localized string for `key`
+pub fn t(key: &str) -> String {
+    translate(locale(), key)
+}
+
+/// Localized string for `key` with `{name}` placeholders replaced
+pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// First preferred language from System Settings
+#[cfg(target_os = "macos")]
+fn system_locale() -> Option<&'static str> {
+    let languages = NSLocale::preferredLanguages();
+    let first = languages.firstObject()?;
+    supported_locale(&first.to_string())
+}
+
+/// First language of the POSIX locale ("ja_JP.UTF-8")
+#[cfg(not(target_os = "macos"))]
+fn system_locale() -> Option<&'static str> {
+    let tag = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))?;
+    supported_locale(&tag)
+}
+
+/// Pick the saved language, else the system one (called from setup)
+pub fn init(app_handle: &AppHandle) {
+    let saved = app_handle
+        .store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(LOCALE_KEY))
+        .and_then(|value| value.as_str().and_then(supported_locale));
+    let locale = saved.or_else(system_locale).unwrap_or(FALLBACK_LOCALE);
+    if let Ok(mut current) = LOCALE.lock() {
+        *current = locale;
+    }
+}
+
+/// Switch the backend locale and refresh the strings already on screen.
+/// The choice itself is persisted by the frontend under the same store key.
+pub fn set_locale(app_handle: &AppHandle, tag: &str) -> Result<(), String> {
+    let locale = supported_locale(tag)
+        .ok_or_else(|| t_with("error.unsupportedLocale", &[("locale", tag)]))?;
+    {
+        let mut current = LOCALE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if *current == locale {
+            return Ok(());
+        }
+        *current = locale;
+    }
+
+    crate::tray::refresh_strings(app_handle);
+    crate::notification::refresh_notification_category();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tags_map_to_supported_locales() {
+        assert_eq!(supported_locale("ja"), Some("ja"));
+        assert_eq!(supported_locale("ja-JP"), Some("ja"));
+        assert_eq!(supported_locale("en_US"), Some("en"));
+        assert_eq!(supported_locale("EN"), Some("en"));
+        assert_eq!(supported_locale("fr-FR"), None);
+        assert_eq!(supported_locale(""), None);
+    }
+
+    #[test]
+    fn missing_strings_fall_back_to_english_then_key() {
+        assert_eq!(translate("ja", "tray.settings"), "設定...");
+        assert_eq!(translate("fr", "tray.settings"), "Settings...");
+        assert_eq!(translate("ja", "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn every_locale_has_every_key() {
+        for (locale, table) in LOCALES {
+            for (key, _) in EN {
+                assert!(
+                    table.iter().any(|(k, _)| k == key),
+                    "{} is missing {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn placeholders_are_replaced() {
+        assert_eq!(
+            translate("en", "tray.waitingMany").replace("{count}", "3"),
+            "3 projects waiting"
+        );
+        assert_eq!(
+            t_with("error.editorNotRunning", &[("name", "Cursor")]),
+            "Editor not running: Cursor"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Single-instance guard and messages forwarded from later launches
+//!
+//! Launching the app again (Spotlight, `open -a`, a script) must not start a
+//! second set of observers. tauri-plugin-single-instance makes the new process
+//! exit and hands its argv and working directory to the running instance,
+//! which turns them into an `InstanceMessage` and acts on it. Both types
+//! serialize to JSON so the same shape can be logged and sent to the frontend.
+
+use crate::cli::{self, CliCommand};
+use crate::editor;
+use crate::editor_config::{enabled_editors, get_editor_by_id};
+use crate::project_groups::{self, GroupMember, MemberOutcome};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// What a later launch asks the running instance to do
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum InstanceMessage {
+    /// Plain relaunch: bring up the settings window
+    Show,
+    /// Focus the project's window, opening it if no window has it.
+    /// `editor` is an editor ID ("cursor") to use instead of any editor.
+    Focus {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        editor: Option<String>,
+    },
+}
+
+impl InstanceMessage {
+    /// Message for a CLI command; None for commands run by the launching process.
+    /// Relative paths are resolved against `cwd` and normalized.
+    pub fn from_command(command: CliCommand, cwd: &Path) -> Option<Self> {
+        match command {
+            CliCommand::Show => Some(InstanceMessage::Show),
+            CliCommand::Focus { path } => Some(InstanceMessage::Focus {
+                path: resolve_path(cwd, &path).to_string_lossy().to_string(),
+                editor: None,
+            }),
+            CliCommand::Help | CliCommand::List { .. } | CliCommand::Status { .. } => None,
+        }
+    }
+}
+
+/// Absolute form of a CLI path, comparable with the paths editors report.
+/// Paths that do not exist are only normalized lexically ("." and "..").
+fn resolve_path(cwd: &Path, path: &str) -> PathBuf {
+    let joined = cwd.join(path);
+    std::fs::canonicalize(&joined).unwrap_or_else(|_| editor::normalize_relative(&joined))
+}
+
+/// Arguments of a later launch, as received from the plugin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardedLaunch {
+    /// Arguments after the executable name
+    pub args: Vec<String>,
+    /// Working directory of the later launch, for resolving relative paths
+    pub cwd: String,
+}
+
+impl ForwardedLaunch {
+    pub fn from_argv(argv: Vec<String>, cwd: String) -> Self {
+        Self {
+            args: cli::user_args(argv),
+            cwd,
+        }
+    }
+
+    pub fn message(&self) -> Result<InstanceMessage, String> {
+        match cli::parse(&self.args)? {
+            None => Ok(InstanceMessage::Show),
+            Some(command) => InstanceMessage::from_command(command, Path::new(&self.cwd))
+                .ok_or_else(|| format!("{} is not sent to the running app", self.args[0])),
+        }
+    }
+}
+
+/// Callback for tauri-plugin-single-instance (runs in the primary instance)
+pub fn handle_second_launch(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let launch = ForwardedLaunch::from_argv(argv, cwd);
+    log::info!("Forwarded launch: {}", serde_json::to_string(&launch).unwrap_or_default());
+    let message = launch.message().unwrap_or_else(|e| {
+        log::warn!("{}; showing the app instead", e);
+        InstanceMessage::Show
+    });
+    dispatch(app, message);
+}
+
+/// Act on a message from a later launch, or from our own command line
+pub fn dispatch(app: &AppHandle, message: InstanceMessage) {
+    let _ = app.emit("instance-message", &message);
+    match message {
+        InstanceMessage::Show => {
+            if let Err(e) = crate::settings_window::open(app) {
+                log::warn!("Failed to show settings window: {}", e);
+            }
+        }
+        InstanceMessage::Focus { path, editor } => {
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = focus_project(&path, editor.as_deref()) {
+                    log::warn!("Failed to focus {}: {}", path, e);
+                }
+            });
+        }
+    }
+}
+
+/// Focus or open `path`, in `editor_id` when given. Otherwise a window that
+/// already has the project wins; projects that aren't open go to the first
+/// running editor, or the first enabled one when none is running.
+pub(crate) fn focus_project(path: &str, editor_id: Option<&str>) -> Result<(), String> {
+    let mut windows = editor::get_all_editor_windows();
+    let bundle_id = match editor_id {
+        Some(id) => {
+            let config = get_editor_by_id(id).ok_or_else(|| format!("Unknown editor: {}", id))?;
+            windows.retain(|window| window.bundle_id == config.bundle_id);
+            config.bundle_id.to_string()
+        }
+        None => windows
+            .first()
+            .map(|window| window.bundle_id.clone())
+            .or_else(|| enabled_editors().next().map(|editor| editor.bundle_id.to_string()))
+            .ok_or_else(|| "Every editor is turned off".to_string())?,
+    };
+    let member = GroupMember {
+        path: path.to_string(),
+        bundle_id,
+    };
+    match project_groups::focus_or_open(&member, &windows) {
+        MemberOutcome::Failed { message } => Err(message),
+        MemberOutcome::Focused | MemberOutcome::Opened => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn plain_relaunch_shows_the_app() {
+        let exe = "/Applications/Editor Tab Manager.app/Contents/MacOS/editor-tab-manager";
+        let launch =
+            ForwardedLaunch::from_argv(argv(&[exe, "-psn_0_12345", ""]), "/Users/me".to_string());
+        assert!(launch.args.is_empty());
+        assert_eq!(launch.message(), Ok(InstanceMessage::Show));
+    }
+
+    #[test]
+    fn focus_paths_are_resolved_against_the_launch_directory() {
+        let cwd = "/Users/me/work".to_string();
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "focus", "api"]), cwd);
+        assert_eq!(
+            launch.message(),
+            Ok(InstanceMessage::Focus {
+                path: "/Users/me/work/api".to_string(),
+                editor: None,
+            })
+        );
+
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "focus", "/srv/web"]), "/".into());
+        assert_eq!(
+            launch.message(),
+            Ok(InstanceMessage::Focus {
+                path: "/srv/web".to_string(),
+                editor: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parent_directory_components_are_collapsed() {
+        let cwd = "/Users/me/work/web".to_string();
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "focus", "../api/./src"]), cwd);
+        assert_eq!(
+            launch.message(),
+            Ok(InstanceMessage::Focus {
+                path: "/Users/me/work/api/src".to_string(),
+                editor: None,
+            })
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["api", "web"] {
+            std::fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        let cwd = dir.path().join("web").to_string_lossy().to_string();
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "focus", "../api"]), cwd);
+        let expected = std::fs::canonicalize(dir.path().join("api")).unwrap();
+        assert_eq!(
+            launch.message(),
+            Ok(InstanceMessage::Focus {
+                path: expected.to_string_lossy().to_string(),
+                editor: None,
+            })
+        );
+    }
+
+    #[test]
+    fn commands_for_the_launching_process_are_not_forwarded() {
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "list", "--json"]), String::new());
+        assert!(launch.message().is_err());
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "frobnicate"]), String::new());
+        assert_eq!(launch.message(), Err("Unknown command: frobnicate".to_string()));
+    }
+
+    #[test]
+    fn messages_serialize_with_a_command_tag() {
+        assert_eq!(
+            serde_json::to_value(InstanceMessage::Show).unwrap(),
+            serde_json::json!({ "command": "show" })
+        );
+        let focus = InstanceMessage::Focus {
+            path: "/srv/web".to_string(),
+            editor: None,
+        };
+        assert_eq!(
+            serde_json::to_value(focus).unwrap(),
+            serde_json::json!({ "command": "focus", "path": "/srv/web" })
+        );
+        let launch = ForwardedLaunch::from_argv(argv(&["app", "show"]), "/tmp".to_string());
+        assert_eq!(
+            serde_json::to_value(&launch).unwrap(),
+            serde_json::json!({ "args": ["show"], "cwd": "/tmp" })
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Launch at login
+//!
+//! On macOS 13+ the app registers itself with SMAppService, which lists it
+//! under Login Items in System Settings. macOS can hold a registration until
+//! the user approves it there; that is reported as `RequiresApproval` so the UI
+//! can send the user to System Settings instead of showing the toggle as on.
+//! Older macOS versions use the autostart plugin's LaunchAgent, which is also
+//! what earlier versions of the app installed, and what other platforms use.
+
+#[cfg(target_os = "macos")]
+use objc2::msg_send;
+#[cfg(target_os = "macos")]
+use objc2::rc::Retained;
+#[cfg(target_os = "macos")]
+use objc2::runtime::{AnyClass, AnyObject, NSObject};
+#[cfg(target_os = "macos")]
+use objc2_foundation::NSString;
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_autostart::ManagerExt;
+
+#[cfg(target_os = "macos")]
+#[link(name = "ServiceManagement", kind = "framework")]
+extern "C" {}
+
+/// SMAppServiceStatus
+const SM_STATUS_ENABLED: isize = 1;
+const SM_STATUS_REQUIRES_APPROVAL: isize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchAtLoginStatus {
+    Enabled,
+    Disabled,
+    /// Registered, but waiting for approval in System Settings > Login Items
+    RequiresApproval,
+}
+
+fn status_from_service(status: isize) -> LaunchAtLoginStatus {
+    match status {
+        SM_STATUS_ENABLED => LaunchAtLoginStatus::Enabled,
+        SM_STATUS_REQUIRES_APPROVAL => LaunchAtLoginStatus::RequiresApproval,
+        // NotRegistered / NotFound
+        _ => LaunchAtLoginStatus::Disabled,
+    }
+}
+
+/// SMAppService.mainAppService (None before macOS 13)
+#[cfg(target_os = "macos")]
+fn main_app_service() -> Option<Retained<AnyObject>> {
+    let class = AnyClass::get(c"SMAppService")?;
+    unsafe { msg_send![class, mainAppService] }
+}
+
+#[cfg(target_os = "macos")]
+fn service_status(service: &AnyObject) -> isize {
+    unsafe { msg_send![service, status] }
+}
+
+#[cfg(target_os = "macos")]
+fn error_message(error: &NSObject) -> String {
+    let description: Retained<NSString> = unsafe { msg_send![error, localizedDescription] };
+    description.to_string()
+}
+
+/// The LaunchAgent written by the autostart plugin
+fn legacy_enabled(app: &AppHandle) -> bool {
+    app.autolaunch().is_enabled().unwrap_or(false)
+}
+
+/// Current launch-at-login state
+pub fn status(app: &AppHandle) -> LaunchAtLoginStatus {
+    // 以前のバージョンが作った LaunchAgent も起動時に効くので有効として扱う
+    if legacy_enabled(app) {
+        return LaunchAtLoginStatus::Enabled;
+    }
+    #[cfg(target_os = "macos")]
+    if let Some(service) = main_app_service() {
+        return status_from_service(service_status(&service));
+    }
+    LaunchAtLoginStatus::Disabled
+}
+
+/// Turn launch at login on or off and return the resulting state
+pub fn set(app: &AppHandle, enabled: bool) -> Result<LaunchAtLoginStatus, String> {
+    #[cfg(target_os = "macos")]
+    if let Some(service) = main_app_service() {
+        return set_service(app, &service, enabled);
+    }
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| format!("Failed to update login item: {}", e))?;
+    Ok(status(app))
+}
+
+/// Register or unregister with SMAppService
+#[cfg(target_os = "macos")]
+fn set_service(
+    app: &AppHandle,
+    service: &AnyObject,
+    enabled: bool,
+) -> Result<LaunchAtLoginStatus, String> {
+    let current = service_status(service);
+    if enabled {
+        if current != SM_STATUS_ENABLED {
+            let result: Result<(), Retained<NSObject>> =
+                unsafe { msg_send![service, registerAndReturnError: _] };
+            result.map_err(|e| format!("Failed to add login item: {}", error_message(&e)))?;
+        }
+    } else if matches!(current, SM_STATUS_ENABLED | SM_STATUS_REQUIRES_APPROVAL) {
+        let result: Result<(), Retained<NSObject>> =
+            unsafe { msg_send![service, unregisterAndReturnError: _] };
+        result.map_err(|e| format!("Failed to remove login item: {}", error_message(&e)))?;
+    }
+    // SMAppService に移行したので古い LaunchAgent は二重起動しないよう消す
+    if legacy_enabled(app) {
+        if let Err(e) = app.autolaunch().disable() {
+            log::warn!("Failed to remove the old launch agent: {}", e);
+        }
+    }
+    Ok(status_from_service(service_status(service)))
+}
+
+/// Tauri command: whether the app starts at login
+#[tauri::command]
+pub fn get_launch_at_login(app: AppHandle) -> LaunchAtLoginStatus {
+    status(&app)
+}
+
+/// Tauri command: turn launch at login on or off.
+/// Returns `requires_approval` when macOS is waiting for the user.
+#[tauri::command]
+pub fn set_launch_at_login(app: AppHandle, enabled: bool) -> Result<LaunchAtLoginStatus, String> {
+    let status = set(&app, enabled)?;
+    crate::tray::schedule_rebuild(&app);
+    Ok(status)
+}
+
+/// Tauri command: open System Settings > General > Login Items
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn open_login_items_settings() -> Result<(), String> {
+    match AnyClass::get(c"SMAppService") {
+        Some(class) => {
+            let _: () = unsafe { msg_send![class, openSystemSettingsLoginItems] };
+            Ok(())
+        }
+        None => std::process::Command::new("open")
+            .arg("/System/Library/PreferencePanes/Accounts.prefPane")
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to open System Settings: {}", e)),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn open_login_items_settings() -> Result<(), String> {
+    Err("Login items settings are only available on macOS".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_statuses_map_to_three_states() {
+        assert_eq!(status_from_service(0), LaunchAtLoginStatus::Disabled);
+        assert_eq!(status_from_service(1), LaunchAtLoginStatus::Enabled);
+        assert_eq!(status_from_service(2), LaunchAtLoginStatus::RequiresApproval);
+        assert_eq!(status_from_service(3), LaunchAtLoginStatus::Disabled);
+        assert_eq!(
+            serde_json::to_value(LaunchAtLoginStatus::RequiresApproval).unwrap(),
+            serde_json::json!("requires_approval")
+        );
+    }
+}
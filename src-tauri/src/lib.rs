@@ -1,67 +1,197 @@
+// 他のプラットフォームではmacOS固有の層がスタブになり、それが呼ぶコードは使われない
+#![cfg_attr(not(target_os = "macos"), allow(dead_code))]
+
+#[cfg_attr(not(target_os = "macos"), path = "unsupported/ax_helper.rs")]
 mod ax_helper;
+#[cfg_attr(not(target_os = "macos"), path = "unsupported/ax_observer.rs")]
 mod ax_observer;
+mod autohide;
 mod claude_status;
+mod cli;
 mod cursor_ipc;
+mod debounce;
+mod deep_link;
+mod diagnostics;
 mod editor;
+mod editor_icon;
 mod editor_config;
 mod editor_model;
+mod focus_history;
+mod i18n;
+mod instance;
+mod launch_at_login;
+mod local_api;
+mod logging;
+mod move_project;
+mod main_thread;
 mod notification;
+#[cfg_attr(not(target_os = "macos"), path = "unsupported/observer.rs")]
 mod observer;
+mod path_actions;
+mod polling;
+mod power;
+mod project_appearance;
+mod project_groups;
+mod recently_closed;
+mod saved_sessions;
+mod settings_store;
+mod settings_transfer;
+mod settings_window;
+mod snapshot;
+mod tab_bars;
+mod tray;
+mod update_check;
+mod window_close;
+mod window_manager;
+mod window_filter;
 mod window_offset;
+mod window_order;
 mod window_registry;
+mod workspace_history;
+mod workspace_uri;
 
 use tauri::menu::{Menu, MenuItem};
-use tauri::tray::{TrayIconBuilder, TrayIconId};
-use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri::tray::TrayIconBuilder;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, RunEvent, WebviewWindow};
 use tauri_plugin_autostart::MacosLauncher;
+use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-use editor::{EditorState, EditorWindow};
+use editor::{EditorState, EditorWindow, RunningEditor};
+
+/// Look up a display by id for window-list filtering
+fn display_filter(display_id: Option<u32>) -> Option<window_offset::DisplayFrame> {
+    let display_id = display_id?;
+    window_offset::get_displays()
+        .into_iter()
+        .find(|d| d.display_id == display_id)
+}
+
+/// Run AX queries, subprocesses and filesystem work on the blocking pool so
+/// the IPC thread stays free for other commands
+pub(crate) async fn run_blocking<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Blocking task failed: {}", e))
+}
+
+/// Current time in epoch milliseconds (0 if the clock is before 1970)
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
 
 // Editor commands with optional bundle_id support
 #[tauri::command(rename_all = "snake_case")]
-fn get_editor_windows(bundle_id: Option<&str>) -> Vec<EditorWindow> {
-    match bundle_id {
-        Some(id) => editor::get_editor_windows(id),
-        None => editor::get_any_editor_windows(),
-    }
+async fn get_editor_windows(
+    app: AppHandle,
+    bundle_id: Option<String>,
+    display_id: Option<u32>,
+    order: Option<String>,
+) -> Result<Vec<EditorWindow>, String> {
+    let order = window_order::parse(order.as_deref())?;
+    run_blocking(move || {
+        let windows = match bundle_id {
+            Some(id) => editor::get_editor_windows(&id),
+            None => editor::get_any_editor_windows(),
+        };
+        let mut windows = match display_filter(display_id) {
+            Some(display) => editor::filter_windows_by_display(windows, &display),
+            None => windows,
+        };
+        window_order::sort_windows(&app, &mut windows, order);
+        windows
+    })
+    .await
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn get_editor_state(bundle_id: Option<&str>) -> EditorState {
-    match bundle_id {
-        Some(id) => editor::get_editor_state(id),
-        None => editor::get_any_editor_state(),
-    }
+/// Every supported editor with running flag, pid and window count
+#[tauri::command]
+async fn get_running_editors() -> Result<Vec<RunningEditor>, String> {
+    run_blocking(editor::get_running_editors).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn focus_editor_window(bundle_id: &str, window_id: u32) -> Result<(), String> {
-    editor::focus_editor_window(bundle_id, window_id)
+async fn get_editor_state(
+    app: AppHandle,
+    bundle_id: Option<String>,
+    display_id: Option<u32>,
+    order: Option<String>,
+) -> Result<EditorState, String> {
+    let order = window_order::parse(order.as_deref())?;
+    run_blocking(move || {
+        let state = match bundle_id {
+            Some(id) => editor::get_editor_state(&id),
+            None => editor::get_any_editor_state(),
+        };
+        let state = match display_filter(display_id) {
+            Some(display) => editor::filter_state_by_display(state, &display),
+            None => state,
+        };
+        window_order::sort_state(&app, state, order)
+    })
+    .await
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn open_new_editor(bundle_id: &str) -> Result<(), String> {
-    editor::open_new_editor(bundle_id)
+async fn focus_editor_window(
+    bundle_id: String,
+    window_id: u32,
+) -> Result<(), editor::FocusError> {
+    run_blocking(move || editor::focus_editor_window(&bundle_id, window_id)).await?
 }
 
+// エディタが前面に来るのと新しいウィンドウが出るのを待つのでブロッキングプールで動かす
 #[tauri::command(rename_all = "snake_case")]
-fn close_editor_window(bundle_id: &str, window_id: u32) -> Result<(), String> {
-    editor::close_editor_window(bundle_id, window_id)
+async fn open_new_editor(bundle_id: String) -> Result<(), String> {
+    run_blocking(move || editor::open_new_editor(&bundle_id)).await?
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn open_project_in_editor(bundle_id: &str, path: &str) -> Result<(), String> {
-    editor::open_project_in_editor(bundle_id, path)
+async fn close_editor_window(
+    bundle_id: String,
+    window_id: u32,
+) -> Result<(), editor::CloseWindowError> {
+    run_blocking(move || editor::close_editor_window(&bundle_id, window_id)).await?
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn maximize_editor_window(bundle_id: &str, window_id: u32, tab_bar_height: f64) -> Result<(), String> {
-    window_offset::maximize_window(bundle_id, window_id, tab_bar_height)
+async fn open_project_in_editor(bundle_id: String, path: String) -> Result<(), String> {
+    run_blocking(move || editor::open_project_in_editor(&bundle_id, &path)).await?
 }
 
+/// Focus the window that was in front before the current one, in any editor
 #[tauri::command]
-fn get_all_editor_windows() -> Vec<EditorWindow> {
-    editor::get_all_editor_windows()
+async fn focus_previous_window() -> Result<(), String> {
+    run_blocking(focus_history::focus_previous_window).await?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn maximize_editor_window(
+    bundle_id: String,
+    window_id: u32,
+    tab_bar_height: f64,
+) -> Result<(), String> {
+    run_blocking(move || window_offset::maximize_window(&bundle_id, window_id, tab_bar_height))
+        .await?
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn get_all_editor_windows(display_id: Option<u32>) -> Result<Vec<EditorWindow>, String> {
+    run_blocking(move || {
+        let windows = editor::get_all_editor_windows();
+        match display_filter(display_id) {
+            Some(display) => editor::filter_windows_by_display(windows, &display),
+            None => windows,
+        }
+    })
+    .await
 }
 
 #[tauri::command]
@@ -74,18 +204,35 @@ fn request_windows_refresh() {
     window_registry::request_refresh("manual");
 }
 
+/// Forget resolved project paths and re-resolve them (manual recovery)
+#[tauri::command]
+fn clear_project_path_cache() {
+    editor::clear_project_path_cache();
+    window_registry::request_refresh("path-cache-cleared");
+}
+
 #[tauri::command(rename_all = "snake_case")]
 fn is_editor_active() -> bool {
     editor::is_editor_active()
 }
 
+/// Which app is frontmost, as the app-activated event would report it.
+/// Sync so it runs on the main thread, where the focused screen can be read.
+#[tauri::command]
+fn get_frontmost_app() -> Option<editor_model::FrontmostApp> {
+    observer::frontmost_app()
+}
+
 #[tauri::command(rename_all = "snake_case")]
-fn open_file_in_default_app(path: String) -> Result<(), String> {
-    std::process::Command::new("open")
-        .arg(&path)
-        .spawn()
-        .map_err(|e| e.to_string())?;
-    Ok(())
+async fn open_file_in_default_app(path: String) -> Result<(), String> {
+    run_blocking(move || {
+        std::process::Command::new("open")
+            .arg(&path)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    })
+    .await?
 }
 
 #[tauri::command]
@@ -100,6 +247,15 @@ fn check_accessibility_permission() -> bool {
     }
 }
 
+/// Editor state, Claude statuses, frontmost app and permission in one call
+#[tauri::command(rename_all = "snake_case")]
+async fn get_snapshot(bundle_id: Option<String>) -> Result<snapshot::AppSnapshot, String> {
+    run_blocking(move || {
+        snapshot::collect(bundle_id.as_deref(), check_accessibility_permission())
+    })
+    .await
+}
+
 #[tauri::command]
 fn request_accessibility_permission() -> bool {
     #[cfg(target_os = "macos")]
@@ -125,65 +281,188 @@ fn open_accessibility_settings() -> Result<(), String> {
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn apply_window_offset(bundle_id: &str, offset_y: f64) -> Result<(), String> {
-    window_offset::apply_offset(bundle_id, offset_y)
+async fn apply_window_offset(
+    window: WebviewWindow,
+    bundle_id: String,
+    offset_y: f64,
+    display_id: Option<u32>,
+) -> Result<(), String> {
+    run_blocking(move || {
+        // Stage Manager 有効時はウィンドウを動かさずタブバーを重ねる
+        if !window_manager::offsets_enabled(window.app_handle()) {
+            return Ok(());
+        }
+        // 省略時は呼び出し元のタブバーが表示されているディスプレイを対象にする
+        // (NSWindow の参照はメインスレッドで行う)
+        let display_id = match display_id {
+            Some(display_id) => Some(display_id),
+            None => main_thread::run(move || Ok(tab_bars::display_id_of(&window)))?,
+        };
+        let display = window_offset::get_display_or_primary(display_id)
+            .ok_or_else(|| "No display found".to_string())?;
+        window_offset::apply_offset(&bundle_id, offset_y, &display)
+    })
+    .await?
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn restore_window_positions(bundle_id: &str) -> Result<(), String> {
-    window_offset::restore_positions(bundle_id)
+async fn update_window_offset(
+    app: AppHandle,
+    bundle_id: String,
+    tab_bar_height: f64,
+) -> Result<(), String> {
+    run_blocking(move || {
+        if !window_manager::offsets_enabled(&app) {
+            return Ok(());
+        }
+        window_offset::update_offset(&bundle_id, tab_bar_height)
+    })
+    .await?
 }
 
+/// Geometry of every display, including menu bar auto-hide and notch metrics
 #[tauri::command]
-fn restore_all_window_positions() -> Result<(), String> {
-    window_offset::restore_all_pending()
+fn get_screen_metrics() -> Vec<window_offset::DisplayFrame> {
+    window_offset::get_displays()
+}
+
+// 検出に defaults を起動し、有効化直後はウィンドウ位置も戻すのでメインスレッドでは行わない
+#[tauri::command]
+async fn get_window_manager_info(
+    app: AppHandle,
+) -> Result<window_manager::WindowManagerInfo, String> {
+    run_blocking(move || window_manager::get_window_manager_info(&app)).await
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn update_tray_menu(app: AppHandle, settings_label: String, quit_label: String) -> Result<(), String> {
-    let tray = app
-        .tray_by_id(&TrayIconId::new("main"))
-        .ok_or_else(|| "Tray icon not found".to_string())?;
-    let settings_item =
-        MenuItem::with_id(&app, "settings", &settings_label, true, None::<&str>)
-            .map_err(|e| e.to_string())?;
-    let quit_item =
-        MenuItem::with_id(&app, "quit", &quit_label, true, None::<&str>)
-            .map_err(|e| e.to_string())?;
-    let menu = Menu::with_items(&app, &[&settings_item, &quit_item])
-        .map_err(|e| e.to_string())?;
-    tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
-    Ok(())
+fn create_tab_bar_for_display(app: AppHandle, display_id: u32) -> Result<String, String> {
+    tab_bars::create_for_display(&app, display_id)
 }
 
-fn open_settings_window(app: &AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("settings") {
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
-    } else {
-        WebviewWindowBuilder::new(app, "settings", WebviewUrl::App("index.html".into()))
-            .title("Editor Tab Manager Settings")
-            .inner_size(600.0, 600.0)
-            .center()
-            .resizable(false)
-            .build()
-            .map_err(|e| e.to_string())?;
-    }
-    Ok(())
+#[tauri::command]
+fn get_tab_bar_display_id(window: WebviewWindow) -> Option<u32> {
+    tab_bars::display_id_of(&window)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn restore_window_positions(bundle_id: String) -> Result<(), String> {
+    run_blocking(move || window_offset::restore_positions(&bundle_id)).await?
+}
+
+#[tauri::command]
+async fn restore_all_window_positions() -> Result<(), String> {
+    run_blocking(window_offset::restore_all_pending).await?
+}
+
+#[tauri::command]
+fn get_claude_events_file() -> String {
+    claude_status::events_file().to_string_lossy().to_string()
+}
+
+#[tauri::command]
+fn set_claude_events_file(app: AppHandle, path: Option<String>) -> Result<String, String> {
+    claude_status::set_events_file(&app, path)
+}
+
+#[tauri::command]
+fn get_claude_statuses() -> claude_status::ClaudeStatusPayload {
+    claude_status::latest_statuses()
+}
+
+// ウォッチャースレッドの再計算を待つので async ランタイムのワーカーも塞がないようにする
+#[tauri::command]
+async fn refresh_claude_statuses() -> Result<claude_status::ClaudeStatusPayload, String> {
+    run_blocking(claude_status::refresh_statuses).await?
+}
+
+/// Stop watching agent statuses; App Nap may throttle the app again
+#[tauri::command]
+fn pause_watcher() {
+    claude_status::stop_claude_status_watcher();
+    power::set_paused(true);
+}
+
+#[tauri::command]
+fn resume_watcher(app: AppHandle) {
+    power::set_paused(false);
+    claude_status::start_claude_status_watcher(app);
+}
+
+#[tauri::command]
+fn get_long_generation_notification() -> claude_status::LongGenerationSettings {
+    claude_status::long_generation_settings()
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_long_generation_notification(
+    app: AppHandle,
+    enabled: bool,
+    threshold_secs: u64,
+) -> Result<(), String> {
+    claude_status::set_long_generation_settings(
+        &app,
+        claude_status::LongGenerationSettings {
+            enabled,
+            threshold_secs,
+        },
+    )
+}
+
+#[tauri::command]
+fn get_generic_agent_status_file() -> Option<claude_status::GenericStatusFile> {
+    claude_status::generic_status_file()
+}
+
+#[tauri::command]
+fn set_generic_agent_status_file(
+    app: AppHandle,
+    file: Option<claude_status::GenericStatusFile>,
+) -> Result<(), String> {
+    claude_status::set_generic_status_file(&app, file)
+}
+
+#[tauri::command]
+fn get_debug_logging() -> bool {
+    logging::debug_logging_enabled()
+}
+
+#[tauri::command]
+fn set_debug_logging(app: AppHandle, enabled: bool) -> Result<(), String> {
+    logging::set_debug_logging(&app, enabled)
+}
+
+#[tauri::command]
+fn get_log_file_path() -> String {
+    logging::log_file_path().to_string_lossy().to_string()
+}
+
+#[tauri::command]
+fn get_locale() -> String {
+    i18n::locale().to_string()
 }
 
 #[tauri::command]
-fn show_settings_window(app: AppHandle) -> Result<(), String> {
-    open_settings_window(&app)
+fn set_locale(app: AppHandle, locale: String) -> Result<(), String> {
+    i18n::set_locale(&app, &locale)
 }
 
 fn setup_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     // Cmd+Shift+T: New editor window
     let new_tab_shortcut = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyT);
 
+    // Cmd+Option+Shift+T: Reopen the last closed project window
+    let reopen_closed_shortcut = Shortcut::new(
+        Some(Modifiers::SUPER | Modifiers::ALT | Modifiers::SHIFT),
+        Code::KeyT,
+    );
+
     // Cmd+W: Close current tab
     let close_tab_shortcut = Shortcut::new(Some(Modifiers::SUPER), Code::KeyW);
 
+    // Cmd+Option+`: Back to the previously focused window (across editors)
+    let previous_window_shortcut =
+        Shortcut::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::Backquote);
+
     // Cmd+1~9: Switch to tab
     let tab_shortcuts: Vec<Shortcut> = (1..=9)
         .map(|i| {
@@ -206,10 +485,15 @@ fn setup_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let app_handle = app.clone();
 
     app.global_shortcut().on_shortcuts(
-        [new_tab_shortcut, close_tab_shortcut]
-            .into_iter()
-            .chain(tab_shortcuts.clone())
-            .collect::<Vec<_>>(),
+        [
+            new_tab_shortcut,
+            reopen_closed_shortcut,
+            close_tab_shortcut,
+            previous_window_shortcut,
+        ]
+        .into_iter()
+        .chain(tab_shortcuts.clone())
+        .collect::<Vec<_>>(),
         move |_app, shortcut, event| {
             if event.state != ShortcutState::Pressed {
                 return;
@@ -224,10 +508,27 @@ fn setup_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 if let Some(window) = app_handle.get_webview_window("main") {
                     let _ = window.emit("open-new-editor-tab", ());
                 }
+            } else if shortcut == &reopen_closed_shortcut {
+                // 起動待ちのエディタがあるときは新規ウィンドウの操作を優先する
+                if window_registry::launch_pending() {
+                    return;
+                }
+                let app = app_handle.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) = recently_closed::reopen_last(&app) {
+                        log::debug!("Reopen last closed window: {}", e);
+                    }
+                });
             } else if shortcut == &close_tab_shortcut {
                 if let Some(window) = app_handle.get_webview_window("main") {
                     let _ = window.emit("close-current-tab", ());
                 }
+            } else if shortcut == &previous_window_shortcut {
+                tauri::async_runtime::spawn_blocking(|| {
+                    if let Err(e) = focus_history::focus_previous_window() {
+                        log::debug!("Back to previous window: {}", e);
+                    }
+                });
             } else {
                 // Check if it's a tab switch shortcut
                 for (i, tab_shortcut) in tab_shortcuts.iter().enumerate() {
@@ -245,28 +546,161 @@ fn setup_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Set while global shortcuts are temporarily unregistered from the tray
+static SHORTCUTS_PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn shortcuts_paused() -> bool {
+    SHORTCUTS_PAUSED.load(Ordering::SeqCst)
+}
+
+/// Unregister every global shortcut, or register them again from the current configuration
+pub(crate) fn apply_shortcuts_paused(app: &AppHandle, paused: bool) -> Result<(), String> {
+    if SHORTCUTS_PAUSED.swap(paused, Ordering::SeqCst) == paused {
+        return Ok(());
+    }
+    let result = if paused {
+        app.global_shortcut().unregister_all().map_err(|e| e.to_string())
+    } else {
+        setup_shortcuts(app).map_err(|e| e.to_string())
+    };
+    if let Err(e) = result {
+        SHORTCUTS_PAUSED.store(!paused, Ordering::SeqCst);
+        return Err(e);
+    }
+
+    log::info!("Shortcuts {}", if paused { "paused" } else { "resumed" });
+    tab_bars::emit_to_tab_bars(app, "shortcuts-paused-changed", paused);
+    tray::schedule_rebuild(app);
+    Ok(())
+}
+
+/// Register the shortcuts again from the current configuration (unless paused)
+pub(crate) fn reregister_shortcuts(app: &AppHandle) -> Result<(), String> {
+    if shortcuts_paused() {
+        return Ok(());
+    }
+    app.global_shortcut().unregister_all().map_err(|e| e.to_string())?;
+    setup_shortcuts(app).map_err(|e| e.to_string())
+}
+
+/// Called by a tab bar once its event listeners are registered
+#[tauri::command]
+fn frontend_ready(app: AppHandle) {
+    autohide::frontend_ready();
+    observer::frontend_ready(&app);
+}
+
+#[tauri::command]
+fn get_shortcuts_paused() -> bool {
+    shortcuts_paused()
+}
+
+#[tauri::command]
+fn set_shortcuts_paused(app: AppHandle, paused: bool) -> Result<(), String> {
+    apply_shortcuts_paused(&app, paused)
+}
+
+/// Upper bound for each AX call while restoring window positions on quit or
+/// after a panic, so an unresponsive editor can't hang exit
+const EXIT_AX_TIMEOUT_SECS: f32 = 0.5;
+
+/// Set once exit cleanup has run (tray "quit" and ExitRequested can both trigger it)
+static EXIT_CLEANUP_DONE: AtomicBool = AtomicBool::new(false);
+
+/// Undo everything the app changed outside its own process before exiting
+fn cleanup_before_exit(app: &AppHandle) {
+    if EXIT_CLEANUP_DONE.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    settings_window::prepare_exit();
+
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        log::error!("Failed to unregister shortcuts on quit: {}", e);
+    }
+
+    observer::stop_observer();
+    ax_observer::unregister_all();
+    local_api::stop();
+
+    // メインスレッドから呼ばれるので、別スレッドで待つとウィンドウ移動
+    // (main_thread::run) がデッドロックする。ここで直接戻し、AX 呼び出しごとに上限を設ける
+    ax_helper::set_global_messaging_timeout(EXIT_AX_TIMEOUT_SECS);
+    if let Err(e) = window_offset::restore_all() {
+        log::error!("Failed to restore window positions on quit: {}", e);
+    }
+}
+
+/// Restore offset windows when the backend panics (release builds abort right after the hook)
+fn install_panic_hook() {
+    static PANICKED: AtomicBool = AtomicBool::new(false);
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        // 復元処理中の二重パニックで再入しない
+        if !PANICKED.swap(true, Ordering::SeqCst) {
+            ax_helper::set_global_messaging_timeout(EXIT_AX_TIMEOUT_SECS);
+            window_offset::restore_after_panic();
+        }
+    }));
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    logging::init();
+
+    let context = tauri::generate_context!();
+    settings_store::set_app_identifier(&context.config().identifier);
+    // list / status はアプリを起動せずに出力して終了する
+    let launch_message = match cli::parse(&cli::user_args(std::env::args())) {
+        Ok(Some(command)) if command.is_standalone() => {
+            std::process::exit(cli::run_standalone(&command));
+        }
+        Ok(command) => {
+            let cwd = std::env::current_dir().unwrap_or_default();
+            command.and_then(|command| instance::InstanceMessage::from_command(command, &cwd))
+        }
+        Err(e) => {
+            eprintln!("editor-tab-manager: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    install_panic_hook();
+
     tauri::Builder::default()
+        // 二重起動時は既存のインスタンスに引数を渡して終了する（最初に登録する必要がある）
+        .plugin(tauri_plugin_single_instance::init(instance::handle_second_launch))
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, None))
+        .plugin(tauri_plugin_deep_link::init())
         .invoke_handler(tauri::generate_handler![
             // Editor commands with bundle_id support
             get_editor_windows,
             get_all_editor_windows,
             get_windows_snapshot,
             request_windows_refresh,
+            clear_project_path_cache,
             get_editor_state,
+            get_running_editors,
             focus_editor_window,
             open_new_editor,
             close_editor_window,
+            window_close::close_all_windows,
+            window_close::close_other_windows,
+            move_project::reopen_in_editor,
+            recently_closed::get_recently_closed,
+            recently_closed::reopen_last_closed,
             open_project_in_editor,
             maximize_editor_window,
+            focus_previous_window,
             is_editor_active,
+            get_frontmost_app,
+            get_snapshot,
+            editor_icon::get_editor_icon,
             // File operations
             open_file_in_default_app,
             // Accessibility permissions
@@ -275,72 +709,208 @@ pub fn run() {
             open_accessibility_settings,
             // Window offset management
             apply_window_offset,
+            update_window_offset,
             restore_window_positions,
             restore_all_window_positions,
+            get_window_manager_info,
+            get_screen_metrics,
+            // Per-display tab bars
+            create_tab_bar_for_display,
+            get_tab_bar_display_id,
+            tab_bars::move_tab_bar_to_display,
+            tab_bars::configure_tab_bar_window,
+            tab_bars::get_tab_bar_behavior,
+            tab_bars::get_tab_bar_position,
+            tab_bars::set_tab_bar_position,
+            tab_bars::place_tab_bar,
+            autohide::get_autohide,
+            autohide::set_autohide,
+            window_registry::get_legacy_window_events,
+            window_registry::set_legacy_window_events,
+            // Claude Code integration
+            get_claude_statuses,
+            refresh_claude_statuses,
+            pause_watcher,
+            resume_watcher,
+            get_claude_events_file,
+            set_claude_events_file,
+            get_long_generation_notification,
+            set_long_generation_notification,
+            get_generic_agent_status_file,
+            set_generic_agent_status_file,
             // Native notification
             notification::send_notification,
+            notification::clear_notifications_for_project,
+            notification::get_notification_settings,
+            notification::set_notification_settings,
+            notification::get_notification_permission,
+            notification::request_notification_permission,
+            // Logging
+            get_debug_logging,
+            set_debug_logging,
+            get_log_file_path,
+            // Launch at login
+            launch_at_login::get_launch_at_login,
+            launch_at_login::set_launch_at_login,
+            launch_at_login::open_login_items_settings,
+            // Local scripting API
+            local_api::get_api_enabled,
+            local_api::set_api_enabled,
+            local_api::get_api_socket_path,
+            // Update check
+            update_check::check_for_updates,
+            update_check::get_update_check_settings,
+            update_check::set_update_check_settings,
+            // Diagnostics
+            diagnostics::get_diagnostics,
+            // Settings export/import
+            settings_transfer::export_settings,
+            settings_transfer::import_settings,
+            // Backend strings
+            get_locale,
+            set_locale,
             // Tray menu
-            update_tray_menu,
+            tray::get_tray_title_settings,
+            tray::set_tray_title_settings,
+            // Tab list filtering
+            window_filter::get_window_filter_settings,
+            window_filter::set_window_filter_settings,
+            window_filter::get_editor_filter_settings,
+            window_filter::set_editor_filter_settings,
+            // Project folder actions
+            path_actions::reveal_in_finder,
+            path_actions::copy_path_to_clipboard,
+            path_actions::open_terminal_at,
+            path_actions::get_default_terminal,
+            path_actions::set_default_terminal,
+            // Project colors
+            project_appearance::get_project_appearances,
+            project_appearance::set_project_appearance,
+            // Saved sessions
+            saved_sessions::save_session,
+            saved_sessions::list_sessions,
+            saved_sessions::restore_session,
+            // Project groups
+            project_groups::create_group,
+            project_groups::add_to_group,
+            project_groups::list_groups,
+            project_groups::activate_group,
+            project_groups::deactivate_group,
+            // Project history scan
+            workspace_history::get_workspace_scan_limit,
+            workspace_history::set_workspace_scan_limit,
+            // Shortcut interception
+            get_shortcuts_paused,
+            set_shortcuts_paused,
             // Settings window
-            show_settings_window
+            settings_window::open_settings_window,
+            // Startup handshake
+            frontend_ready
         ])
         .setup(|app| {
+            // Apply the saved debug logging setting before anything else logs
+            logging::load_setting(app.handle());
+            i18n::init(app.handle());
+            let stored_settings = settings_store::StoredSettings::from_app(app.handle());
+            window_filter::load_settings(&stored_settings);
+            path_actions::load_settings(app.handle());
+            project_appearance::load_settings(&stored_settings);
+            window_registry::load_settings(&stored_settings);
+            workspace_history::load_settings(app.handle());
+            recently_closed::load(app.handle());
+
             // Set app as accessory (no Dock icon, menu bar only)
             #[cfg(target_os = "macos")]
             app.set_activation_policy(tauri::ActivationPolicy::Accessory);
 
-            // Set custom window level above NSModalPanelWindowLevel (8)
-            // This ensures the tab bar stays above editor sub-windows (settings, modals, etc.)
-            // but below Dock (20), main menu (24), and status bar (25) levels
-            #[cfg(target_os = "macos")]
-            {
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.with_webview(|webview| {
-                        unsafe {
-                            let ns_window: &objc2_app_kit::NSWindow =
-                                &*webview.ns_window().cast();
-                            // NSModalPanelWindowLevel = 8, set to 9 to be above modal panels
-                            ns_window.setLevel(9);
-                        }
-                    });
-                }
+            // Apply the saved window level and Spaces behavior
+            // (by default above editor sub-windows such as settings and modals)
+            tab_bars::load_settings(&stored_settings);
+            autohide::load_settings(&stored_settings);
+            if let Some(window) = app.get_webview_window(tab_bars::MAIN_LABEL) {
+                tab_bars::apply_window_behavior(&window);
             }
 
+            // Capture display geometry on the main thread for background callers
+            window_offset::refresh_displays();
+
             // Restore any pending window positions from previous crash
             if window_offset::has_pending_restorations() {
                 if let Err(e) = window_offset::restore_all_pending() {
-                    eprintln!("Failed to restore pending window positions: {}", e);
+                    log::error!("Failed to restore pending window positions: {}", e);
                 }
             }
 
+            // Detect Stage Manager up front so offsets start in the right mode
+            // (off the main thread since it runs `defaults`)
+            let window_manager_handle = app.handle().clone();
+            std::thread::spawn(move || {
+                window_manager::get_window_manager_info(&window_manager_handle);
+            });
+
             // Setup menu bar tray icon
-            let settings_item = MenuItem::with_id(app, "settings", "Settings...", true, None::<&str>)?;
-            let quit_item = MenuItem::with_id(app, "quit", "Quit Editor Tab Manager", true, None::<&str>)?;
+            // (editor windows are added by tray::rebuild_tray_menu once the registry is populated)
+            let settings_item = MenuItem::with_id(app, tray::SETTINGS_ITEM_ID, i18n::t("tray.settings"), true, None::<&str>)?;
+            let quit_item = MenuItem::with_id(app, tray::QUIT_ITEM_ID, i18n::t("tray.quit"), true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&settings_item, &quit_item])?;
 
-            let _tray = TrayIconBuilder::with_id("main")
-                .icon(app.default_window_icon().unwrap().clone())
+            let tray_icon = TrayIconBuilder::with_id(tray::TRAY_ID)
+                .icon(tray::TrayStatus::Idle.icon())
                 .icon_as_template(false)
                 .menu(&menu)
                 .on_menu_event(|app, event| {
-                    if event.id.as_ref() == "settings" {
-                        if let Err(e) = open_settings_window(app) {
-                            eprintln!("Failed to show settings window: {}", e);
+                    let id = event.id.as_ref();
+                    if let Some((bundle_id, window_id)) = tray::parse_window_item_id(id) {
+                        // フォーカスの確認待ちでメインスレッドを止めない
+                        let bundle_id = bundle_id.to_string();
+                        tauri::async_runtime::spawn_blocking(move || {
+                            if let Err(e) = editor::focus_editor_window(&bundle_id, window_id) {
+                                log::error!("Failed to focus editor window from tray: {}", e);
+                            }
+                        });
+                    } else if id == tray::PAUSE_SHORTCUTS_ITEM_ID {
+                        if let Err(e) = apply_shortcuts_paused(app, !shortcuts_paused()) {
+                            log::error!("Failed to toggle shortcuts: {}", e);
+                        }
+                    } else if id == tray::LAUNCH_AT_LOGIN_ITEM_ID {
+                        let enabled = launch_at_login::status(app)
+                            != launch_at_login::LaunchAtLoginStatus::Enabled;
+                        match launch_at_login::set(app, enabled) {
+                            // システム設定での承認待ちならログイン項目の設定を開く
+                            Ok(launch_at_login::LaunchAtLoginStatus::RequiresApproval) => {
+                                let _ = launch_at_login::open_login_items_settings();
+                            }
+                            Ok(_) => {}
+                            Err(e) => log::error!("Failed to toggle launch at login: {}", e),
+                        }
+                        tray::schedule_rebuild(app);
+                    } else if id == tray::SHOW_TAB_BAR_ITEM_ID {
+                        if let Err(e) = tray::show_tab_bar(app) {
+                            log::error!("Failed to show tab bar: {}", e);
                         }
-                    } else if event.id.as_ref() == "quit" {
-                        // Restore window positions before quitting
-                        if let Err(e) = window_offset::restore_all_pending() {
-                            eprintln!("Failed to restore window positions on quit: {}", e);
+                    } else if id == tray::SETTINGS_ITEM_ID {
+                        if let Err(e) = settings_window::open(app) {
+                            log::error!("Failed to show settings window: {}", e);
                         }
+                    } else if id == tray::QUIT_ITEM_ID {
+                        // Restore window positions and release shortcuts before quitting
+                        cleanup_before_exit(app);
                         app.exit(0);
                     }
                 })
                 .build(app)?;
+            tray::manage(app.handle(), tray_icon);
 
             if let Err(e) = setup_shortcuts(app.handle()) {
-                eprintln!("Failed to setup shortcuts: {}", e);
+                log::error!("Failed to setup shortcuts: {}", e);
             }
 
+            // AX window mutations from other threads are routed through the main thread
+            main_thread::init(app.handle().clone());
+
+            // Parse editor workspace state off the main thread before the first query
+            editor::prewarm_workspace_state();
+
             // Initialize window registry (single source of truth for windows)
             window_registry::init(app.handle().clone());
 
@@ -356,11 +926,90 @@ pub fn run() {
             // Start Claude Code status watcher
             claude_status::start_claude_status_watcher(app.handle().clone());
 
+            // Re-resolve project paths when editors open new workspaces
+            editor::start_workspace_storage_watcher();
+
             // Setup native notification delegate for click handling
             notification::setup_notification_delegate(app.handle().clone());
 
+            // editortabs:// URLs (registered in tauri.conf.json)
+            let deep_link_handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                deep_link::handle_urls(&deep_link_handle, event.urls());
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                deep_link::handle_urls(app.handle(), urls);
+            }
+
+            // Opt-in local API for scripts (off unless turned on in Settings)
+            local_api::load_settings(app.handle());
+
+            // Background update check (sleeps until turned on in Settings)
+            update_check::start(app.handle().clone());
+
+            // 起動時のコマンド（focus など）は転送されたときと同じように処理する
+            if let Some(message) = launch_message {
+                instance::dispatch(app.handle(), message);
+            }
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            RunEvent::ExitRequested { .. } => cleanup_before_exit(app),
+            // 設定を開いている間は Dock アイコンがあるので、クリックで設定を前面に戻す
+            #[cfg(target_os = "macos")]
+            RunEvent::Reopen { .. }
+                if settings_window::activation_policy()
+                    == settings_window::ActivationPolicy::Regular =>
+            {
+                if let Err(e) = settings_window::open(app) {
+                    log::warn!("Failed to show settings window: {}", e);
+                }
+            }
+            _ => {}
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn concurrent_blocking_commands_do_not_queue_behind_each_other() {
+        const CALLS: usize = 16;
+        let running = Arc::new(AtomicUsize::new(0));
+        let started = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        tauri::async_runtime::block_on(async {
+            // A burst of tab clicks: each call blocks until all of them have started
+            // (or 2s pass), so queued calls would never all be running at once
+            let handles: Vec<_> = (0..CALLS)
+                .map(|i| {
+                    let running = Arc::clone(&running);
+                    let started = Arc::clone(&started);
+                    let peak = Arc::clone(&peak);
+                    tauri::async_runtime::spawn(run_blocking(move || {
+                        let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        started.fetch_add(1, Ordering::SeqCst);
+                        let until = Instant::now() + Duration::from_secs(2);
+                        while started.load(Ordering::SeqCst) < CALLS && Instant::now() < until {
+                            std::thread::sleep(Duration::from_millis(1));
+                        }
+                        running.fetch_sub(1, Ordering::SeqCst);
+                        i
+                    }))
+                })
+                .collect();
+            for (i, handle) in handles.into_iter().enumerate() {
+                assert_eq!(handle.await.unwrap(), Ok(i));
+            }
+        });
+        assert_eq!(peak.load(Ordering::SeqCst), CALLS);
+    }
 }
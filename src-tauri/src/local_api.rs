@@ -0,0 +1,282 @@
+//! Local scripting API over a Unix domain socket
+//!
+//! Off by default. When enabled, a small HTTP server listens on a socket under
+//! Application Support so Raycast, tmux status lines and shell scripts can read
+//! the window list and Claude statuses, or focus/open projects:
+//!
+//! ```text
+//! curl --unix-socket "$HOME/Library/Application Support/com.editor-tab-manager.app/api.sock" \
+//!   http://localhost/windows
+//! ```
+//!
+//! The socket is only accessible to the current user (0600), so there is no
+//! token. Requests are served one at a time on a background thread.
+
+use crate::editor_config::get_editor_by_id;
+use crate::settings_store::{self, STORE_FILE};
+use crate::{claude_status, editor, instance, window_registry};
+use serde::Deserialize;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Store key of the local API switch
+pub(crate) const API_ENABLED_KEY: &str = "settings:apiEnabled";
+
+const SOCKET_FILE_NAME: &str = "api.sock";
+
+/// Request bodies larger than this are rejected
+const MAX_BODY_BYTES: u64 = 64 * 1024;
+
+static SERVER: Mutex<Option<Arc<Server>>> = Mutex::new(None);
+
+/// ~/Library/Application Support/<identifier>/api.sock
+pub fn socket_path() -> PathBuf {
+    settings_store::app_support_dir().join(SOCKET_FILE_NAME)
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+struct FocusBody {
+    path: String,
+    /// Editor ID ("cursor"); any editor when omitted
+    #[serde(default)]
+    editor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+struct OpenBody {
+    path: String,
+    editor: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ApiRequest {
+    Windows,
+    ClaudeStatus,
+    Focus(FocusBody),
+    Open(OpenBody),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+fn parse_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(body).map_err(|e| ApiError::new(400, format!("Invalid body: {}", e)))
+}
+
+/// Match a request to an endpoint and decode its body
+fn parse_request(method: &Method, url: &str, body: &[u8]) -> Result<ApiRequest, ApiError> {
+    let path = url.split('?').next().unwrap_or_default().trim_end_matches('/');
+    let request = match (method, path) {
+        (Method::Get, "/windows") => ApiRequest::Windows,
+        (Method::Get, "/claude-status") => ApiRequest::ClaudeStatus,
+        (Method::Post, "/focus") => ApiRequest::Focus(parse_body(body)?),
+        (Method::Post, "/open") => ApiRequest::Open(parse_body(body)?),
+        (_, "/windows" | "/claude-status" | "/focus" | "/open") => {
+            return Err(ApiError::new(405, format!("{} is not allowed on {}", method, path)));
+        }
+        _ => return Err(ApiError::new(404, format!("No endpoint {}", path))),
+    };
+    Ok(request)
+}
+
+fn execute(request: ApiRequest) -> Result<serde_json::Value, ApiError> {
+    let to_json = |value: Result<serde_json::Value, serde_json::Error>| {
+        value.map_err(|e| ApiError::new(500, e.to_string()))
+    };
+    match request {
+        ApiRequest::Windows => to_json(serde_json::to_value(window_registry::snapshot())),
+        ApiRequest::ClaudeStatus => to_json(serde_json::to_value(claude_status::latest_statuses())),
+        ApiRequest::Focus(body) => {
+            instance::focus_project(&body.path, body.editor.as_deref())
+                .map_err(|e| ApiError::new(422, e))?;
+            Ok(serde_json::json!({ "ok": true }))
+        }
+        ApiRequest::Open(body) => {
+            let config = get_editor_by_id(&body.editor)
+                .ok_or_else(|| ApiError::new(400, format!("Unknown editor: {}", body.editor)))?;
+            editor::open_project_in_editor(config.bundle_id, &body.path)
+                .map_err(|e| ApiError::new(422, e))?;
+            window_registry::request_refresh("api-open");
+            Ok(serde_json::json!({ "ok": true }))
+        }
+    }
+}
+
+/// Read a request body, rejecting one over `MAX_BODY_BYTES` instead of parsing it clipped
+fn read_body(reader: impl Read) -> Result<Vec<u8>, ApiError> {
+    let mut body = Vec::new();
+    reader
+        .take(MAX_BODY_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| ApiError::new(400, format!("Failed to read body: {}", e)))?;
+    if body.len() as u64 > MAX_BODY_BYTES {
+        return Err(ApiError::new(413, format!("Body is larger than {} bytes", MAX_BODY_BYTES)));
+    }
+    Ok(body)
+}
+
+fn handle(mut request: Request) {
+    let result = read_body(request.as_reader())
+        .and_then(|body| parse_request(request.method(), request.url(), &body))
+        .and_then(execute);
+    let (status, json) = match result {
+        Ok(json) => (200, json),
+        Err(e) => {
+            log::debug!("API {} {}: {}", request.method(), request.url(), e.message);
+            (e.status, serde_json::json!({ "error": e.message }))
+        }
+    };
+    let content_type = Header::from_bytes("Content-Type", "application/json")
+        .expect("static header is valid");
+    let response = Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    if let Err(e) = request.respond(response) {
+        log::debug!("Failed to send API response: {}", e);
+    }
+}
+
+fn start() -> Result<(), String> {
+    let mut server_slot = SERVER.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if server_slot.is_some() {
+        return Ok(());
+    }
+    let path = socket_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // 前回異常終了したときのソケットファイルが残っていると bind できない
+    let _ = std::fs::remove_file(&path);
+    let server = Server::http_unix(&path)
+        .map(Arc::new)
+        .map_err(|e| format!("Failed to listen on {}: {}", path.display(), e))?;
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let worker = Arc::clone(&server);
+    std::thread::spawn(move || {
+        // unblock() されると None が返ってループを抜ける
+        for request in worker.incoming_requests() {
+            handle(request);
+        }
+        log::debug!("Local API server stopped");
+    });
+    log::info!("Local API listening on {}", path.display());
+    *server_slot = Some(server);
+    Ok(())
+}
+
+/// Stop the server and remove its socket (also called on quit)
+pub fn stop() {
+    let server = SERVER.lock().ok().and_then(|mut server| server.take());
+    if let Some(server) = server {
+        server.unblock();
+        let _ = std::fs::remove_file(socket_path());
+    }
+}
+
+/// Start or stop the server to match the saved setting (setup, settings import)
+pub fn load_settings(app_handle: &AppHandle) {
+    let enabled = app_handle
+        .store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(API_ENABLED_KEY))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    if !enabled {
+        stop();
+    } else if let Err(e) = start() {
+        log::error!("Failed to start local API: {}", e);
+    }
+}
+
+/// Tauri command: whether the local API is running
+#[tauri::command]
+pub fn get_api_enabled() -> bool {
+    SERVER.lock().map(|server| server.is_some()).unwrap_or(false)
+}
+
+/// Tauri command: turn the local API on or off and remember the choice
+#[tauri::command]
+pub fn set_api_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    if enabled {
+        start()?;
+    } else {
+        stop();
+    }
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(API_ENABLED_KEY, enabled);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Tauri command: path of the API socket, for showing in Settings
+#[tauri::command]
+pub fn get_api_socket_path() -> String {
+    socket_path().to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_are_matched_by_method_and_path() {
+        assert_eq!(parse_request(&Method::Get, "/windows", b""), Ok(ApiRequest::Windows));
+        assert_eq!(
+            parse_request(&Method::Get, "/claude-status/?pretty", b""),
+            Ok(ApiRequest::ClaudeStatus)
+        );
+        assert_eq!(
+            parse_request(&Method::Post, "/open", br#"{"path":"/work/api","editor":"zed"}"#),
+            Ok(ApiRequest::Open(OpenBody {
+                path: "/work/api".to_string(),
+                editor: "zed".to_string(),
+            }))
+        );
+        assert_eq!(
+            parse_request(&Method::Post, "/focus", br#"{"path":"/work/api"}"#),
+            Ok(ApiRequest::Focus(FocusBody {
+                path: "/work/api".to_string(),
+                editor: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn bad_requests_get_client_errors() {
+        let status = |method: Method, url: &str, body: &[u8]| {
+            parse_request(&method, url, body).unwrap_err().status
+        };
+        assert_eq!(status(Method::Post, "/windows", b""), 405);
+        assert_eq!(status(Method::Get, "/focus", b""), 405);
+        assert_eq!(status(Method::Get, "/nope", b""), 404);
+        assert_eq!(status(Method::Post, "/focus", b"not json"), 400);
+        assert_eq!(status(Method::Post, "/open", br#"{"path":"/work/api"}"#), 400);
+    }
+
+    #[test]
+    fn oversized_bodies_are_rejected_before_parsing() {
+        let limit = MAX_BODY_BYTES as usize;
+        assert_eq!(read_body(vec![b' '; limit].as_slice()).map(|body| body.len()), Ok(limit));
+        assert_eq!(read_body(vec![b' '; limit + 1].as_slice()).unwrap_err().status, 413);
+    }
+}
@@ -0,0 +1,153 @@
+//! Application logging
+//!
+//! Records always go to stderr, as the old `eprintln!` calls did. Debug logging
+//! can be switched on at runtime: it lowers the level to Debug and tees every
+//! record into a size-rotated file under Application Support, so users can
+//! attach it to bug reports.
+
+use crate::settings_store::{self, STORE_FILE};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store key of the debug logging switch
+pub(crate) const DEBUG_LOGGING_KEY: &str = "settings:debugLogging";
+
+const LOG_FILE_NAME: &str = "editor-tab-manager.log";
+
+/// このサイズを超えたら .1 にローテーションする（1世代のみ保持）
+const MAX_LOG_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Level used while debug logging is off
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Info;
+
+struct Logger {
+    /// Debug logging が有効な間だけ開いているログファイル
+    file: Mutex<Option<File>>,
+}
+
+static LOGGER: Logger = Logger {
+    file: Mutex::new(None),
+};
+
+/// Directory holding the log files (~/Library/Application Support/<identifier>/logs)
+fn log_dir() -> PathBuf {
+    settings_store::app_support_dir().join("logs")
+}
+
+/// Path of the current log file (it only exists once debug logging has been enabled)
+pub fn log_file_path() -> PathBuf {
+    log_dir().join(LOG_FILE_NAME)
+}
+
+fn open_log_file() -> Option<File> {
+    let _ = fs::create_dir_all(log_dir());
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_file_path())
+        .ok()
+}
+
+/// 上限を超えていれば現在のファイルを .1 に移して開き直す
+fn rotate_if_needed(file: &mut Option<File>) {
+    let too_large = file
+        .as_ref()
+        .and_then(|f| f.metadata().ok())
+        .is_some_and(|metadata| metadata.len() > MAX_LOG_FILE_SIZE);
+    if !too_large {
+        return;
+    }
+    let path = log_file_path();
+    let _ = fs::rename(&path, path.with_extension("log.1"));
+    *file = open_log_file();
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!("[{}] {}: {}", record.level(), record.target(), record.args());
+        eprintln!("{}", line);
+
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Some(f) = file.as_mut() {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0);
+            let _ = writeln!(f, "{:.3} {}", timestamp, line);
+            rotate_if_needed(&mut file);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(f) = file.as_mut() {
+                let _ = f.flush();
+            }
+        }
+    }
+}
+
+/// Install the logger. Call once, before anything logs.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(DEFAULT_LEVEL);
+    }
+}
+
+/// Switch the level and the log file without persisting the setting
+fn apply_debug_logging(enabled: bool) {
+    let file = if enabled { open_log_file() } else { None };
+    if let Ok(mut current) = LOGGER.file.lock() {
+        *current = file;
+    }
+    log::set_max_level(if enabled {
+        LevelFilter::Debug
+    } else {
+        DEFAULT_LEVEL
+    });
+}
+
+/// Whether debug logging is currently on
+pub fn debug_logging_enabled() -> bool {
+    log::max_level() >= LevelFilter::Debug
+}
+
+/// Turn debug logging on or off and remember the choice for the next launch
+pub fn set_debug_logging(app_handle: &AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(DEBUG_LOGGING_KEY, enabled);
+    store.save().map_err(|e| e.to_string())?;
+
+    apply_debug_logging(enabled);
+    log::info!("Debug logging {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Apply the saved setting (called from setup once the store plugin is available,
+/// and again after a settings import)
+pub fn load_setting(app_handle: &AppHandle) {
+    let enabled = app_handle
+        .store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(DEBUG_LOGGING_KEY))
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    if enabled != debug_logging_enabled() {
+        apply_debug_logging(enabled);
+    }
+}
@@ -0,0 +1,70 @@
+//! Main-thread execution for AX mutations
+//!
+//! Raising, closing and moving another app's windows through AX from Tauri's
+//! command pool or from watcher threads intermittently does nothing, or hangs,
+//! with Electron editors. Mutating AX calls go through `run`, which executes
+//! them on the main run loop one at a time and hands the `Result` back to the
+//! calling thread. Read-only enumeration stays on the caller's thread so the
+//! UI isn't stalled by slow editors.
+
+use std::sync::{mpsc, OnceLock};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// How long a caller waits for the main thread before giving up
+const MAIN_THREAD_TIMEOUT: Duration = Duration::from_secs(3);
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Remember the app handle used to reach the main thread (called from setup)
+pub fn init(app_handle: AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+/// Whether the caller is on the main thread
+#[cfg(target_os = "macos")]
+pub fn is_main_thread() -> bool {
+    objc2::MainThreadMarker::new().is_some()
+}
+
+/// Whether the caller is on the main thread (std names it "main")
+#[cfg(not(target_os = "macos"))]
+pub fn is_main_thread() -> bool {
+    std::thread::current().name() == Some("main")
+}
+
+/// Run `f` on the main thread and wait for its result.
+/// Runs inline when already on the main thread, or before `init` (tests, early startup).
+pub fn run<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    // メインスレッドから待つとデッドロックするのでそのまま実行する
+    if is_main_thread() {
+        return f();
+    }
+    let Some(app_handle) = APP_HANDLE.get() else {
+        return f();
+    };
+
+    let (tx, rx) = mpsc::channel();
+    app_handle
+        .run_on_main_thread(move || {
+            let _ = tx.send(f());
+        })
+        .map_err(|e| format!("Failed to schedule on main thread: {}", e))?;
+    rx.recv_timeout(MAIN_THREAD_TIMEOUT)
+        .map_err(|_| "Timed out waiting for the main thread".to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_inline_without_an_app_handle() {
+        assert_eq!(run(|| Ok(7)), Ok(7));
+        assert_eq!(run::<(), _>(|| Err("boom".to_string())), Err("boom".to_string()));
+    }
+}
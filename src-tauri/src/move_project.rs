@@ -0,0 +1,160 @@
+//! Moving a project from one editor to another
+//!
+//! `reopen_in_editor` opens the project in the target editor (or focuses the
+//! window it already has there), waits until the new window shows up in the
+//! editor's window list, and can then close the window in the original editor.
+//! Closing goes through the unsaved-changes check, so a window that asks to
+//! save is left open and reported instead.
+
+use crate::editor::{self, CloseWindowError, EditorWindow};
+use crate::editor_config::get_editor_by_bundle_id;
+use crate::i18n;
+use crate::project_groups::{self, same_path, GroupMember, MemberOutcome};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// How long a freshly opened (possibly launching) editor gets to list the window
+const OPEN_TIMEOUT: Duration = Duration::from_secs(10);
+const OPEN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReopenError {
+    /// The target editor is not installed
+    NotInstalled { bundle_id: String },
+    Failed { message: String },
+}
+
+impl std::fmt::Display for ReopenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotInstalled { bundle_id } => {
+                write!(f, "{}", i18n::t_with("error.appNotInstalled", &[("app", bundle_id)]))
+            }
+            Self::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for ReopenError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReopenResult {
+    /// Window the project had in the original editor (None if it wasn't open there)
+    pub old_window_id: Option<u32>,
+    /// Window of the project in the target editor
+    pub new_window_id: u32,
+    /// The original window was closed
+    pub closed_original: bool,
+    /// Why the original window is still open after closing was requested
+    /// (`blocked_by_dialog` when it asked to save changes)
+    pub close_error: Option<CloseWindowError>,
+}
+
+/// The target editor's window for `path`: one resolved to the path, else a
+/// window that wasn't there before opening (its path may not be resolved yet)
+fn find_new_window(before: &[u32], after: &[EditorWindow], path: &str) -> Option<u32> {
+    after
+        .iter()
+        .find(|window| same_path(&window.path, path))
+        .or_else(|| after.iter().find(|window| !before.contains(&window.id)))
+        .map(|window| window.id)
+}
+
+fn wait_for_window(bundle_id: &str, before: &[u32], path: &str) -> Result<u32, String> {
+    let deadline = Instant::now() + OPEN_TIMEOUT;
+    loop {
+        let windows = editor::get_editor_windows(bundle_id);
+        if let Some(window_id) = find_new_window(before, &windows, path) {
+            return Ok(window_id);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!("Timed out waiting for {} to open in {}", path, bundle_id));
+        }
+        std::thread::sleep(OPEN_POLL_INTERVAL);
+    }
+}
+
+fn reopen(
+    path: &str,
+    from_bundle_id: &str,
+    to_bundle_id: &str,
+    close_original: bool,
+) -> Result<ReopenResult, ReopenError> {
+    let target = get_editor_by_bundle_id(to_bundle_id)
+        .ok_or_else(|| format!("Unknown editor: {}", to_bundle_id))?;
+    if from_bundle_id == to_bundle_id {
+        return Err(format!("{} is already open in {}", path, target.display_name).into());
+    }
+    if crate::path_actions::application_path(to_bundle_id).is_none() {
+        return Err(ReopenError::NotInstalled {
+            bundle_id: to_bundle_id.to_string(),
+        });
+    }
+
+    let old_window_id = editor::get_editor_windows(from_bundle_id)
+        .into_iter()
+        .find(|window| same_path(&window.path, path))
+        .map(|window| window.id);
+
+    let target_windows = editor::get_editor_windows(to_bundle_id);
+    let before: Vec<u32> = target_windows.iter().map(|window| window.id).collect();
+    let member = GroupMember {
+        path: path.to_string(),
+        bundle_id: to_bundle_id.to_string(),
+    };
+    let outcome = project_groups::focus_or_open(&member, &target_windows);
+    if let MemberOutcome::Failed { message } = outcome {
+        return Err(message.into());
+    }
+    let new_window_id = wait_for_window(to_bundle_id, &before, path)?;
+
+    // 新しいウィンドウが出てから元を閉じる（保存確認が出たら閉じずに返す）
+    let close_result = match old_window_id {
+        Some(window_id) if close_original => {
+            Some(editor::close_editor_window(from_bundle_id, window_id))
+        }
+        _ => None,
+    };
+    Ok(ReopenResult {
+        old_window_id,
+        new_window_id,
+        closed_original: matches!(close_result, Some(Ok(()))),
+        close_error: close_result.and_then(Result::err),
+    })
+}
+
+/// Tauri command: open a project in another editor, optionally closing the
+/// window it had in the original one
+#[tauri::command(rename_all = "snake_case")]
+pub async fn reopen_in_editor(
+    path: String,
+    from_bundle_id: String,
+    to_bundle_id: String,
+    close_original: bool,
+) -> Result<ReopenResult, ReopenError> {
+    crate::run_blocking(move || reopen(&path, &from_bundle_id, &to_bundle_id, close_original))
+        .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u32, path: &str) -> EditorWindow {
+        EditorWindow::for_test("com.todesktop.230313mzl4w4u92", id, path)
+    }
+
+    #[test]
+    fn new_window_is_found_by_path_or_as_the_one_that_appeared() {
+        let after = [window(1, "/work/api"), window(2, ""), window(3, "/work/web/")];
+        assert_eq!(find_new_window(&[1, 2], &after, "/work/web"), Some(3));
+        // パス解決前でも、開く前になかったウィンドウを新しいものとみなす
+        assert_eq!(find_new_window(&[1, 3], &after, "/work/cli"), Some(2));
+        assert_eq!(find_new_window(&[1, 2, 3], &after, "/work/cli"), None);
+    }
+}
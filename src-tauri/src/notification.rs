@@ -1,18 +1,65 @@
+use crate::i18n;
+use crate::settings_store::STORE_FILE;
 use lazy_static::lazy_static;
+#[cfg(target_os = "macos")]
 use objc2::rc::Retained;
-use objc2::runtime::{AnyClass, AnyObject, ClassBuilder, Sel};
+#[cfg(target_os = "macos")]
+use objc2::runtime::{AnyClass, AnyObject, Bool, ClassBuilder, Sel};
+#[cfg(target_os = "macos")]
 use objc2::{class, msg_send, sel};
-use objc2_foundation::NSString;
+#[cfg(target_os = "macos")]
+use objc2_foundation::{NSCalendar, NSCalendarUnit, NSDate, NSString};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+#[cfg(target_os = "macos")]
 use std::ffi::CStr;
+#[cfg(target_os = "macos")]
+use std::sync::mpsc;
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager};
+use std::time::Duration;
+use tauri::AppHandle;
+#[cfg(target_os = "macos")]
+use tauri::{Emitter, Manager};
+use tauri_plugin_store::StoreExt;
 
 lazy_static! {
     static ref APP_HANDLE: Mutex<Option<AppHandle>> = Mutex::new(None);
+    /// 最後に分かった通知の許可状態（まだ問い合わせていなければ None）
+    #[cfg(target_os = "macos")]
+    static ref PERMISSION: Mutex<Option<NotificationPermission>> = Mutex::new(None);
 }
 
+/// Store keys of the notification rules
+pub(crate) const ENABLED_KEY: &str = "notification:enabled";
+pub(crate) const QUIET_HOURS_KEY: &str = "notification:quietHours";
+pub(crate) const MUTED_PROJECTS_KEY: &str = "notification:mutedProjects";
+pub(crate) const PROJECT_SOUNDS_KEY: &str = "notification:projectSounds";
+
+/// Category attached to every request so macOS shows the action buttons
+const CATEGORY_ID: &str = "ETM_PROJECT";
+const ACTION_FOCUS: &str = "ETM_FOCUS";
+const ACTION_DISMISS: &str = "ETM_DISMISS";
+const ACTION_MUTE: &str = "ETM_MUTE";
+
+/// UNNotificationDefaultActionIdentifier (the body of the notification was clicked)
+const DEFAULT_ACTION: &str = "com.apple.UNNotificationDefaultActionIdentifier";
+
+/// UNAuthorizationOptionBadge | UNAuthorizationOptionSound | UNAuthorizationOptionAlert
+const AUTHORIZATION_OPTIONS: usize = 1 | 2 | 4;
+
+/// How long to wait for the settings completion handler
+const SETTINGS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait for the user to answer the permission prompt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// UNNotificationActionOptions
+const ACTION_OPTION_DESTRUCTIVE: usize = 1 << 1;
+const ACTION_OPTION_FOREGROUND: usize = 1 << 2;
+
 /// Returns true if running inside a proper .app bundle (i.e. has a bundle identifier).
 /// In debug builds (cargo run / tauri dev), there is no bundle so UNUserNotificationCenter crashes.
+#[cfg(target_os = "macos")]
 fn has_bundle_identifier() -> bool {
     unsafe {
         let bundle: *mut AnyObject = msg_send![class!(NSBundle), mainBundle];
@@ -21,16 +68,393 @@ fn has_bundle_identifier() -> bool {
     }
 }
 
+/// Notification authorization status (UNAuthorizationStatus)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationPermission {
+    NotDetermined,
+    Denied,
+    Authorized,
+    Provisional,
+    Ephemeral,
+    /// No bundle identifier (debug build), so UNUserNotificationCenter can't be used
+    Unavailable,
+}
+
+impl NotificationPermission {
+    fn from_status(status: isize) -> Self {
+        match status {
+            1 => Self::Denied,
+            2 => Self::Authorized,
+            3 => Self::Provisional,
+            4 => Self::Ephemeral,
+            _ => Self::NotDetermined,
+        }
+    }
+
+    /// Whether macOS will show the notification banners we post
+    fn allows_delivery(self) -> bool {
+        matches!(self, Self::Authorized | Self::Provisional | Self::Ephemeral)
+    }
+}
+
+/// Why a notification was not sent. Serialized so the UI can point users at System Settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationError {
+    /// The user denied (or has not yet granted) notification permission
+    PermissionDenied { status: NotificationPermission },
+    /// Running outside an .app bundle
+    Unavailable,
+}
+
+impl std::fmt::Display for NotificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PermissionDenied { status } => {
+                let status = format!("{:?}", status);
+                let message = i18n::t_with("error.notificationPermission", &[("status", &status)]);
+                write!(f, "{}", message)
+            }
+            Self::Unavailable => write!(f, "{}", i18n::t("error.notificationsUnavailable")),
+        }
+    }
+}
+
+/// Ask for the authorization status. The completion handler updates the cached status
+/// and forwards it to `tx` when given.
+#[cfg(target_os = "macos")]
+fn query_permission(tx: Option<mpsc::Sender<NotificationPermission>>) {
+    let block = block2::RcBlock::new(move |settings: *mut AnyObject| {
+        let status: isize = if settings.is_null() {
+            0
+        } else {
+            unsafe { msg_send![settings, authorizationStatus] }
+        };
+        let permission = NotificationPermission::from_status(status);
+        *PERMISSION.lock().unwrap() = Some(permission);
+        if let Some(tx) = &tx {
+            let _ = tx.send(permission);
+        }
+    });
+    unsafe {
+        let center: *mut AnyObject =
+            msg_send![class!(UNUserNotificationCenter), currentNotificationCenter];
+        let _: () = msg_send![center, getNotificationSettingsWithCompletionHandler: &*block];
+    }
+}
+
+/// Read the current authorization status, waiting for the completion handler.
+/// Returns None when it did not answer in time.
+#[cfg(target_os = "macos")]
+fn read_permission() -> Option<NotificationPermission> {
+    if !has_bundle_identifier() {
+        return Some(NotificationPermission::Unavailable);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    query_permission(Some(tx));
+    rx.recv_timeout(SETTINGS_TIMEOUT).ok()
+}
+
+/// Notifications go through UNUserNotificationCenter, which only exists on macOS
+#[cfg(not(target_os = "macos"))]
+fn read_permission() -> Option<NotificationPermission> {
+    Some(NotificationPermission::Unavailable)
+}
+
+/// Refresh the cached authorization status without waiting for the answer.
+/// Called at startup and whenever an app is activated, since the user may have
+/// changed it in System Settings.
+#[cfg(target_os = "macos")]
+pub fn refresh_permission() {
+    if has_bundle_identifier() {
+        query_permission(None);
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn refresh_permission() {}
+
+/// Whether a notification may be posted given the last known status.
+/// An unknown status (never answered) is not treated as denied; we try delivering.
+fn check_delivery(permission: Option<NotificationPermission>) -> Result<(), NotificationError> {
+    match permission {
+        Some(status) if !status.allows_delivery() => {
+            Err(NotificationError::PermissionDenied { status })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Tauri command: current notification authorization status
+#[tauri::command]
+pub async fn get_notification_permission() -> NotificationPermission {
+    crate::run_blocking(read_permission)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or(NotificationPermission::NotDetermined)
+}
+
+/// Tauri command: show the macOS permission prompt (only the first time) and return the resulting status
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub async fn request_notification_permission() -> Result<NotificationPermission, String> {
+    if !has_bundle_identifier() {
+        return Ok(NotificationPermission::Unavailable);
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let block = block2::RcBlock::new(move |_granted: Bool, error: *mut AnyObject| {
+        let message = (!error.is_null()).then(|| unsafe {
+            let description: Retained<NSString> = msg_send![error, localizedDescription];
+            description.to_string()
+        });
+        let _ = tx.send(message);
+    });
+    unsafe {
+        let center: *mut AnyObject =
+            msg_send![class!(UNUserNotificationCenter), currentNotificationCenter];
+        let _: () = msg_send![
+            center,
+            requestAuthorizationWithOptions: AUTHORIZATION_OPTIONS,
+            completionHandler: &*block
+        ];
+    }
+
+    match rx.recv_timeout(REQUEST_TIMEOUT) {
+        Ok(None) => Ok(read_permission().unwrap_or(NotificationPermission::NotDetermined)),
+        Ok(Some(message)) => Err(format!("Notification authorization failed: {}", message)),
+        Err(e) => Err(format!("No answer to the notification permission request: {}", e)),
+    }
+}
+
+/// Tauri command: there is no permission prompt to show on this platform
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub async fn request_notification_permission() -> Result<NotificationPermission, String> {
+    Ok(NotificationPermission::Unavailable)
+}
+
 #[derive(Clone, serde::Serialize)]
 struct NotificationClickedPayload {
     project_path: String,
 }
 
+/// Event emitted for a notification response, by action identifier
+fn event_for_action(action: &str) -> Option<&'static str> {
+    match action {
+        DEFAULT_ACTION => Some("notification-clicked"),
+        ACTION_FOCUS => Some("notification-focus"),
+        ACTION_DISMISS => Some("notification-dismiss"),
+        ACTION_MUTE => Some("notification-mute"),
+        _ => None,
+    }
+}
+
+/// Quiet hours in local time, as minutes since midnight. `start > end` spans midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl QuietHours {
+    fn contains(&self, minute_of_day: u16) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute_of_day)
+        } else {
+            minute_of_day >= self.start || minute_of_day < self.end
+        }
+    }
+}
+
+/// Persisted notification preferences, consulted before every notification
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// Global switch (the same flag the frontend checks for completion notifications)
+    pub enabled: bool,
+    pub quiet_hours: Option<QuietHours>,
+    /// Project paths that never notify
+    pub muted_projects: Vec<String>,
+    /// Project path -> sound ("default", "none", or a sound name such as "Glass")
+    #[serde(default)]
+    pub project_sounds: HashMap<String, String>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quiet_hours: None,
+            muted_projects: Vec::new(),
+            project_sounds: HashMap::new(),
+        }
+    }
+}
+
+/// Sound played with a notification
+#[derive(Debug, PartialEq, Eq)]
+enum NotificationSound<'a> {
+    Default,
+    Silent,
+    /// `UNNotificationSound soundNamed:` (e.g. "Glass", "Submarine")
+    Named(&'a str),
+}
+
+/// Pick the sound: an explicit `sound` argument wins over the project's preference
+fn resolve_sound<'a>(
+    requested: Option<&'a str>,
+    settings: &'a NotificationSettings,
+    project_path: &str,
+) -> NotificationSound<'a> {
+    let name = requested.or_else(|| settings.project_sounds.get(project_path).map(String::as_str));
+    match name.map(str::trim) {
+        None | Some("") | Some("default") => NotificationSound::Default,
+        Some("none") => NotificationSound::Silent,
+        Some(name) => NotificationSound::Named(name),
+    }
+}
+
+/// Whether a notification for `project_path` may be shown at `minute_of_day` (local time)
+fn should_notify(settings: &NotificationSettings, project_path: &str, minute_of_day: u16) -> bool {
+    settings.enabled
+        && !settings
+            .quiet_hours
+            .is_some_and(|quiet| quiet.contains(minute_of_day))
+        && !settings.muted_projects.iter().any(|path| path == project_path)
+}
+
+/// Minutes since local midnight
+#[cfg(target_os = "macos")]
+fn local_minute_of_day() -> u16 {
+    let calendar = NSCalendar::currentCalendar();
+    let now = NSDate::now();
+    let hour = calendar.component_fromDate(NSCalendarUnit::Hour, &now);
+    let minute = calendar.component_fromDate(NSCalendarUnit::Minute, &now);
+    (hour * 60 + minute) as u16
+}
+
+fn load_settings(app_handle: &AppHandle) -> NotificationSettings {
+    let Ok(store) = app_handle.store(STORE_FILE) else {
+        return NotificationSettings::default();
+    };
+    let defaults = NotificationSettings::default();
+    NotificationSettings {
+        enabled: store
+            .get(ENABLED_KEY)
+            .and_then(|value| value.as_bool())
+            .unwrap_or(defaults.enabled),
+        quiet_hours: store
+            .get(QUIET_HOURS_KEY)
+            .and_then(|value| serde_json::from_value(value).ok()),
+        muted_projects: store
+            .get(MUTED_PROJECTS_KEY)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or(defaults.muted_projects),
+        project_sounds: store
+            .get(PROJECT_SOUNDS_KEY)
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or(defaults.project_sounds),
+    }
+}
+
+fn save_settings(app_handle: &AppHandle, settings: &NotificationSettings) -> Result<(), String> {
+    let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(ENABLED_KEY, settings.enabled);
+    match settings.quiet_hours {
+        Some(quiet) => store.set(QUIET_HOURS_KEY, serde_json::json!(quiet)),
+        None => {
+            store.delete(QUIET_HOURS_KEY);
+        }
+    }
+    let mut muted = settings.muted_projects.clone();
+    muted.sort();
+    muted.dedup();
+    store.set(MUTED_PROJECTS_KEY, serde_json::json!(muted));
+    store.set(PROJECT_SOUNDS_KEY, serde_json::json!(settings.project_sounds));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Tauri command: current notification settings
+#[tauri::command]
+pub fn get_notification_settings(app: AppHandle) -> NotificationSettings {
+    load_settings(&app)
+}
+
+/// Tauri command: replace the notification settings
+#[tauri::command]
+pub fn set_notification_settings(app: AppHandle, settings: NotificationSettings) -> Result<(), String> {
+    if let Some(quiet) = settings.quiet_hours {
+        if quiet.start >= 24 * 60 || quiet.end >= 24 * 60 {
+            return Err(format!("Invalid quiet hours: {}-{}", quiet.start, quiet.end));
+        }
+    }
+    save_settings(&app, &settings)
+}
+
+/// Stop sending notifications for a project and remember it across launches
+pub fn mute_project(app_handle: &AppHandle, project_path: &str) -> Result<(), String> {
+    let mut settings = load_settings(app_handle);
+    if settings.muted_projects.iter().any(|path| path == project_path) {
+        return Ok(());
+    }
+    settings.muted_projects.push(project_path.to_string());
+    save_settings(app_handle, &settings)
+}
+
+/// Current settings (defaults until the delegate has been set up)
+fn current_settings() -> NotificationSettings {
+    let app_handle = APP_HANDLE.lock().unwrap().clone();
+    app_handle
+        .map(|app_handle| load_settings(&app_handle))
+        .unwrap_or_default()
+}
+
+/// Register the notification category carrying the Focus / Dismiss / Mute actions
+#[cfg(target_os = "macos")]
+unsafe fn register_notification_category(center: *mut AnyObject) {
+    let action = |identifier: &str, title: &str, options: usize| -> Retained<AnyObject> {
+        let identifier = NSString::from_str(identifier);
+        let title = NSString::from_str(title);
+        msg_send![
+            class!(UNNotificationAction),
+            actionWithIdentifier: &*identifier,
+            title: &*title,
+            options: options
+        ]
+    };
+
+    let actions: Retained<AnyObject> = msg_send![class!(NSMutableArray), array];
+    for item in [
+        // Foreground でアプリを前面に出してからフォーカスする
+        action(ACTION_FOCUS, &i18n::t("notification.focus"), ACTION_OPTION_FOREGROUND),
+        action(ACTION_DISMISS, &i18n::t("notification.dismiss"), 0),
+        action(ACTION_MUTE, &i18n::t("notification.mute"), ACTION_OPTION_DESTRUCTIVE),
+    ] {
+        let _: () = msg_send![&*actions, addObject: &*item];
+    }
+
+    let category_id = NSString::from_str(CATEGORY_ID);
+    let intent_identifiers: Retained<AnyObject> = msg_send![class!(NSArray), array];
+    let category: Retained<AnyObject> = msg_send![
+        class!(UNNotificationCategory),
+        categoryWithIdentifier: &*category_id,
+        actions: &*actions,
+        intentIdentifiers: &*intent_identifiers,
+        options: 0usize
+    ];
+    let categories: Retained<AnyObject> = msg_send![class!(NSSet), setWithObject: &*category];
+    let _: () = msg_send![center, setNotificationCategories: &*categories];
+}
+
 /// Register the custom delegate class for UNUserNotificationCenter.
 /// Must be called once at app startup.
+#[cfg(target_os = "macos")]
 pub fn setup_notification_delegate(app_handle: AppHandle) {
     if !has_bundle_identifier() {
-        eprintln!("Skipping notification delegate setup: no bundle identifier (debug build)");
+        log::warn!("Skipping notification delegate setup: no bundle identifier (debug build)");
         return;
     }
 
@@ -48,10 +472,33 @@ pub fn setup_notification_delegate(app_handle: AppHandle) {
 
         // Set the delegate
         let _: () = msg_send![center, setDelegate: delegate];
+
+        register_notification_category(center);
+    }
+    refresh_permission();
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn setup_notification_delegate(_app_handle: AppHandle) {}
+
+/// Re-register the action category so button titles follow the current locale
+#[cfg(target_os = "macos")]
+pub fn refresh_notification_category() {
+    if !has_bundle_identifier() {
+        return;
+    }
+    unsafe {
+        let center: *mut AnyObject =
+            msg_send![class!(UNUserNotificationCenter), currentNotificationCenter];
+        register_notification_category(center);
     }
 }
 
+#[cfg(not(target_os = "macos"))]
+pub fn refresh_notification_category() {}
+
 /// Register the Objective-C delegate class at runtime.
+#[cfg(target_os = "macos")]
 fn register_delegate_class() -> &'static AnyClass {
     let class_name = CStr::from_bytes_with_nul(b"ETMNotificationDelegate\0").unwrap();
 
@@ -89,6 +536,7 @@ fn register_delegate_class() -> &'static AnyClass {
 }
 
 /// Called when user interacts with a notification (click, dismiss, etc.)
+#[cfg(target_os = "macos")]
 unsafe extern "C" fn did_receive_response(
     _this: *mut AnyObject,
     _sel: Sel,
@@ -106,17 +554,27 @@ unsafe extern "C" fn did_receive_response(
     let key = NSString::from_str("projectPath");
     let value: *mut AnyObject = msg_send![user_info, objectForKey: &*key];
 
-    if !value.is_null() {
+    // Which button was pressed (or the default action for a click on the body)
+    let action_identifier: *mut AnyObject = msg_send![response, actionIdentifier];
+    let action = if action_identifier.is_null() {
+        String::new()
+    } else {
+        (*(action_identifier as *const NSString)).to_string()
+    };
+
+    if let (false, Some(event)) = (value.is_null(), event_for_action(&action)) {
         let value_nsstring: &NSString = &*(value as *const NSString);
         let project_path = value_nsstring.to_string();
 
         // Emit event to frontend
         if let Some(app_handle) = APP_HANDLE.lock().unwrap().as_ref() {
+            if action == ACTION_MUTE {
+                if let Err(e) = mute_project(app_handle, &project_path) {
+                    log::error!("Failed to mute notifications for {}: {}", project_path, e);
+                }
+            }
             if let Some(window) = app_handle.get_webview_window("main") {
-                let _ = window.emit(
-                    "notification-clicked",
-                    NotificationClickedPayload { project_path },
-                );
+                let _ = window.emit(event, NotificationClickedPayload { project_path });
             }
         }
     }
@@ -132,6 +590,7 @@ unsafe extern "C" fn did_receive_response(
 /// Called when a notification is about to be presented while the app is in the foreground.
 /// We return UNNotificationPresentationOptionBanner | UNNotificationPresentationOptionSound
 /// so that the notification shows up even when our app has focus.
+#[cfg(target_os = "macos")]
 unsafe extern "C" fn will_present_notification(
     _this: *mut AnyObject,
     _sel: Sel,
@@ -151,6 +610,7 @@ unsafe extern "C" fn will_present_notification(
 
 /// Remove all delivered notifications from the notification center.
 /// Called when an editor is activated, since the notifications have served their purpose.
+#[cfg(target_os = "macos")]
 pub fn remove_all_delivered_notifications() {
     if !has_bundle_identifier() {
         return;
@@ -163,14 +623,64 @@ pub fn remove_all_delivered_notifications() {
     }
 }
 
-/// Tauri command: send a native notification via UNUserNotificationCenter
+/// Request identifier shared by every notification of a project
+fn request_identifier(project_path: &str) -> String {
+    format!("etm-{}", project_path)
+}
+
+/// Tauri command: remove a project's delivered notification from Notification Center
+#[cfg(target_os = "macos")]
 #[tauri::command(rename_all = "snake_case")]
-pub fn send_notification(title: String, subtitle: String, body: String, project_path: String) {
+pub fn clear_notifications_for_project(project_path: String) {
     if !has_bundle_identifier() {
-        eprintln!("Skipping notification: no bundle identifier (debug build)");
         return;
     }
 
+    unsafe {
+        let identifier = NSString::from_str(&request_identifier(&project_path));
+        let identifiers: Retained<AnyObject> =
+            msg_send![class!(NSArray), arrayWithObject: &*identifier];
+        let center: *mut AnyObject =
+            msg_send![class!(UNUserNotificationCenter), currentNotificationCenter];
+        let _: () = msg_send![center, removeDeliveredNotificationsWithIdentifiers: &*identifiers];
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command(rename_all = "snake_case")]
+pub fn clear_notifications_for_project(project_path: String) {
+    let _ = project_path;
+}
+
+/// Tauri command: send a native notification via UNUserNotificationCenter.
+/// `sound` is "default", "none" or a sound name; when omitted the project's preference is used.
+/// Fails with `PermissionDenied` when the user has not allowed notifications.
+#[cfg(target_os = "macos")]
+#[tauri::command(rename_all = "snake_case")]
+pub fn send_notification(
+    title: String,
+    subtitle: String,
+    body: String,
+    project_path: String,
+    sound: Option<String>,
+) -> Result<(), NotificationError> {
+    if !has_bundle_identifier() {
+        log::warn!("Skipping notification: no bundle identifier (debug build)");
+        return Err(NotificationError::Unavailable);
+    }
+    let settings = current_settings();
+    if !should_notify(&settings, &project_path, local_minute_of_day()) {
+        log::debug!("Skipping notification for {} (disabled, quiet hours or muted)", project_path);
+        return Ok(());
+    }
+    // メインスレッドから呼ばれるので問い合わせを待たず、アプリの切り替えごとに更新される
+    // キャッシュを使う。まだ分からなければ問い合わせだけ出しておく
+    let permission = *PERMISSION.lock().unwrap();
+    if permission.is_none() {
+        refresh_permission();
+    }
+    check_delivery(permission)?;
+
     unsafe {
         // Create UNMutableNotificationContent
         let content: Retained<AnyObject> = msg_send![class!(UNMutableNotificationContent), new];
@@ -187,10 +697,19 @@ pub fn send_notification(title: String, subtitle: String, body: String, project_
         let ns_body = NSString::from_str(&body);
         let _: () = msg_send![&*content, setBody: &*ns_body];
 
-        // Set sound to default
-        let default_sound: Retained<AnyObject> =
-            msg_send![class!(UNNotificationSound), defaultSound];
-        let _: () = msg_send![&*content, setSound: &*default_sound];
+        // Set sound (no sound object means a silent banner)
+        let sound: Option<Retained<AnyObject>> =
+            match resolve_sound(sound.as_deref(), &settings, &project_path) {
+                NotificationSound::Default => msg_send![class!(UNNotificationSound), defaultSound],
+                NotificationSound::Silent => None,
+                NotificationSound::Named(name) => {
+                    let name = NSString::from_str(name);
+                    msg_send![class!(UNNotificationSound), soundNamed: &*name]
+                }
+            };
+        if let Some(sound) = sound {
+            let _: () = msg_send![&*content, setSound: &*sound];
+        }
 
         // Set userInfo with projectPath
         let key = NSString::from_str("projectPath");
@@ -202,11 +721,16 @@ pub fn send_notification(title: String, subtitle: String, body: String, project_
         ];
         let _: () = msg_send![&*content, setUserInfo: &*user_info];
 
-        // Create a unique identifier for the request
-        let identifier = NSString::from_str(&format!("etm-{}", std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis()));
+        // Show the Focus / Dismiss / Mute actions
+        let category_id = NSString::from_str(CATEGORY_ID);
+        let _: () = msg_send![&*content, setCategoryIdentifier: &*category_id];
+
+        // Group banners of the same project in Notification Center
+        let thread_identifier = NSString::from_str(&project_path);
+        let _: () = msg_send![&*content, setThreadIdentifier: &*thread_identifier];
+
+        // A stable identifier per project replaces the previous banner instead of stacking
+        let identifier = NSString::from_str(&request_identifier(&project_path));
 
         // Create UNNotificationRequest
         let request: Retained<AnyObject> = msg_send![
@@ -221,4 +745,142 @@ pub fn send_notification(title: String, subtitle: String, body: String, project_
             msg_send![class!(UNUserNotificationCenter), currentNotificationCenter];
         let _: () = msg_send![center, addNotificationRequest: &*request, withCompletionHandler: std::ptr::null::<AnyObject>()];
     }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command(rename_all = "snake_case")]
+pub fn send_notification(
+    title: String,
+    subtitle: String,
+    body: String,
+    project_path: String,
+    sound: Option<String>,
+) -> Result<(), NotificationError> {
+    let _ = (title, subtitle, body, project_path, sound);
+    Err(NotificationError::Unavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_map_to_events() {
+        assert_eq!(event_for_action(DEFAULT_ACTION), Some("notification-clicked"));
+        assert_eq!(event_for_action(ACTION_FOCUS), Some("notification-focus"));
+        assert_eq!(event_for_action(ACTION_DISMISS), Some("notification-dismiss"));
+        assert_eq!(event_for_action(ACTION_MUTE), Some("notification-mute"));
+        // 通知センターで閉じた場合などは何もしない
+        assert_eq!(
+            event_for_action("com.apple.UNNotificationDismissActionIdentifier"),
+            None
+        );
+    }
+
+    fn settings(quiet_hours: Option<QuietHours>, muted: &[&str]) -> NotificationSettings {
+        NotificationSettings {
+            enabled: true,
+            quiet_hours,
+            muted_projects: muted.iter().map(|path| path.to_string()).collect(),
+            project_sounds: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn disabled_settings_block_everything() {
+        let disabled = NotificationSettings {
+            enabled: false,
+            ..NotificationSettings::default()
+        };
+        assert!(!should_notify(&disabled, "/repo", 12 * 60));
+        assert!(should_notify(&NotificationSettings::default(), "/repo", 12 * 60));
+    }
+
+    #[test]
+    fn quiet_hours_within_a_day() {
+        // 13:00-14:30
+        let s = settings(Some(QuietHours { start: 780, end: 870 }), &[]);
+        assert!(should_notify(&s, "/repo", 779));
+        assert!(!should_notify(&s, "/repo", 780));
+        assert!(!should_notify(&s, "/repo", 869));
+        assert!(should_notify(&s, "/repo", 870));
+    }
+
+    #[test]
+    fn quiet_hours_spanning_midnight() {
+        // 22:00-07:00
+        let s = settings(Some(QuietHours { start: 1320, end: 420 }), &[]);
+        assert!(!should_notify(&s, "/repo", 23 * 60));
+        assert!(!should_notify(&s, "/repo", 0));
+        assert!(!should_notify(&s, "/repo", 419));
+        assert!(should_notify(&s, "/repo", 420));
+        assert!(should_notify(&s, "/repo", 12 * 60));
+    }
+
+    #[test]
+    fn empty_quiet_hours_never_match() {
+        let s = settings(Some(QuietHours { start: 600, end: 600 }), &[]);
+        assert!(should_notify(&s, "/repo", 600));
+    }
+
+    #[test]
+    fn muted_projects_are_skipped() {
+        let s = settings(None, &["/scratch"]);
+        assert!(!should_notify(&s, "/scratch", 0));
+        assert!(should_notify(&s, "/repo", 0));
+    }
+
+    #[test]
+    fn sound_falls_back_to_project_preference() {
+        let mut s = settings(None, &[]);
+        s.project_sounds.insert("/work".to_string(), "Submarine".to_string());
+        s.project_sounds.insert("/quiet".to_string(), "none".to_string());
+
+        assert_eq!(resolve_sound(None, &s, "/work"), NotificationSound::Named("Submarine"));
+        assert_eq!(resolve_sound(None, &s, "/quiet"), NotificationSound::Silent);
+        assert_eq!(resolve_sound(None, &s, "/other"), NotificationSound::Default);
+        // 明示的に指定された音が優先される
+        assert_eq!(resolve_sound(Some("none"), &s, "/work"), NotificationSound::Silent);
+        assert_eq!(resolve_sound(Some("default"), &s, "/quiet"), NotificationSound::Default);
+    }
+
+    #[test]
+    fn request_identifier_is_stable_per_project() {
+        assert_eq!(request_identifier("/repo/a"), request_identifier("/repo/a"));
+        assert_ne!(request_identifier("/repo/a"), request_identifier("/repo/b"));
+    }
+
+    #[test]
+    fn authorization_status_is_mapped() {
+        assert_eq!(NotificationPermission::from_status(0), NotificationPermission::NotDetermined);
+        assert_eq!(NotificationPermission::from_status(1), NotificationPermission::Denied);
+        assert!(NotificationPermission::from_status(2).allows_delivery());
+        assert!(NotificationPermission::from_status(3).allows_delivery());
+        assert!(!NotificationPermission::Denied.allows_delivery());
+        assert!(!NotificationPermission::NotDetermined.allows_delivery());
+    }
+
+    #[test]
+    fn unknown_permission_still_tries_delivering() {
+        assert_eq!(check_delivery(None), Ok(()));
+        assert_eq!(check_delivery(Some(NotificationPermission::Authorized)), Ok(()));
+        assert_eq!(
+            check_delivery(Some(NotificationPermission::Denied)),
+            Err(NotificationError::PermissionDenied {
+                status: NotificationPermission::Denied
+            })
+        );
+    }
+
+    #[test]
+    fn permission_error_is_tagged_for_the_frontend() {
+        let error = NotificationError::PermissionDenied {
+            status: NotificationPermission::Denied,
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "permission_denied", "status": "denied" })
+        );
+    }
 }
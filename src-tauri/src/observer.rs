@@ -1,30 +1,55 @@
 use crate::ax_observer;
-use crate::editor_config::is_supported_editor;
+use crate::debounce::{self, DebounceKey};
+use crate::editor_config::{get_editor_by_bundle_id, is_supported_editor};
+use crate::editor_model::{AppActivationPayload, FrontmostApp};
 use crate::notification;
-use objc2::MainThreadMarker;
+use crate::polling;
+use crate::power;
+use crate::tab_bars;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, NSObjectProtocol, ProtocolObject};
+use objc2::{MainThreadMarker, Message};
 use objc2_app_kit::{NSRunningApplication, NSScreen, NSWorkspace};
 use objc2_foundation::{
-    NSNotification, NSNotificationCenter, NSNotificationName, NSOperationQueue, NSString,
+    NSDistributedNotificationCenter, NSNotification, NSNotificationCenter, NSNotificationName,
+    NSOperationQueue, NSString,
 };
 use std::ptr::NonNull;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
 
 static OBSERVER_RUNNING: AtomicBool = AtomicBool::new(false);
 
-static DEBOUNCE_VERSION: AtomicU64 = AtomicU64::new(0);
-const DEBOUNCE_DELAY_MS: u64 = 150;
+/// Background thread that owns the notification-center registrations
+static OBSERVER_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Set once a tab bar has registered its listeners (see `frontend_ready`)
+static FRONTEND_READY: AtomicBool = AtomicBool::new(false);
+/// app-activated payloads emitted before the frontend was ready, oldest first
+static EARLY_EVENTS: Mutex<Vec<AppActivationPayload>> = Mutex::new(Vec::new());
+/// Most recent app-activated payload, read by `get_snapshot`
+static LAST_ACTIVATION: Mutex<Option<AppActivationPayload>> = Mutex::new(None);
+/// Only the latest activations matter, so older queued ones are dropped past this
+const MAX_EARLY_EVENTS: usize = 16;
+/// Flush the queue anyway if the frontend never reports ready
+const FRONTEND_READY_TIMEOUT_MS: u64 = 5000;
 
-static DISPLAY_DEBOUNCE_VERSION: AtomicU64 = AtomicU64::new(0);
+const DEBOUNCE_DELAY_MS: u64 = 150;
 const DISPLAY_DEBOUNCE_DELAY_MS: u64 = 300;
+const SPACE_DEBOUNCE_DELAY_MS: u64 = 300;
+
+static WAKE_VERSION: AtomicU64 = AtomicU64::new(0);
+/// Editors take a few seconds to rebuild their AX tree after wake, so recovery
+/// runs once after a short pause and again later for slow editors.
+const WAKE_RECOVERY_DELAYS_MS: [u64; 2] = [2000, 5000];
 
 /// Check if the given app is a supported editor (VSCode, Cursor, etc)
 fn is_target_app(app: &NSRunningApplication) -> bool {
-    if let Some(bundle_id) = app.bundleIdentifier() {
-        return is_supported_editor(&bundle_id.to_string());
+    if let Some(bundle_id) = crate::ax_helper::app_bundle_id(app) {
+        return is_supported_editor(&bundle_id);
     }
     false
 }
@@ -34,13 +59,40 @@ fn is_tab_manager(app: &NSRunningApplication, our_pid: i32) -> bool {
     app.processIdentifier() == our_pid
 }
 
-/// Payload for app activation events
+/// Bundle id and pid of the app carried by an NSWorkspace notification.
+/// NSWorkspace notifications provide the app via NSWorkspaceApplicationKey in userInfo.
+fn notification_app(notification: NonNull<NSNotification>) -> Option<(Option<String>, i32)> {
+    unsafe {
+        let notif = notification.as_ref();
+        notif.userInfo().and_then(|info| {
+            let key = NSString::from_str("NSWorkspaceApplicationKey");
+            info.objectForKey(&*key).map(|obj| {
+                let app = &*(&*obj as *const _ as *const NSRunningApplication);
+                (
+                    crate::ax_helper::app_bundle_id(app),
+                    app.processIdentifier(),
+                )
+            })
+        })
+    }
+}
+
+/// Payload for editor-quit events
 #[derive(Clone, serde::Serialize, Debug)]
-pub struct AppActivationPayload {
-    pub app_type: String, // "editor", "tab_manager", or "other"
-    pub bundle_id: Option<String>,
-    pub is_on_primary_screen: bool,
-    pub covers_editor: bool,
+pub struct EditorQuitPayload {
+    pub bundle_id: String,
+}
+
+impl FrontmostApp {
+    fn new(payload: AppActivationPayload, pid: i32) -> Self {
+        Self {
+            is_editor_context: payload.app_type != "other",
+            app_type: payload.app_type,
+            bundle_id: payload.bundle_id,
+            pid,
+            is_on_primary_screen: payload.is_on_primary_screen,
+        }
+    }
 }
 
 /// Get PIDs of all running supported editors.
@@ -50,8 +102,8 @@ fn get_running_editor_pids() -> Vec<i32> {
     running_apps
         .iter()
         .filter_map(|app| {
-            let bid = app.bundleIdentifier()?;
-            if is_supported_editor(&bid.to_string()) {
+            let bid = crate::ax_helper::app_bundle_id(&app)?;
+            if is_supported_editor(&bid) {
                 Some(app.processIdentifier())
             } else {
                 None
@@ -89,56 +141,151 @@ const COLD_START_RETRY_INTERVAL_MS: u64 = 500;
 /// Re-check window size for a cold-starting app.
 /// Called when the initial check found no windows (app still launching).
 /// If the new window covers the editor, re-emits app-activated to hide the tab bar.
+/// Shares the "other" debounce key, so switching to another app drops the rechecks.
 fn schedule_cold_start_recheck(
     pid: i32,
     bundle_id: Option<String>,
     app_handle: Arc<AppHandle>,
-    debounce_version: u64,
+    retries_left: u32,
 ) {
-    thread::spawn(move || {
-        for _ in 0..COLD_START_RETRY_COUNT {
-            thread::sleep(Duration::from_millis(COLD_START_RETRY_INTERVAL_MS));
-
-            if DEBOUNCE_VERSION.load(Ordering::SeqCst) != debounce_version {
-                return; // User switched to another app
-            }
-
-            // AX API is thread-safe, check from background thread
-            match is_front_covering_editor(pid) {
+    if retries_left == 0 {
+        return;
+    }
+    debounce::schedule(
+        DebounceKey::OtherApp,
+        Duration::from_millis(COLD_START_RETRY_INTERVAL_MS),
+        move || {
+            // The launching app may answer AX slowly; query it off the shared debounce
+            // worker so other debounced tasks don't stall, and reschedule from there
+            std::thread::spawn(move || match is_front_covering_editor(pid) {
                 Some(true) => {
                     // Window covers the editor → hide tab bar
-                    let bid = bundle_id;
                     let app_handle_main = Arc::clone(&app_handle);
                     let _ = app_handle.run_on_main_thread(move || {
                         let payload = AppActivationPayload {
                             app_type: "other".to_string(),
-                            bundle_id: bid,
+                            bundle_id,
                             is_on_primary_screen: is_focused_on_primary_screen(),
                             covers_editor: true,
                         };
                         emit_app_activated(&app_handle_main, payload);
                     });
-                    return;
                 }
                 Some(false) => {
                     // Window doesn't cover editor → tab bar already visible
-                    return;
                 }
                 None => {
                     // No windows yet → continue retrying
+                    schedule_cold_start_recheck(pid, bundle_id, app_handle, retries_left - 1);
                 }
+            });
+        },
+    );
+}
+
+/// Emit an app-activated event to every tab bar window.
+/// Until the frontend is ready the payload is queued instead.
+fn emit_app_activated(app_handle: &AppHandle, payload: AppActivationPayload) {
+    if let Ok(mut last) = LAST_ACTIVATION.lock() {
+        *last = Some(payload.clone());
+    }
+    crate::autohide::on_activation(app_handle, &payload);
+    if !FRONTEND_READY.load(Ordering::SeqCst) {
+        if let Ok(mut queue) = EARLY_EVENTS.lock() {
+            // Re-check under the lock so a concurrent flush can't miss this payload
+            if !FRONTEND_READY.load(Ordering::SeqCst) {
+                push_early_event(&mut queue, payload);
+                return;
             }
         }
-    });
+    }
+    tab_bars::emit_to_tab_bars(app_handle, "app-activated", payload);
 }
 
-/// Emit an app-activated event to the main window.
-fn emit_app_activated(app_handle: &AppHandle, payload: AppActivationPayload) {
-    if let Some(window) = app_handle.get_webview_window("main") {
-        let _ = window.emit("app-activated", payload);
+/// The last activation emitted (or queued) for the tab bars
+pub fn last_activation() -> Option<AppActivationPayload> {
+    LAST_ACTIVATION.lock().ok().and_then(|last| last.clone())
+}
+
+fn push_early_event(queue: &mut Vec<AppActivationPayload>, payload: AppActivationPayload) {
+    if queue.len() >= MAX_EARLY_EVENTS {
+        queue.remove(0);
+    }
+    queue.push(payload);
+}
+
+/// Mark the frontend ready and emit the activations queued before it was.
+/// Called by the `frontend_ready` command and by the startup timeout fallback.
+pub fn frontend_ready(app_handle: &AppHandle) {
+    let queued = {
+        let Ok(mut queue) = EARLY_EVENTS.lock() else {
+            return;
+        };
+        if FRONTEND_READY.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        std::mem::take(&mut *queue)
+    };
+    for payload in queued {
+        tab_bars::emit_to_tab_bars(app_handle, "app-activated", payload);
+    }
+}
+
+/// Activation payload for the app that is frontmost right now.
+/// Without `check_coverage`, other apps are assumed to cover the editor (tab bar hidden).
+fn frontmost_activation_payload(
+    our_pid: i32,
+    check_coverage: bool,
+) -> Option<AppActivationPayload> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let frontmost = workspace.frontmostApplication()?;
+    Some(activation_payload_for(&frontmost, our_pid, check_coverage))
+}
+
+fn activation_payload_for(
+    app: &NSRunningApplication,
+    our_pid: i32,
+    check_coverage: bool,
+) -> AppActivationPayload {
+    let bundle_id_str = crate::ax_helper::app_bundle_id(app);
+
+    if is_tab_manager(app, our_pid) {
+        AppActivationPayload {
+            app_type: "tab_manager".to_string(),
+            bundle_id: None,
+            is_on_primary_screen: true,
+            covers_editor: false,
+        }
+    } else if is_target_app(app) {
+        AppActivationPayload {
+            app_type: "editor".to_string(),
+            bundle_id: bundle_id_str,
+            is_on_primary_screen: true,
+            covers_editor: false,
+        }
+    } else {
+        let covers_editor =
+            !check_coverage || is_front_covering_editor(app.processIdentifier()).unwrap_or(true);
+        AppActivationPayload {
+            app_type: "other".to_string(),
+            bundle_id: bundle_id_str,
+            is_on_primary_screen: is_focused_on_primary_screen(),
+            covers_editor,
+        }
     }
 }
 
+/// The frontmost app, classified the same way as app-activated events
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let frontmost = workspace.frontmostApplication()?;
+    let our_pid = std::process::id() as i32;
+    Some(FrontmostApp::new(
+        activation_payload_for(&frontmost, our_pid, false),
+        frontmost.processIdentifier(),
+    ))
+}
+
 /// NSScreen::mainScreen() はフォーカス中ウィンドウのスクリーンを返す。
 /// プライマリスクリーンの origin は常に (0, 0)。
 fn is_focused_on_primary_screen() -> bool {
@@ -152,24 +299,17 @@ fn is_focused_on_primary_screen() -> bool {
     origin.x.abs() < 1.0 && origin.y.abs() < 1.0
 }
 
-/// Cancel any pending "other" event by incrementing the debounce version.
+/// Cancel any pending "other" event (and its cold-start rechecks).
 /// Called from ax_observer when editor activation is confirmed via AX events.
 pub fn cancel_pending_other_event() {
-    DEBOUNCE_VERSION.fetch_add(1, Ordering::SeqCst);
+    debounce::cancel(DebounceKey::OtherApp);
 }
 
 /// Schedule an "other" event to be emitted after the debounce delay
 fn schedule_other_event(bundle_id: Option<String>, app_handle: Arc<AppHandle>) {
-    let version = DEBOUNCE_VERSION.fetch_add(1, Ordering::SeqCst) + 1;
-
     let app_handle_for_thread = Arc::clone(&app_handle);
-    thread::spawn(move || {
-        thread::sleep(Duration::from_millis(DEBOUNCE_DELAY_MS));
-
-        if DEBOUNCE_VERSION.load(Ordering::SeqCst) != version {
-            return;
-        }
-
+    let delay = Duration::from_millis(DEBOUNCE_DELAY_MS);
+    debounce::schedule(DebounceKey::OtherApp, delay, move || {
         let app_handle_main = Arc::clone(&app_handle_for_thread);
         let app_handle_retry = Arc::clone(&app_handle_for_thread);
         let _ = app_handle_for_thread.run_on_main_thread(move || {
@@ -178,10 +318,11 @@ fn schedule_other_event(bundle_id: Option<String>, app_handle: Arc<AppHandle>) {
             let workspace = NSWorkspace::sharedWorkspace();
             if let Some(frontmost) = workspace.frontmostApplication() {
                 if is_target_app(&frontmost) {
-                    let bid = frontmost.bundleIdentifier().map(|s| s.to_string());
+                    let bid = crate::ax_helper::app_bundle_id(&frontmost);
                     notification::remove_all_delivered_notifications();
                     ax_observer::register_all_editors();
                     crate::window_registry::request_refresh("app-activated");
+                    crate::tray::update_title(&app_handle_main);
                     let payload = AppActivationPayload {
                         app_type: "editor".to_string(),
                         bundle_id: bid,
@@ -192,6 +333,8 @@ fn schedule_other_event(bundle_id: Option<String>, app_handle: Arc<AppHandle>) {
                     return;
                 }
 
+                crate::tray::clear_title(&app_handle_main);
+
                 // Check if the other app's window covers the editor
                 let pid = frontmost.processIdentifier();
                 match is_front_covering_editor(pid) {
@@ -219,11 +362,12 @@ fn schedule_other_event(bundle_id: Option<String>, app_handle: Arc<AppHandle>) {
                             pid,
                             bid_retry,
                             app_handle_retry,
-                            version,
+                            COLD_START_RETRY_COUNT,
                         );
                     }
                 }
             } else {
+                crate::tray::clear_title(&app_handle_main);
                 let payload = AppActivationPayload {
                     app_type: "other".to_string(),
                     bundle_id,
@@ -236,16 +380,77 @@ fn schedule_other_event(bundle_id: Option<String>, app_handle: Arc<AppHandle>) {
     });
 }
 
+/// Recover from sleep: re-create AX observers (the old ones go silent once
+/// editors rebuild their AX tree), re-read windows, and recompute Claude status.
+fn schedule_wake_recovery(app_handle: Arc<AppHandle>) {
+    let version = WAKE_VERSION.fetch_add(1, Ordering::SeqCst) + 1;
+    thread::spawn(move || {
+        let mut waited_ms = 0;
+        for delay_ms in WAKE_RECOVERY_DELAYS_MS {
+            thread::sleep(Duration::from_millis(delay_ms - waited_ms));
+            waited_ms = delay_ms;
+            // A later wake restarts the sequence
+            if WAKE_VERSION.load(Ordering::SeqCst) != version {
+                return;
+            }
+            let _ = app_handle.run_on_main_thread(|| {
+                ax_observer::unregister_all();
+                ax_observer::register_all_editors();
+                crate::window_registry::request_full_refresh("wake");
+            });
+        }
+        if let Err(e) = crate::claude_status::refresh_statuses() {
+            log::debug!("Claude status refresh after wake failed: {}", e);
+        }
+    });
+}
+
+/// Notification-center observers added by the workspace observer.
+/// Dropping this removes every one of them, so a restarted observer never sees events twice.
+#[derive(Default)]
+struct Registrations {
+    tokens: Vec<(
+        Retained<NSNotificationCenter>,
+        Retained<ProtocolObject<dyn NSObjectProtocol>>,
+    )>,
+}
+
+impl Registrations {
+    fn observe(
+        &mut self,
+        center: &NSNotificationCenter,
+        name: &NSNotificationName,
+        queue: &NSOperationQueue,
+        block: &block2::DynBlock<dyn Fn(NonNull<NSNotification>)>,
+    ) {
+        let token = unsafe {
+            center.addObserverForName_object_queue_usingBlock(Some(name), None, Some(queue), block)
+        };
+        self.tokens.push((center.retain(), token));
+    }
+}
+
+impl Drop for Registrations {
+    fn drop(&mut self) {
+        for (center, token) in self.tokens.drain(..) {
+            let observer: &AnyObject = (*token).as_ref();
+            unsafe { center.removeObserver(observer) };
+        }
+    }
+}
+
 /// Start the workspace observer in a background thread
 pub fn start_observer(app_handle: AppHandle) {
     if OBSERVER_RUNNING.swap(true, Ordering::SeqCst) {
         return;
     }
+    power::watcher_started(power::Watcher::Observer);
 
     let our_pid = std::process::id() as i32;
     let app_handle = Arc::new(app_handle);
 
-    thread::spawn(move || {
+    let handle = thread::spawn(move || {
+        let mut registrations = Registrations::default();
         let workspace = NSWorkspace::sharedWorkspace();
         let notification_center = workspace.notificationCenter();
 
@@ -256,22 +461,9 @@ pub fn start_observer(app_handle: AppHandle) {
 
         let block = block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
             // Approach 1: Try to get the activated app from notification's userInfo.
-            // NSWorkspaceDidActivateApplicationNotification provides the app via
-            // NSWorkspaceApplicationKey. This is more accurate than frontmostApplication()
-            // for Dock-click scenarios where the frontmost app hasn't updated yet.
-            let app_info: Option<(Option<String>, i32)> = unsafe {
-                let notif = notification.as_ref();
-                notif.userInfo().and_then(|info| {
-                    let key = NSString::from_str("NSWorkspaceApplicationKey");
-                    info.objectForKey(&*key).map(|obj| {
-                        let app = &*(&*obj as *const _ as *const NSRunningApplication);
-                        (
-                            app.bundleIdentifier().map(|s| s.to_string()),
-                            app.processIdentifier(),
-                        )
-                    })
-                })
-            };
+            // This is more accurate than frontmostApplication() for Dock-click
+            // scenarios where the frontmost app hasn't updated yet.
+            let app_info = notification_app(notification);
 
             // Fallback to frontmostApplication() if userInfo extraction failed
             let (bundle_id_str, app_pid) = match app_info {
@@ -282,12 +474,15 @@ pub fn start_observer(app_handle: AppHandle) {
                         return;
                     };
                     (
-                        app.bundleIdentifier().map(|s| s.to_string()),
+                        crate::ax_helper::app_bundle_id(&app),
                         app.processIdentifier(),
                     )
                 }
             };
 
+            // The user may have come back from changing notification permission in System Settings
+            notification::refresh_permission();
+
             if app_pid == our_pid {
                 // Tab manager is active → cancel pending "other" and emit immediately
                 cancel_pending_other_event();
@@ -307,6 +502,7 @@ pub fn start_observer(app_handle: AppHandle) {
                 notification::remove_all_delivered_notifications();
                 ax_observer::register_all_editors();
                 crate::window_registry::request_refresh("app-activated");
+                crate::tray::update_title(&app_handle_clone);
                 let payload = AppActivationPayload {
                     app_type: "editor".to_string(),
                     bundle_id: bundle_id_str,
@@ -322,12 +518,116 @@ pub fn start_observer(app_handle: AppHandle) {
 
         let main_queue = NSOperationQueue::mainQueue();
 
-        unsafe {
-            notification_center.addObserverForName_object_queue_usingBlock(
-                Some(&notification_name),
-                None,
-                Some(&main_queue),
-                &block,
+        registrations.observe(&notification_center, &notification_name, &main_queue, &block);
+
+        // Editor launch/quit: observe a new editor before it is first activated,
+        // and drop observers whose process is gone
+        let launch_notification_name =
+            NSNotificationName::from_str("NSWorkspaceDidLaunchApplicationNotification");
+        let launch_block = block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
+            let Some((Some(bundle_id), pid)) = notification_app(notification) else {
+                return;
+            };
+            if !is_supported_editor(&bundle_id) {
+                return;
+            }
+            ax_observer::register_for_pid(pid);
+            crate::window_registry::expect_launch(&bundle_id);
+            crate::window_registry::request_refresh("editor-launched");
+        });
+
+        let terminate_notification_name =
+            NSNotificationName::from_str("NSWorkspaceDidTerminateApplicationNotification");
+        let app_handle_for_terminate = Arc::clone(&app_handle);
+        let terminate_block =
+            block2::RcBlock::new(move |notification: NonNull<NSNotification>| {
+                let Some((Some(bundle_id), pid)) = notification_app(notification) else {
+                    return;
+                };
+                if !is_supported_editor(&bundle_id) {
+                    return;
+                }
+                ax_observer::unregister_for_pid(pid);
+                // 別インスタンスが残っていれば、そのウィンドウの位置とパスはまだ有効
+                let other_instance_running = crate::ax_helper::get_pids_by_bundle_id(&bundle_id)
+                    .into_iter()
+                    .any(|other_pid| other_pid != pid);
+                if !other_instance_running {
+                    crate::window_offset::forget_editor(&bundle_id);
+                    if let Some(config) = get_editor_by_bundle_id(&bundle_id) {
+                        crate::editor::invalidate_path_cache_for_editor(config.id);
+                    }
+                }
+                crate::window_registry::forget_launch(&bundle_id);
+                crate::window_registry::request_refresh("editor-quit");
+                tab_bars::emit_to_tab_bars(
+                    &app_handle_for_terminate,
+                    "editor-quit",
+                    EditorQuitPayload { bundle_id },
+                );
+            });
+
+        registrations.observe(
+            &notification_center,
+            &launch_notification_name,
+            &main_queue,
+            &launch_block,
+        );
+        registrations.observe(
+            &notification_center,
+            &terminate_notification_name,
+            &main_queue,
+            &terminate_block,
+        );
+
+        // Active Space change: the frontmost app (and its screen) may differ on the new Space
+        let space_notification_name =
+            NSNotificationName::from_str("NSWorkspaceActiveSpaceDidChangeNotification");
+        let app_handle_for_space = Arc::clone(&app_handle);
+        let space_block = block2::RcBlock::new(move |_notification: NonNull<NSNotification>| {
+            let app_handle_debounce = Arc::clone(&app_handle_for_space);
+            let delay = Duration::from_millis(SPACE_DEBOUNCE_DELAY_MS);
+            debounce::schedule(DebounceKey::Space, delay, move || {
+                let app_handle_main = Arc::clone(&app_handle_debounce);
+                let _ = app_handle_debounce.run_on_main_thread(move || {
+                    tab_bars::emit_to_tab_bars(&app_handle_main, "space-changed", ());
+                    // Evaluated on the main thread so is_on_primary_screen reflects the new Space
+                    let Some(payload) = frontmost_activation_payload(our_pid, true) else {
+                        return;
+                    };
+                    if payload.app_type == "editor" {
+                        crate::window_registry::request_refresh("space-changed");
+                        crate::tray::update_title(&app_handle_main);
+                    } else if payload.app_type == "other" {
+                        crate::tray::clear_title(&app_handle_main);
+                    }
+                    emit_app_activated(&app_handle_main, payload);
+                });
+            });
+        });
+        registrations.observe(
+            &notification_center,
+            &space_notification_name,
+            &main_queue,
+            &space_block,
+        );
+
+        // Sleep/wake: both the system and the displays waking can leave observers stale
+        let app_handle_for_wake = Arc::clone(&app_handle);
+        let wake_block = block2::RcBlock::new(move |_notification: NonNull<NSNotification>| {
+            log::info!("Woke from sleep; re-registering observers");
+            schedule_wake_recovery(Arc::clone(&app_handle_for_wake));
+        });
+        for name in [
+            "NSWorkspaceDidWakeNotification",
+            "NSWorkspaceScreensDidWakeNotification",
+        ] {
+            let wake_notification_name = NSNotificationName::from_str(name);
+            registrations.observe(
+                &notification_center,
+                &wake_notification_name,
+                &main_queue,
+                &wake_block,
             );
         }
 
@@ -342,74 +642,128 @@ pub fn start_observer(app_handle: AppHandle) {
         let app_handle_for_display = Arc::clone(&app_handle);
         let display_block =
             block2::RcBlock::new(move |_notification: NonNull<NSNotification>| {
-                let version =
-                    DISPLAY_DEBOUNCE_VERSION.fetch_add(1, Ordering::SeqCst) + 1;
                 let app_handle_debounce = Arc::clone(&app_handle_for_display);
-                thread::spawn(move || {
-                    thread::sleep(Duration::from_millis(DISPLAY_DEBOUNCE_DELAY_MS));
-                    if DISPLAY_DEBOUNCE_VERSION.load(Ordering::SeqCst) != version {
-                        return;
-                    }
+                let delay = Duration::from_millis(DISPLAY_DEBOUNCE_DELAY_MS);
+                debounce::schedule(DebounceKey::Display, delay, move || {
                     let app_handle_main = Arc::clone(&app_handle_debounce);
                     let _ = app_handle_debounce.run_on_main_thread(move || {
-                        if let Some(window) = app_handle_main.get_webview_window("main") {
-                            let _ = window.emit("display-changed", ());
-                        }
+                        // Display geometry (menu bar, Dock position/size) may have changed
+                        crate::window_offset::refresh_displays();
+                        // 画面構成の変更でレベルや Spaces の設定が外れることがあるので掛け直す
+                        tab_bars::apply_to_all(&app_handle_main);
+                        // 外したモニタの座標に取り残されないよう、バーを置き直す
+                        tab_bars::reposition_all(&app_handle_main);
+                        tab_bars::emit_to_tab_bars(&app_handle_main, "display-changed", ());
                     });
                 });
             });
 
         let default_center = NSNotificationCenter::defaultCenter();
-        unsafe {
-            default_center.addObserverForName_object_queue_usingBlock(
-                Some(&display_notification_name),
-                None,
-                Some(&main_queue),
-                &display_block,
-            );
-        }
-
-        // Send initial state with a small delay to ensure frontend listener is ready
-        thread::sleep(std::time::Duration::from_millis(500));
-        let workspace = NSWorkspace::sharedWorkspace();
-        if let Some(frontmost) = workspace.frontmostApplication() {
-            let bundle_id_str = frontmost.bundleIdentifier().map(|s| s.to_string());
-
-            let payload = if is_tab_manager(&frontmost, our_pid) {
-                AppActivationPayload {
-                    app_type: "tab_manager".to_string(),
-                    bundle_id: None,
-                    is_on_primary_screen: true,
-                    covers_editor: false,
-                }
-            } else if is_target_app(&frontmost) {
-                AppActivationPayload {
-                    app_type: "editor".to_string(),
-                    bundle_id: bundle_id_str,
-                    is_on_primary_screen: true,
-                    covers_editor: false,
-                }
-            } else {
-                AppActivationPayload {
-                    app_type: "other".to_string(),
-                    bundle_id: bundle_id_str,
-                    is_on_primary_screen: is_focused_on_primary_screen(),
-                    covers_editor: true, // default: hide for initial state
-                }
-            };
-
+        registrations.observe(
+            &default_center,
+            &display_notification_name,
+            &main_queue,
+            &display_block,
+        );
+
+        // "Automatically hide and show the menu bar" changes the reserved menu bar height
+        let menu_bar_hiding_name =
+            NSNotificationName::from_str("AppleInterfaceMenuBarHidingChangedNotification");
+        let distributed_center = NSDistributedNotificationCenter::defaultCenter();
+        registrations.observe(
+            &distributed_center,
+            &menu_bar_hiding_name,
+            &main_queue,
+            &display_block,
+        );
+
+        // Initial state is queued until the frontend calls frontend_ready
+        if let Some(payload) = frontmost_activation_payload(our_pid, false) {
             emit_app_activated(&app_handle, payload);
         }
 
-        // Keep the thread alive
+        // Keep the thread alive until stop_observer unparks it
+        let ready_deadline = Instant::now() + Duration::from_millis(FRONTEND_READY_TIMEOUT_MS);
         while OBSERVER_RUNNING.load(Ordering::SeqCst) {
-            thread::sleep(std::time::Duration::from_millis(100));
+            // stop_observer は unpark するので、間隔は起動直後の待ち合わせにだけ効く
+            let mut timeout = polling::interval();
+            if !FRONTEND_READY.load(Ordering::SeqCst) {
+                timeout = timeout.min(ready_deadline.saturating_duration_since(Instant::now()));
+            }
+            thread::park_timeout(timeout);
+            if !FRONTEND_READY.load(Ordering::SeqCst) && Instant::now() >= ready_deadline {
+                log::warn!("Frontend did not report ready; flushing queued activation events");
+                frontend_ready(&app_handle);
+            }
         }
+
+        // The blocks capture the AppHandle, so unregister them before the thread exits
+        drop(registrations);
     });
+
+    if let Ok(mut slot) = OBSERVER_THREAD.lock() {
+        *slot = Some(handle);
+    }
 }
 
-/// Stop the workspace observer
-#[allow(dead_code)]
+/// Stop the workspace observer, remove its notification observers and wait for its thread.
+/// `start_observer` can be called again afterwards.
 pub fn stop_observer() {
-    OBSERVER_RUNNING.store(false, Ordering::SeqCst);
+    if !OBSERVER_RUNNING.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    power::watcher_stopped(power::Watcher::Observer);
+    let handle = OBSERVER_THREAD.lock().ok().and_then(|mut slot| slot.take());
+    if let Some(handle) = handle {
+        handle.thread().unpark();
+        if handle.join().is_err() {
+            log::error!("Workspace observer thread panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(bundle_id: &str) -> AppActivationPayload {
+        AppActivationPayload {
+            app_type: "other".to_string(),
+            bundle_id: Some(bundle_id.to_string()),
+            is_on_primary_screen: true,
+            covers_editor: true,
+        }
+    }
+
+    #[test]
+    fn early_event_queue_keeps_the_latest() {
+        let mut queue = Vec::new();
+        for i in 0..MAX_EARLY_EVENTS + 3 {
+            push_early_event(&mut queue, payload(&format!("app.{}", i)));
+        }
+        assert_eq!(queue.len(), MAX_EARLY_EVENTS);
+        assert_eq!(queue[0].bundle_id.as_deref(), Some("app.3"));
+        assert_eq!(
+            queue.last().and_then(|p| p.bundle_id.as_deref()),
+            Some(format!("app.{}", MAX_EARLY_EVENTS + 2).as_str())
+        );
+    }
+
+    #[test]
+    fn tab_manager_counts_as_editor_context() {
+        let tab_manager = AppActivationPayload {
+            app_type: "tab_manager".to_string(),
+            bundle_id: None,
+            is_on_primary_screen: true,
+            covers_editor: false,
+        };
+        let app = FrontmostApp::new(tab_manager, 42);
+        assert_eq!(app.app_type, "tab_manager");
+        assert_eq!(app.pid, 42);
+        assert!(app.is_editor_context);
+
+        let other = FrontmostApp::new(payload("com.apple.Safari"), 7);
+        assert_eq!(other.bundle_id.as_deref(), Some("com.apple.Safari"));
+        assert!(!other.is_editor_context);
+    }
 }
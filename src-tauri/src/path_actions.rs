@@ -0,0 +1,305 @@
+//! Project folder actions for the tab context menu
+//!
+//! Reveal the folder in Finder, copy its path, or open a terminal there. The
+//! terminal is one of a few known apps, each launched its own way so a new
+//! window opens in the folder even when the app is already running.
+
+use crate::i18n;
+use crate::settings_store::STORE_FILE;
+#[cfg(target_os = "macos")]
+use objc2_app_kit::{NSPasteboard, NSPasteboardTypeString, NSWorkspace};
+#[cfg(target_os = "macos")]
+use objc2_foundation::NSString;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store key of the terminal `open_terminal_at` launches by default
+pub(crate) const DEFAULT_TERMINAL_KEY: &str = "settings:defaultTerminal";
+
+/// Terminal apps `open_terminal_at` knows how to launch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalApp {
+    #[default]
+    Terminal,
+    Iterm2,
+    Wezterm,
+    Kitty,
+}
+
+impl TerminalApp {
+    fn bundle_id(self) -> &'static str {
+        match self {
+            Self::Terminal => "com.apple.Terminal",
+            Self::Iterm2 => "com.googlecode.iterm2",
+            Self::Wezterm => "com.github.wez.wezterm",
+            Self::Kitty => "net.kovidgoyal.kitty",
+        }
+    }
+
+    fn display_name(self) -> &'static str {
+        match self {
+            Self::Terminal => "Terminal",
+            Self::Iterm2 => "iTerm2",
+            Self::Wezterm => "WezTerm",
+            Self::Kitty => "kitty",
+        }
+    }
+
+    /// Program and arguments that open a new window in `dir`.
+    /// `app_path` is the installed .app bundle.
+    fn launch_command(self, app_path: &Path, dir: &Path) -> (PathBuf, Vec<OsString>) {
+        match self {
+            // Terminal と iTerm2 はフォルダを渡すとそこで新しいウィンドウを開く
+            Self::Terminal | Self::Iterm2 => (
+                PathBuf::from("open"),
+                vec!["-a".into(), app_path.into(), dir.into()],
+            ),
+            // 起動中の GUI があればそこに新しいウィンドウを追加する
+            Self::Wezterm => (
+                app_path.join("Contents/MacOS/wezterm"),
+                vec!["start".into(), "--cwd".into(), dir.into()],
+            ),
+            Self::Kitty => (
+                app_path.join("Contents/MacOS/kitty"),
+                vec!["--single-instance".into(), "--directory".into(), dir.into()],
+            ),
+        }
+    }
+}
+
+/// Why a path action failed. Serialized so the UI can tell the cases apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PathActionError {
+    /// The path is empty or no longer exists
+    PathNotFound { path: String },
+    /// The chosen terminal app is not installed
+    TerminalNotInstalled { app: TerminalApp },
+    /// The system call or launched process failed
+    Failed { message: String },
+}
+
+impl std::fmt::Display for PathActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PathNotFound { path } => {
+                write!(f, "{}", i18n::t_with("error.pathNotFound", &[("path", path)]))
+            }
+            Self::TerminalNotInstalled { app } => {
+                let message =
+                    i18n::t_with("error.terminalNotInstalled", &[("app", app.display_name())]);
+                write!(f, "{}", message)
+            }
+            Self::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+static DEFAULT_TERMINAL: Mutex<TerminalApp> = Mutex::new(TerminalApp::Terminal);
+
+/// The path as given, if it exists on disk
+fn existing_path(path: &str) -> Result<PathBuf, PathActionError> {
+    let candidate = PathBuf::from(path);
+    if path.is_empty() || !candidate.exists() {
+        return Err(PathActionError::PathNotFound {
+            path: path.to_string(),
+        });
+    }
+    Ok(candidate)
+}
+
+/// Installed location of an app, looked up by bundle ID. An editor is also
+/// looked up under the other bundle IDs it has shipped under.
+#[cfg(target_os = "macos")]
+pub(crate) fn application_path(bundle_id: &str) -> Option<PathBuf> {
+    let workspace = NSWorkspace::sharedWorkspace();
+    let candidates = match crate::editor_config::get_editor_by_bundle_id(bundle_id) {
+        Some(editor) => editor.bundle_ids.to_vec(),
+        None => vec![bundle_id],
+    };
+    candidates.iter().find_map(|id| {
+        let url = workspace.URLForApplicationWithBundleIdentifier(&NSString::from_str(id))?;
+        url.path().map(|path| PathBuf::from(path.to_string()))
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn application_path(_bundle_id: &str) -> Option<PathBuf> {
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+fn unsupported() -> PathActionError {
+    PathActionError::Failed {
+        message: "Not supported on this platform".to_string(),
+    }
+}
+
+/// Tauri command: show the path in Finder with it selected
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn reveal_in_finder(path: String) -> Result<(), PathActionError> {
+    let path = existing_path(&path)?;
+    let full_path = NSString::from_str(&path.to_string_lossy());
+    // ルートを空にすると新しい Finder ウィンドウで親フォルダを開いて選択する
+    let root = NSString::from_str("");
+    let workspace = NSWorkspace::sharedWorkspace();
+    if !workspace.selectFile_inFileViewerRootedAtPath(Some(&full_path), &root) {
+        return Err(PathActionError::Failed {
+            message: format!("Finder could not select {}", path.display()),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn reveal_in_finder(path: String) -> Result<(), PathActionError> {
+    existing_path(&path)?;
+    Err(unsupported())
+}
+
+/// Tauri command: put the path on the clipboard as plain text
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn copy_path_to_clipboard(path: String) -> Result<(), PathActionError> {
+    let path = existing_path(&path)?;
+    let pasteboard = NSPasteboard::generalPasteboard();
+    pasteboard.clearContents();
+    let text = NSString::from_str(&path.to_string_lossy());
+    if !pasteboard.setString_forType(&text, unsafe { NSPasteboardTypeString }) {
+        return Err(PathActionError::Failed {
+            message: "Could not write to the clipboard".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn copy_path_to_clipboard(path: String) -> Result<(), PathActionError> {
+    existing_path(&path)?;
+    Err(unsupported())
+}
+
+/// Tauri command: open a terminal window in the folder.
+/// Uses the saved default terminal when `app` is not given.
+#[tauri::command]
+pub fn open_terminal_at(path: String, app: Option<TerminalApp>) -> Result<(), PathActionError> {
+    let path = existing_path(&path)?;
+    // ファイルが渡されたらそのフォルダで開く
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().map(Path::to_path_buf).unwrap_or(path)
+    };
+    let app = app.unwrap_or_else(get_default_terminal);
+    let app_path =
+        application_path(app.bundle_id()).ok_or(PathActionError::TerminalNotInstalled { app })?;
+
+    let (program, args) = app.launch_command(&app_path, &dir);
+    Command::new(&program)
+        .args(&args)
+        .current_dir(&dir)
+        .spawn()
+        .map_err(|e| PathActionError::Failed {
+            message: format!("Failed to launch {}: {}", app.display_name(), e),
+        })?;
+    Ok(())
+}
+
+/// Load the saved default terminal (called from setup)
+pub fn load_settings(app_handle: &AppHandle) {
+    let terminal: TerminalApp = app_handle
+        .store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(DEFAULT_TERMINAL_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *DEFAULT_TERMINAL.lock().unwrap_or_else(|e| e.into_inner()) = terminal;
+}
+
+/// Tauri command: terminal used by `open_terminal_at` when none is given
+#[tauri::command]
+pub fn get_default_terminal() -> TerminalApp {
+    *DEFAULT_TERMINAL.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Tauri command: persist the default terminal
+#[tauri::command]
+pub fn set_default_terminal(app: AppHandle, terminal: TerminalApp) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(DEFAULT_TERMINAL_KEY, serde_json::json!(terminal));
+    store.save().map_err(|e| e.to_string())?;
+
+    *DEFAULT_TERMINAL.lock().unwrap_or_else(|e| e.into_inner()) = terminal;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_paths_are_rejected() {
+        assert_eq!(
+            existing_path(""),
+            Err(PathActionError::PathNotFound {
+                path: String::new()
+            })
+        );
+        assert!(matches!(
+            existing_path("/definitely/not/here"),
+            Err(PathActionError::PathNotFound { .. })
+        ));
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_string_lossy().to_string();
+        assert_eq!(existing_path(&dir), Ok(tmp.path().to_path_buf()));
+    }
+
+    #[test]
+    fn each_terminal_has_its_own_launch_command() {
+        let dir = Path::new("/Users/me/my app");
+
+        let terminal = Path::new("/System/Applications/Utilities/Terminal.app");
+        let (program, args) = TerminalApp::Terminal.launch_command(terminal, dir);
+        assert_eq!(program, PathBuf::from("open"));
+        assert_eq!(args.last(), Some(&OsString::from("/Users/me/my app")));
+
+        let (program, args) =
+            TerminalApp::Wezterm.launch_command(Path::new("/Applications/WezTerm.app"), dir);
+        assert_eq!(program, PathBuf::from("/Applications/WezTerm.app/Contents/MacOS/wezterm"));
+        assert_eq!(args, vec![OsString::from("start"), "--cwd".into(), dir.into()]);
+
+        let (program, args) =
+            TerminalApp::Kitty.launch_command(Path::new("/Applications/kitty.app"), dir);
+        assert_eq!(program, PathBuf::from("/Applications/kitty.app/Contents/MacOS/kitty"));
+        assert!(args.contains(&OsString::from("--single-instance")));
+    }
+
+    #[test]
+    fn terminal_setting_round_trips_as_snake_case() {
+        assert_eq!(serde_json::json!(TerminalApp::Iterm2), "iterm2");
+        let parsed: TerminalApp = serde_json::from_value(serde_json::json!("wezterm")).unwrap();
+        assert_eq!(parsed, TerminalApp::Wezterm);
+        assert!(serde_json::from_value::<TerminalApp>(serde_json::json!("hyper")).is_err());
+    }
+
+    #[test]
+    fn errors_serialize_with_a_kind_tag() {
+        let error = PathActionError::TerminalNotInstalled {
+            app: TerminalApp::Kitty,
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "terminal_not_installed", "app": "kitty" })
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Interval policy for the remaining polling loops
+//!
+//! File and AX events drive most updates, but the status watcher's safety-net
+//! tick and the observer's keep-alive loop still wake up on a timer. Both ask
+//! `interval()` here instead of using fixed constants: fast while an editor is
+//! frontmost and an agent is generating, slower when nobody is looking at the
+//! tabs, and slowest once the user has been away from the machine.
+
+use crate::claude_status::{self, ClaudeStatus};
+use serde::Serialize;
+use std::time::Duration;
+
+/// No keyboard or mouse input for this long counts as idle
+const IDLE_THRESHOLD: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollingTier {
+    /// An editor is frontmost and an agent is generating
+    Active,
+    /// Editors are in use, nothing is generating
+    Normal,
+    /// The tab bar is hidden or no editor is running
+    Background,
+    /// No user input for a while
+    Idle,
+}
+
+impl PollingTier {
+    pub fn interval(self) -> Duration {
+        match self {
+            Self::Active => Duration::from_millis(500),
+            Self::Normal => Duration::from_secs(2),
+            Self::Background => Duration::from_secs(5),
+            Self::Idle => Duration::from_secs(30),
+        }
+    }
+}
+
+/// What the tier is decided from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Signals {
+    /// An editor (or the tab manager itself) is the frontmost app
+    editor_frontmost: bool,
+    /// Another app covers the editor, so the tab bar is hidden
+    tab_bar_hidden: bool,
+    editors_running: bool,
+    generating: bool,
+    /// Time since the last keyboard or mouse input
+    idle_for: Duration,
+}
+
+fn select_tier(signals: &Signals) -> PollingTier {
+    if signals.idle_for >= IDLE_THRESHOLD {
+        PollingTier::Idle
+    } else if !signals.editors_running || signals.tab_bar_hidden {
+        PollingTier::Background
+    } else if signals.editor_frontmost && signals.generating {
+        PollingTier::Active
+    } else {
+        PollingTier::Normal
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+}
+
+/// Time since the last input event in the login session
+#[cfg(target_os = "macos")]
+fn idle_for() -> Duration {
+    // kCGEventSourceStateCombinedSessionState, kCGAnyInputEventType
+    let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(0, u32::MAX) };
+    Duration::try_from_secs_f64(seconds).unwrap_or(Duration::ZERO)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn idle_for() -> Duration {
+    Duration::ZERO
+}
+
+fn current_signals() -> Signals {
+    let activation = crate::observer::last_activation();
+    let generating = claude_status::latest_statuses()
+        .statuses
+        .values()
+        .any(|status| *status == ClaudeStatus::Generating);
+    Signals {
+        editor_frontmost: activation
+            .as_ref()
+            .is_some_and(|activation| activation.app_type != "other"),
+        tab_bar_hidden: activation
+            .as_ref()
+            .is_some_and(|activation| activation.app_type == "other" && activation.covers_editor),
+        editors_running: crate::power::state().editors_running,
+        generating,
+        idle_for: idle_for(),
+    }
+}
+
+/// Tier for the current activity
+pub fn current_tier() -> PollingTier {
+    select_tier(&current_signals())
+}
+
+/// How long a polling loop should sleep before its next tick
+pub fn interval() -> Duration {
+    current_tier().interval()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals() -> Signals {
+        Signals {
+            editor_frontmost: true,
+            tab_bar_hidden: false,
+            editors_running: true,
+            generating: false,
+            idle_for: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn generating_in_a_frontmost_editor_is_active() {
+        let generating = Signals {
+            generating: true,
+            ..signals()
+        };
+        assert_eq!(select_tier(&generating), PollingTier::Active);
+        assert_eq!(select_tier(&signals()), PollingTier::Normal);
+
+        let elsewhere = Signals {
+            editor_frontmost: false,
+            ..generating
+        };
+        assert_eq!(select_tier(&elsewhere), PollingTier::Normal);
+    }
+
+    #[test]
+    fn hidden_tab_bar_or_no_editor_backs_off() {
+        let hidden = Signals {
+            tab_bar_hidden: true,
+            generating: true,
+            ..signals()
+        };
+        assert_eq!(select_tier(&hidden), PollingTier::Background);
+
+        let no_editor = Signals {
+            editors_running: false,
+            ..signals()
+        };
+        assert_eq!(select_tier(&no_editor), PollingTier::Background);
+    }
+
+    #[test]
+    fn idle_wins_over_everything() {
+        let idle = Signals {
+            generating: true,
+            idle_for: IDLE_THRESHOLD,
+            ..signals()
+        };
+        assert_eq!(select_tier(&idle), PollingTier::Idle);
+
+        let almost = Signals {
+            idle_for: IDLE_THRESHOLD - Duration::from_secs(1),
+            ..signals()
+        };
+        assert_eq!(select_tier(&almost), PollingTier::Normal);
+    }
+
+    #[test]
+    fn tiers_get_slower() {
+        let intervals: Vec<Duration> = [
+            PollingTier::Active,
+            PollingTier::Normal,
+            PollingTier::Background,
+            PollingTier::Idle,
+        ]
+        .iter()
+        .map(|tier| tier.interval())
+        .collect();
+        assert!(intervals.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(intervals[0], Duration::from_millis(500));
+        assert_eq!(intervals[3], Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,167 @@
+//! Keeps App Nap from throttling the watchers
+//!
+//! The app runs as an accessory, so once the tab bar has been hidden for a
+//! while macOS App-Naps the process: timers are coalesced and Claude status
+//! updates arrive in bursts seconds late. While a watcher is running, the
+//! watchers are not paused and an editor is running, one `NSProcessInfo`
+//! activity is held on behalf of all of them. It is ended as soon as any of
+//! those stops being true, so an idle app can still be napped.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+#[cfg(target_os = "macos")]
+use objc2::rc::Retained;
+#[cfg(target_os = "macos")]
+use objc2::runtime::{NSObjectProtocol, ProtocolObject};
+#[cfg(target_os = "macos")]
+use objc2_foundation::{NSActivityOptions, NSProcessInfo, NSString};
+
+/// Background work that needs timely wake-ups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Watcher {
+    /// Claude / agent status watcher
+    Status,
+    /// NSWorkspace activation observer
+    Observer,
+}
+
+/// App Nap prevention state, as shown in diagnostics
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PowerState {
+    /// An NSProcessInfo activity is currently held
+    pub activity_held: bool,
+    pub watchers: Vec<Watcher>,
+    pub paused: bool,
+    pub editors_running: bool,
+}
+
+/// Token returned by `beginActivityWithOptions:reason:`
+#[cfg(target_os = "macos")]
+struct Activity(Retained<ProtocolObject<dyn NSObjectProtocol>>);
+
+// トークンは endActivity に渡すだけの不透明なオブジェクトで、どのスレッドから終了してもよい
+#[cfg(target_os = "macos")]
+unsafe impl Send for Activity {}
+
+/// App Nap only exists on macOS
+#[cfg(not(target_os = "macos"))]
+struct Activity;
+
+struct State {
+    watchers: BTreeSet<Watcher>,
+    paused: bool,
+    editors_running: bool,
+    activity: Option<Activity>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State {
+    watchers: BTreeSet::new(),
+    paused: false,
+    // 最初のレジストリ更新までは起動中とみなす
+    editors_running: true,
+    activity: None,
+});
+
+/// Whether the activity should be held
+fn wants_activity(watchers: &BTreeSet<Watcher>, paused: bool, editors_running: bool) -> bool {
+    !watchers.is_empty() && !paused && editors_running
+}
+
+/// Begin an activity that opts out of App Nap. Unlike `UserInitiated`, it
+/// still lets the machine go to idle sleep.
+#[cfg(target_os = "macos")]
+fn begin_activity() -> Option<Activity> {
+    let reason = NSString::from_str("Watching editor windows and agent statuses");
+    let token = NSProcessInfo::processInfo().beginActivityWithOptions_reason(
+        NSActivityOptions::UserInitiatedAllowingIdleSystemSleep,
+        &reason,
+    );
+    Some(Activity(token))
+}
+
+#[cfg(target_os = "macos")]
+fn end_activity(activity: Activity) {
+    unsafe { NSProcessInfo::processInfo().endActivity(&activity.0) };
+}
+
+#[cfg(not(target_os = "macos"))]
+fn begin_activity() -> Option<Activity> {
+    None
+}
+
+#[cfg(not(target_os = "macos"))]
+fn end_activity(_activity: Activity) {}
+
+/// Take or end the activity so it matches the current state
+fn update(mutate: impl FnOnce(&mut State)) {
+    let mut state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    mutate(&mut state);
+    let wanted = wants_activity(&state.watchers, state.paused, state.editors_running);
+    if wanted && state.activity.is_none() {
+        state.activity = begin_activity();
+        if state.activity.is_some() {
+            log::debug!("App Nap prevention started for {:?}", state.watchers);
+        }
+    } else if !wanted {
+        if let Some(activity) = state.activity.take() {
+            end_activity(activity);
+            log::debug!("App Nap prevention ended");
+        }
+    }
+}
+
+/// A watcher started running
+pub fn watcher_started(watcher: Watcher) {
+    update(|state| {
+        state.watchers.insert(watcher);
+    });
+}
+
+/// A watcher stopped
+pub fn watcher_stopped(watcher: Watcher) {
+    update(|state| {
+        state.watchers.remove(&watcher);
+    });
+}
+
+/// The watchers were paused or resumed by the user
+pub fn set_paused(paused: bool) {
+    update(|state| state.paused = paused);
+}
+
+/// Whether any supported editor is running (from the window registry)
+pub fn set_editors_running(running: bool) {
+    update(|state| state.editors_running = running);
+}
+
+/// Current state for diagnostics
+pub fn state() -> PowerState {
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    PowerState {
+        activity_held: state.activity.is_some(),
+        watchers: state.watchers.iter().copied().collect(),
+        paused: state.paused,
+        editors_running: state.editors_running,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_needs_an_unpaused_watcher_and_a_running_editor() {
+        let none = BTreeSet::new();
+        let status = BTreeSet::from([Watcher::Status]);
+        let both = BTreeSet::from([Watcher::Status, Watcher::Observer]);
+
+        assert!(wants_activity(&status, false, true));
+        assert!(wants_activity(&both, false, true));
+        assert!(!wants_activity(&none, false, true));
+        assert!(!wants_activity(&both, true, true));
+        assert!(!wants_activity(&both, false, false));
+    }
+}
@@ -0,0 +1,158 @@
+//! Per-project tab color and emoji
+//!
+//! Users can pick a color, and optionally an emoji, for each project path.
+//! Projects without one get a palette color derived from a hash of the path,
+//! so tabs are distinct out of the box and keep their color across restarts.
+
+use crate::editor_model::ProjectAppearance;
+use crate::settings_store::{StoredSettings, STORE_FILE};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store key of the per-project tab appearances
+pub(crate) const APPEARANCES_KEY: &str = "settings:projectAppearances";
+
+/// Color ids of the frontend's tab palette (src/constants/tabColors.ts)
+const PALETTE: &[&str] = &["red", "orange", "yellow", "green", "teal", "blue", "purple", "pink"];
+
+/// Project path -> appearance the user picked
+static APPEARANCES: LazyLock<Mutex<HashMap<String, ProjectAppearance>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// FNV-1a; unlike DefaultHasher it is stable across Rust releases
+fn path_hash(path: &str) -> u64 {
+    path.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Color picked for a project nobody has customized
+pub fn default_appearance(path: &str) -> ProjectAppearance {
+    let index = (path_hash(path) % PALETTE.len() as u64) as usize;
+    ProjectAppearance {
+        color: PALETTE[index].to_string(),
+        emoji: None,
+        is_default: true,
+    }
+}
+
+/// Appearance shown for a window's project. None for windows without a path.
+pub fn appearance_for(path: &str) -> Option<ProjectAppearance> {
+    if path.is_empty() {
+        return None;
+    }
+    let custom = APPEARANCES
+        .lock()
+        .ok()
+        .and_then(|appearances| appearances.get(path).cloned());
+    Some(custom.unwrap_or_else(|| default_appearance(path)))
+}
+
+fn is_valid_color(color: &str) -> bool {
+    PALETTE.contains(&color)
+        || color
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Load saved appearances (setup, or the CLI before listing)
+pub fn load_settings(stored: &StoredSettings) {
+    let appearances: HashMap<String, ProjectAppearance> = stored
+        .get(APPEARANCES_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *APPEARANCES.lock().unwrap_or_else(|e| e.into_inner()) = appearances;
+}
+
+/// Tauri command: appearances the user picked, by project path
+#[tauri::command]
+pub fn get_project_appearances() -> HashMap<String, ProjectAppearance> {
+    APPEARANCES
+        .lock()
+        .map(|appearances| appearances.clone())
+        .unwrap_or_default()
+}
+
+/// Tauri command: set a project's appearance, or clear it with None
+#[tauri::command]
+pub fn set_project_appearance(
+    app: AppHandle,
+    path: String,
+    appearance: Option<ProjectAppearance>,
+) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("Project path is empty".to_string());
+    }
+    let appearance = match appearance {
+        Some(appearance) => {
+            if !is_valid_color(&appearance.color) {
+                return Err(format!("Unknown color: {}", appearance.color));
+            }
+            let emoji = appearance
+                .emoji
+                .map(|emoji| emoji.trim().to_string())
+                .filter(|emoji| !emoji.is_empty());
+            Some(ProjectAppearance {
+                color: appearance.color,
+                emoji,
+                is_default: false,
+            })
+        }
+        None => None,
+    };
+
+    let mut appearances = get_project_appearances();
+    match appearance {
+        Some(appearance) => appearances.insert(path, appearance),
+        None => appearances.remove(&path),
+    };
+
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(APPEARANCES_KEY, serde_json::json!(appearances));
+    store.save().map_err(|e| e.to_string())?;
+
+    *APPEARANCES.lock().unwrap_or_else(|e| e.into_inner()) = appearances;
+    crate::window_registry::request_refresh("project-appearance-changed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_colors_are_stable_and_spread_out() {
+        let first = default_appearance("/Users/me/projects/api");
+        assert_eq!(first, default_appearance("/Users/me/projects/api"));
+        assert!(first.is_default);
+        assert!(PALETTE.contains(&first.color.as_str()));
+
+        let colors: std::collections::HashSet<String> = (0..40)
+            .map(|i| default_appearance(&format!("/Users/me/projects/p{}", i)).color)
+            .collect();
+        assert!(colors.len() > PALETTE.len() / 2);
+    }
+
+    #[test]
+    fn path_hash_is_fnv1a() {
+        // FNV-1a の既知の値（空文字列と "a"）
+        assert_eq!(path_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(path_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn windows_without_a_path_have_no_appearance() {
+        assert_eq!(appearance_for(""), None);
+        assert!(appearance_for("/tmp/project").is_some());
+    }
+
+    #[test]
+    fn colors_are_palette_ids_or_hex() {
+        assert!(is_valid_color("teal"));
+        assert!(is_valid_color("#1a2B3c"));
+        assert!(!is_valid_color("#12345"));
+        assert!(!is_valid_color("chartreuse"));
+    }
+}
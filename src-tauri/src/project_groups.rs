@@ -0,0 +1,336 @@
+//! Project groups that are switched to together
+//!
+//! A group is a named list of project paths, each with the editor to open it
+//! in. Activating a group focuses every member that is already open and opens
+//! the rest; exclusive activation also minimizes other project windows.
+//! Membership is by path, so it survives window ids changing across restarts.
+//! The window that was focused before activation is remembered in memory so
+//! `deactivate_group` can go back to it.
+
+use crate::editor::{self, EditorWindow};
+use crate::settings_store::STORE_FILE;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store key of the saved project groups
+pub(crate) const GROUPS_KEY: &str = "projectGroups:saved";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupMember {
+    pub path: String,
+    /// Editor the project is opened in when no window has it open
+    pub bundle_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectGroup {
+    pub name: String,
+    pub members: Vec<GroupMember>,
+}
+
+/// Outcome of bringing up one member
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum MemberOutcome {
+    Focused,
+    Opened,
+    Failed { message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberResult {
+    pub path: String,
+    #[serde(flatten)]
+    pub outcome: MemberOutcome,
+}
+
+/// The group last activated and what to undo when leaving it
+struct ActiveGroup {
+    name: String,
+    /// (bundle_id, window_id) focused before the group was activated
+    previous_window: Option<(String, u32)>,
+    /// Windows minimized by exclusive activation
+    minimized: Vec<(String, u32)>,
+}
+
+static ACTIVE_GROUP: Mutex<Option<ActiveGroup>> = Mutex::new(None);
+
+/// Whether two project paths name the same folder (an empty path matches nothing)
+pub(crate) fn same_path(a: &str, b: &str) -> bool {
+    !a.is_empty() && Path::new(a) == Path::new(b)
+}
+
+fn load_groups(app: &AppHandle) -> Result<BTreeMap<String, ProjectGroup>, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    Ok(store
+        .get(GROUPS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+fn save_groups(app: &AppHandle, groups: &BTreeMap<String, ProjectGroup>) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(GROUPS_KEY, serde_json::json!(groups));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Add `member`, replacing the editor of a member with the same path
+fn add_member(group: &mut ProjectGroup, member: GroupMember) {
+    match group
+        .members
+        .iter_mut()
+        .find(|existing| same_path(&existing.path, &member.path))
+    {
+        Some(existing) => existing.bundle_id = member.bundle_id,
+        None => group.members.push(member),
+    }
+}
+
+/// Windows to minimize for an exclusive activation: resolved projects outside
+/// the group. Windows without a path are left alone since they may be members.
+fn windows_outside_group<'a>(
+    group: &ProjectGroup,
+    windows: &'a [EditorWindow],
+) -> Vec<&'a EditorWindow> {
+    windows
+        .iter()
+        .filter(|window| !window.path.is_empty() && !window.hidden)
+        .filter(|window| {
+            !group
+                .members
+                .iter()
+                .any(|member| same_path(&window.path, &member.path))
+        })
+        .collect()
+}
+
+/// Focus the member's window if one is open, otherwise open the project
+pub(crate) fn focus_or_open(member: &GroupMember, windows: &[EditorWindow]) -> MemberOutcome {
+    let result = match windows.iter().find(|window| same_path(&window.path, &member.path)) {
+        Some(window) => {
+            // 以前の排他モードで最小化されていることがあるので戻してから前面に出す
+            let _ = editor::set_editor_window_minimized(&window.bundle_id, window.id, false);
+            editor::focus_editor_window(&window.bundle_id, window.id)
+                .map(|()| MemberOutcome::Focused)
+                .map_err(|e| e.to_string())
+        }
+        None => editor::open_project_in_editor(&member.bundle_id, &member.path)
+            .map(|()| MemberOutcome::Opened),
+    };
+    result.unwrap_or_else(|message| MemberOutcome::Failed { message })
+}
+
+/// Tauri command: create an empty group
+#[tauri::command]
+pub fn create_group(app: AppHandle, name: String) -> Result<ProjectGroup, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Group name is empty".to_string());
+    }
+    let mut groups = load_groups(&app)?;
+    if groups.contains_key(&name) {
+        return Err(format!("A group named {} already exists", name));
+    }
+    let group = ProjectGroup {
+        name: name.clone(),
+        members: Vec::new(),
+    };
+    groups.insert(name, group.clone());
+    save_groups(&app, &groups)?;
+    Ok(group)
+}
+
+/// Tauri command: add a project to a group
+#[tauri::command(rename_all = "snake_case")]
+pub fn add_to_group(
+    app: AppHandle,
+    name: String,
+    path: String,
+    bundle_id: String,
+) -> Result<ProjectGroup, String> {
+    if path.is_empty() {
+        return Err("Project path is empty".to_string());
+    }
+    let mut groups = load_groups(&app)?;
+    let group = groups
+        .get_mut(&name)
+        .ok_or_else(|| format!("No group named {}", name))?;
+    add_member(group, GroupMember { path, bundle_id });
+    let group = group.clone();
+    save_groups(&app, &groups)?;
+    Ok(group)
+}
+
+/// Tauri command: all groups, by name
+#[tauri::command]
+pub fn list_groups(app: AppHandle) -> Result<Vec<ProjectGroup>, String> {
+    Ok(load_groups(&app)?.into_values().collect())
+}
+
+/// Tauri command: focus or open every project in the group.
+/// With `exclusive`, other project windows are minimized.
+#[tauri::command]
+pub async fn activate_group(
+    app: AppHandle,
+    name: String,
+    exclusive: bool,
+) -> Result<Vec<MemberResult>, String> {
+    let group = load_groups(&app)?
+        .remove(&name)
+        .ok_or_else(|| format!("No group named {}", name))?;
+    let results = crate::run_blocking(move || {
+        let (windows, active_id) = editor::get_all_editor_window_snapshot();
+        let previous_window = active_id.and_then(|id| {
+            windows
+                .iter()
+                .find(|window| window.id == id)
+                .map(|window| (window.bundle_id.clone(), id))
+        });
+
+        let mut minimized = Vec::new();
+        if exclusive {
+            for window in windows_outside_group(&group, &windows) {
+                match editor::set_editor_window_minimized(&window.bundle_id, window.id, true) {
+                    Ok(()) => minimized.push((window.bundle_id.clone(), window.id)),
+                    Err(e) => log::warn!("Failed to minimize window {}: {}", window.id, e),
+                }
+            }
+        }
+
+        let results = group
+            .members
+            .iter()
+            .map(|member| MemberResult {
+                path: member.path.clone(),
+                outcome: focus_or_open(member, &windows),
+            })
+            .collect::<Vec<_>>();
+
+        let mut active = ACTIVE_GROUP.lock().unwrap_or_else(|e| e.into_inner());
+        // グループ間を切り替えても、戻り先は最初にグループに入る前のウィンドウのまま
+        let (previous_window, mut earlier_minimized) = match active.take() {
+            Some(active) => (active.previous_window, active.minimized),
+            None => (previous_window, Vec::new()),
+        };
+        earlier_minimized.extend(minimized);
+        *active = Some(ActiveGroup {
+            name: group.name,
+            previous_window,
+            minimized: earlier_minimized,
+        });
+        results
+    })
+    .await?;
+
+    crate::window_registry::request_refresh("group-activated");
+    Ok(results)
+}
+
+/// Tauri command: leave the active group, restoring the windows it minimized
+/// and focusing the window that was in front before it was activated
+#[tauri::command]
+pub async fn deactivate_group() -> Result<(), String> {
+    let Some(active) = ACTIVE_GROUP
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+    else {
+        return Ok(());
+    };
+    crate::run_blocking(move || {
+        log::debug!("Leaving project group {}", active.name);
+        for (bundle_id, window_id) in &active.minimized {
+            // 閉じられたウィンドウもあるので失敗は無視する
+            let _ = editor::set_editor_window_minimized(bundle_id, *window_id, false);
+        }
+        match active.previous_window {
+            Some((bundle_id, window_id)) => {
+                editor::focus_editor_window(&bundle_id, window_id).map_err(|e| e.to_string())
+            }
+            None => Ok(()),
+        }
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(id: u32, path: &str) -> EditorWindow {
+        EditorWindow {
+            editor_name: "Visual Studio Code".to_string(),
+            ..EditorWindow::for_test("com.microsoft.VSCode", id, path)
+        }
+    }
+
+    fn member(path: &str, bundle_id: &str) -> GroupMember {
+        GroupMember {
+            path: path.to_string(),
+            bundle_id: bundle_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn members_are_keyed_by_path() {
+        let mut group = ProjectGroup {
+            name: "client-a".to_string(),
+            members: Vec::new(),
+        };
+        add_member(&mut group, member("/work/api", "com.microsoft.VSCode"));
+        add_member(&mut group, member("/work/web", "com.microsoft.VSCode"));
+        add_member(&mut group, member("/work/api/", "dev.zed.Zed"));
+
+        assert_eq!(
+            group.members,
+            vec![
+                member("/work/api", "dev.zed.Zed"),
+                member("/work/web", "com.microsoft.VSCode"),
+            ]
+        );
+    }
+
+    #[test]
+    fn exclusive_activation_only_minimizes_other_resolved_projects() {
+        let group = ProjectGroup {
+            name: "oss".to_string(),
+            members: vec![member("/oss/lib", "com.microsoft.VSCode")],
+        };
+        let mut hidden = window(4, "/work/hidden");
+        hidden.hidden = true;
+        let windows = vec![
+            window(1, "/oss/lib"),
+            window(2, "/work/api"),
+            window(3, ""),
+            hidden,
+        ];
+
+        let ids: Vec<u32> = windows_outside_group(&group, &windows)
+            .iter()
+            .map(|window| window.id)
+            .collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn results_serialize_with_a_status_tag() {
+        let result = MemberResult {
+            path: "/work/api".to_string(),
+            outcome: MemberOutcome::Failed {
+                message: "Unknown editor".to_string(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "path": "/work/api",
+                "status": "failed",
+                "message": "Unknown editor"
+            })
+        );
+    }
+}
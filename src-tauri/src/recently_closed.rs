@@ -0,0 +1,196 @@
+//! Recently closed project windows, for reopening a tab closed by mistake
+//!
+//! The window registry reports every window that disappears from a snapshot,
+//! whether it was closed in the editor (AXUIElementDestroyed) or from the tab
+//! bar. Windows of an editor that quit are not recorded: the editor restores
+//! those itself. The stack is kept in its own store file so it survives
+//! restarts without ending up in settings exports.
+
+use crate::editor::{self, EditorWindow};
+use crate::now_millis;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+const STORE_FILE: &str = "recently-closed.json";
+const CLOSED_KEY: &str = "windows";
+
+/// Windows remembered, most recent first
+const MAX_ENTRIES: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClosedWindow {
+    pub bundle_id: String,
+    pub path: String,
+    pub name: String,
+    /// Epoch millis
+    pub closed_at: u64,
+}
+
+static CLOSED: Mutex<Vec<ClosedWindow>> = Mutex::new(Vec::new());
+
+/// Windows of `previous` that are gone from `current`. Only local projects
+/// count, and not when the editor quit or the path is still open elsewhere
+/// (a window that came back under a new id).
+fn closed_windows(
+    previous: &[EditorWindow],
+    current: &[EditorWindow],
+    editor_running: impl Fn(&str) -> bool,
+    closed_at: u64,
+) -> Vec<ClosedWindow> {
+    previous
+        .iter()
+        .filter(|window| !window.path.is_empty() && window.remote.is_none())
+        .filter(|window| {
+            !current.iter().any(|open| {
+                (open.bundle_id == window.bundle_id && open.id == window.id)
+                    || open.path == window.path
+            })
+        })
+        .filter(|window| editor_running(&window.bundle_id))
+        .map(|window| ClosedWindow {
+            bundle_id: window.bundle_id.clone(),
+            path: window.path.clone(),
+            name: window.name.clone(),
+            closed_at,
+        })
+        .collect()
+}
+
+/// Put `entries` on top of the stack, dropping older entries for the same project
+fn push_entries(stack: &mut Vec<ClosedWindow>, entries: Vec<ClosedWindow>) {
+    for entry in entries {
+        stack.retain(|existing| {
+            !(existing.bundle_id == entry.bundle_id && existing.path == entry.path)
+        });
+        stack.insert(0, entry);
+    }
+    stack.truncate(MAX_ENTRIES);
+}
+
+fn save(app: &AppHandle, stack: &[ClosedWindow]) {
+    let result = app.store(STORE_FILE).map_err(|e| e.to_string()).and_then(|store| {
+        store.set(CLOSED_KEY, serde_json::json!(stack));
+        store.save().map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        log::error!("Failed to save recently closed windows: {}", e);
+    }
+}
+
+/// Load the stack saved by the previous run (setup)
+pub fn load(app: &AppHandle) {
+    let stack: Vec<ClosedWindow> = app
+        .store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(CLOSED_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *CLOSED.lock().unwrap_or_else(|e| e.into_inner()) = stack;
+}
+
+/// Record the windows that disappeared between two registry snapshots
+pub fn record_snapshot(app: &AppHandle, previous: &[EditorWindow], current: &[EditorWindow]) {
+    let closed = closed_windows(
+        previous,
+        current,
+        |bundle_id| crate::ax_helper::get_pid_by_bundle_id(bundle_id).is_some(),
+        now_millis(),
+    );
+    if closed.is_empty() {
+        return;
+    }
+    let mut stack = CLOSED.lock().unwrap_or_else(|e| e.into_inner());
+    push_entries(&mut stack, closed);
+    save(app, &stack);
+}
+
+/// Tauri command: recently closed windows, most recent first
+#[tauri::command]
+pub fn get_recently_closed(limit: usize) -> Vec<ClosedWindow> {
+    let stack = CLOSED.lock().unwrap_or_else(|e| e.into_inner());
+    stack.iter().take(limit).cloned().collect()
+}
+
+/// Reopen the most recently closed project whose folder still exists.
+/// Entries for deleted folders are dropped on the way. None when there is
+/// nothing to reopen.
+pub fn reopen_last(app: &AppHandle) -> Result<Option<ClosedWindow>, String> {
+    let entry = {
+        let mut stack = CLOSED.lock().unwrap_or_else(|e| e.into_inner());
+        let mut found = None;
+        while !stack.is_empty() {
+            let candidate = stack.remove(0);
+            // 削除されたフォルダの項目は捨てて次を見る
+            if Path::new(&candidate.path).exists() {
+                found = Some(candidate);
+                break;
+            }
+        }
+        save(app, &stack);
+        found
+    };
+    if let Some(entry) = &entry {
+        if let Err(e) = editor::open_project_in_editor(&entry.bundle_id, &entry.path) {
+            // 開けなかった項目は次に備えて戻しておく
+            let mut stack = CLOSED.lock().unwrap_or_else(|e| e.into_inner());
+            push_entries(&mut stack, vec![entry.clone()]);
+            save(app, &stack);
+            return Err(e);
+        }
+    }
+    Ok(entry)
+}
+
+/// Tauri command: reopen the last closed project window
+#[tauri::command]
+pub async fn reopen_last_closed(app: AppHandle) -> Result<Option<ClosedWindow>, String> {
+    crate::run_blocking(move || reopen_last(&app)).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(bundle_id: &str, id: u32, path: &str) -> EditorWindow {
+        EditorWindow::for_test(bundle_id, id, path)
+    }
+
+    fn entry(bundle_id: &str, path: &str, closed_at: u64) -> ClosedWindow {
+        ClosedWindow {
+            bundle_id: bundle_id.to_string(),
+            path: path.to_string(),
+            name: "project".to_string(),
+            closed_at,
+        }
+    }
+
+    #[test]
+    fn only_windows_closed_in_a_running_editor_are_recorded() {
+        let previous = [
+            window("code", 1, "/work/api"),
+            window("code", 2, "/work/web"),
+            window("code", 3, ""),
+            window("zed", 4, "/work/cli"),
+            window("code", 5, "/work/docs"),
+        ];
+        // 5 は再起動で ID が 9 に変わっただけ
+        let current = [window("code", 1, "/work/api"), window("code", 9, "/work/docs")];
+
+        let closed = closed_windows(&previous, &current, |bundle_id| bundle_id == "code", 42);
+        assert_eq!(closed, vec![entry("code", "/work/web", 42)]);
+    }
+
+    #[test]
+    fn reclosing_a_project_moves_it_to_the_top() {
+        let mut stack = vec![entry("code", "/a", 1), entry("code", "/b", 0)];
+        push_entries(&mut stack, vec![entry("code", "/b", 2)]);
+        assert_eq!(stack, vec![entry("code", "/b", 2), entry("code", "/a", 1)]);
+
+        push_entries(&mut stack, (0..40).map(|i| entry("zed", &format!("/p{}", i), 3)).collect());
+        assert_eq!(stack.len(), MAX_ENTRIES);
+        assert_eq!(stack[0].path, "/p39");
+    }
+}
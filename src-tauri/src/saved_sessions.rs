@@ -0,0 +1,261 @@
+//! Named sets of open projects, saved and reopened later
+//!
+//! A saved session records which project is open in which editor. Restoring
+//! it reopens whatever is not already open, one project at a time so the
+//! editors are not asked to open ten windows at once.
+
+use crate::editor::{self, EditorWindow};
+use crate::now_millis;
+use crate::settings_store::STORE_FILE;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store key of the saved window sessions
+pub(crate) const SESSIONS_KEY: &str = "sessions:saved";
+
+/// Pause between opens while restoring
+const RESTORE_OPEN_DELAY: Duration = Duration::from_millis(400);
+
+/// One project window in a saved session
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SessionEntry {
+    pub bundle_id: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub name: String,
+    /// Epoch millis
+    pub saved_at: u64,
+    pub entries: Vec<SessionEntry>,
+}
+
+/// Outcome of restoring one entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RestoreOutcome {
+    Opened,
+    AlreadyOpen,
+    Failed { message: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestoreResult {
+    #[serde(flatten)]
+    pub entry: SessionEntry,
+    #[serde(flatten)]
+    pub outcome: RestoreOutcome,
+}
+
+/// Windows worth saving: local projects only, one entry per editor and path
+fn session_entries(windows: &[EditorWindow]) -> Vec<SessionEntry> {
+    let mut seen = HashSet::new();
+    windows
+        .iter()
+        .filter(|window| !window.path.is_empty() && window.remote.is_none())
+        .map(|window| SessionEntry {
+            bundle_id: window.bundle_id.clone(),
+            path: window.path.clone(),
+        })
+        .filter(|entry| seen.insert(entry.clone()))
+        .collect()
+}
+
+fn load_sessions(app: &AppHandle) -> Result<BTreeMap<String, SavedSession>, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    Ok(store
+        .get(SESSIONS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+fn save_sessions(app: &AppHandle, sessions: &BTreeMap<String, SavedSession>) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(SESSIONS_KEY, serde_json::json!(sessions));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Tauri command: save every open project under `name`, replacing a session
+/// of the same name
+#[tauri::command]
+pub async fn save_session(app: AppHandle, name: String) -> Result<SavedSession, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Session name is empty".to_string());
+    }
+    crate::run_blocking(move || {
+        let session = SavedSession {
+            name: name.clone(),
+            saved_at: now_millis(),
+            entries: session_entries(&editor::get_all_editor_windows()),
+        };
+        let mut sessions = load_sessions(&app)?;
+        sessions.insert(name, session.clone());
+        save_sessions(&app, &sessions)?;
+        Ok(session)
+    })
+    .await?
+}
+
+/// Tauri command: saved sessions, most recent first
+#[tauri::command]
+pub fn list_sessions(app: AppHandle) -> Result<Vec<SavedSession>, String> {
+    let mut sessions: Vec<SavedSession> = load_sessions(&app)?.into_values().collect();
+    sessions.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    Ok(sessions)
+}
+
+/// Tauri command: reopen the projects of a saved session that aren't open.
+/// Opens one at a time and reports each entry's outcome.
+#[tauri::command]
+pub async fn restore_session(app: AppHandle, name: String) -> Result<Vec<RestoreResult>, String> {
+    let session = load_sessions(&app)?
+        .remove(&name)
+        .ok_or_else(|| format!("No saved session named {}", name))?;
+    let results = crate::run_blocking(move || {
+        let open: HashSet<SessionEntry> = session_entries(&editor::get_all_editor_windows())
+            .into_iter()
+            .collect();
+        restore_entries(session.entries, &open, RESTORE_OPEN_DELAY, |entry| {
+            editor::open_project_in_editor(&entry.bundle_id, &entry.path)
+        })
+    })
+    .await?;
+
+    crate::window_registry::request_refresh("session-restored");
+    Ok(results)
+}
+
+/// Open each entry that isn't already open, pausing `delay` between opens
+fn restore_entries(
+    entries: Vec<SessionEntry>,
+    open: &HashSet<SessionEntry>,
+    delay: Duration,
+    open_project: impl Fn(&SessionEntry) -> Result<(), String>,
+) -> Vec<RestoreResult> {
+    let mut opened_any = false;
+    entries
+        .into_iter()
+        .map(|entry| {
+            let outcome = if open.contains(&entry) {
+                RestoreOutcome::AlreadyOpen
+            } else if !Path::new(&entry.path).exists() {
+                RestoreOutcome::Failed {
+                    message: format!("{} no longer exists", entry.path),
+                }
+            } else {
+                // 連続で開くとエディタが取りこぼすので間隔を空ける
+                if opened_any {
+                    std::thread::sleep(delay);
+                }
+                opened_any = true;
+                match open_project(&entry) {
+                    Ok(()) => RestoreOutcome::Opened,
+                    Err(message) => RestoreOutcome::Failed { message },
+                }
+            };
+            RestoreResult { entry, outcome }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn window(bundle_id: &str, path: &str, remote: Option<&str>) -> EditorWindow {
+        EditorWindow {
+            editor_name: "Visual Studio Code".to_string(),
+            remote: remote.map(str::to_string),
+            ..EditorWindow::for_test(bundle_id, 1, path)
+        }
+    }
+
+    fn entry(bundle_id: &str, path: &str) -> SessionEntry {
+        SessionEntry {
+            bundle_id: bundle_id.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn only_local_projects_are_saved_once() {
+        let windows = [
+            window("com.microsoft.VSCode", "/work/api", None),
+            window("com.microsoft.VSCode", "/work/api", None),
+            window("dev.zed.Zed", "/work/api", None),
+            window("com.microsoft.VSCode", "", None),
+            window("com.microsoft.VSCode", "", Some("SSH: host")),
+        ];
+        assert_eq!(
+            session_entries(&windows),
+            vec![
+                entry("com.microsoft.VSCode", "/work/api"),
+                entry("dev.zed.Zed", "/work/api"),
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_skips_open_projects_and_reports_failures() {
+        let tmp = tempfile::tempdir().unwrap();
+        let first = tmp.path().join("first");
+        let second = tmp.path().join("second");
+        std::fs::create_dir(&first).unwrap();
+        std::fs::create_dir(&second).unwrap();
+        let first = first.to_string_lossy().to_string();
+        let second = second.to_string_lossy().to_string();
+
+        let entries = vec![
+            entry("com.microsoft.VSCode", &first),
+            entry("com.microsoft.VSCode", "/definitely/not/here"),
+            entry("com.microsoft.VSCode", &second),
+            entry("dev.zed.Zed", &second),
+        ];
+        let open = HashSet::from([entry("com.microsoft.VSCode", &first)]);
+        let opened = Mutex::new(Vec::new());
+
+        let results = restore_entries(entries, &open, Duration::ZERO, |entry| {
+            opened.lock().unwrap().push(entry.bundle_id.clone());
+            if entry.bundle_id == "dev.zed.Zed" {
+                Err("Zed is not installed".to_string())
+            } else {
+                Ok(())
+            }
+        });
+
+        let outcomes: Vec<_> = results.iter().map(|result| result.outcome.clone()).collect();
+        assert_eq!(outcomes[0], RestoreOutcome::AlreadyOpen);
+        assert!(matches!(outcomes[1], RestoreOutcome::Failed { .. }));
+        assert_eq!(outcomes[2], RestoreOutcome::Opened);
+        assert_eq!(
+            outcomes[3],
+            RestoreOutcome::Failed {
+                message: "Zed is not installed".to_string()
+            }
+        );
+        assert_eq!(*opened.lock().unwrap(), vec!["com.microsoft.VSCode", "dev.zed.Zed"]);
+    }
+
+    #[test]
+    fn results_serialize_flat_with_a_status_tag() {
+        let result = RestoreResult {
+            entry: entry("dev.zed.Zed", "/work/api"),
+            outcome: RestoreOutcome::AlreadyOpen,
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "bundle_id": "dev.zed.Zed",
+                "path": "/work/api",
+                "status": "already_open"
+            })
+        );
+    }
+}
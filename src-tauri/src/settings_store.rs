@@ -0,0 +1,85 @@
+//! Settings read from the store file, with or without a running app
+//!
+//! Settings live in tab-order.json, written by tauri-plugin-store in the app
+//! data directory. The app reads them through the plugin; CLI commands exit
+//! before Tauri starts, so they read the same file directly.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// フロントエンドと共有するストアファイル
+pub const STORE_FILE: &str = "tab-order.json";
+
+/// Bundle identifier from the Tauri config (differs between release and dev builds)
+static APP_IDENTIFIER: OnceLock<String> = OnceLock::new();
+
+/// Record the bundle identifier. Called at startup before anything uses `app_support_dir`.
+pub fn set_app_identifier(identifier: &str) {
+    let _ = APP_IDENTIFIER.set(identifier.to_string());
+}
+
+/// ~/Library/Application Support/<identifier>, the same directory as the store
+/// plugin's BaseDirectory::AppData. Holds the offsets, logs, icon cache and API socket.
+pub fn app_support_dir() -> PathBuf {
+    let identifier = APP_IDENTIFIER
+        .get()
+        .map(String::as_str)
+        .unwrap_or(env!("CARGO_PKG_NAME"));
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(identifier)
+}
+
+/// Snapshot of every key in the store
+#[derive(Debug, Default)]
+pub struct StoredSettings(HashMap<String, serde_json::Value>);
+
+impl StoredSettings {
+    pub fn from_app(app_handle: &AppHandle) -> Self {
+        let entries = app_handle
+            .store(STORE_FILE)
+            .map(|store| store.entries().into_iter().collect())
+            .unwrap_or_default();
+        Self(entries)
+    }
+
+    /// Read a store file; missing or unreadable files give empty settings
+    pub fn from_file(path: &Path) -> Self {
+        let entries = std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        Self(entries)
+    }
+
+    /// The store file the app reads through the plugin
+    pub fn app_store_path() -> PathBuf {
+        app_support_dir().join(STORE_FILE)
+    }
+
+    pub fn get(&self, key: &str) -> Option<serde_json::Value> {
+        self.0.get(key).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_files_are_read_as_json_objects() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(STORE_FILE);
+        std::fs::write(&path, r#"{ "settings:locale": "ja" }"#).unwrap();
+
+        let settings = StoredSettings::from_file(&path);
+        assert_eq!(settings.get("settings:locale"), Some(serde_json::json!("ja")));
+        assert_eq!(settings.get("settings:missing"), None);
+        assert!(StoredSettings::from_file(&tmp.path().join("none.json"))
+            .get("settings:locale")
+            .is_none());
+    }
+}
@@ -0,0 +1,285 @@
+//! Export and import of every persisted setting as one JSON document
+//!
+//! The export holds every key of the store, including keys this version does
+//! not know about, so a document written by a newer build round-trips intact.
+//! Imports check the document version and the shape of known keys first, then
+//! write all keys and save once; if the save fails the previous values are put
+//! back, so an import is applied completely or not at all.
+
+use crate::now_millis;
+use crate::settings_store::{StoredSettings, STORE_FILE};
+use crate::{
+    autohide, claude_status, i18n, local_api, logging, notification, path_actions,
+    project_appearance, project_groups, saved_sessions, tab_bars, tray, update_check,
+    window_filter, window_registry, workspace_history,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+/// Format version written by `export_settings`; imports accept up to this
+const EXPORT_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SettingsDocument {
+    version: u64,
+    app_version: String,
+    /// Epoch millis
+    exported_at: u64,
+    settings: BTreeMap<String, Value>,
+}
+
+/// State of this Mac (when updates were last checked), left out of exports and imports
+const MACHINE_LOCAL_KEYS: &[&str] =
+    &[update_check::LAST_CHECKED_KEY, update_check::NOTIFIED_VERSION_KEY];
+
+fn parse<T: DeserializeOwned>(key: &str, value: &Value) -> Result<T, String> {
+    serde_json::from_value::<T>(value.clone())
+        .map_err(|e| format!("Invalid value for {}: {}", key, e))
+}
+
+fn check<T: DeserializeOwned>(key: &str, value: &Value) -> Result<(), String> {
+    parse::<T>(key, value).map(|_| ())
+}
+
+/// Check that a known key holds what its module expects. Unknown keys
+/// (frontend-only state, settings from newer versions) are kept as they are.
+fn validate_entry(key: &str, value: &Value) -> Result<(), String> {
+    match key {
+        window_filter::SETTINGS_KEY => check::<window_filter::WindowFilterSettings>(key, value),
+        window_filter::EDITOR_FILTER_KEY => {
+            check::<window_filter::EditorFilterSettings>(key, value)
+        }
+        project_appearance::APPEARANCES_KEY => {
+            check::<HashMap<String, crate::editor_model::ProjectAppearance>>(key, value)
+        }
+        tray::TITLE_SETTINGS_KEY => check::<tray::TrayTitleSettings>(key, value),
+        tab_bars::BEHAVIOR_KEY => check::<tab_bars::TabBarBehavior>(key, value),
+        tab_bars::POSITION_KEY => {
+            check::<crate::window_offset::TabBarPosition>(key, value)
+        }
+        path_actions::DEFAULT_TERMINAL_KEY => check::<path_actions::TerminalApp>(key, value),
+        project_groups::GROUPS_KEY => {
+            check::<BTreeMap<String, project_groups::ProjectGroup>>(key, value)
+        }
+        saved_sessions::SESSIONS_KEY => {
+            check::<BTreeMap<String, saved_sessions::SavedSession>>(key, value)
+        }
+        logging::DEBUG_LOGGING_KEY
+        | local_api::API_ENABLED_KEY
+        | notification::ENABLED_KEY
+        | autohide::AUTOHIDE_KEY
+        | window_registry::LEGACY_EVENTS_KEY => check::<bool>(key, value),
+        workspace_history::SCAN_LIMIT_KEY => check::<u64>(key, value),
+        update_check::SETTINGS_KEY => check::<update_check::UpdateCheckSettings>(key, value),
+        i18n::LOCALE_KEY => check::<String>(key, value),
+        notification::QUIET_HOURS_KEY => check::<notification::QuietHours>(key, value),
+        notification::MUTED_PROJECTS_KEY => check::<Vec<String>>(key, value),
+        notification::PROJECT_SOUNDS_KEY => check::<HashMap<String, String>>(key, value),
+        claude_status::LONG_GENERATION_KEY => {
+            check::<claude_status::LongGenerationSettings>(key, value)
+        }
+        claude_status::EVENTS_FILE_KEY => {
+            let path = parse::<String>(key, value)?;
+            claude_status::validate_events_file(Path::new(&path))
+                .map_err(|e| format!("Invalid value for {}: {}", key, e))
+        }
+        claude_status::GENERIC_STATUS_FILE_KEY => {
+            let file = parse::<claude_status::GenericStatusFile>(key, value)?;
+            claude_status::validate_generic_status_file(&file)
+                .map_err(|e| format!("Invalid value for {}: {}", key, e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Read an exported document and return its settings, validated
+fn parse_document(json: &str) -> Result<BTreeMap<String, Value>, String> {
+    let document: Value =
+        serde_json::from_str(json).map_err(|e| format!("Not a settings export: {}", e))?;
+    let version = document
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| "Settings export has no version".to_string())?;
+    if version == 0 || version > EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported settings export version {} (this app reads up to {})",
+            version, EXPORT_VERSION
+        ));
+    }
+    let mut settings: BTreeMap<String, Value> = document
+        .get("settings")
+        .cloned()
+        .ok_or_else(|| "Settings export has no settings".to_string())
+        .and_then(|value| serde_json::from_value(value).map_err(|e| e.to_string()))?;
+    // 古いバージョンの書き出しに含まれていても、この Mac の状態は上書きしない
+    settings.retain(|key, _| !MACHINE_LOCAL_KEYS.contains(&key.as_str()));
+    for (key, value) in &settings {
+        validate_entry(key, value)?;
+    }
+    Ok(settings)
+}
+
+/// Tauri command: every stored setting as a versioned JSON document
+#[tauri::command]
+pub fn export_settings(app: AppHandle) -> Result<String, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let document = SettingsDocument {
+        version: EXPORT_VERSION,
+        app_version: app.package_info().version.to_string(),
+        exported_at: now_millis(),
+        settings: store
+            .entries()
+            .into_iter()
+            .filter(|(key, _)| !MACHINE_LOCAL_KEYS.contains(&key.as_str()))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&document).map_err(|e| e.to_string())
+}
+
+/// Tauri command: apply a document from `export_settings`.
+/// Keys missing from the document keep their current values.
+#[tauri::command]
+pub fn import_settings(app: AppHandle, json: String) -> Result<(), String> {
+    let settings = parse_document(&json)?;
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+
+    let previous: Vec<(String, Option<Value>)> = settings
+        .keys()
+        .map(|key| (key.clone(), store.get(key)))
+        .collect();
+    for (key, value) in &settings {
+        store.set(key.clone(), value.clone());
+    }
+    if let Err(e) = store.save() {
+        // 途中まで書いた状態を残さないよう、元の値に戻す
+        for (key, value) in previous {
+            match value {
+                Some(value) => store.set(key, value),
+                None => {
+                    store.delete(&key);
+                }
+            }
+        }
+        if let Err(e) = store.save() {
+            log::error!("Failed to restore settings after a failed import: {}", e);
+        }
+        return Err(format!("Failed to save imported settings: {}", e));
+    }
+
+    log::info!("Imported {} settings", settings.len());
+    let keys: Vec<&String> = settings.keys().collect();
+    reload(&app, &keys);
+    app.emit("settings-changed", keys)
+        .map_err(|e| e.to_string())
+}
+
+/// Apply the stored settings to every module that caches them.
+/// `keys` are the imported keys.
+fn reload(app: &AppHandle, keys: &[&String]) {
+    logging::load_setting(app);
+    i18n::init(app);
+
+    let stored = StoredSettings::from_app(app);
+    window_filter::load_settings(&stored);
+    project_appearance::load_settings(&stored);
+    tab_bars::load_settings(&stored);
+    autohide::load_settings(&stored);
+    window_registry::load_settings(&stored);
+    tab_bars::apply_to_all(app);
+    tab_bars::reposition_all(app);
+    crate::claude_status::reload_settings(app, keys.iter().map(|key| key.as_str()));
+    path_actions::load_settings(app);
+    workspace_history::load_settings(app);
+    local_api::load_settings(app);
+
+    if let Err(e) = crate::reregister_shortcuts(app) {
+        log::warn!("Failed to re-register shortcuts: {}", e);
+    }
+    tray::refresh_strings(app);
+    window_registry::request_full_refresh("settings-imported");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_round_trip_with_unknown_keys() {
+        let document = SettingsDocument {
+            version: EXPORT_VERSION,
+            app_version: "1.0.0".to_string(),
+            exported_at: 0,
+            settings: BTreeMap::from([
+                ("settings:debugLogging".to_string(), serde_json::json!(true)),
+                ("tabOrder:com.microsoft.VSCode".to_string(), serde_json::json!([3, 1, 2])),
+                ("settings:fromTheFuture".to_string(), serde_json::json!({ "a": 1 })),
+            ]),
+        };
+        let json = serde_json::to_string(&document).unwrap();
+        assert_eq!(parse_document(&json).unwrap(), document.settings);
+    }
+
+    #[test]
+    fn missing_and_newer_versions_are_rejected() {
+        assert!(parse_document(r#"{ "settings": {} }"#).is_err());
+        let newer = format!(r#"{{ "version": {}, "settings": {{}} }}"#, EXPORT_VERSION + 1);
+        assert!(parse_document(&newer).unwrap_err().contains("Unsupported"));
+    }
+
+    #[test]
+    fn known_keys_must_have_the_right_shape() {
+        let json = r#"{
+            "version": 1,
+            "settings": {
+                "settings:windowFilter": { "excluded_titles": ["Welcome"] },
+                "settings:defaultTerminal": "hyper"
+            }
+        }"#;
+        let error = parse_document(json).unwrap_err();
+        assert!(error.contains("settings:defaultTerminal"), "{}", error);
+    }
+
+    #[test]
+    fn notification_rules_and_status_files_are_validated() {
+        let document = |key: &str, value: Value| {
+            serde_json::json!({ "version": 1, "settings": { key: value } }).to_string()
+        };
+        let invalid = [
+            (notification::QUIET_HOURS_KEY, serde_json::json!("22:00-07:00")),
+            (notification::MUTED_PROJECTS_KEY, serde_json::json!("/work/api")),
+            (notification::PROJECT_SOUNDS_KEY, serde_json::json!(["Glass"])),
+            (claude_status::LONG_GENERATION_KEY, serde_json::json!(120)),
+            (claude_status::EVENTS_FILE_KEY, serde_json::json!("relative/events")),
+            (
+                claude_status::GENERIC_STATUS_FILE_KEY,
+                serde_json::json!({ "agent": " ", "path": "/tmp/aider-status" }),
+            ),
+        ];
+        for (key, value) in invalid {
+            let error = parse_document(&document(key, value)).unwrap_err();
+            assert!(error.contains(key), "{}", error);
+        }
+        let quiet_hours = serde_json::json!({ "start": 1320, "end": 420 });
+        assert!(parse_document(&document(notification::QUIET_HOURS_KEY, quiet_hours)).is_ok());
+    }
+
+    #[test]
+    fn update_check_bookkeeping_stays_on_this_mac() {
+        let json = serde_json::json!({
+            "version": 1,
+            "settings": {
+                "updateCheck:lastCheckedAt": 1_700_000_000_000u64,
+                "updateCheck:notifiedVersion": "9.9.9",
+                "settings:debugLogging": true,
+            }
+        })
+        .to_string();
+        let settings = parse_document(&json).unwrap();
+        assert_eq!(settings.keys().collect::<Vec<_>>(), ["settings:debugLogging"]);
+    }
+}
@@ -0,0 +1,113 @@
+//! The settings window
+//!
+//! Settings live in their own webview (label "settings"), created on first use
+//! and destroyed when closed, so the tab bars never have to resize to show
+//! them. The tray menu, a second launch and the `editortabs://settings` deep
+//! link all open it through `open`. Closing it emits `settings-closed`.
+//!
+//! The app normally runs as an accessory (no Dock icon), and on some macOS
+//! versions an accessory app's window doesn't reliably become key, so
+//! keyboard input in the settings goes missing. While the window is open the
+//! app switches to a regular app with a Dock icon, and back when it closes.
+
+use crate::tab_bars;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder};
+
+pub(crate) const LABEL: &str = "settings";
+
+/// Activation policy the app is running with, reported in diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationPolicy {
+    /// Menu bar only, no Dock icon
+    Accessory,
+    /// Dock icon while the settings window is open
+    Regular,
+}
+
+static REGULAR: AtomicBool = AtomicBool::new(false);
+/// Set once quitting starts; windows going away then don't flip the policy
+static EXITING: AtomicBool = AtomicBool::new(false);
+
+pub fn activation_policy() -> ActivationPolicy {
+    if REGULAR.load(Ordering::SeqCst) {
+        ActivationPolicy::Regular
+    } else {
+        ActivationPolicy::Accessory
+    }
+}
+
+fn set_activation_policy(app: &AppHandle, policy: ActivationPolicy) {
+    if activation_policy() == policy {
+        return;
+    }
+    REGULAR.store(policy == ActivationPolicy::Regular, Ordering::SeqCst);
+    #[cfg(target_os = "macos")]
+    {
+        let native = match policy {
+            ActivationPolicy::Accessory => tauri::ActivationPolicy::Accessory,
+            ActivationPolicy::Regular => tauri::ActivationPolicy::Regular,
+        };
+        if let Err(e) = app.set_activation_policy(native) {
+            log::warn!("Failed to switch activation policy to {:?}: {}", policy, e);
+        }
+    }
+    // ポリシーを切り替えるとタブバーのウィンドウレベルと Spaces の設定が戻ることがある
+    tab_bars::apply_to_all(app);
+}
+
+/// Whether the settings window is on screen
+pub fn is_open(app: &AppHandle) -> bool {
+    app.get_webview_window(LABEL)
+        .is_some_and(|window| window.is_visible().unwrap_or(false))
+}
+
+/// Show the settings window, creating it if needed
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        set_activation_policy(app, ActivationPolicy::Regular);
+        window.show().map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+
+    set_activation_policy(app, ActivationPolicy::Regular);
+    let window = WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("index.html".into()))
+        .title("Editor Tab Manager Settings")
+        .inner_size(600.0, 600.0)
+        .center()
+        .resizable(false)
+        .build()
+        .map_err(|e| {
+            set_activation_policy(app, ActivationPolicy::Accessory);
+            e.to_string()
+        })?;
+    window.set_focus().map_err(|e| e.to_string())?;
+
+    let app_handle = app.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            // 終了中はウィンドウの破棄に合わせてポリシーを戻さない
+            if EXITING.load(Ordering::SeqCst) {
+                return;
+            }
+            set_activation_policy(&app_handle, ActivationPolicy::Accessory);
+            let _ = app_handle.emit("settings-closed", ());
+        }
+    });
+    Ok(())
+}
+
+/// Quitting (tray, Cmd+Q while the settings window has the Dock icon):
+/// leave the policy alone from here on
+pub fn prepare_exit() {
+    EXITING.store(true, Ordering::SeqCst);
+}
+
+/// Tauri command: open the settings window
+#[tauri::command]
+pub fn open_settings_window(app: AppHandle) -> Result<(), String> {
+    open(&app)
+}
@@ -0,0 +1,59 @@
+//! One-call snapshot for focus changes
+//!
+//! Bundles the editor state, the Claude statuses and the last app activation
+//! so the frontend makes one IPC round trip instead of three or four. Only the
+//! editor state is queried live; the rest is whatever the status watcher and
+//! the activation observer last published.
+
+use crate::claude_status::{self, ClaudeStatusPayload};
+use crate::editor::{self, EditorState};
+use crate::editor_model::AppActivationPayload;
+use crate::observer;
+use serde::Serialize;
+
+/// Payload of `get_snapshot`
+#[derive(Clone, Serialize)]
+pub struct AppSnapshot {
+    /// The requested editor, or the frontmost (else first) running editor
+    pub editor_state: EditorState,
+    pub claude_statuses: ClaudeStatusPayload,
+    /// Last app-activated payload; None before the first activation
+    pub frontmost: Option<AppActivationPayload>,
+    pub has_accessibility_permission: bool,
+}
+
+/// Build a snapshot. Blocks on AX while reading the editor state.
+pub fn collect(bundle_id: Option<&str>, has_accessibility_permission: bool) -> AppSnapshot {
+    let editor_state = match bundle_id {
+        Some(bundle_id) => editor::get_editor_state(bundle_id),
+        None => editor::get_any_editor_state(),
+    };
+    AppSnapshot {
+        editor_state,
+        claude_statuses: claude_status::latest_statuses(),
+        frontmost: observer::last_activation(),
+        has_accessibility_permission,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_editors_snapshot_as_not_running() {
+        let snapshot = collect(Some("com.example.not-an-editor"), true);
+        assert!(!snapshot.editor_state.is_running);
+        assert!(snapshot.editor_state.windows.is_empty());
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        for key in [
+            "editor_state",
+            "claude_statuses",
+            "frontmost",
+            "has_accessibility_permission",
+        ] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+    }
+}
@@ -0,0 +1,537 @@
+//! Tab bar window registry
+//!
+//! The "main" webview is the primary tab bar. Additional tab bars can be
+//! spawned per display; each one is tracked here by webview label so events
+//! can be fanned out to every bar and window lists can be filtered to the
+//! display a bar lives on.
+
+use crate::settings_store::{StoredSettings, STORE_FILE};
+use crate::window_offset::{self, DisplayFrame, TabBarPosition};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::{
+    AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder,
+};
+use tauri_plugin_store::StoreExt;
+
+/// Label of the tab bar window declared in tauri.conf.json
+pub const MAIN_LABEL: &str = "main";
+
+/// Store key of the window level and Spaces behavior
+pub(crate) const BEHAVIOR_KEY: &str = "settings:tabBarBehavior";
+
+/// Store key of the edge the bars sit on
+pub(crate) const POSITION_KEY: &str = "settings:tabBarPosition";
+
+/// Height of a tab bar window (matches TAB_BAR_HEIGHT in the frontend)
+const TAB_BAR_HEIGHT: f64 = 36.0;
+
+/// NSWindowCollectionBehavior bits managed here, and the ones they cannot be
+/// combined with (AppKit raises on conflicting Spaces or fullscreen flags)
+const CAN_JOIN_ALL_SPACES: usize = 1 << 0;
+const MOVE_TO_ACTIVE_SPACE: usize = 1 << 1;
+const FULL_SCREEN_PRIMARY: usize = 1 << 7;
+const FULL_SCREEN_AUXILIARY: usize = 1 << 8;
+const FULL_SCREEN_NONE: usize = 1 << 9;
+
+/// Window level of the tab bars
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabBarLevel {
+    /// Above editor sub-windows and modal panels (the default)
+    #[default]
+    AboveModals,
+    /// Other windows can cover the bar
+    Normal,
+    Floating,
+    /// Above everything but the menu bar, including fullscreen editors
+    Status,
+}
+
+impl TabBarLevel {
+    /// NSWindowLevel value
+    fn window_level(self) -> isize {
+        match self {
+            // NSModalPanelWindowLevel = 8, set to 9 to be above modal panels
+            Self::AboveModals => 9,
+            Self::Normal => 0,
+            Self::Floating => 3,
+            Self::Status => 25,
+        }
+    }
+}
+
+/// Spaces and fullscreen behavior of the tab bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectionBehavior {
+    /// Show the bar on every Space instead of the one it was opened on
+    CanJoinAllSpaces,
+    /// Allow the bar on a fullscreen app's Space
+    FullScreenAuxiliary,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TabBarBehavior {
+    pub level: TabBarLevel,
+    pub collection_behavior: Vec<CollectionBehavior>,
+}
+
+static BEHAVIOR: Mutex<TabBarBehavior> = Mutex::new(TabBarBehavior {
+    level: TabBarLevel::AboveModals,
+    collection_behavior: Vec::new(),
+});
+
+static POSITION: Mutex<TabBarPosition> = Mutex::new(TabBarPosition::Top);
+
+/// webview label -> CGDirectDisplayID for tab bars spawned per display
+static TAB_BARS: LazyLock<Mutex<HashMap<String, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn label_for_display(display_id: u32) -> String {
+    format!("tab-bar-{}", display_id)
+}
+
+/// Labels of every tab bar window, starting with the main one
+pub fn tab_bar_labels() -> Vec<String> {
+    let mut labels = vec![MAIN_LABEL.to_string()];
+    if let Ok(bars) = TAB_BARS.lock() {
+        labels.extend(bars.keys().cloned());
+    }
+    labels
+}
+
+/// Emit an event to every tab bar window
+pub fn emit_to_tab_bars<S: Serialize + Clone>(app_handle: &AppHandle, event: &str, payload: S) {
+    for label in tab_bar_labels() {
+        if let Some(window) = app_handle.get_webview_window(&label) {
+            let _ = window.emit(event, payload.clone());
+        }
+    }
+}
+
+/// Resolve the display a tab bar window belongs to.
+/// Spawned bars are pinned to their display; the main bar follows its NSWindow's screen.
+pub fn display_id_of(window: &WebviewWindow) -> Option<u32> {
+    if let Some(display_id) = TAB_BARS
+        .lock()
+        .ok()
+        .and_then(|bars| bars.get(window.label()).copied())
+    {
+        return Some(display_id);
+    }
+    screen_display_id(window)
+}
+
+#[cfg(target_os = "macos")]
+fn screen_display_id(window: &WebviewWindow) -> Option<u32> {
+    let ns_window = window.ns_window().ok()?;
+    let ns_window: &objc2_app_kit::NSWindow = unsafe { &*ns_window.cast() };
+    ns_window.screen().map(|screen| screen.CGDirectDisplayID())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn screen_display_id(_window: &WebviewWindow) -> Option<u32> {
+    None
+}
+
+/// Collection behavior bits with the chosen flags applied to `current`.
+/// Flags that conflict with a chosen one are cleared.
+fn collection_bits(current: usize, behaviors: &[CollectionBehavior]) -> usize {
+    let mut bits = current & !(CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY);
+    for behavior in behaviors {
+        bits = match behavior {
+            CollectionBehavior::CanJoinAllSpaces => {
+                (bits & !MOVE_TO_ACTIVE_SPACE) | CAN_JOIN_ALL_SPACES
+            }
+            CollectionBehavior::FullScreenAuxiliary => {
+                (bits & !(FULL_SCREEN_PRIMARY | FULL_SCREEN_NONE)) | FULL_SCREEN_AUXILIARY
+            }
+        };
+    }
+    bits
+}
+
+/// Apply the saved window level and Spaces behavior to a tab bar
+pub fn apply_window_behavior(window: &WebviewWindow) {
+    let behavior = BEHAVIOR.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    // 既定では NSModalPanelWindowLevel (8) より上に置き、エディタのサブウィンドウ
+    // （設定・モーダルなど）に隠れず、Dock (20) やメニューバー (24) よりは下になる
+    #[cfg(target_os = "macos")]
+    let _ = window.with_webview(move |webview| unsafe {
+        use objc2_app_kit::NSWindowCollectionBehavior;
+        let ns_window: &objc2_app_kit::NSWindow = &*webview.ns_window().cast();
+        ns_window.setLevel(behavior.level.window_level());
+        let bits = collection_bits(ns_window.collectionBehavior().0, &behavior.collection_behavior);
+        ns_window.setCollectionBehavior(NSWindowCollectionBehavior(bits));
+    });
+    #[cfg(not(target_os = "macos"))]
+    let _ = (window, behavior);
+}
+
+/// Re-apply the behavior to every tab bar (after a change or `display-changed`)
+pub fn apply_to_all(app_handle: &AppHandle) {
+    for label in tab_bar_labels() {
+        if let Some(window) = app_handle.get_webview_window(&label) {
+            apply_window_behavior(&window);
+        }
+    }
+}
+
+/// Load the saved behavior (setup, settings import)
+pub fn load_settings(stored: &StoredSettings) {
+    let behavior: TabBarBehavior = stored
+        .get(BEHAVIOR_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *BEHAVIOR.lock().unwrap_or_else(|e| e.into_inner()) = behavior;
+    let position: TabBarPosition = stored
+        .get(POSITION_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *POSITION.lock().unwrap_or_else(|e| e.into_inner()) = position;
+}
+
+/// Edge of the display the bars sit on (also decides how windows are offset)
+pub fn position() -> TabBarPosition {
+    *POSITION.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Tauri command: current window level and Spaces behavior
+#[tauri::command]
+pub fn get_tab_bar_behavior() -> TabBarBehavior {
+    BEHAVIOR.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Tauri command: set the window level and Spaces behavior of every tab bar
+/// and persist it
+#[tauri::command(rename_all = "snake_case")]
+pub fn configure_tab_bar_window(
+    app: AppHandle,
+    level: TabBarLevel,
+    collection_behavior: Vec<CollectionBehavior>,
+) -> Result<(), String> {
+    let behavior = TabBarBehavior {
+        level,
+        collection_behavior,
+    };
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(BEHAVIOR_KEY, serde_json::json!(behavior));
+    store.save().map_err(|e| e.to_string())?;
+
+    *BEHAVIOR.lock().unwrap_or_else(|e| e.into_inner()) = behavior;
+    apply_to_all(&app);
+    Ok(())
+}
+
+/// Frame (x, y, width, height) of a tab bar `height` tall on `display`: at the top below
+/// the menu bar, or at the bottom above the area the Dock reserves
+fn tab_bar_frame(
+    display: &DisplayFrame,
+    position: TabBarPosition,
+    height: f64,
+) -> (f64, f64, f64, f64) {
+    let y = match position {
+        TabBarPosition::Top => display.y + display.menu_bar_height,
+        TabBarPosition::Bottom => display.usable.y + display.usable.height - height,
+    };
+    (display.x, y, display.width, height)
+}
+
+/// Display chosen for the main bar with `move_tab_bar_to_display`
+static MAIN_DISPLAY: Mutex<Option<u32>> = Mutex::new(None);
+
+/// The first of `preferred` that is still connected, else the primary display
+fn pick_display<'a>(
+    displays: &'a [DisplayFrame],
+    preferred: &[Option<u32>],
+) -> Option<&'a DisplayFrame> {
+    preferred
+        .iter()
+        .flatten()
+        .find_map(|id| displays.iter().find(|display| display.display_id == *id))
+        .or_else(|| displays.first())
+}
+
+/// Where a bar belongs. The main bar prefers the display it was moved to,
+/// then the one it is on; a spawned bar has none once its display is gone.
+fn target_display(window: &WebviewWindow, displays: &[DisplayFrame]) -> Option<DisplayFrame> {
+    let pinned = TAB_BARS
+        .lock()
+        .ok()
+        .and_then(|bars| bars.get(window.label()).copied());
+    if let Some(display_id) = pinned {
+        return displays
+            .iter()
+            .find(|display| display.display_id == display_id)
+            .copied();
+    }
+    let chosen = *MAIN_DISPLAY.lock().unwrap_or_else(|e| e.into_inner());
+    pick_display(displays, &[chosen, screen_display_id(window)]).copied()
+}
+
+/// Full width at the configured edge of `display`. `extra_height` makes room for
+/// menus; at the bottom the window grows upwards.
+fn place(window: &WebviewWindow, display: &DisplayFrame, extra_height: f64) {
+    let (x, y, width, height) = tab_bar_frame(display, position(), TAB_BAR_HEIGHT + extra_height);
+    let size = LogicalSize::new(width, height);
+    let _ = window.set_max_size(Some(size));
+    let _ = window.set_size(size);
+    let _ = window.set_position(LogicalPosition::new(x, y));
+}
+
+/// Put a tab bar back in place on its display
+pub fn position_tab_bar(window: &WebviewWindow) {
+    if let Some(display) = target_display(window, &window_offset::get_displays()) {
+        place(window, &display, 0.0);
+    }
+}
+
+/// Tauri command: place the calling bar on its display, `extra_height` taller
+/// while a menu is open
+#[tauri::command(rename_all = "snake_case")]
+pub fn place_tab_bar(window: WebviewWindow, extra_height: f64) {
+    if let Some(display) = target_display(&window, &window_offset::get_displays()) {
+        place(&window, &display, extra_height.max(0.0));
+    }
+}
+
+/// Move the frontmost editor's windows out of the main bar's way again.
+/// With `restore_first`, windows offset for the other edge are put back first.
+fn reapply_editor_offset(app_handle: &AppHandle, display: DisplayFrame, restore_first: bool) {
+    let Some(bundle_id) = crate::observer::last_activation()
+        .filter(|activation| activation.app_type == "editor")
+        .and_then(|activation| activation.bundle_id)
+    else {
+        return;
+    };
+    let app_handle = app_handle.clone();
+    // AX でウィンドウを動かすのでメインスレッドを塞がない
+    std::thread::spawn(move || {
+        if restore_first {
+            if let Err(e) = window_offset::restore_all() {
+                log::error!("Failed to restore window positions: {}", e);
+            }
+        }
+        if !crate::window_manager::offsets_enabled(&app_handle) {
+            return;
+        }
+        if let Err(e) = window_offset::apply_offset(&bundle_id, TAB_BAR_HEIGHT, &display) {
+            log::debug!("Failed to re-apply the offset for {}: {}", bundle_id, e);
+        }
+    });
+}
+
+/// Bring every bar back onto a connected display (on `display-changed`).
+/// Spawned bars whose display was disconnected are closed.
+pub fn reposition_all(app_handle: &AppHandle) {
+    reposition(app_handle, false);
+}
+
+fn reposition(app_handle: &AppHandle, restore_first: bool) {
+    let displays = window_offset::get_displays();
+    for label in tab_bar_labels() {
+        let Some(window) = app_handle.get_webview_window(&label) else {
+            continue;
+        };
+        match target_display(&window, &displays) {
+            Some(display) => {
+                place(&window, &display, 0.0);
+                if label == MAIN_LABEL {
+                    reapply_editor_offset(app_handle, display, restore_first);
+                }
+            }
+            None => {
+                log::info!("Closing tab bar {}: its display was disconnected", label);
+                let _ = window.close();
+            }
+        }
+    }
+}
+
+/// Tauri command: move the main tab bar to a display and keep it there
+/// across display changes
+#[tauri::command(rename_all = "snake_case")]
+pub fn move_tab_bar_to_display(app: AppHandle, display_id: u32) -> Result<(), String> {
+    let display = window_offset::get_displays()
+        .into_iter()
+        .find(|d| d.display_id == display_id)
+        .ok_or_else(|| format!("Display not found: {}", display_id))?;
+    let window = app
+        .get_webview_window(MAIN_LABEL)
+        .ok_or_else(|| "Tab bar window not found".to_string())?;
+    *MAIN_DISPLAY.lock().unwrap_or_else(|e| e.into_inner()) = Some(display_id);
+    place(&window, &display, 0.0);
+    reapply_editor_offset(&app, display, false);
+    Ok(())
+}
+
+/// Tauri command: current edge of the bars
+#[tauri::command]
+pub fn get_tab_bar_position() -> TabBarPosition {
+    position()
+}
+
+/// Tauri command: move every bar to the top or bottom edge and persist it.
+/// Offset windows are restored and offset again for the new edge.
+#[tauri::command]
+pub fn set_tab_bar_position(app: AppHandle, position: TabBarPosition) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(POSITION_KEY, serde_json::json!(position));
+    store.save().map_err(|e| e.to_string())?;
+
+    let previous = std::mem::replace(
+        &mut *POSITION.lock().unwrap_or_else(|e| e.into_inner()),
+        position,
+    );
+    reposition(&app, previous != position);
+    emit_to_tab_bars(&app, "tab-bar-position-changed", position);
+    Ok(())
+}
+
+/// Spawn (or reuse) a tab bar window on the given display. Returns its webview label.
+pub fn create_for_display(app_handle: &AppHandle, display_id: u32) -> Result<String, String> {
+    let display = window_offset::get_displays()
+        .into_iter()
+        .find(|d| d.display_id == display_id)
+        .ok_or_else(|| format!("Display not found: {}", display_id))?;
+
+    let label = label_for_display(display_id);
+    let (x, y, width, height) = tab_bar_frame(&display, position(), TAB_BAR_HEIGHT);
+
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        window.show().map_err(|e| e.to_string())?;
+        return Ok(label);
+    }
+
+    let window = WebviewWindowBuilder::new(app_handle, &label, WebviewUrl::App("index.html".into()))
+        .title("Editor Tab Manager")
+        .position(x, y)
+        .inner_size(width, height)
+        .resizable(true)
+        .transparent(true)
+        .decorations(false)
+        .skip_taskbar(true)
+        .shadow(false)
+        .accept_first_mouse(true)
+        .disable_drag_drop_handler()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    apply_window_behavior(&window);
+
+    TAB_BARS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .insert(label.clone(), display_id);
+
+    let destroyed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Destroyed = event {
+            if let Ok(mut bars) = TAB_BARS.lock() {
+                bars.remove(&destroyed_label);
+            }
+        }
+    });
+
+    Ok(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_bar_sits_below_secondary_menu_bar() {
+        let display = DisplayFrame {
+            display_id: 2,
+            x: 1440.0,
+            y: -180.0,
+            width: 1920.0,
+            height: 1080.0,
+            menu_bar_height: 25.0,
+            notch_height: 0.0,
+            menu_bar_auto_hidden: false,
+            usable: crate::window_offset::WindowFrame {
+                x: 1440.0,
+                y: -155.0,
+                width: 1920.0,
+                height: 1055.0,
+            },
+            dock: crate::window_offset::DockPosition::Hidden,
+        };
+        assert_eq!(
+            tab_bar_frame(&display, TabBarPosition::Top, 36.0),
+            (1440.0, -155.0, 1920.0, 36.0)
+        );
+        // 下に置くときは Dock を除いた領域の下端に合わせる
+        assert_eq!(
+            tab_bar_frame(&display, TabBarPosition::Bottom, 36.0),
+            (1440.0, 864.0, 1920.0, 36.0)
+        );
+    }
+
+    fn display(display_id: u32) -> DisplayFrame {
+        DisplayFrame {
+            display_id,
+            x: 0.0,
+            y: 0.0,
+            width: 1440.0,
+            height: 900.0,
+            menu_bar_height: 25.0,
+            notch_height: 0.0,
+            menu_bar_auto_hidden: false,
+            usable: crate::window_offset::WindowFrame {
+                x: 0.0,
+                y: 25.0,
+                width: 1440.0,
+                height: 875.0,
+            },
+            dock: crate::window_offset::DockPosition::Hidden,
+        }
+    }
+
+    #[test]
+    fn disconnected_displays_fall_back_to_the_primary() {
+        let displays = [display(1), display(2)];
+        let pick = |preferred: &[Option<u32>]| {
+            pick_display(&displays, preferred).map(|display| display.display_id)
+        };
+
+        assert_eq!(pick(&[Some(2), Some(1)]), Some(2));
+        // 外したモニタ (3) は飛ばして、今いるディスプレイを使う
+        assert_eq!(pick(&[Some(3), Some(2)]), Some(2));
+        assert_eq!(pick(&[Some(3), None]), Some(1));
+        assert_eq!(pick_display(&[], &[Some(1)]), None);
+    }
+
+    #[test]
+    fn collection_flags_replace_conflicting_ones() {
+        let all_spaces = collection_bits(
+            MOVE_TO_ACTIVE_SPACE | FULL_SCREEN_PRIMARY,
+            &[CollectionBehavior::CanJoinAllSpaces],
+        );
+        assert_eq!(all_spaces, CAN_JOIN_ALL_SPACES | FULL_SCREEN_PRIMARY);
+
+        let both = collection_bits(
+            FULL_SCREEN_PRIMARY,
+            &[
+                CollectionBehavior::CanJoinAllSpaces,
+                CollectionBehavior::FullScreenAuxiliary,
+            ],
+        );
+        assert_eq!(both, CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY);
+
+        // 選択を外すと管理対象のフラグだけが消える
+        assert_eq!(collection_bits(both | 1 << 2, &[]), 1 << 2);
+    }
+
+    #[test]
+    fn labels_are_derived_from_display_id() {
+        assert_eq!(label_for_display(69733382), "tab-bar-69733382");
+        assert_eq!(tab_bar_labels()[0], MAIN_LABEL);
+    }
+}
@@ -0,0 +1,515 @@
+//! Menu bar tray menu
+//!
+//! Besides Pause Shortcuts, Launch at Login, Settings and Quit, the menu lists every open editor window grouped
+//! by editor, so a window can be brought to the front without the tab bar
+//! being visible. The menu is rebuilt (debounced) whenever the window registry
+//! publishes a new snapshot.
+//!
+//! The icon itself mirrors the aggregated Claude status, so a waiting project
+//! is noticeable from the menu bar alone. Optionally the text next to the icon
+//! shows the focused editor project and branch.
+
+use crate::claude_status::{ClaudeStatus, ClaudeStatusPayload};
+use crate::debounce::{self, DebounceKey};
+use crate::editor::EditorWindow;
+use crate::i18n::{t, t_with};
+use crate::launch_at_login::LaunchAtLoginStatus;
+use crate::settings_store::STORE_FILE;
+use crate::tab_bars;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// Store key of what the tray title shows
+pub(crate) const TITLE_SETTINGS_KEY: &str = "settings:trayTitle";
+
+/// Id of the tray icon created in setup
+pub const TRAY_ID: &str = "main";
+
+pub const SETTINGS_ITEM_ID: &str = "settings";
+pub const QUIT_ITEM_ID: &str = "quit";
+pub const PAUSE_SHORTCUTS_ITEM_ID: &str = "pause_shortcuts";
+pub const LAUNCH_AT_LOGIN_ITEM_ID: &str = "launch_at_login";
+/// Overflow item shown when more windows are open than the menu lists
+pub const SHOW_TAB_BAR_ITEM_ID: &str = "show_tab_bar";
+
+/// Prefix of menu item ids that focus an editor window (`window:<bundle_id>:<window_id>`)
+const WINDOW_ITEM_PREFIX: &str = "window:";
+
+/// Maximum number of window entries listed across all editors
+const MAX_WINDOW_ITEMS: usize = 15;
+
+const REBUILD_DEBOUNCE_DELAY_MS: u64 = 300;
+
+/// Settings for the project/branch title shown next to the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrayTitleSettings {
+    pub enabled: bool,
+    /// Longer titles are truncated with an ellipsis (in characters)
+    pub max_length: usize,
+}
+
+impl Default for TrayTitleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_length: 30,
+        }
+    }
+}
+
+/// Tray handle and title state, kept in managed state so observer callbacks can reach it
+pub struct TrayState {
+    tray: TrayIcon,
+    title_settings: Mutex<TrayTitleSettings>,
+    /// Title currently shown next to the icon (None = cleared)
+    title: Mutex<Option<String>>,
+}
+
+/// Store the tray handle created in setup
+pub fn manage(app: &AppHandle, tray: TrayIcon) {
+    app.manage(TrayState {
+        tray,
+        title_settings: Mutex::new(load_title_settings(app)),
+        title: Mutex::new(None),
+    });
+}
+
+fn tray_icon(app: &AppHandle) -> Option<TrayIcon> {
+    app.try_state::<TrayState>().map(|state| state.tray.clone())
+}
+
+/// Icon shown in the menu bar, derived from every project's Claude status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayStatus {
+    Idle,
+    Generating,
+    Waiting,
+}
+
+impl TrayStatus {
+    /// Pick the highest-priority status across projects.
+    /// Waiting wins over Generating so a project needing input is never masked
+    /// by another one that is still busy.
+    fn from_payload(payload: &ClaudeStatusPayload) -> Self {
+        payload.statuses.values().fold(TrayStatus::Idle, |current, status| {
+            let status = match status {
+                ClaudeStatus::Waiting => TrayStatus::Waiting,
+                ClaudeStatus::Generating => TrayStatus::Generating,
+                ClaudeStatus::Error => TrayStatus::Idle,
+            };
+            current.max_priority(status)
+        })
+    }
+
+    fn priority(self) -> u8 {
+        match self {
+            TrayStatus::Idle => 0,
+            TrayStatus::Generating => 1,
+            TrayStatus::Waiting => 2,
+        }
+    }
+
+    fn max_priority(self, other: Self) -> Self {
+        if other.priority() > self.priority() {
+            other
+        } else {
+            self
+        }
+    }
+
+    pub fn icon(self) -> Image<'static> {
+        match self {
+            TrayStatus::Idle => tauri::include_image!("./icons/tray/idle.png"),
+            TrayStatus::Generating => tauri::include_image!("./icons/tray/generating.png"),
+            TrayStatus::Waiting => tauri::include_image!("./icons/tray/waiting.png"),
+        }
+    }
+}
+
+/// Last (status, waiting count) applied to the tray, to skip redundant updates
+static APPLIED_STATUS: Mutex<Option<(TrayStatus, usize)>> = Mutex::new(None);
+
+/// Tooltip for the given number of waiting projects
+fn status_tooltip(waiting: usize) -> String {
+    match waiting {
+        0 => t("tray.tooltip"),
+        1 => t("tray.waitingOne"),
+        n => t_with("tray.waitingMany", &[("count", &n.to_string())]),
+    }
+}
+
+/// Update the tray icon and tooltip from a Claude status payload.
+/// Safe to call from any thread; the tray handle is only touched on the main thread.
+pub fn apply_claude_status(app: &AppHandle, payload: &ClaudeStatusPayload) {
+    let status = TrayStatus::from_payload(payload);
+    let waiting = payload
+        .statuses
+        .values()
+        .filter(|s| **s == ClaudeStatus::Waiting)
+        .count();
+    {
+        let Ok(mut applied) = APPLIED_STATUS.lock() else {
+            return;
+        };
+        if *applied == Some((status, waiting)) {
+            return;
+        }
+        *applied = Some((status, waiting));
+    }
+
+    let handle = app.clone();
+    let result = app.run_on_main_thread(move || {
+        let Some(tray) = tray_icon(&handle) else {
+            return;
+        };
+        if let Err(e) = tray.set_icon(Some(status.icon())) {
+            log::warn!("Failed to set tray icon: {}", e);
+        }
+        if let Err(e) = tray.set_tooltip(Some(status_tooltip(waiting))) {
+            log::warn!("Failed to set tray tooltip: {}", e);
+        }
+    });
+    if let Err(e) = result {
+        log::warn!("Failed to schedule tray status update: {}", e);
+    }
+}
+
+/// Menu item id for an editor window
+fn window_item_id(bundle_id: &str, window_id: u32) -> String {
+    format!("{}{}:{}", WINDOW_ITEM_PREFIX, bundle_id, window_id)
+}
+
+/// Parse a menu item id produced by `window_item_id` back into (bundle_id, window_id)
+pub fn parse_window_item_id(id: &str) -> Option<(&str, u32)> {
+    let rest = id.strip_prefix(WINDOW_ITEM_PREFIX)?;
+    let (bundle_id, window_id) = rest.rsplit_once(':')?;
+    if bundle_id.is_empty() {
+        return None;
+    }
+    Some((bundle_id, window_id.parse().ok()?))
+}
+
+/// Label of a window entry: project name, plus the branch when known
+fn window_label(window: &EditorWindow) -> String {
+    match &window.branch {
+        Some(branch) => format!("{} ({})", window.name, branch),
+        None => window.name.clone(),
+    }
+}
+
+/// One submenu per editor: (editor name, [(item id, label)])
+type WindowGroup = (String, Vec<(String, String)>);
+
+/// Group windows by editor in snapshot order, keeping at most `limit` entries.
+/// Returns the groups and whether some windows were left out.
+fn group_windows(windows: &[EditorWindow], limit: usize) -> (Vec<WindowGroup>, bool) {
+    let mut groups: Vec<(String, WindowGroup)> = Vec::new();
+    for window in windows.iter().take(limit) {
+        let entry = (window_item_id(&window.bundle_id, window.id), window_label(window));
+        match groups.iter_mut().find(|(bundle_id, _)| *bundle_id == window.bundle_id) {
+            Some((_, (_, items))) => items.push(entry),
+            None => groups.push((
+                window.bundle_id.clone(),
+                (window.editor_name.clone(), vec![entry]),
+            )),
+        }
+    }
+    let groups = groups.into_iter().map(|(_, group)| group).collect();
+    (groups, windows.len() > limit)
+}
+
+/// Rebuild the tray menu from the current window registry snapshot
+pub fn rebuild_tray_menu(app: &AppHandle) -> Result<(), String> {
+    let tray = tray_icon(app).ok_or_else(|| "Tray icon not found".to_string())?;
+    let windows = crate::window_registry::snapshot().windows;
+    let (groups, overflow) = group_windows(&windows, MAX_WINDOW_ITEMS);
+
+    let menu = Menu::new(app).map_err(|e| e.to_string())?;
+    for (editor_name, items) in &groups {
+        let submenu = Submenu::new(app, editor_name, true).map_err(|e| e.to_string())?;
+        for (id, label) in items {
+            let item = MenuItem::with_id(app, id, label, true, None::<&str>)
+                .map_err(|e| e.to_string())?;
+            submenu.append(&item).map_err(|e| e.to_string())?;
+        }
+        menu.append(&submenu).map_err(|e| e.to_string())?;
+    }
+    if overflow {
+        let more_item =
+            MenuItem::with_id(app, SHOW_TAB_BAR_ITEM_ID, t("tray.moreWindows"), true, None::<&str>)
+                .map_err(|e| e.to_string())?;
+        menu.append(&more_item).map_err(|e| e.to_string())?;
+    }
+    if !groups.is_empty() {
+        let separator = PredefinedMenuItem::separator(app).map_err(|e| e.to_string())?;
+        menu.append(&separator).map_err(|e| e.to_string())?;
+    }
+
+    let pause_item = CheckMenuItem::with_id(
+        app,
+        PAUSE_SHORTCUTS_ITEM_ID,
+        t("tray.pauseShortcuts"),
+        true,
+        crate::shortcuts_paused(),
+        None::<&str>,
+    )
+    .map_err(|e| e.to_string())?;
+    let launch_at_login_item = CheckMenuItem::with_id(
+        app,
+        LAUNCH_AT_LOGIN_ITEM_ID,
+        t("tray.launchAtLogin"),
+        true,
+        crate::launch_at_login::status(app) == LaunchAtLoginStatus::Enabled,
+        None::<&str>,
+    )
+    .map_err(|e| e.to_string())?;
+    let settings_item =
+        MenuItem::with_id(app, SETTINGS_ITEM_ID, t("tray.settings"), true, None::<&str>)
+            .map_err(|e| e.to_string())?;
+    let quit_item = MenuItem::with_id(app, QUIT_ITEM_ID, t("tray.quit"), true, None::<&str>)
+        .map_err(|e| e.to_string())?;
+    menu.append_items(&[&pause_item, &launch_at_login_item, &settings_item, &quit_item])
+        .map_err(|e| e.to_string())?;
+
+    tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Re-apply every localized string after the locale changes
+pub fn refresh_strings(app: &AppHandle) {
+    if let Err(e) = rebuild_tray_menu(app) {
+        log::warn!("Failed to rebuild tray menu: {}", e);
+    }
+    if let Ok(mut applied) = APPLIED_STATUS.lock() {
+        *applied = None;
+    }
+    apply_claude_status(app, &crate::claude_status::latest_statuses());
+}
+
+/// Rebuild the menu once window changes settle (called on every registry snapshot)
+pub fn schedule_rebuild(app: &AppHandle) {
+    let app = app.clone();
+    let delay = Duration::from_millis(REBUILD_DEBOUNCE_DELAY_MS);
+    debounce::schedule(DebounceKey::TrayRebuild, delay, move || {
+        if let Err(e) = rebuild_tray_menu(&app) {
+            log::warn!("Failed to rebuild tray menu: {}", e);
+        }
+    });
+}
+
+/// Tray title for a window: "project · branch"
+fn project_title(window: &EditorWindow) -> String {
+    match &window.branch {
+        Some(branch) => format!("{} · {}", window.name, branch),
+        None => window.name.clone(),
+    }
+}
+
+/// Cut `title` down to `max_length` characters, ending with an ellipsis when shortened
+fn truncate_title(title: &str, max_length: usize) -> String {
+    if title.chars().count() <= max_length {
+        return title.to_string();
+    }
+    let mut truncated: String = title.chars().take(max_length.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn set_title(app: &AppHandle, title: Option<String>) {
+    let Some(state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let settings = *state.title_settings.lock().unwrap_or_else(|e| e.into_inner());
+    let title = title
+        .filter(|_| settings.enabled)
+        .map(|title| truncate_title(&title, settings.max_length));
+    {
+        let mut current = state.title.lock().unwrap_or_else(|e| e.into_inner());
+        if *current == title {
+            return;
+        }
+        *current = title.clone();
+    }
+    if let Err(e) = state.tray.set_title(title) {
+        log::warn!("Failed to set tray title: {}", e);
+    }
+}
+
+/// Show the focused editor window's project and branch next to the icon.
+/// Called whenever the registry snapshot changes (focus, title and branch changes).
+pub fn update_title(app: &AppHandle) {
+    let snapshot = crate::window_registry::snapshot();
+    let title = snapshot
+        .active_id
+        .and_then(|id| snapshot.windows.iter().find(|w| w.id == id))
+        .map(project_title);
+    set_title(app, title);
+}
+
+/// Clear the title (no editor is frontmost)
+pub fn clear_title(app: &AppHandle) {
+    set_title(app, None);
+}
+
+fn load_title_settings(app: &AppHandle) -> TrayTitleSettings {
+    app.store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(TITLE_SETTINGS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Tauri command: current tray title settings
+#[tauri::command]
+pub fn get_tray_title_settings(app: AppHandle) -> TrayTitleSettings {
+    load_title_settings(&app)
+}
+
+/// Tauri command: persist tray title settings and apply them right away
+#[tauri::command]
+pub fn set_tray_title_settings(app: AppHandle, settings: TrayTitleSettings) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(TITLE_SETTINGS_KEY, serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())?;
+
+    if let Some(state) = app.try_state::<TrayState>() {
+        *state.title_settings.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+    }
+    update_title(&app);
+    Ok(())
+}
+
+/// Show the main tab bar (overflow item)
+pub fn show_tab_bar(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window(tab_bars::MAIN_LABEL)
+        .ok_or_else(|| "Main window not found".to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mk(id: u32, name: &str, bundle: &str, editor: &str) -> EditorWindow {
+        EditorWindow {
+            runtime_id: format!("{}:{}", bundle, id),
+            id,
+            name: name.to_string(),
+            path: String::new(),
+            branch: None,
+            repository_id: None,
+            repository_name: None,
+            bundle_id: bundle.to_string(),
+            editor_name: editor.to_string(),
+            resolution: crate::editor::WorkspaceResolution::Unresolved,
+            hidden: false,
+            remote: None,
+            has_unsaved: false,
+            workspace_kind: None,
+            path_source: crate::editor::PathSource::Unresolved,
+            appearance: None,
+        }
+    }
+
+    fn payload(statuses: &[(&str, ClaudeStatus)]) -> ClaudeStatusPayload {
+        ClaudeStatusPayload {
+            statuses: statuses
+                .iter()
+                .map(|(path, status)| (path.to_string(), status.clone()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn waiting_outranks_generating() {
+        assert_eq!(TrayStatus::from_payload(&payload(&[])), TrayStatus::Idle);
+        assert_eq!(
+            TrayStatus::from_payload(&payload(&[("/a", ClaudeStatus::Error)])),
+            TrayStatus::Idle
+        );
+        assert_eq!(
+            TrayStatus::from_payload(&payload(&[("/a", ClaudeStatus::Generating)])),
+            TrayStatus::Generating
+        );
+        assert_eq!(
+            TrayStatus::from_payload(&payload(&[
+                ("/a", ClaudeStatus::Generating),
+                ("/b", ClaudeStatus::Waiting),
+                ("/c", ClaudeStatus::Generating),
+            ])),
+            TrayStatus::Waiting
+        );
+    }
+
+    #[test]
+    fn tooltip_counts_waiting_projects() {
+        assert_eq!(status_tooltip(0), "Editor Tab Manager");
+        assert_eq!(status_tooltip(1), "1 project waiting");
+        assert_eq!(status_tooltip(3), "3 projects waiting");
+    }
+
+    #[test]
+    fn titles_are_truncated_with_ellipsis() {
+        let mut window = mk(1, "my-api", "b1", "VS Code");
+        window.branch = Some("main".to_string());
+        assert_eq!(project_title(&window), "my-api · main");
+
+        assert_eq!(truncate_title("my-api · main", 30), "my-api · main");
+        assert_eq!(truncate_title("my-api · main", 13), "my-api · main");
+        assert_eq!(truncate_title("my-api · main", 8), "my-api …");
+        assert_eq!(truncate_title("プロジェクト", 4), "プロジ…");
+    }
+
+    #[test]
+    fn window_item_ids_round_trip() {
+        let id = window_item_id("com.microsoft.VSCode", 42);
+        assert_eq!(id, "window:com.microsoft.VSCode:42");
+        assert_eq!(parse_window_item_id(&id), Some(("com.microsoft.VSCode", 42)));
+        assert_eq!(parse_window_item_id("settings"), None);
+        assert_eq!(parse_window_item_id("window::1"), None);
+        assert_eq!(parse_window_item_id("window:com.todesktop.cursor:x"), None);
+    }
+
+    #[test]
+    fn labels_include_branch() {
+        let mut window = mk(1, "alpha", "b1", "VS Code");
+        assert_eq!(window_label(&window), "alpha");
+        window.branch = Some("main".to_string());
+        assert_eq!(window_label(&window), "alpha (main)");
+    }
+
+    #[test]
+    fn windows_are_grouped_by_editor_and_capped() {
+        let windows = vec![
+            mk(1, "alpha", "b1", "VS Code"),
+            mk(2, "beta", "b2", "Cursor"),
+            mk(3, "gamma", "b1", "VS Code"),
+            mk(4, "delta", "b2", "Cursor"),
+        ];
+
+        let (groups, overflow) = group_windows(&windows, 15);
+        assert!(!overflow);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0, "VS Code");
+        assert_eq!(
+            groups[0].1,
+            vec![
+                ("window:b1:1".to_string(), "alpha".to_string()),
+                ("window:b1:3".to_string(), "gamma".to_string()),
+            ]
+        );
+
+        let (groups, overflow) = group_windows(&windows, 3);
+        assert!(overflow);
+        assert_eq!(groups[1].1.len(), 1);
+    }
+}
@@ -0,0 +1,110 @@
+//! Stand-in for the Accessibility helper on platforms other than macOS
+//!
+//! Keeps the crate building (and the platform-independent tests running)
+//! elsewhere. No editor is ever found running, so lookups come back empty and
+//! anything that would change a window fails with an unsupported platform error.
+
+use crate::editor_model::{FocusOutcome, NativeEditorWindow};
+
+pub type WindowFrameInfo = (u32, f64, f64, f64, f64);
+
+fn unsupported<T>() -> Result<T, String> {
+    Err("Window management is not supported on this platform".to_string())
+}
+
+pub fn get_pids_by_bundle_id(_bundle_id: &str) -> Vec<i32> {
+    Vec::new()
+}
+
+pub fn get_pid_by_bundle_id(_bundle_id: &str) -> Option<i32> {
+    None
+}
+
+pub fn find_window_pid(_pids: &[i32], _window_id: u32) -> Option<i32> {
+    None
+}
+
+pub fn is_app_hidden(_bundle_id: &str) -> bool {
+    false
+}
+
+pub fn is_process_running(_pid: i32) -> bool {
+    false
+}
+
+pub fn unhide_app(_pid: i32) -> bool {
+    false
+}
+
+pub fn set_global_messaging_timeout(_seconds: f32) {}
+
+pub fn get_frontmost_pid() -> Option<i32> {
+    None
+}
+
+pub fn get_native_windows_ax(
+    _pid: i32,
+    _bundle_id: &str,
+    _include_renderer_pids: bool,
+) -> Result<Vec<NativeEditorWindow>, String> {
+    unsupported()
+}
+
+pub fn close_window_by_id(_pid: i32, _target_window_id: u32) -> Result<(), String> {
+    unsupported()
+}
+
+pub fn attached_sheet_text(_pid: i32, _target_window_id: u32) -> Option<String> {
+    None
+}
+
+pub fn open_new_window_ax(_pid: i32) -> Result<Option<u32>, String> {
+    unsupported()
+}
+
+pub fn get_frontmost_editor_bundle_id(_editor_bundle_ids: &[&str]) -> Option<String> {
+    None
+}
+
+pub fn is_editor_frontmost(_editor_bundle_ids: &[&str]) -> bool {
+    false
+}
+
+pub fn get_all_window_frames(_pid: i32) -> Result<Vec<WindowFrameInfo>, String> {
+    unsupported()
+}
+
+pub fn set_window_frame_by_id(
+    _pid: i32,
+    _target_window_id: u32,
+    _x: f64,
+    _y: f64,
+    _width: f64,
+    _height: f64,
+) -> Result<(), String> {
+    unsupported()
+}
+
+pub fn focus_window_by_id(_pid: i32, _target_window_id: u32) -> Result<FocusOutcome, String> {
+    unsupported()
+}
+
+pub fn is_window_minimized_by_id(_pid: i32, _target_window_id: u32) -> Result<bool, String> {
+    unsupported()
+}
+
+pub fn set_window_minimized_by_id(
+    _pid: i32,
+    _target_window_id: u32,
+    _minimized: bool,
+) -> Result<(), String> {
+    unsupported()
+}
+
+pub fn get_document_path(_pid: i32, _target_window_id: u32) -> Option<String> {
+    None
+}
+
+pub fn is_window_fullscreen_by_id(_pid: i32, _target_window_id: u32) -> Result<bool, String> {
+    unsupported()
+}
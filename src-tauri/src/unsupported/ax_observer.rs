@@ -0,0 +1,19 @@
+//! Stand-in for the AX observer on platforms other than macOS
+//!
+//! There are no AX notifications to subscribe to, so window changes are only
+//! picked up by the registry's own refreshes.
+
+use crate::diagnostics::ObserverStatus;
+use tauri::AppHandle;
+
+pub fn init(_app_handle: AppHandle) {}
+
+pub fn register_for_editor(_bundle_id: &str) {}
+
+pub fn unregister_for_editor(_bundle_id: &str) {}
+
+pub fn unregister_all() {}
+
+pub fn observer_statuses() -> Vec<ObserverStatus> {
+    Vec::new()
+}
@@ -0,0 +1,23 @@
+//! Stand-in for the NSWorkspace observer on platforms other than macOS
+//!
+//! No app activations are observed, so there is never a frontmost app to
+//! report and the tab bars get no `app-activated` events.
+
+use crate::editor_model::{AppActivationPayload, FrontmostApp};
+use tauri::AppHandle;
+
+pub fn last_activation() -> Option<AppActivationPayload> {
+    None
+}
+
+pub fn frontend_ready(_app_handle: &AppHandle) {}
+
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    None
+}
+
+pub fn start_observer(_app_handle: AppHandle) {
+    log::warn!("App activation is not observed on this platform");
+}
+
+pub fn stop_observer() {}
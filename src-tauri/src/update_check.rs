@@ -0,0 +1,249 @@
+//! Checks GitHub releases for a newer version of the app
+//!
+//! Only detection: the result carries the release page URL and installing is
+//! left to the user. `check_for_updates` asks on demand (the About tab); the
+//! background check, off by default, asks once per interval and announces a
+//! newer release with an `update-available` event and, once per version, a
+//! notification. Failures (offline, rate limits) are logged and retried at
+//! the next wake-up.
+
+use crate::now_millis;
+use crate::settings_store::STORE_FILE;
+use crate::{i18n, notification};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+
+/// Store keys of the update check settings and its bookkeeping
+pub(crate) const SETTINGS_KEY: &str = "settings:updateCheck";
+pub(crate) const LAST_CHECKED_KEY: &str = "updateCheck:lastCheckedAt";
+pub(crate) const NOTIFIED_VERSION_KEY: &str = "updateCheck:notifiedVersion";
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/884js/editor-tab-manager/releases/latest";
+
+/// Keeps a check on a bad network from hanging around
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the background thread looks at whether a check is due
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Result of comparing the running version with the latest release
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateInfo {
+    pub current: String,
+    pub latest: String,
+    /// Release page on GitHub
+    pub url: String,
+    pub is_newer: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdateCheckSettings {
+    /// Check in the background (off by default; the About tab always checks)
+    pub enabled: bool,
+    /// Also post a notification when a newer release is found
+    pub notify: bool,
+    pub interval_hours: u32,
+}
+
+impl Default for UpdateCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            notify: true,
+            interval_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Wakes the background thread when the settings change
+static WAKE_TX: Mutex<Option<mpsc::Sender<()>>> = Mutex::new(None);
+
+fn fetch_latest_release() -> Result<Release, String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("editor-tab-manager/", env!("CARGO_PKG_VERSION")))
+        .build();
+    agent
+        .get(LATEST_RELEASE_URL)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .map_err(|e| format!("Could not reach GitHub: {}", e))?
+        .into_json()
+        .map_err(|e| format!("Unexpected response from GitHub: {}", e))
+}
+
+/// Compare the running version with a release tag ("v0.19.0" or "0.19.0")
+fn compare(current: &Version, release: Release) -> Result<UpdateInfo, String> {
+    let tag = release.tag_name.trim();
+    let latest = Version::parse(tag.strip_prefix('v').unwrap_or(tag))
+        .map_err(|e| format!("Release tag {} is not a version: {}", tag, e))?;
+    Ok(UpdateInfo {
+        current: current.to_string(),
+        is_newer: latest > *current,
+        latest: latest.to_string(),
+        url: release.html_url,
+    })
+}
+
+fn check(app: &AppHandle) -> Result<UpdateInfo, String> {
+    compare(&app.package_info().version, fetch_latest_release()?)
+}
+
+/// Whether a background check is due, `interval_hours` after the last one
+fn is_due(last_checked: Option<u64>, now: u64, interval_hours: u32) -> bool {
+    let interval = u64::from(interval_hours.max(1)) * 60 * 60 * 1000;
+    last_checked.is_none_or(|last| now.saturating_sub(last) >= interval)
+}
+
+fn load_settings(app: &AppHandle) -> UpdateCheckSettings {
+    app.store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(SETTINGS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Check if the settings say so and the interval has passed
+fn run_due_check(app: &AppHandle) {
+    let settings = load_settings(app);
+    let Ok(store) = app.store(STORE_FILE) else {
+        return;
+    };
+    let last_checked = store.get(LAST_CHECKED_KEY).and_then(|value| value.as_u64());
+    if !settings.enabled || !is_due(last_checked, now_millis(), settings.interval_hours) {
+        return;
+    }
+
+    let info = match check(app) {
+        Ok(info) => info,
+        Err(e) => {
+            // オフラインなどは次の起床時に再試行する
+            log::debug!("Update check failed: {}", e);
+            return;
+        }
+    };
+    store.set(LAST_CHECKED_KEY, now_millis());
+    if !info.is_newer {
+        let _ = store.save();
+        return;
+    }
+
+    log::info!("Update available: {} -> {}", info.current, info.latest);
+    let _ = app.emit("update-available", &info);
+
+    // 同じバージョンの通知は一度だけ
+    let already_notified = store
+        .get(NOTIFIED_VERSION_KEY)
+        .is_some_and(|value| value.as_str() == Some(info.latest.as_str()));
+    if settings.notify && !already_notified {
+        if let Err(e) = notification::send_notification(
+            i18n::t_with("notification.updateAvailable", &[("version", &info.latest)]),
+            String::new(),
+            i18n::t_with("notification.updateCurrentVersion", &[("version", &info.current)]),
+            String::new(),
+            None,
+        ) {
+            log::debug!("Update notification not sent: {}", e);
+        }
+        store.set(NOTIFIED_VERSION_KEY, info.latest.clone());
+    }
+    let _ = store.save();
+}
+
+/// Start the background check (called from setup). It sleeps while disabled.
+pub fn start(app_handle: AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    *WAKE_TX.lock().unwrap_or_else(|e| e.into_inner()) = Some(tx);
+    std::thread::spawn(move || loop {
+        run_due_check(&app_handle);
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    });
+}
+
+/// Tauri command: compare the running version with the latest GitHub release
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<UpdateInfo, String> {
+    crate::run_blocking(move || check(&app)).await?
+}
+
+/// Tauri command: background check settings
+#[tauri::command]
+pub fn get_update_check_settings(app: AppHandle) -> UpdateCheckSettings {
+    load_settings(&app)
+}
+
+/// Tauri command: persist background check settings; turning it on checks
+/// right away if the interval has passed
+#[tauri::command]
+pub fn set_update_check_settings(
+    app: AppHandle,
+    settings: UpdateCheckSettings,
+) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(SETTINGS_KEY, serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())?;
+
+    if let Some(tx) = WAKE_TX.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        let _ = tx.send(());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            html_url: format!("https://github.com/884js/editor-tab-manager/releases/tag/{}", tag),
+        }
+    }
+
+    #[test]
+    fn release_tags_are_compared_as_semver() {
+        let current = Version::new(0, 18, 0);
+
+        let info = compare(&current, release("v0.19.0")).unwrap();
+        assert!(info.is_newer);
+        assert_eq!(info.current, "0.18.0");
+        assert_eq!(info.latest, "0.19.0");
+
+        // 0.9 < 0.18 は文字列比較だと逆になる
+        assert!(!compare(&current, release("0.9.0")).unwrap().is_newer);
+        assert!(!compare(&current, release("v0.18.0")).unwrap().is_newer);
+        assert!(!compare(&current, release("v0.18.0-beta.1")).unwrap().is_newer);
+    }
+
+    #[test]
+    fn tags_that_are_not_versions_are_errors() {
+        let current = Version::new(0, 18, 0);
+        assert!(compare(&current, release("nightly")).is_err());
+    }
+
+    #[test]
+    fn checks_are_due_once_per_interval() {
+        let hour = 60 * 60 * 1000;
+        assert!(is_due(None, 0, 24));
+        assert!(!is_due(Some(10 * hour), 33 * hour, 24));
+        assert!(is_due(Some(10 * hour), 34 * hour, 24));
+        // 0 時間は 1 時間として扱う
+        assert!(!is_due(Some(0), hour / 2, 0));
+    }
+}
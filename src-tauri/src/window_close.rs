@@ -0,0 +1,138 @@
+//! Closing several windows of one editor at once
+//!
+//! `close_all_windows` and `close_other_windows` press each window's close
+//! button in turn and wait for the window to go away before the next one. A
+//! window that puts up a "save changes?" sheet stops the batch so the editor's
+//! dialogs don't stack up, and the report says how far it got.
+
+use crate::editor::{self, CloseWindowError};
+use serde::Serialize;
+
+/// A window whose close button could not be pressed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CloseFailure {
+    pub window_id: u32,
+    pub error: String,
+}
+
+/// The window whose dialog stopped the batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockedWindow {
+    pub window_id: u32,
+    /// Text of the sheet, empty if it has none
+    pub title: String,
+}
+
+/// Outcome of a batch close
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CloseReport {
+    /// Windows that went away
+    pub closed: Vec<u32>,
+    /// Windows whose close failed; the batch went on with the next one
+    pub failed: Vec<CloseFailure>,
+    /// Window that asked something before closing (brought to front). The
+    /// batch stopped here.
+    pub blocked: Option<BlockedWindow>,
+    /// Windows not tried because the batch stopped
+    pub remaining: Vec<u32>,
+}
+
+/// Close `targets` (owning pid, window id) one at a time with `close`, which
+/// waits for each window to go away
+fn close_in_turn(
+    targets: &[(i32, u32)],
+    mut close: impl FnMut(i32, u32) -> Result<(), CloseWindowError>,
+) -> CloseReport {
+    let mut report = CloseReport::default();
+    for (index, &(pid, window_id)) in targets.iter().enumerate() {
+        match close(pid, window_id) {
+            Ok(()) => report.closed.push(window_id),
+            Err(CloseWindowError::Failed { message }) => report.failed.push(CloseFailure {
+                window_id,
+                error: message,
+            }),
+            Err(CloseWindowError::BlockedByDialog { title }) => {
+                // 保存確認のダイアログが出ている間は次を閉じない
+                report.blocked = Some(BlockedWindow { window_id, title });
+                report.remaining = targets[index + 1..].iter().map(|&(_, id)| id).collect();
+                break;
+            }
+        }
+    }
+    report
+}
+
+/// Close every window of the editor except `keep`. Only windows found in this
+/// editor's own processes are touched, so an id belonging to another editor is
+/// never closed.
+fn close_windows(bundle_id: &str, keep: Option<u32>) -> Result<CloseReport, String> {
+    let windows = editor::native_window_ids(bundle_id)?;
+    if let Some(keep) = keep {
+        if !windows.iter().any(|&(_, id)| id == keep) {
+            return Err(format!("Window with ID {} not found", keep));
+        }
+    }
+    let targets: Vec<(i32, u32)> = windows
+        .into_iter()
+        .filter(|&(_, id)| Some(id) != keep)
+        .collect();
+    Ok(close_in_turn(&targets, editor::close_window_confirmed))
+}
+
+/// Tauri command: close every window of an editor
+#[tauri::command(rename_all = "snake_case")]
+pub async fn close_all_windows(bundle_id: String) -> Result<CloseReport, String> {
+    crate::run_blocking(move || close_windows(&bundle_id, None)).await?
+}
+
+/// Tauri command: close every window of an editor but one
+#[tauri::command(rename_all = "snake_case")]
+pub async fn close_other_windows(
+    bundle_id: String,
+    keep_window_id: u32,
+) -> Result<CloseReport, String> {
+    crate::run_blocking(move || close_windows(&bundle_id, Some(keep_window_id))).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_stops_at_a_window_that_stays_open() {
+        let targets = [(10, 1), (10, 2), (10, 3), (11, 4)];
+        let mut pressed = Vec::new();
+        let report = close_in_turn(&targets, |_, id| {
+            pressed.push(id);
+            if id == 2 {
+                return Err(CloseWindowError::BlockedByDialog {
+                    title: "Do you want to save the changes you made to main.rs?".to_string(),
+                });
+            }
+            Ok(())
+        });
+
+        assert_eq!(pressed, vec![1, 2]);
+        assert_eq!(report.closed, vec![1]);
+        assert_eq!(report.blocked.map(|blocked| blocked.window_id), Some(2));
+        assert_eq!(report.remaining, vec![3, 4]);
+    }
+
+    #[test]
+    fn failed_closes_are_reported_and_skipped() {
+        let targets = [(10, 1), (10, 2), (10, 3)];
+        let report = close_in_turn(&targets, |_, id| {
+            if id == 2 {
+                Err("Failed to get close button: AXError -25205".to_string().into())
+            } else {
+                Ok(())
+            }
+        });
+
+        assert_eq!(report.closed, vec![1, 3]);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].window_id, 2);
+        assert_eq!(report.blocked, None);
+        assert!(report.remaining.is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+//! Tab list window filtering
+//!
+//! Editors open windows that aren't projects: VSCode's Settings editor in its
+//! own window, Electron DevTools, untitled startup windows. Those are left out
+//! of the tab list by title, using built-in patterns per editor plus patterns
+//! the user adds in Settings. Whole editors can be turned off, and projects
+//! under ignored folders are hidden once their path is known.
+
+use crate::editor_config::{EditorConfig, EDITORS};
+use crate::settings_store::{StoredSettings, STORE_FILE};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store keys of the window and editor filters
+pub(crate) const SETTINGS_KEY: &str = "settings:windowFilter";
+pub(crate) const EDITOR_FILTER_KEY: &str = "settings:editorFilter";
+
+/// Title separator used by every supported editor
+const TITLE_SEPARATOR: &str = " — ";
+
+/// Electron DevTools windows ("Developer Tools - file:///...")
+const DEVTOOLS_PREFIX: &str = "Developer Tools";
+
+/// Windows excluded when their title is just this one segment (plus the app name)
+const ELECTRON_SINGLE_SEGMENT_TITLES: &[&str] = &["Settings", "Keyboard Shortcuts", "Welcome"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowFilterSettings {
+    /// Titles to hide. `*` at either end matches any prefix/suffix; case-insensitive.
+    pub excluded_titles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EditorFilterSettings {
+    /// Editor IDs ("zed") that are not tracked at all
+    pub disabled_editors: Vec<String>,
+    /// Project folders to hide; a folder also hides everything under it
+    pub ignored_paths: Vec<String>,
+}
+
+static SETTINGS: LazyLock<Mutex<WindowFilterSettings>> =
+    LazyLock::new(|| Mutex::new(WindowFilterSettings::default()));
+
+static EDITOR_FILTER: LazyLock<Mutex<EditorFilterSettings>> =
+    LazyLock::new(|| Mutex::new(EditorFilterSettings::default()));
+
+fn is_electron_editor(config: &EditorConfig) -> bool {
+    matches!(config.id, "vscode" | "cursor")
+}
+
+/// Title segments without the trailing app name ("a — b — Cursor" -> ["a", "b"])
+fn title_segments<'a>(title: &'a str, config: &EditorConfig) -> Vec<&'a str> {
+    let mut segments: Vec<&str> = title.split(TITLE_SEPARATOR).map(str::trim).collect();
+    if segments.len() > 1 {
+        let last = segments[segments.len() - 1];
+        if last == config.app_name || last.contains(config.display_name) {
+            segments.pop();
+        }
+    }
+    segments
+}
+
+/// Case-insensitive match with `*` wildcards at the start and/or end
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let text = text.to_lowercase();
+    if pattern.trim_matches('*').is_empty() {
+        return false;
+    }
+    match (pattern.strip_prefix('*'), pattern.strip_suffix('*')) {
+        (Some(rest), _) if rest.ends_with('*') => text.contains(rest.trim_end_matches('*')),
+        (Some(suffix), None) => text.ends_with(suffix),
+        (None, Some(prefix)) => text.starts_with(prefix),
+        _ => text == pattern,
+    }
+}
+
+/// Whether a window with this title should stay out of the tab list
+pub fn is_excluded_title(title: &str, config: &EditorConfig, user_patterns: &[String]) -> bool {
+    let title = title.trim();
+    if title.is_empty() || title == "Untitled" {
+        return true;
+    }
+
+    if is_electron_editor(config) {
+        if title.starts_with(DEVTOOLS_PREFIX) {
+            return true;
+        }
+        if let [only] = title_segments(title, config).as_slice() {
+            if ELECTRON_SINGLE_SEGMENT_TITLES.contains(only) {
+                return true;
+            }
+        }
+    }
+
+    // ユーザー定義パターンはタイトル全体と各セグメントの両方に当てる
+    let segments = title_segments(title, config);
+    user_patterns.iter().any(|pattern| {
+        matches_pattern(pattern, title)
+            || segments.iter().any(|segment| matches_pattern(pattern, segment))
+    })
+}
+
+/// Check a title against the built-in and saved patterns
+pub fn is_excluded(title: &str, config: &EditorConfig) -> bool {
+    let settings = SETTINGS.lock().unwrap_or_else(|e| e.into_inner());
+    is_excluded_title(title, config, &settings.excluded_titles)
+}
+
+/// `~/` prefix expanded and trailing slashes dropped
+fn normalize_ignored_path(pattern: &str) -> PathBuf {
+    let pattern = pattern.trim();
+    let expanded = match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(pattern),
+    };
+    // components() で末尾の "/" や "." を落として比較する
+    expanded.components().collect()
+}
+
+/// Whether `path` is one of the ignored folders or inside one.
+/// Matching is per path component, so "/work/api" doesn't hide "/work/api-v2".
+pub fn is_ignored_path_in(path: &Path, ignored_paths: &[String]) -> bool {
+    ignored_paths
+        .iter()
+        .filter(|pattern| !pattern.trim().is_empty())
+        .any(|pattern| path.starts_with(normalize_ignored_path(pattern)))
+}
+
+/// Check a resolved project path against the saved ignore list
+pub fn is_ignored_path(path: &Path) -> bool {
+    let settings = EDITOR_FILTER.lock().unwrap_or_else(|e| e.into_inner());
+    is_ignored_path_in(path, &settings.ignored_paths)
+}
+
+/// Whether the user turned this editor off (by editor ID)
+pub fn is_editor_disabled(editor_id: &str) -> bool {
+    let settings = EDITOR_FILTER.lock().unwrap_or_else(|e| e.into_inner());
+    settings.disabled_editors.iter().any(|id| id == editor_id)
+}
+
+/// Load saved patterns and the editor filter (setup, or the CLI before listing)
+pub fn load_settings(stored: &StoredSettings) {
+    let settings: WindowFilterSettings = stored
+        .get(SETTINGS_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *SETTINGS.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+
+    let editor_filter: EditorFilterSettings = stored
+        .get(EDITOR_FILTER_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    *EDITOR_FILTER.lock().unwrap_or_else(|e| e.into_inner()) = editor_filter;
+}
+
+/// Tauri command: current window filter settings
+#[tauri::command]
+pub fn get_window_filter_settings() -> WindowFilterSettings {
+    SETTINGS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Tauri command: persist window filter settings and refresh the tab list
+#[tauri::command]
+pub fn set_window_filter_settings(
+    app: AppHandle,
+    settings: WindowFilterSettings,
+) -> Result<(), String> {
+    let settings = WindowFilterSettings {
+        excluded_titles: settings
+            .excluded_titles
+            .into_iter()
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect(),
+    };
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(SETTINGS_KEY, serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())?;
+
+    *SETTINGS.lock().unwrap_or_else(|e| e.into_inner()) = settings;
+    crate::window_registry::request_refresh("window-filter-changed");
+    Ok(())
+}
+
+/// Tauri command: disabled editors and ignored project folders
+#[tauri::command]
+pub fn get_editor_filter_settings() -> EditorFilterSettings {
+    EDITOR_FILTER.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Tauri command: persist disabled editors and ignored folders.
+/// Observers follow the editor list, then the tab list is refreshed.
+#[tauri::command]
+pub fn set_editor_filter_settings(
+    app: AppHandle,
+    settings: EditorFilterSettings,
+) -> Result<(), String> {
+    let settings = EditorFilterSettings {
+        disabled_editors: settings
+            .disabled_editors
+            .into_iter()
+            .filter(|id| EDITORS.iter().any(|editor| editor.id == id))
+            .collect(),
+        ignored_paths: settings
+            .ignored_paths
+            .into_iter()
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .collect(),
+    };
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(EDITOR_FILTER_KEY, serde_json::json!(settings));
+    store.save().map_err(|e| e.to_string())?;
+
+    let previous = std::mem::replace(
+        &mut *EDITOR_FILTER.lock().unwrap_or_else(|e| e.into_inner()),
+        settings.clone(),
+    );
+    for editor in EDITORS {
+        let was_disabled = previous.disabled_editors.iter().any(|id| id == editor.id);
+        let is_disabled = settings.disabled_editors.iter().any(|id| id == editor.id);
+        if is_disabled && !was_disabled {
+            crate::ax_observer::unregister_for_editor(editor.bundle_id);
+        } else if was_disabled && !is_disabled {
+            crate::ax_observer::register_for_editor(editor.bundle_id);
+        }
+    }
+    crate::window_registry::request_refresh("editor-filter-changed");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(id: &str) -> &'static EditorConfig {
+        EDITORS.iter().find(|e| e.id == id).unwrap()
+    }
+
+    #[test]
+    fn empty_and_untitled_windows_are_excluded() {
+        assert!(is_excluded_title("", editor("vscode"), &[]));
+        assert!(is_excluded_title("Untitled", editor("cursor"), &[]));
+    }
+
+    #[test]
+    fn devtools_windows_are_excluded() {
+        let vscode = editor("vscode");
+        assert!(is_excluded_title("Developer Tools - file:///app/index.html", vscode, &[]));
+        assert!(is_excluded_title("Developer Tools", editor("cursor"), &[]));
+    }
+
+    #[test]
+    fn settings_is_excluded_only_as_the_sole_segment() {
+        let vscode = editor("vscode");
+        assert!(is_excluded_title("Settings — Visual Studio Code", vscode, &[]));
+        assert!(is_excluded_title("Settings", vscode, &[]));
+        // A file or project named Settings is a real window
+        assert!(!is_excluded_title("Settings — my-app — Visual Studio Code", vscode, &[]));
+        assert!(!is_excluded_title("main.rs — Settings — Visual Studio Code", vscode, &[]));
+    }
+
+    #[test]
+    fn project_windows_are_kept() {
+        assert!(!is_excluded_title("main.rs — my-app — Cursor", editor("cursor"), &[]));
+        // Built-in Electron patterns don't apply to Zed
+        assert!(!is_excluded_title("Settings", editor("zed"), &[]));
+    }
+
+    #[test]
+    fn user_patterns_match_titles_and_segments() {
+        let cursor = editor("cursor");
+        let patterns = vec!["scratch*".to_string(), "*.tmp".to_string(), "*notes*".to_string()];
+        assert!(is_excluded_title("scratchpad — Cursor", cursor, &patterns));
+        assert!(is_excluded_title("a.tmp — proj — Cursor", cursor, &patterns));
+        assert!(is_excluded_title("Meeting NOTES — Cursor", cursor, &patterns));
+        assert!(!is_excluded_title("main.rs — proj — Cursor", cursor, &patterns));
+    }
+
+    #[test]
+    fn wildcard_only_patterns_match_nothing() {
+        assert!(!matches_pattern("*", "anything"));
+        assert!(!matches_pattern("**", "anything"));
+        assert!(!matches_pattern("  ", "anything"));
+        assert!(matches_pattern("Exact Title", "exact title"));
+    }
+
+    #[test]
+    fn ignored_paths_hide_the_folder_and_its_subprojects() {
+        let ignored = vec!["/work/noisy/".to_string(), "/tmp/scratch".to_string()];
+        assert!(is_ignored_path_in(Path::new("/work/noisy"), &ignored));
+        assert!(is_ignored_path_in(Path::new("/work/noisy/packages/web"), &ignored));
+        assert!(is_ignored_path_in(Path::new("/tmp/scratch"), &ignored));
+        // Prefix matching is per component, not per character
+        assert!(!is_ignored_path_in(Path::new("/work/noisy-v2"), &ignored));
+        assert!(!is_ignored_path_in(Path::new("/work"), &ignored));
+    }
+
+    #[test]
+    fn ignored_paths_expand_home_and_skip_blanks() {
+        let home = dirs::home_dir().unwrap();
+        let ignored = vec!["~/scratch".to_string(), "  ".to_string()];
+        assert!(is_ignored_path_in(&home.join("scratch/app"), &ignored));
+        assert!(!is_ignored_path_in(&home.join("projects/app"), &ignored));
+        assert!(!is_ignored_path_in(Path::new("/"), &["".to_string()]));
+    }
+}
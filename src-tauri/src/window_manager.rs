@@ -0,0 +1,133 @@
+//! Window manager detection
+//!
+//! With Stage Manager enabled, macOS manages window frames itself and shifting
+//! editor windows below the tab bar fights it (windows snap back and the stored
+//! original frames become meaningless). In that case the tab bar overlays the
+//! editor instead of offsetting it.
+
+use crate::tab_bars;
+use crate::window_offset;
+use serde::Serialize;
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// How long a detection result is reused before `defaults` is queried again
+const DETECTION_TTL: Duration = Duration::from_secs(5);
+
+/// How the tab bar keeps editor content visible
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffsetMode {
+    /// Editor windows are moved below the tab bar
+    Offset,
+    /// Editor windows are left alone and the tab bar floats over them
+    Overlay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WindowManagerInfo {
+    pub stage_manager_enabled: bool,
+    pub offset_mode: OffsetMode,
+}
+
+impl WindowManagerInfo {
+    fn from_stage_manager(stage_manager_enabled: bool) -> Self {
+        Self {
+            stage_manager_enabled,
+            offset_mode: if stage_manager_enabled {
+                OffsetMode::Overlay
+            } else {
+                OffsetMode::Offset
+            },
+        }
+    }
+}
+
+struct Detection {
+    checked_at: Instant,
+    stage_manager_enabled: bool,
+}
+
+static DETECTION: LazyLock<Mutex<Option<Detection>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Parse the output of `defaults read com.apple.WindowManager GloballyEnabled`
+fn parse_globally_enabled(output: &str) -> bool {
+    matches!(output.trim(), "1" | "true" | "YES")
+}
+
+fn read_stage_manager_enabled() -> bool {
+    // キーが存在しない場合（一度も有効化していない）は defaults が失敗するので無効扱い
+    Command::new("defaults")
+        .args(["read", "com.apple.WindowManager", "GloballyEnabled"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_globally_enabled(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or(false)
+}
+
+/// Current window manager state, re-detected at most every `DETECTION_TTL`.
+/// Blocks on `defaults`, so call it off the main thread.
+///
+/// When Stage Manager turns on, offset windows are restored and
+/// `stage-manager-detected` is emitted so the frontend can explain why offsets are off.
+/// When it turns off again, `stage-manager-disabled` clears that explanation.
+pub fn get_window_manager_info(app_handle: &AppHandle) -> WindowManagerInfo {
+    let (enabled, changed) = {
+        let mut detection = DETECTION.lock().unwrap_or_else(|e| e.into_inner());
+        match detection.as_ref() {
+            Some(d) if d.checked_at.elapsed() < DETECTION_TTL => (d.stage_manager_enabled, false),
+            previous => {
+                let was_enabled = previous.map(|d| d.stage_manager_enabled).unwrap_or(false);
+                let enabled = read_stage_manager_enabled();
+                *detection = Some(Detection {
+                    checked_at: Instant::now(),
+                    stage_manager_enabled: enabled,
+                });
+                (enabled, enabled != was_enabled)
+            }
+        }
+    };
+
+    let info = WindowManagerInfo::from_stage_manager(enabled);
+    if changed && enabled {
+        if let Err(e) = window_offset::restore_all() {
+            log::error!("Failed to restore window positions for Stage Manager: {}", e);
+        }
+        tab_bars::emit_to_tab_bars(app_handle, "stage-manager-detected", info);
+    } else if changed {
+        tab_bars::emit_to_tab_bars(app_handle, "stage-manager-disabled", info);
+    }
+    info
+}
+
+/// Whether editor windows may be moved/resized to make room for the tab bar
+pub fn offsets_enabled(app_handle: &AppHandle) -> bool {
+    get_window_manager_info(app_handle).offset_mode == OffsetMode::Offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_defaults_output() {
+        assert!(parse_globally_enabled("1\n"));
+        assert!(!parse_globally_enabled("0\n"));
+        assert!(!parse_globally_enabled(""));
+    }
+
+    #[test]
+    fn stage_manager_switches_to_overlay() {
+        assert_eq!(
+            WindowManagerInfo::from_stage_manager(true).offset_mode,
+            OffsetMode::Overlay
+        );
+        assert_eq!(
+            WindowManagerInfo::from_stage_manager(false).offset_mode,
+            OffsetMode::Offset
+        );
+    }
+}
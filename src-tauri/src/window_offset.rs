@@ -4,25 +4,32 @@
 //! editor UI elements (like search bars) from being hidden behind the tab bar.
 
 use crate::ax_helper;
-use objc2::MainThreadMarker;
-use objc2_app_kit::NSScreen;
+#[cfg(target_os = "macos")]
+use objc2::runtime::NSObjectProtocol;
+#[cfg(target_os = "macos")]
+use objc2::{sel, MainThreadMarker};
+#[cfg(target_os = "macos")]
+use objc2_app_kit::{NSScreen, NSStatusBar};
+#[cfg(target_os = "macos")]
+use objc2_foundation::{NSRect, NSString, NSUserDefaults};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::{LazyLock, Mutex};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, TryLockError};
+use std::time::{Duration, Instant};
 
 /// Get the file path for storing original window positions
 /// Uses ~/Library/Application Support/ instead of /tmp for security
 fn get_offset_file_path() -> PathBuf {
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-    let dir = PathBuf::from(&home).join("Library/Application Support/com.editor-tab-manager.app");
+    let dir = crate::settings_store::app_support_dir();
     let _ = fs::create_dir_all(&dir);
     dir.join("offsets.json")
 }
 
 /// Window frame data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct WindowFrame {
     pub x: f64,
     pub y: f64,
@@ -30,11 +37,53 @@ pub struct WindowFrame {
     pub height: f64,
 }
 
-/// Stored window positions keyed by bundle_id -> window_id -> frame
-#[derive(Debug, Default, Serialize, Deserialize)]
+/// Edge of the display the tab bar is anchored to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TabBarPosition {
+    /// Just below the menu bar; windows are pushed down
+    #[default]
+    Top,
+    /// Just above the Dock; windows are shortened from the bottom
+    Bottom,
+}
+
+/// Original frame of an offset window plus the tab bar height it was offset for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OffsetEntry {
+    /// Frame before any offset was applied (restore target)
+    #[serde(flatten)]
+    pub original: WindowFrame,
+    /// Tab bar height the current offset was computed for
+    /// (None for entries written before this was tracked)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tab_bar_height: Option<f64>,
+    /// Project path of the window, used to find it again after the editor restarts
+    /// (CGWindowIDs are not stable across editor launches)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// Current offsets.json format version.
+/// Files without a `version` field are treated as version 0 (same layout as version 1).
+const OFFSET_STORE_VERSION: u32 = 1;
+
+/// Stored window positions keyed by bundle_id -> window_id -> entry
+#[derive(Debug, Serialize, Deserialize)]
 pub struct OffsetStore {
-    /// bundle_id -> (window_id -> original_frame)
-    pub positions: HashMap<String, HashMap<u32, WindowFrame>>,
+    #[serde(default)]
+    pub version: u32,
+    /// bundle_id -> (window_id -> original frame + applied tab bar height)
+    pub positions: HashMap<String, HashMap<u32, OffsetEntry>>,
+}
+
+impl Default for OffsetStore {
+    fn default() -> Self {
+        Self {
+            version: OFFSET_STORE_VERSION,
+            positions: HashMap::new(),
+        }
+    }
 }
 
 /// Global store for original window positions
@@ -47,15 +96,80 @@ static OFFSET_STORE: LazyLock<Mutex<OffsetStore>> = LazyLock::new(|| {
 /// Load offset store from temporary file
 fn load_from_file() -> Option<OffsetStore> {
     let content = fs::read_to_string(get_offset_file_path()).ok()?;
-    serde_json::from_str(&content).ok()
+    let store = parse_store(&content);
+    if store.is_none() {
+        log::warn!("Offset file is corrupt, ignoring it");
+    }
+    store
+}
+
+/// Parse offsets.json, migrating older formats to the current version
+fn parse_store(content: &str) -> Option<OffsetStore> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version > OFFSET_STORE_VERSION as u64 {
+        log::warn!("Offset file version {} is newer than supported, trying anyway", version);
+    }
+
+    if let Ok(mut store) = serde_json::from_value::<OffsetStore>(value.clone()) {
+        store.version = OFFSET_STORE_VERSION;
+        return Some(store);
+    }
+
+    migrate_entries(&value)
+}
+
+/// Best-effort migration that keeps every entry it can read.
+/// Entries keyed by something other than a CGWindowID (the old window-title keys)
+/// can't be matched to a window anymore and are dropped.
+fn migrate_entries(value: &serde_json::Value) -> Option<OffsetStore> {
+    let positions = value.get("positions")?.as_object()?;
+    let mut store = OffsetStore::default();
+    let mut dropped = 0;
+
+    for (bundle_id, windows) in positions {
+        let Some(windows) = windows.as_object() else {
+            continue;
+        };
+        let mut entries = HashMap::new();
+        for (key, entry) in windows {
+            let window_id = key.parse::<u32>().ok();
+            let entry = serde_json::from_value::<OffsetEntry>(entry.clone()).ok();
+            match (window_id, entry) {
+                (Some(window_id), Some(entry)) => {
+                    entries.insert(window_id, entry);
+                }
+                _ => dropped += 1,
+            }
+        }
+        if !entries.is_empty() {
+            store.positions.insert(bundle_id.clone(), entries);
+        }
+    }
+
+    if dropped > 0 {
+        log::warn!("Dropped {} unreadable offset entries during migration", dropped);
+    }
+    Some(store)
 }
 
 /// Save offset store to temporary file
 fn save_to_file(store: &OffsetStore) -> Result<(), String> {
     let content = serde_json::to_string_pretty(store)
         .map_err(|e| format!("Failed to serialize offset store: {}", e))?;
-    fs::write(get_offset_file_path(), content)
-        .map_err(|e| format!("Failed to write offset file: {}", e))?;
+    write_atomically(&get_offset_file_path(), &content)
+}
+
+/// Write via a temp file in the same directory and rename it over the target,
+/// so a crash mid-write never leaves a truncated offsets.json behind
+fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to create temp offset file: {}", e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write temp offset file: {}", e))?;
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace offset file: {}", e))?;
     Ok(())
 }
 
@@ -64,58 +178,272 @@ fn delete_offset_file() {
     let _ = fs::remove_file(get_offset_file_path());
 }
 
-/// プライマリディスプレイのサイズを取得
-/// NSScreen::screens() の最初の要素が常にプライマリディスプレイ
-/// (NSScreen::mainScreen はフォーカス中ウィンドウのスクリーンを返すため不適切)
-pub fn get_primary_screen_size() -> Option<(f64, f64)> {
-    let mtm = MainThreadMarker::new()?;
-    let screens = NSScreen::screens(mtm);
-    let primary = screens.firstObject()?;
-    let frame = primary.frame();
-    Some((frame.size.width, frame.size.height))
+/// Where the Dock sits on a display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DockPosition {
+    Bottom,
+    Left,
+    Right,
+    /// Auto-hidden, or on another display
+    Hidden,
 }
 
-/// macOSのメニューバー高さを動的に取得
-/// Notch付きMacではvisibleFrameがNotchを避けた領域を返す
-fn get_menu_bar_height() -> f64 {
-    // MainThreadMarkerの取得を試みる
-    // GUIアプリなのでメインスレッドから呼ばれることを想定
+/// Display geometry in AX coordinates (origin at the primary display's top-left, Y down)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DisplayFrame {
+    /// CGDirectDisplayID of the display
+    pub display_id: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Height reserved for the menu bar at the top of this display
+    /// (also while an auto-hidden menu bar is currently slid out of view)
+    pub menu_bar_height: f64,
+    /// Height of the camera housing (0 on displays without a notch)
+    pub notch_height: f64,
+    /// "Automatically hide and show the menu bar" is enabled
+    pub menu_bar_auto_hidden: bool,
+    /// Area not covered by the menu bar or the Dock
+    pub usable: WindowFrame,
+    pub dock: DockPosition,
+}
+
+impl DisplayFrame {
+    /// Check whether a point (AX coordinates) lies on this display
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Check whether a window belongs to this display (its center lies on it)
+    pub fn contains_window(&self, x: f64, y: f64, width: f64, height: f64) -> bool {
+        self.contains(x + width / 2.0, y + height / 2.0)
+    }
+}
+
+/// An auto-hidden Dock still reserves a few points at its edge of the visibleFrame
+const HIDDEN_DOCK_MAX_INSET: f64 = 8.0;
+
+/// Infer the Dock position from the insets between frame and visibleFrame
+/// (both in Cocoa coordinates)
+fn dock_position(frame: WindowFrame, visible: WindowFrame) -> DockPosition {
+    let left = visible.x - frame.x;
+    let right = (frame.x + frame.width) - (visible.x + visible.width);
+    let bottom = visible.y - frame.y;
+
+    let (position, inset) = [
+        (DockPosition::Bottom, bottom),
+        (DockPosition::Left, left),
+        (DockPosition::Right, right),
+    ]
+    .into_iter()
+    .fold((DockPosition::Hidden, 0.0), |best, (position, inset)| {
+        if inset > best.1 {
+            (position, inset)
+        } else {
+            best
+        }
+    });
+
+    if inset > HIDDEN_DOCK_MAX_INSET {
+        position
+    } else {
+        DockPosition::Hidden
+    }
+}
+
+/// Convert an NSScreen frame (Cocoa coordinates: origin at the primary display's
+/// bottom-left, Y up) into AX coordinates (origin at the primary's top-left, Y down)
+fn ax_display_frame(
+    display_id: u32,
+    frame: WindowFrame,
+    visible: WindowFrame,
+    primary_height: f64,
+) -> DisplayFrame {
+    // メニューバー高さ = ディスプレイ上端 - 可視領域の上端
+    // (Dockは上端に来ないので、Dockの位置・サイズに影響されない)
+    let frame_top = frame.y + frame.height;
+    let visible_top = visible.y + visible.height;
+    DisplayFrame {
+        display_id,
+        x: frame.x,
+        y: primary_height - frame_top,
+        width: frame.width,
+        height: frame.height,
+        menu_bar_height: (frame_top - visible_top).max(0.0),
+        notch_height: 0.0,
+        menu_bar_auto_hidden: false,
+        usable: WindowFrame {
+            x: visible.x,
+            y: primary_height - visible_top,
+            width: visible.width,
+            height: visible.height,
+        },
+        dock: dock_position(frame, visible),
+    }
+}
+
+/// Menu bar state that can't be derived from frame/visibleFrame
+#[derive(Debug, Clone, Copy, Default)]
+struct MenuBarMetrics {
+    auto_hidden: bool,
+    notch_height: f64,
+    /// NSStatusBar thickness (height of the menu bar when shown)
+    thickness: f64,
+}
+
+/// Reserve the menu bar area even while an auto-hidden menu bar is out of view,
+/// so the tab bar doesn't sit where the menu bar slides in
+fn with_menu_bar_metrics(display: DisplayFrame, metrics: MenuBarMetrics) -> DisplayFrame {
+    let mut display = DisplayFrame {
+        notch_height: metrics.notch_height,
+        menu_bar_auto_hidden: metrics.auto_hidden,
+        ..display
+    };
+    if !metrics.auto_hidden {
+        return display;
+    }
+
+    // ノッチ付きディスプレイではメニューバーがノッチの高さになる
+    let reserved = metrics.thickness.max(metrics.notch_height);
+    let missing = reserved - display.menu_bar_height;
+    if missing > 0.0 {
+        display.menu_bar_height = reserved;
+        let usable_top = display.y + reserved;
+        if display.usable.y < usable_top {
+            let shift = usable_top - display.usable.y;
+            display.usable.y = usable_top;
+            display.usable.height -= shift;
+        }
+    }
+    display
+}
+
+#[cfg(target_os = "macos")]
+fn cocoa_frame(rect: NSRect) -> WindowFrame {
+    WindowFrame {
+        x: rect.origin.x,
+        y: rect.origin.y,
+        width: rect.size.width,
+        height: rect.size.height,
+    }
+}
+
+/// Read the global "Automatically hide and show the menu bar" preference
+#[cfg(target_os = "macos")]
+fn menu_bar_auto_hidden() -> bool {
+    NSUserDefaults::standardUserDefaults().boolForKey(&NSString::from_str("_HIHideMenuBar"))
+}
+
+/// Camera housing height of a screen (safeAreaInsets is macOS 12+)
+#[cfg(target_os = "macos")]
+fn notch_height(screen: &NSScreen) -> f64 {
+    if screen.respondsToSelector(sel!(safeAreaInsets)) {
+        screen.safeAreaInsets().top
+    } else {
+        0.0
+    }
+}
+
+/// Last captured display geometry, so callers off the main thread get real values
+static DISPLAYS: LazyLock<Mutex<Vec<DisplayFrame>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Re-read every display from NSScreen (main thread only) and update the cache.
+/// Called on `display-changed`, which also fires for Dock preference changes.
+#[cfg(target_os = "macos")]
+pub fn refresh_displays() -> Vec<DisplayFrame> {
     let Some(mtm) = MainThreadMarker::new() else {
-        return 25.0; // フォールバック（メインスレッドでない場合）
+        return cached_displays();
     };
     // NSScreen::screens() の最初の要素が常にプライマリディスプレイ
     // (NSScreen::mainScreen はフォーカス中ウィンドウのスクリーンを返すため不適切)
     let screens = NSScreen::screens(mtm);
     let Some(primary) = screens.firstObject() else {
-        return 25.0; // フォールバック
+        return cached_displays();
     };
-    let frame = primary.frame();
-    let visible_frame = primary.visibleFrame();
-    // メニューバー高さ = 画面全体の高さ - 可視領域の高さ - 可視領域のY位置
-    // (Dockが下にある場合、visible_frame.origin.yがDock分だけ上にずれる)
-    let menu_bar_height = frame.size.height - visible_frame.size.height - visible_frame.origin.y;
-    menu_bar_height.max(0.0)
+    let primary_height = primary.frame().size.height;
+    let auto_hidden = menu_bar_auto_hidden();
+    let thickness = NSStatusBar::systemStatusBar().thickness();
+    let displays: Vec<DisplayFrame> = screens
+        .iter()
+        .map(|screen| {
+            let display = ax_display_frame(
+                screen.CGDirectDisplayID(),
+                cocoa_frame(screen.frame()),
+                cocoa_frame(screen.visibleFrame()),
+                primary_height,
+            );
+            with_menu_bar_metrics(
+                display,
+                MenuBarMetrics {
+                    auto_hidden,
+                    notch_height: notch_height(&screen),
+                    thickness,
+                },
+            )
+        })
+        .collect();
+    if let Ok(mut cache) = DISPLAYS.lock() {
+        *cache = displays.clone();
+    }
+    displays
+}
+
+/// No displays to read without NSScreen; the cache stays empty
+#[cfg(not(target_os = "macos"))]
+pub fn refresh_displays() -> Vec<DisplayFrame> {
+    cached_displays()
+}
+
+fn cached_displays() -> Vec<DisplayFrame> {
+    DISPLAYS.lock().map(|cache| cache.clone()).unwrap_or_default()
+}
+
+/// Get the geometry of every connected display in AX coordinates.
+/// The first element is always the primary display.
+pub fn get_displays() -> Vec<DisplayFrame> {
+    if crate::main_thread::is_main_thread() {
+        refresh_displays()
+    } else {
+        cached_displays()
+    }
+}
+
+/// Find a display by CGDirectDisplayID, falling back to the primary display
+pub fn get_display_or_primary(display_id: Option<u32>) -> Option<DisplayFrame> {
+    let displays = get_displays();
+    display_id
+        .and_then(|id| displays.iter().find(|d| d.display_id == id).copied())
+        .or_else(|| displays.first().copied())
+}
+
+/// Maximize frame (x, y, width, height) beside the tab bar within a display's usable area
+fn maximize_frame_for(
+    display: &DisplayFrame,
+    tab_bar_height: f64,
+    position: TabBarPosition,
+) -> (f64, f64, f64, f64) {
+    let usable = &display.usable;
+    let y = match position {
+        TabBarPosition::Top => usable.y + tab_bar_height,
+        TabBarPosition::Bottom => usable.y,
+    };
+    (usable.x, y, usable.width, usable.height - tab_bar_height)
 }
 
 /// Calculate the maximize frame in AX coordinates (origin top-left, Y down)
 /// Returns (x, y, width, height) accounting for menu bar, tab bar, and Dock
-fn get_maximize_frame(tab_bar_height: f64) -> Result<(f64, f64, f64, f64), String> {
-    let mtm = MainThreadMarker::new()
-        .ok_or_else(|| "Not on main thread".to_string())?;
-    let screens = NSScreen::screens(mtm);
-    let primary = screens.firstObject()
+fn get_maximize_frame(
+    tab_bar_height: f64,
+    position: TabBarPosition,
+) -> Result<(f64, f64, f64, f64), String> {
+    let primary = get_displays()
+        .into_iter()
+        .next()
         .ok_or_else(|| "No primary screen found".to_string())?;
 
-    let frame = primary.frame();
-    let visible = primary.visibleFrame();
-
-    // menu_bar_height: macOS coords → AX coords conversion
-    let menu_bar_height = (frame.size.height - visible.size.height - visible.origin.y).max(0.0);
-
-    let ax_x = visible.origin.x;
-    let ax_y = menu_bar_height + tab_bar_height;
-    let width = visible.size.width;
-    let height = visible.size.height - tab_bar_height;
+    let (ax_x, ax_y, width, height) = maximize_frame_for(&primary, tab_bar_height, position);
 
     if height < 100.0 || width < 100.0 {
         return Err("Calculated maximize frame too small".to_string());
@@ -126,8 +454,9 @@ fn get_maximize_frame(tab_bar_height: f64) -> Result<(f64, f64, f64, f64), Strin
 
 /// Maximize a specific window to fill the visible area below the tab bar
 pub fn maximize_window(bundle_id: &str, window_id: u32, tab_bar_height: f64) -> Result<(), String> {
-    let pid = ax_helper::get_pid_by_bundle_id(bundle_id)
-        .ok_or_else(|| format!("Editor not running: {}", bundle_id))?;
+    let config = crate::editor_config::get_editor_by_bundle_id(bundle_id)
+        .ok_or_else(|| format!("Unknown editor: {}", bundle_id))?;
+    let pid = crate::editor::owning_pid(config, window_id)?;
 
     // Skip fullscreen or minimized windows
     if ax_helper::is_window_fullscreen_by_id(pid, window_id).unwrap_or(false) {
@@ -137,22 +466,31 @@ pub fn maximize_window(bundle_id: &str, window_id: u32, tab_bar_height: f64) ->
         return Ok(());
     }
 
-    let (ax_x, ax_y, width, height) = get_maximize_frame(tab_bar_height)?;
+    let position = crate::tab_bars::position();
+    let (ax_x, ax_y, width, height) = get_maximize_frame(tab_bar_height, position)?;
     ax_helper::set_window_frame_by_id(pid, window_id, ax_x, ax_y, width, height)?;
 
     // OFFSET_STORE を更新: restore時にタブバーなしの最大化位置に復元されるようにする
-    let menu_bar_height = get_menu_bar_height();
     let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
     if let Some(editor_positions) = store.positions.get_mut(bundle_id) {
         if let std::collections::hash_map::Entry::Occupied(mut e) = editor_positions.entry(window_id) {
-            e.insert(WindowFrame {
-                x: ax_x,
-                y: menu_bar_height,
-                width,
-                height: height + tab_bar_height,
+            let path = e.get().path.clone();
+            let original_y = match position {
+                TabBarPosition::Top => ax_y - tab_bar_height,
+                TabBarPosition::Bottom => ax_y,
+            };
+            e.insert(OffsetEntry {
+                original: WindowFrame {
+                    x: ax_x,
+                    y: original_y,
+                    width,
+                    height: height + tab_bar_height,
+                },
+                tab_bar_height: Some(tab_bar_height),
+                path,
             });
             if let Err(e) = save_to_file(&store) {
-                eprintln!("Failed to save offset file: {}", e);
+                log::error!("Failed to save offset file: {}", e);
             }
         }
     }
@@ -160,39 +498,190 @@ pub fn maximize_window(bundle_id: &str, window_id: u32, tab_bar_height: f64) ->
     Ok(())
 }
 
+/// Compute how much of a window overlaps the tab bar on `display`: how far its top must
+/// move down (Top) or its bottom must move up (Bottom).
+/// Returns None if the window is on another display or doesn't overlap the tab bar.
+fn required_offset(
+    display: &DisplayFrame,
+    position: TabBarPosition,
+    offset_y: f64,
+    frame: &WindowFrame,
+) -> Option<f64> {
+    // 別ディスプレイ上のウィンドウはスキップ
+    // AXPositionはグローバル座標系（プライマリモニター左上が原点、Y下向き正）
+    if !display.contains(frame.x, frame.y) {
+        return None;
+    }
+
+    let overlap = match position {
+        // タブバーの下端位置 = ディスプレイ上端 + メニューバー + タブバー高さ
+        TabBarPosition::Top => display.y + display.menu_bar_height + offset_y - frame.y,
+        // タブバーの上端位置 = Dock を除いた領域の下端 - タブバー高さ
+        TabBarPosition::Bottom => {
+            frame.y + frame.height - (display.usable.y + display.usable.height - offset_y)
+        }
+    };
+
+    // 0 以下なら「重なっていない」→スキップ
+    if overlap <= 0.0 {
+        return None;
+    }
+
+    Some(overlap)
+}
+
+/// Frame of a window moved out of the tab bar's way by `offset`
+fn offset_frame(frame: &WindowFrame, position: TabBarPosition, offset: f64) -> WindowFrame {
+    let y = match position {
+        TabBarPosition::Top => frame.y + offset,
+        // 下に置いたタブバーでは位置を保ち、高さだけ縮める
+        TabBarPosition::Bottom => frame.y,
+    };
+    WindowFrame {
+        x: frame.x,
+        y,
+        width: frame.width,
+        height: frame.height - offset,
+    }
+}
+
 /// Apply window offset for all windows of the specified editor
 ///
 /// This function:
 /// 1. Gets all windows for the editor by bundle_id
 /// 2. Saves original positions (if not already saved)
-/// 3. Moves windows down by TAB_BAR_HEIGHT if they're at Y < TAB_BAR_HEIGHT
-pub fn apply_offset(bundle_id: &str, offset_y: f64) -> Result<(), String> {
-    let pid = ax_helper::get_pid_by_bundle_id(bundle_id)
-        .ok_or_else(|| format!("Editor not running: {}", bundle_id))?;
-
-    let windows = ax_helper::get_all_window_frames(pid)?;
+/// 3. Moves windows on `display` out of the tab bar's way: down below a bar at the
+///    top, or shortened to end above a bar at the bottom
+pub fn apply_offset(bundle_id: &str, offset_y: f64, display: &DisplayFrame) -> Result<(), String> {
+    let windows = editor_window_frames(bundle_id)?;
 
     if windows.is_empty() {
         return Ok(());
     }
+    let position = crate::tab_bars::position();
 
-    let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
-    let editor_positions = store.positions.entry(bundle_id.to_string()).or_default();
+    // ストアのロック前にパスを解決しておく（エディタ再起動後の復元用）
+    let paths = window_paths(bundle_id);
+
+    // AX の問い合わせはストアのロック外で行う
+    let targets: Vec<(i32, u32, WindowFrame, f64)> = windows
+        .iter()
+        .filter_map(|&(pid, (window_id, x, y, width, height))| {
+            let frame = WindowFrame { x, y, width, height };
+            // タブバーとの重なり判定（タブバーのあるディスプレイ上のウィンドウのみ対象）
+            let actual_offset = required_offset(display, position, offset_y, &frame)?;
+            // Check if window is minimized or fullscreen - skip if so
+            if ax_helper::is_window_minimized_by_id(pid, window_id).unwrap_or(false)
+                || ax_helper::is_window_fullscreen_by_id(pid, window_id).unwrap_or(false)
+            {
+                return None;
+            }
+            Some((pid, window_id, frame, actual_offset))
+        })
+        .collect();
 
-    // プライマリモニターのサイズを取得（セカンダリモニター上のウィンドウをスキップするため）
-    // 取得失敗時はフィルタなしで従来通り動作
-    let primary_screen = get_primary_screen_size();
+    let mut moves = Vec::new();
+    {
+        let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let editor_positions = store.positions.entry(bundle_id.to_string()).or_default();
 
-    for (window_id, x, y, width, height) in windows.iter() {
-        // セカンダリモニター上のウィンドウはスキップ
-        // AXPositionはグローバル座標系（プライマリモニター左上が原点、Y下向き正）
-        if let Some((screen_w, screen_h)) = primary_screen {
-            if *x < 0.0 || *x >= screen_w || *y < 0.0 || *y >= screen_h {
+        for (pid, window_id, original, actual_offset) in targets {
+            // 既にオフセットが適用済みかチェック（二重適用防止）
+            // 一度オフセットを適用したウィンドウは restore_positions() が呼ばれるまで再適用しない
+            if editor_positions.contains_key(&window_id) {
                 continue;
             }
+
+            // Apply offset: 実際の必要量を計算（macOSが部分的に調整済みの場合に対応）
+            let moved = offset_frame(&original, position, actual_offset);
+
+            // Only apply if the new height is still reasonable
+            const MIN_WINDOW_HEIGHT: f64 = 100.0;
+            if moved.height > MIN_WINDOW_HEIGHT {
+                moves.push((pid, window_id, moved.x, moved.y, moved.width, moved.height));
+            }
+
+            // Save original position
+            editor_positions.insert(
+                window_id,
+                OffsetEntry {
+                    original,
+                    tab_bar_height: Some(offset_y),
+                    path: paths.get(&window_id).cloned(),
+                },
+            );
         }
 
-        // Check if window is minimized or fullscreen - skip if so
+        // Save to file for crash recovery (before moving, so a crash can still restore)
+        if let Err(e) = save_to_file(&store) {
+            log::error!("Failed to save offset file: {}", e);
+        }
+    }
+
+    for (pid, window_id, x, y, width, height) in moves {
+        let _ = ax_helper::set_window_frame_by_id(pid, window_id, x, y, width, height);
+    }
+
+    Ok(())
+}
+
+/// Frame after shifting an offset window for a tab bar height change.
+/// Returns None when the height change is a no-op or would make the window too small.
+fn adjusted_frame(
+    current: &WindowFrame,
+    old_height: f64,
+    new_height: f64,
+    position: TabBarPosition,
+) -> Option<WindowFrame> {
+    const MIN_WINDOW_HEIGHT: f64 = 100.0;
+    let delta = new_height - old_height;
+    if delta.abs() < f64::EPSILON {
+        return None;
+    }
+    let height = current.height - delta;
+    if height <= MIN_WINDOW_HEIGHT {
+        return None;
+    }
+    let y = match position {
+        TabBarPosition::Top => current.y + delta,
+        TabBarPosition::Bottom => current.y,
+    };
+    Some(WindowFrame {
+        x: current.x,
+        y,
+        width: current.width,
+        height,
+    })
+}
+
+/// Re-apply offsets for a new tab bar height (e.g. switching to a compact tab bar)
+///
+/// Windows that are already offset are shifted/resized by the difference between the
+/// new height and the one they were offset for. The stored original frames are kept
+/// untouched so restore still returns windows to their pre-offset position.
+pub fn update_offset(bundle_id: &str, new_height: f64) -> Result<(), String> {
+    let editor_positions = {
+        let store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        match store.positions.get(bundle_id) {
+            Some(positions) if !positions.is_empty() => positions.clone(),
+            _ => return Ok(()), // Nothing offset for this editor
+        }
+    };
+
+    let current_windows = editor_window_frames(bundle_id)?;
+    let position = crate::tab_bars::position();
+
+    let mut updated = Vec::new();
+    for (pid, (window_id, x, y, width, height)) in current_windows.iter() {
+        let pid = *pid;
+        let Some(entry) = editor_positions.get(window_id) else {
+            continue;
+        };
+        // 旧フォーマットのエントリは適用時の高さが不明なので触らない
+        let Some(old_height) = entry.tab_bar_height else {
+            continue;
+        };
+
         if ax_helper::is_window_minimized_by_id(pid, *window_id).unwrap_or(false) {
             continue;
         }
@@ -200,112 +689,262 @@ pub fn apply_offset(bundle_id: &str, offset_y: f64) -> Result<(), String> {
             continue;
         }
 
-        // タブバーとの重なり判定
-        // メニューバー高さを動的に取得（Notch付きMac対応）
-        // タブバーの下端位置 = メニューバー + タブバー高さ
-        let menu_bar_height = get_menu_bar_height();
-        let tab_bar_bottom = menu_bar_height + offset_y;
-
-        // ウィンドウ上端がタブバー下端より上にあれば「重なっている」→移動対象
-        // ウィンドウ上端がタブバー下端以下（>=）であれば「重なっていない」→スキップ
-        if *y >= tab_bar_bottom {
+        let current = WindowFrame {
+            x: *x,
+            y: *y,
+            width: *width,
+            height: *height,
+        };
+        let Some(frame) = adjusted_frame(&current, old_height, new_height, position) else {
             continue;
+        };
+        match ax_helper::set_window_frame_by_id(
+            pid,
+            *window_id,
+            frame.x,
+            frame.y,
+            frame.width,
+            frame.height,
+        ) {
+            Ok(()) => updated.push(*window_id),
+            Err(e) => log::error!("Failed to update offset for window_id={}: {}", window_id, e),
         }
+    }
 
-        // 既にオフセットが適用済みかチェック（二重適用防止）
-        // 一度オフセットを適用したウィンドウは restore_positions() が呼ばれるまで再適用しない
-        if editor_positions.contains_key(window_id) {
-            continue;
+    let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(editor_positions) = store.positions.get_mut(bundle_id) {
+        for window_id in updated {
+            if let Some(entry) = editor_positions.get_mut(&window_id) {
+                entry.tab_bar_height = Some(new_height);
+            }
         }
+    }
+    if let Err(e) = save_to_file(&store) {
+        log::error!("Failed to save offset file: {}", e);
+    }
 
-        // Save original position
-        editor_positions.insert(
-            *window_id,
-            WindowFrame {
-                x: *x,
-                y: *y,
-                width: *width,
-                height: *height,
-            },
-        );
+    Ok(())
+}
 
-        // Apply offset: 実際の必要量を計算（macOSが部分的に調整済みの場合に対応）
-        let actual_offset = tab_bar_bottom - y;
-        let new_y = y + actual_offset;
-        let new_height = height - actual_offset;
+/// Frames of the windows of every running instance of the editor, with the
+/// pid that owns each window
+fn editor_window_frames(bundle_id: &str) -> Result<Vec<(i32, ax_helper::WindowFrameInfo)>, String> {
+    let pids = ax_helper::get_pids_by_bundle_id(bundle_id);
+    if pids.is_empty() {
+        return Err(crate::i18n::t_with("error.editorNotRunning", &[("name", bundle_id)]));
+    }
+    let mut frames = Vec::new();
+    for pid in pids {
+        // 1 つのインスタンスが応答しなくても他のインスタンスは処理する
+        match ax_helper::get_all_window_frames(pid) {
+            Ok(windows) => frames.extend(windows.into_iter().map(|window| (pid, window))),
+            Err(e) => log::debug!("Failed to get windows of {} (pid {}): {}", bundle_id, pid, e),
+        }
+    }
+    Ok(frames)
+}
 
-        // Only apply if the new height is still reasonable
-        const MIN_WINDOW_HEIGHT: f64 = 100.0;
-        if new_height > MIN_WINDOW_HEIGHT {
-            let _ = ax_helper::set_window_frame_by_id(pid, *window_id, *x, new_y, *width, new_height);
+/// CGWindowID -> resolved project path for the editor's current windows
+fn window_paths(bundle_id: &str) -> HashMap<u32, String> {
+    crate::editor::get_editor_windows(bundle_id)
+        .into_iter()
+        .filter(|window| !window.path.is_empty())
+        .map(|window| (window.id, window.path))
+        .collect()
+}
+
+/// Pair stored entries with current windows.
+/// Entries are matched by window id first; entries whose window id no longer exists
+/// (e.g. after an editor restart) fall back to the first unmatched window with the same path.
+fn match_entries<'a>(
+    entries: &'a HashMap<u32, OffsetEntry>,
+    current_ids: &[u32],
+    current_paths: &HashMap<u32, String>,
+) -> Vec<(u32, &'a OffsetEntry)> {
+    let mut matched = Vec::new();
+    let mut used = std::collections::HashSet::new();
+
+    for wid in current_ids {
+        if let Some(entry) = entries.get(wid) {
+            matched.push((*wid, entry));
+            used.insert(*wid);
         }
     }
 
-    // Save to file for crash recovery
-    if let Err(e) = save_to_file(&store) {
-        eprintln!("Failed to save offset file: {}", e);
+    let mut stale: Vec<(&u32, &OffsetEntry)> = entries
+        .iter()
+        .filter(|(wid, _)| !current_ids.contains(wid))
+        .collect();
+    stale.sort_by_key(|(wid, _)| **wid);
+
+    for (_, entry) in stale {
+        let Some(path) = entry.path.as_deref() else {
+            continue;
+        };
+        let candidate = current_ids.iter().find(|wid| {
+            !used.contains(*wid) && current_paths.get(*wid).map(String::as_str) == Some(path)
+        });
+        if let Some(wid) = candidate {
+            matched.push((*wid, entry));
+            used.insert(*wid);
+        }
     }
 
-    Ok(())
+    matched
+}
+
+/// Stored window ids of matched entries that were not restored
+fn unrestored_ids(
+    entries: &HashMap<u32, OffsetEntry>,
+    remaining: &[(u32, &OffsetEntry)],
+) -> Vec<u32> {
+    entries
+        .iter()
+        .filter(|(_, entry)| remaining.iter().any(|(_, left)| std::ptr::eq(*left, *entry)))
+        .map(|(window_id, _)| *window_id)
+        .collect()
 }
 
 /// Restore original window positions for the specified editor
 pub fn restore_positions(bundle_id: &str) -> Result<(), String> {
-    let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
-
-    let editor_positions = match store.positions.get(bundle_id) {
-        Some(positions) if !positions.is_empty() => positions.clone(),
-        _ => return Ok(()), // Nothing to restore
-    };
+    restore_positions_until(bundle_id, None)
+}
 
-    let pid = match ax_helper::get_pid_by_bundle_id(bundle_id) {
-        Some(p) => p,
-        None => {
+/// Restore an editor's windows, stopping at `deadline`.
+/// Windows not reached keep their entries so a later restoration can move them.
+fn restore_positions_until(bundle_id: &str, deadline: Option<Instant>) -> Result<(), String> {
+    let running = !ax_helper::get_pids_by_bundle_id(bundle_id).is_empty();
+    let editor_positions = {
+        let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let editor_positions = match store.positions.get(bundle_id) {
+            Some(positions) if !positions.is_empty() => positions.clone(),
+            _ => return Ok(()), // Nothing to restore
+        };
+        if !running {
             // Editor not running, just clear the stored positions
             store.positions.remove(bundle_id);
-            if store.positions.is_empty() {
-                delete_offset_file();
-            } else if let Err(e) = save_to_file(&store) {
-                eprintln!("Failed to save offset file: {}", e);
-            }
+            save_or_delete(&store);
             return Ok(());
         }
+        editor_positions
     };
 
-    let current_windows = ax_helper::get_all_window_frames(pid)?;
+    // AX とパス解決はストアのロック外で行う
+    let current_windows = editor_window_frames(bundle_id)?;
+    let window_pids: HashMap<u32, i32> = current_windows
+        .iter()
+        .map(|&(pid, (window_id, ..))| (window_id, pid))
+        .collect();
+    let current_ids: Vec<u32> = current_windows.iter().map(|(_, w)| w.0).collect();
+
+    // IDが見つからないエントリがある場合のみパスを解決する（エディタ再起動後）
+    let needs_paths = editor_positions
+        .iter()
+        .any(|(wid, entry)| entry.path.is_some() && !current_ids.contains(wid));
+    let current_paths = if needs_paths {
+        window_paths(bundle_id)
+    } else {
+        HashMap::new()
+    };
 
     // Restore each window to its original position
-    for (current_wid, _, _, _, _) in current_windows.iter() {
-        if let Some(original) = editor_positions.get(current_wid) {
-            // Check if window is minimized or fullscreen - skip if so
-            if ax_helper::is_window_minimized_by_id(pid, *current_wid).unwrap_or(false) {
-                continue;
-            }
-            if ax_helper::is_window_fullscreen_by_id(pid, *current_wid).unwrap_or(false) {
-                continue;
-            }
+    let matched = match_entries(&editor_positions, &current_ids, &current_paths);
+    let mut unrestored = Vec::new();
+    for (index, &(current_wid, entry)) in matched.iter().enumerate() {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            unrestored = unrestored_ids(&editor_positions, &matched[index..]);
+            log::warn!(
+                "Ran out of time restoring {}; {} windows left",
+                bundle_id,
+                unrestored.len()
+            );
+            break;
+        }
+        let Some(&pid) = window_pids.get(&current_wid) else {
+            continue;
+        };
+        // Check if window is minimized or fullscreen - skip if so
+        if ax_helper::is_window_minimized_by_id(pid, current_wid).unwrap_or(false) {
+            continue;
+        }
+        if ax_helper::is_window_fullscreen_by_id(pid, current_wid).unwrap_or(false) {
+            continue;
+        }
 
-            if let Err(e) = ax_helper::set_window_frame_by_id(
-                pid,
-                *current_wid,
-                original.x,
-                original.y,
-                original.width,
-                original.height,
-            ) {
-                eprintln!("Failed to restore window frame for window_id={}: {}", current_wid, e);
-            }
+        let original = &entry.original;
+        if let Err(e) = ax_helper::set_window_frame_by_id(
+            pid,
+            current_wid,
+            original.x,
+            original.y,
+            original.width,
+            original.height,
+        ) {
+            log::error!("Failed to restore window frame for window_id={}: {}", current_wid, e);
+        }
+    }
+
+    // Clear the restored entries; ones added meanwhile by apply_offset are kept
+    let mut store = OFFSET_STORE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if let Some(positions) = store.positions.get_mut(bundle_id) {
+        positions.retain(|window_id, _| {
+            !editor_positions.contains_key(window_id) || unrestored.contains(window_id)
+        });
+        if positions.is_empty() {
+            store.positions.remove(bundle_id);
         }
     }
+    save_or_delete(&store);
+
+    Ok(())
+}
 
-    // Clear stored positions for this editor
-    store.positions.remove(bundle_id);
+/// Drop an editor's stored positions after it quit. Its windows are gone, and
+/// the editor reopens them wherever it saved them.
+pub fn forget_editor(bundle_id: &str) {
+    let Ok(mut store) = OFFSET_STORE.lock() else {
+        return;
+    };
+    if store.positions.remove(bundle_id).is_some() {
+        save_or_delete(&store);
+    }
+}
 
-    // Update or delete the file
+/// Persist the store, or remove the file once nothing is offset
+fn save_or_delete(store: &OffsetStore) {
     if store.positions.is_empty() {
         delete_offset_file();
-    } else if let Err(e) = save_to_file(&store) {
-        eprintln!("Failed to save offset file: {}", e);
+    } else if let Err(e) = save_to_file(store) {
+        log::error!("Failed to save offset file: {}", e);
+    }
+}
+
+/// Time budget for `restore_all`. On quit it runs on the main thread, and with
+/// several unresponsive editors the per-call AX timeouts alone add up.
+const RESTORE_ALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Restore every editor that currently has offset windows (called on quit).
+/// Entries not reached within `RESTORE_ALL_TIMEOUT` stay in the offsets file
+/// for `restore_after_panic` or the next launch.
+pub fn restore_all() -> Result<(), String> {
+    let deadline = Instant::now() + RESTORE_ALL_TIMEOUT;
+    let bundle_ids: Vec<String> = OFFSET_STORE
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .positions
+        .keys()
+        .cloned()
+        .collect();
+
+    for bundle_id in bundle_ids {
+        if Instant::now() >= deadline {
+            log::warn!("Ran out of time restoring window positions; leaving the rest for later");
+            break;
+        }
+        // 先に終了したエディタは restore_positions 内でエントリだけ削除される
+        if let Err(e) = restore_positions_until(&bundle_id, Some(deadline)) {
+            log::error!("Failed to restore positions for {}: {}", bundle_id, e);
+        }
     }
 
     Ok(())
@@ -318,7 +957,7 @@ pub fn restore_all_pending() -> Result<(), String> {
     if let Some(store) = store {
         for bundle_id in store.positions.keys() {
             if let Err(e) = restore_positions(bundle_id) {
-                eprintln!("Failed to restore positions for {}: {}", bundle_id, e);
+                log::error!("Failed to restore positions for {}: {}", bundle_id, e);
             }
         }
     }
@@ -329,6 +968,31 @@ pub fn restore_all_pending() -> Result<(), String> {
     Ok(())
 }
 
+/// Flush the offsets file and restore windows from a panic hook.
+/// Never blocks on the store lock: the panicking thread may be the one holding it,
+/// in which case the file written by the last successful update is left for the next launch.
+pub fn restore_after_panic() {
+    let store = match OFFSET_STORE.try_lock() {
+        Ok(store) => store,
+        Err(TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(TryLockError::WouldBlock) => {
+            log::warn!("Offset store is locked, leaving restoration to the next launch");
+            return;
+        }
+    };
+    if store.positions.is_empty() {
+        return;
+    }
+    if let Err(e) = save_to_file(&store) {
+        log::error!("Failed to flush offset file: {}", e);
+    }
+    drop(store);
+
+    if let Err(e) = restore_all_pending() {
+        log::error!("Failed to restore window positions after panic: {}", e);
+    }
+}
+
 /// Check if there are any pending restorations
 pub fn has_pending_restorations() -> bool {
     get_offset_file_path().exists()
@@ -337,6 +1001,7 @@ pub fn has_pending_restorations() -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use TabBarPosition::{Bottom, Top};
 
     #[test]
     fn serialize_and_deserialize_offset_store() {
@@ -344,11 +1009,15 @@ mod tests {
         let mut windows = HashMap::new();
         windows.insert(
             12345u32,
-            WindowFrame {
-                x: 0.0,
-                y: 25.0,
-                width: 1920.0,
-                height: 1080.0,
+            OffsetEntry {
+                original: WindowFrame {
+                    x: 0.0,
+                    y: 25.0,
+                    width: 1920.0,
+                    height: 1080.0,
+                },
+                tab_bar_height: Some(36.0),
+                path: Some("/Users/me/project".to_string()),
             },
         );
         store
@@ -359,7 +1028,10 @@ mod tests {
         let deserialized: OffsetStore = serde_json::from_str(&json).unwrap();
 
         let positions = deserialized.positions.get("com.microsoft.VSCode").unwrap();
-        let frame = positions.get(&12345u32).unwrap();
+        let entry = positions.get(&12345u32).unwrap();
+        assert_eq!(entry.tab_bar_height, Some(36.0));
+        assert_eq!(entry.path.as_deref(), Some("/Users/me/project"));
+        let frame = entry.original;
         assert_eq!(frame.x, 0.0);
         assert_eq!(frame.y, 25.0);
         assert_eq!(frame.width, 1920.0);
@@ -367,8 +1039,8 @@ mod tests {
     }
 
     #[test]
-    fn deserialize_old_string_key_format_fails_gracefully() {
-        // 旧フォーマット（Stringキー）のJSONをデシリアライズするとpanicせずエラーになる
+    fn old_string_key_format_is_migrated_best_effort() {
+        // 旧フォーマット（Stringキー）のJSONはエラーにせず、読めるエントリだけ残す
         let json = r#"{
             "positions": {
                 "com.microsoft.VSCode": {
@@ -382,9 +1054,212 @@ mod tests {
             }
         }"#;
 
-        // 数値でないStringキーはu32にデシリアライズできないのでエラーになる
-        let result: Result<OffsetStore, _> = serde_json::from_str(json);
-        assert!(result.is_err());
+        // 数値でないStringキーはウィンドウと対応付けられないので捨てる
+        let store = parse_store(json).unwrap();
+        assert_eq!(store.version, OFFSET_STORE_VERSION);
+        assert!(store.positions.is_empty());
+    }
+
+    #[test]
+    fn mixed_key_format_keeps_readable_entries() {
+        let json = r#"{
+            "positions": {
+                "com.microsoft.VSCode": {
+                    "main.rs — my-project": { "x": 0.0, "y": 25.0, "width": 1920.0, "height": 1080.0 },
+                    "12345": { "x": 0.0, "y": 25.0, "width": 960.0, "height": 1080.0 }
+                }
+            }
+        }"#;
+
+        let store = parse_store(json).unwrap();
+        let windows = &store.positions["com.microsoft.VSCode"];
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[&12345u32].original.width, 960.0);
+    }
+
+    #[test]
+    fn parse_corrupt_file_returns_none() {
+        assert!(parse_store("").is_none());
+        assert!(parse_store(r#"{"positions": {"com.microsoft.VSCode": {"1": {"x": 0.0,"#).is_none());
+        assert!(parse_store("[]").is_none());
+    }
+
+    #[test]
+    fn parse_unversioned_format() {
+        let json = r#"{
+            "positions": {
+                "com.microsoft.VSCode": {
+                    "12345": { "x": 0.0, "y": 25.0, "width": 1920.0, "height": 1080.0 }
+                }
+            }
+        }"#;
+
+        let store = parse_store(json).unwrap();
+        assert_eq!(store.version, OFFSET_STORE_VERSION);
+        assert_eq!(store.positions["com.microsoft.VSCode"][&12345u32].original.y, 25.0);
+    }
+
+    #[test]
+    fn parse_versioned_format() {
+        let mut store = OffsetStore::default();
+        let mut windows = HashMap::new();
+        windows.insert(1u32, entry(WindowFrame { x: 0.0, y: 25.0, width: 800.0, height: 600.0 }));
+        store.positions.insert("dev.zed.Zed".to_string(), windows);
+
+        let json = serde_json::to_string(&store).unwrap();
+        assert!(json.contains(r#""version":1"#));
+
+        let parsed = parse_store(&json).unwrap();
+        assert_eq!(parsed.version, OFFSET_STORE_VERSION);
+        assert_eq!(parsed.positions["dev.zed.Zed"][&1u32].tab_bar_height, Some(36.0));
+    }
+
+    #[test]
+    fn write_atomically_replaces_file_without_leaving_temp() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("offsets.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomically(&path, "{\"version\":1,\"positions\":{}}").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"version\":1,\"positions\":{}}");
+        assert!(!tmp.path().join("offsets.json.tmp").exists());
+    }
+
+    fn entry(original: WindowFrame) -> OffsetEntry {
+        OffsetEntry {
+            original,
+            tab_bar_height: Some(36.0),
+            path: None,
+        }
+    }
+
+    fn entry_with_path(y: f64, path: &str) -> OffsetEntry {
+        OffsetEntry {
+            path: Some(path.to_string()),
+            ..entry(WindowFrame { x: 0.0, y, width: 1440.0, height: 875.0 })
+        }
+    }
+
+    #[test]
+    fn match_entries_prefers_window_id() {
+        let mut entries = HashMap::new();
+        entries.insert(100u32, entry_with_path(25.0, "/a"));
+        let paths = HashMap::from([(100u32, "/b".to_string())]);
+
+        let matched = match_entries(&entries, &[100], &paths);
+        assert_eq!(matched, vec![(100, &entries[&100])]);
+    }
+
+    #[test]
+    fn match_entries_falls_back_to_path_after_restart() {
+        let mut entries = HashMap::new();
+        entries.insert(100u32, entry_with_path(25.0, "/a"));
+        entries.insert(200u32, entry_with_path(40.0, "/b"));
+        entries.insert(300u32, entry(WindowFrame { x: 0.0, y: 25.0, width: 800.0, height: 600.0 }));
+
+        // 再起動後は新しいIDになっている
+        let paths = HashMap::from([
+            (900u32, "/b".to_string()),
+            (901u32, "/a".to_string()),
+            (902u32, "/c".to_string()),
+        ]);
+        let matched = match_entries(&entries, &[900, 901, 902], &paths);
+
+        assert_eq!(matched.len(), 2);
+        assert!(matched.contains(&(901, &entries[&100])));
+        assert!(matched.contains(&(900, &entries[&200])));
+    }
+
+    #[test]
+    fn match_entries_does_not_reuse_a_window() {
+        let mut entries = HashMap::new();
+        entries.insert(100u32, entry_with_path(25.0, "/a"));
+        entries.insert(200u32, entry_with_path(40.0, "/a"));
+
+        // IDで一致したウィンドウはパス照合の候補から外す
+        let paths = HashMap::from([(200u32, "/a".to_string()), (900u32, "/a".to_string())]);
+        let matched = match_entries(&entries, &[200, 900], &paths);
+
+        assert_eq!(matched, vec![(200, &entries[&200]), (900, &entries[&100])]);
+    }
+
+    #[test]
+    fn unrestored_ids_map_back_to_stored_window_ids() {
+        let mut entries = HashMap::new();
+        entries.insert(100u32, entry_with_path(25.0, "/a"));
+        entries.insert(200u32, entry_with_path(40.0, "/b"));
+        entries.insert(300u32, entry_with_path(40.0, "/c"));
+
+        // 再起動後の新しいIDで照合されたエントリも保存時のIDで残す
+        let paths = HashMap::from([(900u32, "/b".to_string())]);
+        let matched = match_entries(&entries, &[100, 900], &paths);
+        assert_eq!(matched, vec![(100, &entries[&100]), (900, &entries[&200])]);
+
+        assert_eq!(unrestored_ids(&entries, &matched[1..]), vec![200]);
+        assert!(unrestored_ids(&entries, &matched[2..]).is_empty());
+    }
+
+    #[test]
+    fn deserialize_entry_without_tab_bar_height() {
+        // tab_bar_height導入前のファイルも復元できる
+        let json = r#"{
+            "positions": {
+                "com.microsoft.VSCode": {
+                    "12345": { "x": 0.0, "y": 25.0, "width": 1920.0, "height": 1080.0 }
+                }
+            }
+        }"#;
+
+        let store: OffsetStore = serde_json::from_str(json).unwrap();
+        let entry = &store.positions["com.microsoft.VSCode"][&12345u32];
+        assert_eq!(entry.original.y, 25.0);
+        assert_eq!(entry.tab_bar_height, None);
+        assert_eq!(entry.path, None);
+    }
+
+    #[test]
+    fn adjusted_frame_follows_tab_bar_height_change() {
+        let current = WindowFrame { x: 0.0, y: 61.0, width: 1440.0, height: 839.0 };
+
+        // 36 -> 28: 上に8px戻して高さを8px増やす
+        assert_eq!(
+            adjusted_frame(&current, 36.0, 28.0, Top),
+            Some(WindowFrame { x: 0.0, y: 53.0, width: 1440.0, height: 847.0 })
+        );
+        // 36 -> 44: 下に8px押し下げて高さを8px減らす
+        assert_eq!(
+            adjusted_frame(&current, 36.0, 44.0, Top),
+            Some(WindowFrame { x: 0.0, y: 69.0, width: 1440.0, height: 831.0 })
+        );
+        // 変化なし
+        assert_eq!(adjusted_frame(&current, 36.0, 36.0, Top), None);
+    }
+
+    #[test]
+    fn adjusted_frame_keeps_minimum_height() {
+        let current = WindowFrame { x: 0.0, y: 61.0, width: 800.0, height: 120.0 };
+        assert_eq!(adjusted_frame(&current, 36.0, 60.0, Top), None);
+    }
+
+    #[test]
+    fn bottom_tab_bar_shrinks_windows_from_the_bottom() {
+        let current = WindowFrame { x: 0.0, y: 25.0, width: 1440.0, height: 839.0 };
+        // 36 -> 28: 位置はそのままで高さを8px増やす
+        assert_eq!(
+            adjusted_frame(&current, 36.0, 28.0, Bottom),
+            Some(WindowFrame { x: 0.0, y: 25.0, width: 1440.0, height: 847.0 })
+        );
+
+        let window = WindowFrame { x: 0.0, y: 25.0, width: 1440.0, height: 875.0 };
+        assert_eq!(
+            offset_frame(&window, Bottom, 36.0),
+            WindowFrame { x: 0.0, y: 25.0, width: 1440.0, height: 839.0 }
+        );
+        assert_eq!(
+            offset_frame(&window, Top, 36.0),
+            WindowFrame { x: 0.0, y: 61.0, width: 1440.0, height: 839.0 }
+        );
     }
 
     #[test]
@@ -394,18 +1269,18 @@ mod tests {
         let mut vscode_windows = HashMap::new();
         vscode_windows.insert(
             100u32,
-            WindowFrame { x: 0.0, y: 25.0, width: 960.0, height: 1080.0 },
+            entry(WindowFrame { x: 0.0, y: 25.0, width: 960.0, height: 1080.0 }),
         );
         vscode_windows.insert(
             200u32,
-            WindowFrame { x: 960.0, y: 25.0, width: 960.0, height: 1080.0 },
+            entry(WindowFrame { x: 960.0, y: 25.0, width: 960.0, height: 1080.0 }),
         );
         store.positions.insert("com.microsoft.VSCode".to_string(), vscode_windows);
 
         let mut cursor_windows = HashMap::new();
         cursor_windows.insert(
             300u32,
-            WindowFrame { x: 0.0, y: 25.0, width: 1920.0, height: 1080.0 },
+            entry(WindowFrame { x: 0.0, y: 25.0, width: 1920.0, height: 1080.0 }),
         );
         store.positions.insert("com.todesktop.230313mzl4w4u92".to_string(), cursor_windows);
 
@@ -415,8 +1290,231 @@ mod tests {
         assert_eq!(deserialized.positions.len(), 2);
         assert_eq!(deserialized.positions.get("com.microsoft.VSCode").unwrap().len(), 2);
         assert_eq!(
-            deserialized.positions.get("com.todesktop.230313mzl4w4u92").unwrap().get(&300u32).unwrap().width,
+            deserialized.positions.get("com.todesktop.230313mzl4w4u92").unwrap().get(&300u32).unwrap().original.width,
             1920.0
         );
     }
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> WindowFrame {
+        WindowFrame {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn display(id: u32, x: f64, y: f64, width: f64, height: f64, menu: f64) -> DisplayFrame {
+        DisplayFrame {
+            display_id: id,
+            x,
+            y,
+            width,
+            height,
+            menu_bar_height: menu,
+            notch_height: 0.0,
+            menu_bar_auto_hidden: false,
+            usable: WindowFrame { x, y: y + menu, width, height: height - menu },
+            dock: DockPosition::Hidden,
+        }
+    }
+
+    /// Overlap of an 800x600 window at (x, y) with a 36px tab bar at the top
+    fn top_overlap(display: &DisplayFrame, x: f64, y: f64) -> Option<f64> {
+        required_offset(display, Top, 36.0, &rect(x, y, 800.0, 600.0))
+    }
+
+    #[test]
+    fn primary_display_frame_starts_at_origin() {
+        // 1440x900, メニューバー25px, Dock下部70px
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 70.0, 1440.0, 805.0),
+            900.0,
+        );
+        assert_eq!(display.x, 0.0);
+        assert_eq!(display.y, 0.0);
+        assert_eq!(display.menu_bar_height, 25.0);
+    }
+
+    #[test]
+    fn secondary_display_to_the_right_keeps_primary_origin() {
+        // 外部ディスプレイ (1920x1080) をプライマリ (1440x900) の右に下端揃えで配置
+        // Cocoa座標: origin (1440, 0) → AX座標: 上端はプライマリ上端より180px上
+        let display = ax_display_frame(
+            2,
+            rect(1440.0, 0.0, 1920.0, 1080.0),
+            rect(1440.0, 0.0, 1920.0, 1055.0),
+            900.0,
+        );
+        assert_eq!(display.x, 1440.0);
+        assert_eq!(display.y, -180.0);
+        assert_eq!(display.menu_bar_height, 25.0);
+    }
+
+    #[test]
+    fn secondary_display_above_primary_has_negative_y() {
+        let display = ax_display_frame(
+            3,
+            rect(0.0, 900.0, 2560.0, 1440.0),
+            rect(0.0, 900.0, 2560.0, 1415.0),
+            900.0,
+        );
+        assert_eq!(display.y, -1440.0);
+        assert!(display.contains(100.0, -1000.0));
+        assert!(!display.contains(100.0, 10.0));
+    }
+
+    #[test]
+    fn window_on_secondary_display_is_offset_below_its_menu_bar() {
+        let display = display(2, 1440.0, -180.0, 1920.0, 1080.0, 25.0);
+        // ウィンドウ上端がメニューバー直下 (-155) → タブバー高さ36px分だけ移動
+        assert_eq!(top_overlap(&display, 1500.0, -155.0), Some(36.0));
+        // 既にタブバー下端より下
+        assert_eq!(top_overlap(&display, 1500.0, -119.0), None);
+    }
+
+    #[test]
+    fn windows_on_other_displays_are_skipped() {
+        let secondary = display(2, 1440.0, -180.0, 1920.0, 1080.0, 25.0);
+        // プライマリ上のウィンドウはセカンダリのタブバーとは無関係
+        assert_eq!(top_overlap(&secondary, 0.0, 25.0), None);
+
+        let primary = display(1, 0.0, 0.0, 1440.0, 900.0, 25.0);
+        assert_eq!(top_overlap(&primary, 0.0, 25.0), Some(36.0));
+        assert_eq!(top_overlap(&primary, 1500.0, -155.0), None);
+    }
+
+    #[test]
+    fn dock_at_bottom_does_not_leak_into_menu_bar_height() {
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 70.0, 1440.0, 805.0),
+            900.0,
+        );
+        assert_eq!(display.dock, DockPosition::Bottom);
+        assert_eq!(display.menu_bar_height, 25.0);
+        assert_eq!(display.usable, WindowFrame { x: 0.0, y: 25.0, width: 1440.0, height: 805.0 });
+    }
+
+    #[test]
+    fn bottom_tab_bar_sits_above_the_dock() {
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 70.0, 1440.0, 805.0),
+            900.0,
+        );
+        // Dock の上端 (830) からタブバー分 (36) に食い込む分だけ縮める
+        let maximized = rect(0.0, 25.0, 1440.0, 805.0);
+        assert_eq!(required_offset(&display, Bottom, 36.0, &maximized), Some(36.0));
+        let short = rect(0.0, 100.0, 800.0, 600.0);
+        assert_eq!(required_offset(&display, Bottom, 36.0, &short), None);
+        let elsewhere = rect(1500.0, 25.0, 800.0, 900.0);
+        assert_eq!(required_offset(&display, Bottom, 36.0, &elsewhere), None);
+        assert_eq!(maximize_frame_for(&display, 36.0, Bottom), (0.0, 25.0, 1440.0, 769.0));
+    }
+
+    #[test]
+    fn dock_on_left_shifts_usable_origin() {
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(64.0, 0.0, 1376.0, 875.0),
+            900.0,
+        );
+        assert_eq!(display.dock, DockPosition::Left);
+        assert_eq!(display.menu_bar_height, 25.0);
+        assert_eq!(display.usable, WindowFrame { x: 64.0, y: 25.0, width: 1376.0, height: 875.0 });
+        assert_eq!(maximize_frame_for(&display, 36.0, Top), (64.0, 61.0, 1376.0, 839.0));
+    }
+
+    #[test]
+    fn dock_on_right_keeps_usable_origin() {
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 0.0, 1376.0, 875.0),
+            900.0,
+        );
+        assert_eq!(display.dock, DockPosition::Right);
+        assert_eq!(display.menu_bar_height, 25.0);
+        assert_eq!(display.usable, WindowFrame { x: 0.0, y: 25.0, width: 1376.0, height: 875.0 });
+    }
+
+    #[test]
+    fn auto_hidden_dock_reserves_only_a_sliver() {
+        // 自動非表示のDockでもvisibleFrameの下端に4pxが確保される
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 4.0, 1440.0, 871.0),
+            900.0,
+        );
+        assert_eq!(display.dock, DockPosition::Hidden);
+        assert_eq!(display.menu_bar_height, 25.0);
+        assert_eq!(maximize_frame_for(&display, 36.0, Top), (0.0, 61.0, 1440.0, 835.0));
+    }
+
+    #[test]
+    fn notch_menu_bar_is_measured_from_top_edge() {
+        // Notch付きMac: メニューバー37px、Dock下部
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1512.0, 982.0),
+            rect(0.0, 80.0, 1512.0, 865.0),
+            982.0,
+        );
+        assert_eq!(display.menu_bar_height, 37.0);
+        assert_eq!(display.usable.y, 37.0);
+    }
+
+    #[test]
+    fn auto_hidden_menu_bar_keeps_reserved_height() {
+        // メニューバー自動非表示: visibleFrameはディスプレイ上端まで広がる
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 70.0, 1440.0, 830.0),
+            900.0,
+        );
+        assert_eq!(display.menu_bar_height, 0.0);
+
+        let metrics = MenuBarMetrics { auto_hidden: true, notch_height: 0.0, thickness: 24.0 };
+        let display = with_menu_bar_metrics(display, metrics);
+        assert!(display.menu_bar_auto_hidden);
+        assert_eq!(display.menu_bar_height, 24.0);
+        assert_eq!(display.usable, WindowFrame { x: 0.0, y: 24.0, width: 1440.0, height: 806.0 });
+        assert_eq!(top_overlap(&display, 0.0, 0.0), Some(60.0));
+        assert_eq!(maximize_frame_for(&display, 36.0, Top), (0.0, 60.0, 1440.0, 770.0));
+    }
+
+    #[test]
+    fn auto_hidden_menu_bar_on_notch_display_reserves_notch() {
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1512.0, 982.0),
+            rect(0.0, 0.0, 1512.0, 982.0),
+            982.0,
+        );
+        let metrics = MenuBarMetrics { auto_hidden: true, notch_height: 32.0, thickness: 24.0 };
+        let display = with_menu_bar_metrics(display, metrics);
+        assert_eq!(display.notch_height, 32.0);
+        assert_eq!(display.menu_bar_height, 32.0);
+        assert_eq!(display.usable.y, 32.0);
+    }
+
+    #[test]
+    fn visible_menu_bar_is_left_untouched() {
+        let display = ax_display_frame(
+            1,
+            rect(0.0, 0.0, 1440.0, 900.0),
+            rect(0.0, 70.0, 1440.0, 805.0),
+            900.0,
+        );
+        let metrics = MenuBarMetrics { auto_hidden: false, notch_height: 0.0, thickness: 24.0 };
+        assert_eq!(with_menu_bar_metrics(display, metrics), display);
+    }
 }
@@ -0,0 +1,224 @@
+//! Sort orders for the window-list commands
+//!
+//! `get_editor_windows` and `get_editor_state` take an optional `order`. The
+//! windows are assembled and filtered as before, then sorted here as a last
+//! step; without an order they stay in AX order. "custom" reads the tab order
+//! the frontend saves in the store and sorts the way the tab bar does.
+
+use crate::editor_model::{EditorState, EditorWindow};
+use crate::settings_store::StoredSettings;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+use tauri::AppHandle;
+
+/// Tab order saved by the frontend (src/utils/store.ts UNIFIED_ORDER_KEY)
+const UNIFIED_ORDER_KEY: &str = "order:unified";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowOrder {
+    /// Order the editors' accessibility API reports (the default)
+    Ax,
+    /// Project name, case-insensitive
+    Name,
+    /// Most recently focused first
+    Mru,
+    /// The user's drag-and-drop tab order
+    Custom,
+}
+
+impl FromStr for WindowOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ax" => Ok(Self::Ax),
+            "name" => Ok(Self::Name),
+            "mru" => Ok(Self::Mru),
+            "custom" => Ok(Self::Custom),
+            _ => Err(format!(
+                "Unknown window order: {} (expected name, mru, ax or custom)",
+                s
+            )),
+        }
+    }
+}
+
+/// Parse the `order` command argument; None keeps the AX order
+pub fn parse(order: Option<&str>) -> Result<WindowOrder, String> {
+    order.map_or(Ok(WindowOrder::Ax), str::parse)
+}
+
+fn normalize_path(path: &str) -> &str {
+    if path.len() > 1 {
+        path.trim_end_matches('/')
+    } else {
+        path
+    }
+}
+
+/// Same key as `windowKey` in the frontend
+fn window_key(window: &EditorWindow) -> String {
+    if window.path.is_empty() {
+        let runtime = if window.runtime_id.is_empty() {
+            format!("{}:{}", window.bundle_id, window.id)
+        } else {
+            window.runtime_id.clone()
+        };
+        format!("{}:runtime:{}", window.bundle_id, runtime)
+    } else {
+        format!("{}:{}", window.bundle_id, normalize_path(&window.path))
+    }
+}
+
+/// Key used before paths were resolved (`legacyWindowKey` in the frontend)
+fn legacy_window_key(window: &EditorWindow) -> String {
+    format!("{}:{}", window.bundle_id, window.name)
+}
+
+fn by_name(a: &EditorWindow, b: &EditorWindow) -> Ordering {
+    a.name
+        .to_lowercase()
+        .cmp(&b.name.to_lowercase())
+        .then_with(|| window_key(a).cmp(&window_key(b)))
+}
+
+fn sort_by_name(windows: &mut [EditorWindow]) {
+    windows.sort_by(by_name);
+}
+
+/// Windows never focused keep their AX order after the focused ones
+fn sort_by_recency(windows: &mut [EditorWindow], recent: &[(String, u32)]) {
+    windows.sort_by_key(|window| {
+        recent
+            .iter()
+            .position(|(bundle_id, id)| *bundle_id == window.bundle_id && *id == window.id)
+            .unwrap_or(usize::MAX)
+    });
+}
+
+/// Mirrors `sortWindowsByOrder`: windows missing from the saved order go last,
+/// by name
+fn sort_by_custom_order(windows: &mut [EditorWindow], order: &[String]) {
+    let positions: HashMap<&str, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (key.as_str(), index))
+        .collect();
+    let position = |window: &EditorWindow| {
+        positions
+            .get(window_key(window).as_str())
+            .or_else(|| positions.get(legacy_window_key(window).as_str()))
+            .copied()
+    };
+    windows.sort_by(|a, b| match (position(a), position(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => by_name(a, b),
+    });
+}
+
+fn stored_custom_order(app: &AppHandle) -> Vec<String> {
+    StoredSettings::from_app(app)
+        .get(UNIFIED_ORDER_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Sort assembled windows
+pub fn sort_windows(app: &AppHandle, windows: &mut [EditorWindow], order: WindowOrder) {
+    match order {
+        WindowOrder::Ax => {}
+        WindowOrder::Name => sort_by_name(windows),
+        WindowOrder::Mru => sort_by_recency(windows, &crate::focus_history::recent_windows()),
+        WindowOrder::Custom => sort_by_custom_order(windows, &stored_custom_order(app)),
+    }
+}
+
+/// Sort the state's windows, keeping `active_index` on the same window
+pub fn sort_state(app: &AppHandle, mut state: EditorState, order: WindowOrder) -> EditorState {
+    let active_id = state
+        .active_index
+        .and_then(|index| state.windows.get(index))
+        .map(|window| window.id);
+    sort_windows(app, &mut state.windows, order);
+    state.active_index = active_id
+        .and_then(|active_id| state.windows.iter().position(|window| window.id == active_id));
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(bundle_id: &str, id: u32, name: &str, path: &str) -> EditorWindow {
+        EditorWindow {
+            name: name.to_string(),
+            ..EditorWindow::for_test(bundle_id, id, path)
+        }
+    }
+
+    fn ids(windows: &[EditorWindow]) -> Vec<u32> {
+        windows.iter().map(|window| window.id).collect()
+    }
+
+    #[test]
+    fn orders_parse_and_unknown_ones_are_errors() {
+        assert_eq!(parse(None), Ok(WindowOrder::Ax));
+        assert_eq!(parse(Some("mru")), Ok(WindowOrder::Mru));
+        assert_eq!(parse(Some("custom")), Ok(WindowOrder::Custom));
+        assert!(parse(Some("alphabetical")).unwrap_err().contains("alphabetical"));
+        assert!(parse(Some("")).is_err());
+    }
+
+    #[test]
+    fn names_sort_case_insensitively() {
+        let mut windows = vec![
+            window("code", 1, "web", "/b/web"),
+            window("code", 2, "Api", "/api"),
+            window("code", 3, "web", "/a/web"),
+        ];
+        sort_by_name(&mut windows);
+        assert_eq!(ids(&windows), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn recently_focused_windows_come_first() {
+        let mut windows = vec![
+            window("code", 1, "a", "/a"),
+            window("code", 2, "b", "/b"),
+            window("zed", 2, "c", "/c"),
+            window("code", 4, "d", "/d"),
+        ];
+        let recent = vec![("zed".to_string(), 2), ("code".to_string(), 4)];
+        sort_by_recency(&mut windows, &recent);
+        assert_eq!(ids(&windows), vec![2, 4, 1, 2]);
+        assert_eq!(windows[0].bundle_id, "zed");
+    }
+
+    #[test]
+    fn custom_order_matches_the_tab_bar() {
+        let mut windows = vec![
+            window("code", 1, "zeta", "/zeta"),
+            window("code", 2, "alpha", "/alpha"),
+            window("code", 3, "legacy", ""),
+            window("code", 4, "beta", "/beta/"),
+        ];
+        let order = vec![
+            "code:/beta".to_string(),
+            "code:legacy".to_string(),
+            "code:/zeta".to_string(),
+        ];
+        sort_by_custom_order(&mut windows, &order);
+        assert_eq!(ids(&windows), vec![4, 3, 1, 2]);
+    }
+
+    #[test]
+    fn unresolved_windows_use_the_runtime_key() {
+        let mut unresolved = window("code", 7, "x", "");
+        assert_eq!(window_key(&unresolved), "code:runtime:code:7");
+        unresolved.runtime_id = "code:42:7".to_string();
+        assert_eq!(window_key(&unresolved), "code:runtime:code:42:7");
+    }
+}
@@ -2,8 +2,10 @@
 //!
 //! AX events, app activation, and startup all funnel through `request_refresh()`.
 //! The registry pulls a fresh list via `editor::get_all_editor_window_snapshot()`, diffs
-//! against the last snapshot, and emits `windows:snapshot` only when something
-//! actually changed.
+//! against the last snapshot, and emits `windows-diff` (added / removed / updated
+//! windows, keyed on window id) only when something actually changed. The full
+//! `windows:snapshot` event is still sent when the legacy window events setting
+//! is on.
 //!
 //! The registry also owns the "no windows yet" retries:
 //! - **transient-empty retry**: if AX returns empty while editors are still
@@ -13,13 +15,18 @@
 
 use crate::editor::EditorWindow;
 use crate::editor_config::EDITORS;
+use crate::settings_store::{StoredSettings, STORE_FILE};
 use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Mutex;
 use std::thread;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter, Manager};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+pub(crate) const LEGACY_EVENTS_KEY: &str = "settings:legacyWindowEvents";
 
 #[derive(Debug, Clone, Serialize)]
 pub struct WindowsSnapshot {
@@ -29,15 +36,52 @@ pub struct WindowsSnapshot {
     pub source: String,
 }
 
+/// What changed since the previous revision. Applying it to the windows of
+/// revision `revision - 1` gives the registry's current list.
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowsDiff {
+    pub revision: u64,
+    pub added: Vec<EditorWindow>,
+    /// Ids of windows that are gone
+    pub removed: Vec<u32>,
+    pub updated: Vec<EditorWindow>,
+    pub active_window_id: Option<u32>,
+}
+
+/// Work queued for the refresh worker
+enum RefreshRequest {
+    /// Re-query every editor
+    All(String),
+    /// Re-resolve one window whose title changed
+    Window { bundle_id: String, window_id: u32 },
+}
+
+/// Resolves single windows for the registry (the editor module; mocked in tests)
+trait WindowProvider {
+    /// The window re-resolved from its current title; None when it is gone or
+    /// no longer listed
+    fn window(&self, bundle_id: &str, window_id: u32) -> Option<EditorWindow>;
+}
+
+struct EditorWindows;
+
+impl WindowProvider for EditorWindows {
+    fn window(&self, bundle_id: &str, window_id: u32) -> Option<EditorWindow> {
+        crate::editor::resolve_editor_window(bundle_id, window_id)
+    }
+}
+
 struct RegistryState {
     revision: u64,
     windows: Vec<EditorWindow>,
     active_id: Option<u32>,
     /// Last-seen PID per editor_id. Used to detect editor restarts so we can
     /// invalidate editor.rs's workspace.json cache for the restarted editor.
-    editor_pids: HashMap<String, i32>,
+    editor_pids: HashMap<String, Vec<i32>>,
     app_handle: Option<AppHandle>,
-    refresh_tx: Option<Sender<String>>,
+    refresh_tx: Option<Sender<RefreshRequest>>,
+    /// Emit the next snapshot even if it matches the cached one (after wake)
+    force_emit: bool,
 }
 
 lazy_static::lazy_static! {
@@ -48,16 +92,23 @@ lazy_static::lazy_static! {
         editor_pids: HashMap::new(),
         app_handle: None,
         refresh_tx: None,
+        force_emit: false,
     });
 }
 
+/// Also send the full `windows:snapshot` and the raw `windows-changed` AX events
+static LEGACY_EVENTS: AtomicBool = AtomicBool::new(false);
+
+/// Editors that launched and haven't shown a window yet (editor-launched is sent then)
+static PENDING_LAUNCHES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
 const COLD_START_RETRIES: u32 = 6;
 const COLD_START_INTERVAL_MS: u64 = 500;
 const TRANSIENT_EMPTY_RECHECK_MS: u64 = 150;
 
 /// Initialize the registry with the Tauri AppHandle. Called once at startup.
 pub fn init(app_handle: AppHandle) {
-    let (refresh_tx, refresh_rx) = mpsc::channel::<String>();
+    let (refresh_tx, refresh_rx) = mpsc::channel::<RefreshRequest>();
     {
         let mut state = REGISTRY.lock().expect("registry mutex poisoned");
         state.app_handle = Some(app_handle);
@@ -66,11 +117,31 @@ pub fn init(app_handle: AppHandle) {
     thread::spawn(move || run_refresh_worker(refresh_rx));
 }
 
-fn run_refresh_worker(refresh_rx: Receiver<String>) {
-    while let Ok(mut source) = refresh_rx.recv() {
-        while let Ok(next_source) = refresh_rx.try_recv() {
-            source = next_source;
+fn run_refresh_worker(refresh_rx: Receiver<RefreshRequest>) {
+    while let Ok(request) = refresh_rx.recv() {
+        let mut full_source = None;
+        let mut changed_windows: Vec<(String, u32)> = Vec::new();
+        for request in std::iter::once(request).chain(refresh_rx.try_iter()) {
+            match request {
+                RefreshRequest::All(source) => full_source = Some(source),
+                RefreshRequest::Window {
+                    bundle_id,
+                    window_id,
+                } => {
+                    let target = (bundle_id, window_id);
+                    if !changed_windows.contains(&target) {
+                        changed_windows.push(target);
+                    }
+                }
+            }
         }
+        // 全体を取り直すなら個別のウィンドウは解決し直さない
+        let Some(mut source) = full_source else {
+            for (bundle_id, window_id) in &changed_windows {
+                refresh_window_sync(bundle_id, *window_id);
+            }
+            continue;
+        };
         refresh_sync(&source);
 
         if has_current_windows() || !any_editor_running() {
@@ -82,8 +153,10 @@ fn run_refresh_worker(refresh_rx: Receiver<String>) {
                 break;
             }
             source = "cold-start-retry".to_string();
-            while let Ok(next_source) = refresh_rx.try_recv() {
-                source = next_source;
+            for request in refresh_rx.try_iter() {
+                if let RefreshRequest::All(next_source) = request {
+                    source = next_source;
+                }
             }
             refresh_sync(&source);
             if has_current_windows() {
@@ -104,20 +177,92 @@ pub fn snapshot() -> WindowsSnapshot {
     }
 }
 
+/// Send editor-launched for `bundle_id` once its first window is in a snapshot
+pub fn expect_launch(bundle_id: &str) {
+    if let Ok(mut pending) = PENDING_LAUNCHES.lock() {
+        if !pending.iter().any(|pending_id| pending_id == bundle_id) {
+            pending.push(bundle_id.to_string());
+        }
+    }
+}
+
+/// The editor quit before showing a window
+pub fn forget_launch(bundle_id: &str) {
+    if let Ok(mut pending) = PENDING_LAUNCHES.lock() {
+        pending.retain(|pending_id| pending_id != bundle_id);
+    }
+}
+
+/// An editor launched by us has not shown its first window yet
+pub fn launch_pending() -> bool {
+    PENDING_LAUNCHES
+        .lock()
+        .map(|pending| !pending.is_empty())
+        .unwrap_or(false)
+}
+
+/// Remove and return the pending launches that now have windows
+fn take_launched(pending: &mut Vec<String>, windows: &[EditorWindow]) -> Vec<String> {
+    let (launched, waiting): (Vec<String>, Vec<String>) = pending
+        .drain(..)
+        .partition(|bundle_id| windows.iter().any(|window| &window.bundle_id == bundle_id));
+    *pending = waiting;
+    launched
+}
+
+/// Path of a window in the cached snapshot, if it has been resolved
+pub fn window_path(bundle_id: &str, window_id: u32) -> Option<String> {
+    let state = REGISTRY.lock().ok()?;
+    state
+        .windows
+        .iter()
+        .find(|window| window.bundle_id == bundle_id && window.id == window_id)
+        .map(|window| window.path.clone())
+        .filter(|path| !path.is_empty())
+}
+
 /// Request an async refresh. The AX query + diff + emit runs on a background
 /// thread so callers (main thread AX observer callbacks, notification blocks)
 /// do not block. Cold-start retries run on that same worker.
 pub fn request_refresh(source: &str) {
+    send_request(RefreshRequest::All(source.to_string()));
+}
+
+/// Request an async re-resolve of one window whose title changed. The rest
+/// of the cached list is kept, so typing in one project doesn't read the git
+/// state of every other window.
+pub fn request_window_refresh(bundle_id: &str, window_id: u32) {
+    send_request(RefreshRequest::Window {
+        bundle_id: bundle_id.to_string(),
+        window_id,
+    });
+}
+
+fn send_request(request: RefreshRequest) {
     let refresh_tx = REGISTRY
         .lock()
         .expect("registry mutex poisoned")
         .refresh_tx
         .clone();
     if let Some(refresh_tx) = refresh_tx {
-        let _ = refresh_tx.send(source.to_string());
+        let _ = refresh_tx.send(request);
     }
 }
 
+/// Drop every editor's workspace path cache and request a refresh whose
+/// snapshot is emitted even if unchanged. Used after sleep/wake, when editors
+/// may have rebuilt their windows behind the cached state.
+pub fn request_full_refresh(source: &str) {
+    {
+        let mut state = REGISTRY.lock().expect("registry mutex poisoned");
+        state.force_emit = true;
+    }
+    for editor in EDITORS {
+        crate::editor::invalidate_path_cache_for_editor(editor.id);
+    }
+    request_refresh(source);
+}
+
 /// Synchronous refresh. Runs the AX query on the calling thread. Returns true
 /// if the cached snapshot was updated.
 pub fn refresh_sync(source: &str) -> bool {
@@ -126,6 +271,7 @@ pub fn refresh_sync(source: &str) -> bool {
     reconcile_editor_pids();
 
     let (new_windows, new_active) = crate::editor::get_all_editor_window_snapshot();
+    let new_windows = keep_hidden_editor_windows(new_windows);
 
     // Transient-empty guard: if AX returned no windows but we previously had
     // some and an editor is still running, treat this as a flicker and re-
@@ -133,6 +279,7 @@ pub fn refresh_sync(source: &str) -> bool {
     if new_windows.is_empty() && has_current_windows() && any_editor_running() {
         thread::sleep(Duration::from_millis(TRANSIENT_EMPTY_RECHECK_MS));
         let (rechecked, rechecked_active) = crate::editor::get_all_editor_window_snapshot();
+        let rechecked = keep_hidden_editor_windows(rechecked);
         if !rechecked.is_empty() {
             return apply_snapshot(rechecked, rechecked_active, source);
         }
@@ -141,37 +288,196 @@ pub fn refresh_sync(source: &str) -> bool {
     apply_snapshot(new_windows, new_active, source)
 }
 
+/// Re-resolve one window and keep the rest of the cached list. A window the
+/// cache doesn't know yet gets a full refresh instead.
+fn refresh_window_sync(bundle_id: &str, window_id: u32) -> bool {
+    let (cached, active_id) = {
+        let state = REGISTRY.lock().expect("registry mutex poisoned");
+        (state.windows.clone(), state.active_id)
+    };
+    match with_window_refreshed(&EditorWindows, &cached, bundle_id, window_id) {
+        Some(windows) => apply_snapshot(windows, active_id, "ax-title"),
+        None => refresh_sync("ax-title"),
+    }
+}
+
+/// `cached` with one window replaced by its re-resolved version. None when the
+/// window is new to the cache, gone, or no longer listed.
+fn with_window_refreshed(
+    provider: &impl WindowProvider,
+    cached: &[EditorWindow],
+    bundle_id: &str,
+    window_id: u32,
+) -> Option<Vec<EditorWindow>> {
+    let index = cached
+        .iter()
+        .position(|window| window.bundle_id == bundle_id && window.id == window_id)?;
+    let mut refreshed = provider.window(bundle_id, window_id)?;
+    // 非表示の印は一覧全体の更新でしか付け直さないので引き継ぐ
+    refreshed.hidden = cached[index].hidden;
+    let mut windows = cached.to_vec();
+    windows[index] = refreshed;
+    Some(windows)
+}
+
+/// Hidden editors (Cmd+H) report few or no windows through AX. Keep their
+/// last known windows, flagged hidden, so their tabs stay clickable.
+fn keep_hidden_editor_windows(new_windows: Vec<EditorWindow>) -> Vec<EditorWindow> {
+    let hidden_bundle_ids: Vec<&str> = crate::editor_config::enabled_editors()
+        .map(|editor| editor.bundle_id)
+        .filter(|bundle_id| crate::ax_helper::is_app_hidden(bundle_id))
+        .collect();
+    if hidden_bundle_ids.is_empty() {
+        return new_windows;
+    }
+    let previous = REGISTRY
+        .lock()
+        .expect("registry mutex poisoned")
+        .windows
+        .clone();
+    merge_hidden_windows(&previous, new_windows, &hidden_bundle_ids)
+}
+
+fn merge_hidden_windows(
+    previous: &[EditorWindow],
+    mut windows: Vec<EditorWindow>,
+    hidden_bundle_ids: &[&str],
+) -> Vec<EditorWindow> {
+    for bundle_id in hidden_bundle_ids {
+        let reported = windows.iter().any(|window| window.bundle_id == *bundle_id);
+        if reported {
+            for window in windows.iter_mut().filter(|w| w.bundle_id == *bundle_id) {
+                window.hidden = true;
+            }
+            continue;
+        }
+        windows.extend(
+            previous
+                .iter()
+                .filter(|window| window.bundle_id == *bundle_id)
+                .cloned()
+                .map(|mut window| {
+                    window.hidden = true;
+                    window
+                }),
+        );
+    }
+    windows
+}
+
 fn apply_snapshot(
     new_windows: Vec<EditorWindow>,
     new_active_id: Option<u32>,
     source: &str,
 ) -> bool {
-    let (app_handle, revision) = {
+    let (app_handle, revision, previous) = {
         let mut state = REGISTRY.lock().expect("registry mutex poisoned");
-        if !windows_differ(&state.windows, &new_windows) && state.active_id == new_active_id {
+        if !state.force_emit
+            && !windows_differ(&state.windows, &new_windows)
+            && state.active_id == new_active_id
+        {
             return false;
         }
+        state.force_emit = false;
         state.revision = state.revision.wrapping_add(1);
-        state.windows = new_windows.clone();
+        let previous = std::mem::replace(&mut state.windows, new_windows.clone());
         state.active_id = new_active_id;
-        (state.app_handle.clone(), state.revision)
+        (state.app_handle.clone(), state.revision, previous)
     };
+    crate::focus_history::update_paths(&new_windows);
+    let launched = PENDING_LAUNCHES
+        .lock()
+        .map(|mut pending| take_launched(&mut pending, &new_windows))
+        .unwrap_or_default();
 
     if let Some(handle) = app_handle {
-        if let Some(window) = handle.get_webview_window("main") {
+        crate::recently_closed::record_snapshot(&handle, &previous, &new_windows);
+        // 差分はキャッシュ更新後に送るので、受け取った側が取り直しても同じ内容になる
+        let diff = diff_windows(&previous, &new_windows, revision, new_active_id);
+        crate::tab_bars::emit_to_tab_bars(&handle, "windows-diff", diff);
+        if legacy_events_enabled() {
             let payload = WindowsSnapshot {
                 revision,
                 windows: new_windows,
                 active_id: new_active_id,
                 source: source.to_string(),
             };
-            let _ = window.emit("windows:snapshot", payload);
+            crate::tab_bars::emit_to_tab_bars(&handle, "windows:snapshot", payload);
+        }
+        crate::tray::schedule_rebuild(&handle);
+        crate::tray::update_title(&handle);
+        for bundle_id in launched {
+            crate::tab_bars::emit_to_tab_bars(
+                &handle,
+                "editor-launched",
+                crate::editor_model::EditorLaunchedPayload { bundle_id },
+            );
         }
     }
 
     true
 }
 
+/// Windows added, removed or changed between two snapshots, keyed on window id
+fn diff_windows(
+    previous: &[EditorWindow],
+    current: &[EditorWindow],
+    revision: u64,
+    active_window_id: Option<u32>,
+) -> WindowsDiff {
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for window in current {
+        match previous.iter().find(|old| old.id == window.id) {
+            None => added.push(window.clone()),
+            Some(old) if window_changed(old, window) => updated.push(window.clone()),
+            Some(_) => {}
+        }
+    }
+    let removed = previous
+        .iter()
+        .filter(|old| !current.iter().any(|window| window.id == old.id))
+        .map(|old| old.id)
+        .collect();
+    WindowsDiff {
+        revision,
+        added,
+        removed,
+        updated,
+        active_window_id,
+    }
+}
+
+/// Whether the full snapshot events are sent alongside `windows-diff`
+pub fn legacy_events_enabled() -> bool {
+    LEGACY_EVENTS.load(Ordering::SeqCst)
+}
+
+/// Load the saved setting (setup, settings import)
+pub fn load_settings(stored: &StoredSettings) {
+    let enabled = stored
+        .get(LEGACY_EVENTS_KEY)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    LEGACY_EVENTS.store(enabled, Ordering::SeqCst);
+}
+
+/// Tauri command: whether the legacy window events are sent
+#[tauri::command]
+pub fn get_legacy_window_events() -> bool {
+    legacy_events_enabled()
+}
+
+/// Tauri command: persist the legacy window events setting
+#[tauri::command]
+pub fn set_legacy_window_events(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(LEGACY_EVENTS_KEY, enabled);
+    store.save().map_err(|e| e.to_string())?;
+    LEGACY_EVENTS.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
+
 fn has_current_windows() -> bool {
     !REGISTRY
         .lock()
@@ -181,42 +487,42 @@ fn has_current_windows() -> bool {
 }
 
 fn any_editor_running() -> bool {
-    EDITORS
-        .iter()
+    crate::editor_config::enabled_editors()
         .any(|e| crate::ax_helper::get_pid_by_bundle_id(e.bundle_id).is_some())
 }
 
-/// Compare each editor's current PID against the last-seen value. When the PID
-/// changed (start, restart, or exit) invalidate that editor's path cache so a
+/// Compare each editor's current PIDs against the last-seen ones. When they
+/// changed (start, restart, second instance, or exit) invalidate that editor's path cache so a
 /// fresh workspace.json read picks up any projects added/removed while the
 /// editor was closed.
 fn reconcile_editor_pids() {
     let mut changed: Vec<String> = Vec::new();
-    {
+    let any_running = {
         let mut state = REGISTRY.lock().expect("registry mutex poisoned");
         for editor in EDITORS {
-            let new_pid = crate::ax_helper::get_pid_by_bundle_id(editor.bundle_id);
-            let old_pid = state.editor_pids.get(editor.id).copied();
-            if new_pid != old_pid {
+            let mut new_pids = crate::ax_helper::get_pids_by_bundle_id(editor.bundle_id);
+            new_pids.sort_unstable();
+            let old_pids = state.editor_pids.get(editor.id).cloned().unwrap_or_default();
+            if new_pids != old_pids {
                 changed.push(editor.id.to_string());
-                match new_pid {
-                    Some(pid) => {
-                        state.editor_pids.insert(editor.id.to_string(), pid);
-                    }
-                    None => {
-                        state.editor_pids.remove(editor.id);
-                    }
+                if new_pids.is_empty() {
+                    state.editor_pids.remove(editor.id);
+                } else {
+                    state.editor_pids.insert(editor.id.to_string(), new_pids);
                 }
             }
         }
-    }
+        !state.editor_pids.is_empty()
+    };
+    // エディタが一つも動いていなければ App Nap を妨げない
+    crate::power::set_editors_running(any_running);
     for editor_id in &changed {
         crate::editor::invalidate_path_cache_for_editor(editor_id);
     }
 }
 
 /// Two snapshots differ when length, or any identity field (id / name / branch /
-/// path / bundle_id) differs. Order is ignored — frontend reorders independently.
+/// path / bundle_id) or the hidden flag differs. Order is ignored — frontend reorders independently.
 fn windows_differ(a: &[EditorWindow], b: &[EditorWindow]) -> bool {
     if a.len() != b.len() {
         return true;
@@ -227,22 +533,34 @@ fn windows_differ(a: &[EditorWindow], b: &[EditorWindow]) -> bool {
     a_sorted.sort_by_key(|window| (&window.bundle_id, window.id));
     b_sorted.sort_by_key(|window| (&window.bundle_id, window.id));
 
-    a_sorted.iter().zip(b_sorted.iter()).any(|(wa, wb)| {
-        wa.id != wb.id
-            || wa.runtime_id != wb.runtime_id
-            || wa.name != wb.name
-            || wa.branch != wb.branch
-            || wa.path != wb.path
-            || wa.repository_id != wb.repository_id
-            || wa.repository_name != wb.repository_name
-            || wa.bundle_id != wb.bundle_id
-            || wa.resolution != wb.resolution
-    })
+    a_sorted
+        .iter()
+        .zip(b_sorted.iter())
+        .any(|(wa, wb)| wa.id != wb.id || window_changed(wa, wb))
+}
+
+/// Any field the tab bar shows differs between two versions of a window
+fn window_changed(wa: &EditorWindow, wb: &EditorWindow) -> bool {
+    wa.runtime_id != wb.runtime_id
+        || wa.name != wb.name
+        || wa.branch != wb.branch
+        || wa.path != wb.path
+        || wa.repository_id != wb.repository_id
+        || wa.repository_name != wb.repository_name
+        || wa.bundle_id != wb.bundle_id
+        || wa.resolution != wb.resolution
+        || wa.hidden != wb.hidden
+        || wa.remote != wb.remote
+        || wa.has_unsaved != wb.has_unsaved
+        || wa.workspace_kind != wb.workspace_kind
+        || wa.path_source != wb.path_source
+        || wa.appearance != wb.appearance
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
 
     fn mk(id: u32, name: &str, bundle: &str) -> EditorWindow {
         EditorWindow {
@@ -256,6 +574,12 @@ mod tests {
             bundle_id: bundle.to_string(),
             editor_name: String::new(),
             resolution: crate::editor::WorkspaceResolution::Unresolved,
+            hidden: false,
+            remote: None,
+            has_unsaved: false,
+            workspace_kind: None,
+            path_source: crate::editor::PathSource::Unresolved,
+            appearance: None,
         }
     }
 
@@ -307,6 +631,12 @@ mod tests {
             bundle_id: "b1".into(),
             editor_name: String::new(),
             resolution: crate::editor::WorkspaceResolution::Unresolved,
+            hidden: false,
+            remote: None,
+            has_unsaved: false,
+            workspace_kind: None,
+            path_source: crate::editor::PathSource::Unresolved,
+            appearance: None,
         }];
         let b = vec![EditorWindow {
             runtime_id: "b1:1".into(),
@@ -319,10 +649,40 @@ mod tests {
             bundle_id: "b1".into(),
             editor_name: String::new(),
             resolution: crate::editor::WorkspaceResolution::Unresolved,
+            hidden: false,
+            remote: None,
+            has_unsaved: false,
+            workspace_kind: None,
+            path_source: crate::editor::PathSource::Unresolved,
+            appearance: None,
         }];
         assert!(windows_differ(&a, &b));
     }
 
+    #[test]
+    fn hidden_editor_keeps_its_last_windows() {
+        let previous = vec![mk(1, "alpha", "b1"), mk(2, "beta", "b2")];
+        let current = vec![mk(2, "beta", "b2")];
+
+        let merged = merge_hidden_windows(&previous, current, &["b1"]);
+        assert_eq!(merged.len(), 2);
+        let alpha = merged.iter().find(|w| w.id == 1).unwrap();
+        assert!(alpha.hidden);
+        assert!(!merged.iter().find(|w| w.id == 2).unwrap().hidden);
+        assert!(windows_differ(&previous, &merged));
+    }
+
+    #[test]
+    fn hidden_editor_windows_reported_by_ax_are_flagged() {
+        let previous = vec![mk(1, "alpha", "b1")];
+        let current = vec![mk(3, "gamma", "b1")];
+
+        let merged = merge_hidden_windows(&previous, current, &["b1"]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, 3);
+        assert!(merged[0].hidden);
+    }
+
     #[test]
     fn repository_identity_change_is_detected() {
         let a = vec![mk(1, "project", "b1")];
@@ -332,4 +692,60 @@ mod tests {
 
         assert!(windows_differ(&a, &[resolved]));
     }
+
+    #[test]
+    fn launches_are_reported_once_their_first_window_appears() {
+        let mut pending = vec!["b1".to_string(), "b2".to_string()];
+
+        assert!(take_launched(&mut pending, &[]).is_empty());
+        assert_eq!(take_launched(&mut pending, &[mk(1, "alpha", "b2")]), vec!["b2"]);
+        assert_eq!(pending, vec!["b1"]);
+        assert!(take_launched(&mut pending, &[mk(1, "alpha", "b2")]).is_empty());
+    }
+    #[test]
+    fn diff_is_keyed_on_window_id() {
+        let previous = vec![mk(1, "alpha", "b1"), mk(2, "beta", "b1"), mk(3, "gamma", "b2")];
+        let current = vec![mk(3, "gamma", "b2"), mk(2, "renamed", "b1"), mk(4, "delta", "b1")];
+
+        let diff = diff_windows(&previous, &current, 7, Some(4));
+        fn ids(windows: &[EditorWindow]) -> Vec<u32> {
+            windows.iter().map(|window| window.id).collect()
+        }
+        assert_eq!(ids(&diff.added), vec![4]);
+        assert_eq!(diff.removed, vec![1]);
+        assert_eq!(ids(&diff.updated), vec![2]);
+        assert_eq!(diff.updated[0].name, "renamed");
+        assert_eq!((diff.revision, diff.active_window_id), (7, Some(4)));
+    }
+    #[derive(Default)]
+    struct MockProvider {
+        calls: RefCell<Vec<u32>>,
+    }
+
+    impl WindowProvider for MockProvider {
+        fn window(&self, bundle_id: &str, window_id: u32) -> Option<EditorWindow> {
+            self.calls.borrow_mut().push(window_id);
+            let mut window = mk(window_id, "renamed", bundle_id);
+            window.branch = Some("feature".into());
+            Some(window)
+        }
+    }
+
+    #[test]
+    fn title_change_re_resolves_only_that_window() {
+        let cached = vec![mk(1, "alpha", "b1"), mk(2, "beta", "b1"), mk(3, "gamma", "b2")];
+        let provider = MockProvider::default();
+
+        let windows = with_window_refreshed(&provider, &cached, "b1", 2).unwrap();
+        assert_eq!(*provider.calls.borrow(), vec![2]);
+        assert_eq!(windows[1].name, "renamed");
+        assert_eq!(windows[1].branch.as_deref(), Some("feature"));
+        let others = [windows[0].clone(), windows[2].clone()];
+        assert!(!windows_differ(&others, &[cached[0].clone(), cached[2].clone()]));
+
+        // キャッシュにないウィンドウは解決せず、全体の更新に回す
+        assert!(with_window_refreshed(&provider, &cached, "b1", 9).is_none());
+        assert!(with_window_refreshed(&provider, &cached, "b2", 2).is_none());
+        assert_eq!(provider.calls.borrow().len(), 1);
+    }
 }
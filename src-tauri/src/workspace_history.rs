@@ -0,0 +1,245 @@
+//! Project history from the editors' workspaceStorage
+//!
+//! VSCode and Cursor keep one `workspaceStorage/<hash>/workspace.json` per
+//! workspace ever opened. When a window's project name isn't among the windows
+//! listed in storage.json, this history is the next place to look. Long-time
+//! users have thousands of entries, so only the most recently used ones are
+//! read up front and older ones are read in batches when a lookup misses.
+
+use crate::editor;
+use crate::settings_store::STORE_FILE;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+pub(crate) const SCAN_LIMIT_KEY: &str = "settings:workspaceScanLimit";
+
+/// Entries read eagerly unless the user changes it
+pub const DEFAULT_SCAN_LIMIT: usize = 300;
+
+/// Entries read per step when a lookup misses
+const LAZY_BATCH_SIZE: usize = 200;
+
+static SCAN_LIMIT: AtomicUsize = AtomicUsize::new(DEFAULT_SCAN_LIMIT);
+
+/// Editor ID -> history, created on first use
+static HISTORIES: LazyLock<Mutex<HashMap<String, WorkspaceHistory>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// workspaceStorage entries of one editor, newest first, read incrementally
+struct WorkspaceHistory {
+    entries: Vec<PathBuf>,
+    scanned: usize,
+    paths_by_name: HashMap<String, Vec<PathBuf>>,
+}
+
+impl WorkspaceHistory {
+    /// Entry directories with their mtimes; sorted newest first
+    fn new(mut entries: Vec<(PathBuf, SystemTime)>) -> Self {
+        entries.sort_by(|a, b| b.1.cmp(&a.1));
+        Self {
+            entries: entries.into_iter().map(|(dir, _)| dir).collect(),
+            scanned: 0,
+            paths_by_name: HashMap::new(),
+        }
+    }
+
+    fn from_storage_dir(storage_dir: &Path) -> Self {
+        let entries = std::fs::read_dir(storage_dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| {
+                        let metadata = entry.metadata().ok()?;
+                        if !metadata.is_dir() {
+                            return None;
+                        }
+                        Some((entry.path(), metadata.modified().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::new(entries)
+    }
+
+    fn is_complete(&self) -> bool {
+        self.scanned >= self.entries.len()
+    }
+
+    /// Read the next `count` entries
+    fn scan_next(&mut self, count: usize) {
+        let end = (self.scanned + count).min(self.entries.len());
+        for dir in &self.entries[self.scanned..end] {
+            let Some(path) = read_workspace_folder(dir) else {
+                continue;
+            };
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let paths = self.paths_by_name.entry(name.to_string()).or_default();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        self.scanned = end;
+    }
+
+    /// Paths whose folder name matches, reading older entries until one is found
+    fn lookup(&mut self, name: &str) -> Vec<PathBuf> {
+        loop {
+            if let Some(paths) = self.paths_by_name.get(name) {
+                return paths.clone();
+            }
+            if self.is_complete() {
+                return Vec::new();
+            }
+            self.scan_next(LAZY_BATCH_SIZE);
+        }
+    }
+}
+
+/// The folder recorded in `<entry>/workspace.json`
+fn read_workspace_folder(entry_dir: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(entry_dir.join("workspace.json")).ok()?;
+    let json: Value = serde_json::from_str(&content).ok()?;
+    editor::workspace_entry_folder(&json)
+}
+
+fn with_history<T>(editor_id: &str, f: impl FnOnce(&mut WorkspaceHistory) -> T) -> Option<T> {
+    let storage_dir = editor::get_editor_user_dir(editor_id)?.join("workspaceStorage");
+    let mut histories = HISTORIES.lock().ok()?;
+    let history = histories.entry(editor_id.to_string()).or_insert_with(|| {
+        let mut history = WorkspaceHistory::from_storage_dir(&storage_dir);
+        history.scan_next(SCAN_LIMIT.load(Ordering::Relaxed));
+        history
+    });
+    Some(f(history))
+}
+
+/// Read the most recent entries of an editor's history (startup pre-warm)
+pub fn warm(editor_id: &str) {
+    with_history(editor_id, |_| ());
+}
+
+/// Previously opened folders named `name`
+pub fn lookup(editor_id: &str, name: &str) -> Vec<PathBuf> {
+    with_history(editor_id, |history| history.lookup(name)).unwrap_or_default()
+}
+
+/// Drop scanned histories so new workspaceStorage entries are picked up
+pub fn invalidate() {
+    if let Ok(mut histories) = HISTORIES.lock() {
+        histories.clear();
+    }
+}
+
+/// Load the saved eager scan limit (called from setup)
+pub fn load_settings(app_handle: &AppHandle) {
+    let limit = app_handle
+        .store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(SCAN_LIMIT_KEY))
+        .and_then(|value| value.as_u64())
+        .map(|limit| limit as usize)
+        .unwrap_or(DEFAULT_SCAN_LIMIT);
+    SCAN_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Tauri command: number of workspaceStorage entries read eagerly
+#[tauri::command]
+pub fn get_workspace_scan_limit() -> usize {
+    SCAN_LIMIT.load(Ordering::Relaxed)
+}
+
+/// Tauri command: change the eager scan limit
+#[tauri::command]
+pub fn set_workspace_scan_limit(app: AppHandle, limit: usize) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(SCAN_LIMIT_KEY, serde_json::json!(limit));
+    store.save().map_err(|e| e.to_string())?;
+
+    SCAN_LIMIT.store(limit, Ordering::Relaxed);
+    invalidate();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+
+    /// workspaceStorage with one entry per folder; the first is the newest
+    fn storage_tree(root: &Path, folders: &[&str]) -> Vec<(PathBuf, SystemTime)> {
+        let now = SystemTime::now();
+        folders
+            .iter()
+            .enumerate()
+            .map(|(i, folder)| {
+                let entry = root.join(format!("entry{i}"));
+                fs::create_dir_all(&entry).unwrap();
+                let workspace = serde_json::json!({ "folder": format!("file://{folder}") });
+                fs::write(entry.join("workspace.json"), workspace.to_string()).unwrap();
+                (entry, now - Duration::from_secs(i as u64 * 60))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn entries_are_scanned_newest_first_up_to_the_limit() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut entries = storage_tree(tmp.path(), &["/p/new", "/p/mid", "/p/old"]);
+        entries.reverse();
+        let mut history = WorkspaceHistory::new(entries);
+        history.scan_next(2);
+
+        assert_eq!(history.scanned, 2);
+        assert!(history.paths_by_name.contains_key("new"));
+        assert!(history.paths_by_name.contains_key("mid"));
+        assert!(!history.paths_by_name.contains_key("old"));
+    }
+
+    #[test]
+    fn lookup_misses_read_older_entries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let folders: Vec<String> =
+            (0..(LAZY_BATCH_SIZE + 5)).map(|i| format!("/p/f{i}")).collect();
+        let folder_refs: Vec<&str> = folders.iter().map(String::as_str).collect();
+        let mut history = WorkspaceHistory::new(storage_tree(tmp.path(), &folder_refs));
+        history.scan_next(1);
+
+        let oldest = format!("f{}", LAZY_BATCH_SIZE + 4);
+        assert_eq!(history.lookup(&oldest), vec![PathBuf::from(format!("/p/{oldest}"))]);
+        assert!(history.is_complete());
+        assert!(history.lookup("never-opened").is_empty());
+    }
+
+    #[test]
+    fn same_named_folders_are_all_kept() {
+        let tmp = tempfile::tempdir().unwrap();
+        let entries = storage_tree(tmp.path(), &["/work/api", "/personal/api"]);
+        let mut history = WorkspaceHistory::new(entries);
+
+        assert_eq!(
+            history.lookup("api"),
+            vec![PathBuf::from("/work/api"), PathBuf::from("/personal/api")]
+        );
+    }
+
+    #[test]
+    fn entries_without_a_folder_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let entry = tmp.path().join("empty-window");
+        fs::create_dir_all(&entry).unwrap();
+        fs::write(entry.join("workspace.json"), "{}").unwrap();
+        let mut history = WorkspaceHistory::from_storage_dir(tmp.path());
+
+        assert_eq!(history.entries.len(), 1);
+        assert!(history.lookup("empty-window").is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Workspace URI parsing
+//!
+//! Editors store workspace folders as URIs: `file:///Users/me/app`,
+//! `file://localhost/Users/me/app`, or `vscode-remote://ssh-remote+host/app`
+//! for remote windows. This is the small subset of RFC 3986 those need.
+
+use std::path::PathBuf;
+
+/// Where a workspace URI points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceUri {
+    Local(PathBuf),
+    /// Non-file scheme, labelled like remote window titles ("SSH: myhost")
+    Remote { label: String, path: String },
+}
+
+/// Parse a workspace URI. Bare absolute paths are accepted as local.
+pub fn parse(uri: &str) -> Option<WorkspaceUri> {
+    if uri.starts_with('/') {
+        return Some(WorkspaceUri::Local(PathBuf::from(uri)));
+    }
+    let (scheme, rest) = uri.split_once(':')?;
+    if !is_valid_scheme(scheme) {
+        return None;
+    }
+    // クエリとフラグメントはパスに含めない
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (authority, path) = match rest.strip_prefix("//") {
+        Some(after) => match after.find('/') {
+            Some(slash) => (&after[..slash], &after[slash..]),
+            None => (after, ""),
+        },
+        None => ("", rest),
+    };
+    let authority = percent_decode(authority)?;
+    let path = percent_decode(path)?;
+
+    if scheme.eq_ignore_ascii_case("file") {
+        // file://host/... is a network share, not a local folder
+        if !authority.is_empty() && !authority.eq_ignore_ascii_case("localhost") {
+            return None;
+        }
+        if !path.starts_with('/') {
+            return None;
+        }
+        return Some(WorkspaceUri::Local(PathBuf::from(path)));
+    }
+    Some(WorkspaceUri::Remote {
+        label: remote_label(scheme, &authority),
+        path,
+    })
+}
+
+/// Local path of a `file:` URI (or bare path); None for anything else
+pub fn to_local_path(uri: &str) -> Option<PathBuf> {
+    match parse(uri)? {
+        WorkspaceUri::Local(path) => Some(path),
+        WorkspaceUri::Remote { .. } => None,
+    }
+}
+
+/// "ssh-remote+myhost" -> "SSH: myhost", matching the editors' title suffixes
+fn remote_label(scheme: &str, authority: &str) -> String {
+    let (kind, name) = authority.split_once('+').unwrap_or((authority, ""));
+    let kind = match kind {
+        "ssh-remote" => "SSH",
+        "wsl" => "WSL",
+        "dev-container" => return "Dev Container".to_string(),
+        "attached-container" => return "Container".to_string(),
+        "codespaces" => "Codespaces",
+        "tunnel" => "Tunnel",
+        _ if authority.is_empty() => return scheme.to_string(),
+        _ => return authority.to_string(),
+    };
+    if name.is_empty() {
+        kind.to_string()
+    } else {
+        format!("{kind}: {name}")
+    }
+}
+
+fn is_valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// %XX をバイトに戻す。"+" は URI のパスでは空白ではないのでそのまま残す
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3)?;
+            let value = std::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(value, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(path: &str) -> Option<WorkspaceUri> {
+        Some(WorkspaceUri::Local(PathBuf::from(path)))
+    }
+
+    #[test]
+    fn file_uris_are_percent_decoded() {
+        assert_eq!(parse("file:///path/to/no%20encode"), local("/path/to/no encode"));
+        assert_eq!(parse("file:///no_encoding"), local("/no_encoding"));
+    }
+
+    #[test]
+    fn japanese_paths_are_decoded() {
+        // %E3%83%86%E3%82%B9%E3%83%88 = "テスト" in UTF-8
+        assert_eq!(
+            parse("file:///Users/me/%E3%83%86%E3%82%B9%E3%83%88"),
+            local("/Users/me/テスト")
+        );
+    }
+
+    #[test]
+    fn localhost_is_accepted_and_other_hosts_are_not() {
+        assert_eq!(parse("file://localhost/Users/me/app"), local("/Users/me/app"));
+        assert_eq!(parse("file://LOCALHOST/Users/me/app"), local("/Users/me/app"));
+        // Never produce "/fileserver/share/app" or "fileserver/share/app"
+        assert_eq!(parse("file://fileserver/share/app"), None);
+    }
+
+    #[test]
+    fn plus_signs_are_not_spaces() {
+        assert_eq!(parse("file:///Users/me/c%2B%2B+notes"), local("/Users/me/c++notes"));
+    }
+
+    #[test]
+    fn remote_uris_are_labelled() {
+        assert_eq!(
+            parse("vscode-remote://ssh-remote%2Bmyhost/home/me/app"),
+            Some(WorkspaceUri::Remote {
+                label: "SSH: myhost".to_string(),
+                path: "/home/me/app".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("vscode-remote://wsl+Ubuntu/home/me/app"),
+            Some(WorkspaceUri::Remote {
+                label: "WSL: Ubuntu".to_string(),
+                path: "/home/me/app".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("vscode-remote://dev-container%2B7b22/workspaces/app"),
+            Some(WorkspaceUri::Remote {
+                label: "Dev Container".to_string(),
+                path: "/workspaces/app".to_string(),
+            })
+        );
+        assert_eq!(to_local_path("vscode-vfs://github/me/repo"), None);
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert_eq!(parse("file:///bad%2"), None);
+        assert_eq!(parse("file:///bad%zz"), None);
+        assert_eq!(parse("not a uri"), None);
+        assert_eq!(parse("file:relative/path"), None);
+    }
+
+    #[test]
+    fn bare_paths_and_query_strings() {
+        assert_eq!(parse("/Users/me/app"), local("/Users/me/app"));
+        assert_eq!(parse("file:///Users/me/app?x=1#frag"), local("/Users/me/app"));
+    }
+}
@@ -118,6 +118,7 @@ function App() {
       onColorPickerClose={lifecycle.handleColorPickerClose}
       showBranch={lifecycle.showBranch}
       tabLayout={lifecycle.tabLayout}
+      shortcutsPaused={lifecycle.shortcutsPaused}
       history={history.history}
       showAddMenu={history.showAddMenu}
       onAddMenuOpen={lifecycle.handleAddMenuOpen}
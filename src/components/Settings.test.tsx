@@ -1,4 +1,5 @@
 import { fireEvent, render, screen, waitFor } from "@testing-library/react";
+import { invoke } from "@tauri-apps/api/core";
 import { emit } from "@tauri-apps/api/event";
 import Settings from "./Settings";
 
@@ -53,3 +54,119 @@ describe("Settings tab layout", () => {
     expect(list).toBeChecked();
   });
 });
+
+describe("Settings hidden windows", () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset();
+    vi.mocked(invoke).mockImplementation(async (command) => {
+      if (command === "get_window_filter_settings") {
+        return { excluded_titles: ["scratch*"] };
+      }
+      return undefined;
+    });
+  });
+
+  it("saves one pattern per line when the field loses focus", async () => {
+    render(<Settings />);
+
+    const field = await screen.findByRole("textbox", {
+      name: /settings\.excludedTitlesLabel/,
+    });
+    expect(field).toHaveValue("scratch*");
+
+    fireEvent.change(field, { target: { value: "scratch*\n\n  *.log  " } });
+    expect(invoke).not.toHaveBeenCalledWith("set_window_filter_settings", expect.anything());
+
+    fireEvent.blur(field);
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("set_window_filter_settings", {
+        settings: { excluded_titles: ["scratch*", "*.log"] },
+      });
+    });
+  });
+});
+
+describe("Settings tracked editors", () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset();
+    vi.mocked(invoke).mockImplementation(async (command) => {
+      if (command === "get_editor_filter_settings") {
+        return { disabled_editors: [], ignored_paths: ["~/scratch"] };
+      }
+      return undefined;
+    });
+  });
+
+  it("turns an editor off and keeps the ignored folders", async () => {
+    render(<Settings />);
+
+    const zed = await screen.findByRole("switch", { name: "Zed" });
+    expect(zed).toHaveAttribute("aria-checked", "true");
+
+    fireEvent.click(zed);
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("set_editor_filter_settings", {
+        settings: { disabled_editors: ["zed"], ignored_paths: ["~/scratch"] },
+      });
+    });
+  });
+});
+
+describe("Settings tab bar window", () => {
+  beforeEach(() => {
+    vi.mocked(invoke).mockReset();
+    vi.mocked(invoke).mockImplementation(async (command) => {
+      if (command === "get_tab_bar_behavior") {
+        return { level: "above_modals", collection_behavior: [] };
+      }
+      if (command === "get_tab_bar_position") return "top";
+      return undefined;
+    });
+  });
+
+  it("moves the tab bar to the bottom edge", async () => {
+    render(<Settings />);
+
+    const select = await screen.findByRole("combobox", {
+      name: /settings\.tabBarPositionLabel/,
+    });
+    expect(select).toHaveValue("top");
+
+    fireEvent.change(select, { target: { value: "bottom" } });
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("set_tab_bar_position", { position: "bottom" });
+    });
+  });
+
+  it("applies the level and Spaces behavior together", async () => {
+    render(<Settings />);
+
+    const allSpaces = await screen.findByRole("switch", {
+      name: /settings\.tabBarCollectionBehavior\.can_join_all_spaces/,
+    });
+    expect(allSpaces).toHaveAttribute("aria-checked", "false");
+
+    fireEvent.click(allSpaces);
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("configure_tab_bar_window", {
+        level: "above_modals",
+        collection_behavior: ["can_join_all_spaces"],
+      });
+    });
+
+    fireEvent.change(screen.getByRole("combobox", { name: /settings\.tabBarLevelLabel/ }), {
+      target: { value: "status" },
+    });
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("configure_tab_bar_window", {
+        level: "status",
+        collection_behavior: ["can_join_all_spaces"],
+      });
+    });
+  });
+});
@@ -1,12 +1,26 @@
 import { useState, useEffect, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { invoke } from "@tauri-apps/api/core";
-import { emit } from "@tauri-apps/api/event";
+import { emit, listen } from "@tauri-apps/api/event";
 import { homeDir } from "@tauri-apps/api/path";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import { enable, disable, isEnabled } from "@tauri-apps/plugin-autostart";
 import { useLanguage } from "../hooks/useLanguage";
-import type { TabLayout } from "../types/editor";
+import { EDITOR_IDS } from "../types/editor";
+import type {
+  EditorFilterSettings,
+  LaunchAtLoginStatus,
+  NotificationPermission,
+  NotificationSettings,
+  TabBarBehavior,
+  TabBarCollectionBehavior,
+  TabBarLevel,
+  TabBarPosition,
+  TabLayout,
+  TerminalApp,
+  TrayTitleSettings,
+  UpdateCheckSettings,
+  WindowFilterSettings,
+} from "../types/editor";
 import { getStore, loadTabLayout, saveTabLayout } from "../utils/store";
 import VersionInfo from "./VersionInfo";
 
@@ -55,6 +69,22 @@ function highlightJSON(json: string): React.ReactNode[] {
   return tokens;
 }
 
+// Default quiet hours when first enabled: 22:00-07:00
+const DEFAULT_QUIET_HOURS = { start: 22 * 60, end: 7 * 60 };
+
+function minutesToTime(minutes: number): string {
+  const h = Math.floor(minutes / 60).toString().padStart(2, "0");
+  const m = (minutes % 60).toString().padStart(2, "0");
+  return `${h}:${m}`;
+}
+
+function timeToMinutes(time: string): number {
+  const [h, m] = time.split(":").map(Number);
+  return (h || 0) * 60 + (m || 0);
+}
+
+const NOTIFICATION_SETTINGS_URL = "x-apple.systempreferences:com.apple.preference.notifications";
+
 const SETUP_CODE = `{
   "hooks": {
     "UserPromptSubmit": [
@@ -104,14 +134,44 @@ function Settings() {
   const [collapseHover, setCollapseHover] = useState(false);
   const [copyHover, setCopyHover] = useState(false);
   const [notificationEnabled, setNotificationEnabled] = useState(true);
-  const [autostartEnabled, setAutostartEnabled] = useState(false);
+  const [launchAtLogin, setLaunchAtLogin] = useState<LaunchAtLoginStatus>("disabled");
   const [showBranchEnabled, setShowBranchEnabled] = useState(true);
   const [tabLayout, setTabLayout] = useState<TabLayout>("horizontal");
+  const [debugLoggingEnabled, setDebugLoggingEnabled] = useState(false);
+  const [autohide, setAutohide] = useState(true);
+  const [legacyWindowEvents, setLegacyWindowEvents] = useState(false);
+  const [apiEnabled, setApiEnabled] = useState(false);
+  const [updateCheck, setUpdateCheck] = useState<UpdateCheckSettings | null>(null);
+  const [apiSocketPath, setApiSocketPath] = useState("");
+  const [notificationPermission, setNotificationPermission] = useState<NotificationPermission | null>(null);
+  const [permissionHover, setPermissionHover] = useState(false);
+  const [notificationSettings, setNotificationSettings] = useState<NotificationSettings | null>(null);
+  const [openLogsHover, setOpenLogsHover] = useState(false);
+  const [trayTitleSettings, setTrayTitleSettings] = useState<TrayTitleSettings | null>(null);
+  // 入力中はテキストのまま保持し、フォーカスが外れたときに保存する
+  const [excludedTitlesDraft, setExcludedTitlesDraft] = useState<string | null>(null);
+  const [workspaceScanLimit, setWorkspaceScanLimit] = useState<number | null>(null);
+  const [editorFilter, setEditorFilter] = useState<EditorFilterSettings | null>(null);
+  const [ignoredPathsDraft, setIgnoredPathsDraft] = useState("");
+  const [defaultTerminal, setDefaultTerminal] = useState<TerminalApp | null>(null);
+  const [tabBarBehavior, setTabBarBehavior] = useState<TabBarBehavior | null>(null);
+  const [tabBarPosition, setTabBarPosition] = useState<TabBarPosition | null>(null);
 
   useEffect(() => {
     getCurrentWindow().setTitle(t("settings.title"));
   }, [t]);
 
+  // An imported settings file changes many values at once; start over from the store
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    void listen("settings-changed", () => {
+      window.location.reload();
+    }).then((cleanup) => {
+      unlisten = cleanup;
+    });
+    return () => unlisten?.();
+  }, []);
+
   useEffect(() => {
     const init = async () => {
       try {
@@ -123,7 +183,53 @@ function Settings() {
         setTabLayout(await loadTabLayout());
       } catch { /* defaults */ }
       try {
-        setAutostartEnabled(await isEnabled());
+        setLaunchAtLogin(await invoke<LaunchAtLoginStatus>("get_launch_at_login"));
+      } catch { /* defaults */ }
+      try {
+        setDebugLoggingEnabled(await invoke<boolean>("get_debug_logging"));
+      } catch { /* defaults */ }
+      try {
+        setAutohide(await invoke<boolean>("get_autohide"));
+      } catch { /* defaults */ }
+      try {
+        setLegacyWindowEvents(await invoke<boolean>("get_legacy_window_events"));
+      } catch { /* defaults */ }
+      try {
+        setUpdateCheck(await invoke<UpdateCheckSettings>("get_update_check_settings"));
+      } catch { /* defaults */ }
+      try {
+        setApiEnabled(await invoke<boolean>("get_api_enabled"));
+        setApiSocketPath(await invoke<string>("get_api_socket_path"));
+      } catch { /* defaults */ }
+      try {
+        setNotificationPermission(await invoke<NotificationPermission>("get_notification_permission"));
+      } catch { /* defaults */ }
+      try {
+        setNotificationSettings(await invoke<NotificationSettings>("get_notification_settings"));
+      } catch { /* defaults */ }
+      try {
+        setTrayTitleSettings(await invoke<TrayTitleSettings>("get_tray_title_settings"));
+      } catch { /* defaults */ }
+      try {
+        const filter = await invoke<WindowFilterSettings>("get_window_filter_settings");
+        setExcludedTitlesDraft(filter.excluded_titles.join("\n"));
+      } catch { /* defaults */ }
+      try {
+        const filter = await invoke<EditorFilterSettings>("get_editor_filter_settings");
+        setEditorFilter(filter);
+        setIgnoredPathsDraft(filter.ignored_paths.join("\n"));
+      } catch { /* defaults */ }
+      try {
+        setWorkspaceScanLimit(await invoke<number>("get_workspace_scan_limit"));
+      } catch { /* defaults */ }
+      try {
+        setDefaultTerminal(await invoke<TerminalApp>("get_default_terminal"));
+      } catch { /* defaults */ }
+      try {
+        setTabBarBehavior(await invoke<TabBarBehavior>("get_tab_bar_behavior"));
+      } catch { /* defaults */ }
+      try {
+        setTabBarPosition(await invoke<TabBarPosition>("get_tab_bar_position"));
       } catch { /* defaults */ }
     };
     init();
@@ -145,13 +251,20 @@ function Settings() {
 
   const handleAutostartToggle = useCallback(async (enabled: boolean) => {
     try {
-      if (enabled) await enable(); else await disable();
-      setAutostartEnabled(enabled);
+      setLaunchAtLogin(await invoke<LaunchAtLoginStatus>("set_launch_at_login", { enabled }));
     } catch (error) {
       console.error("Failed to toggle autostart:", error);
     }
   }, []);
 
+  const handleOpenLoginItems = useCallback(async () => {
+    try {
+      await invoke("open_login_items_settings");
+    } catch (error) {
+      console.error("Failed to open Login Items settings:", error);
+    }
+  }, []);
+
   const handleShowBranchToggle = useCallback(async (enabled: boolean) => {
     setShowBranchEnabled(enabled);
     try {
@@ -162,6 +275,158 @@ function Settings() {
     }
   }, []);
 
+  const handleDebugLoggingToggle = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_debug_logging", { enabled });
+      setDebugLoggingEnabled(enabled);
+    } catch (error) {
+      console.error("Failed to toggle debug logging:", error);
+    }
+  }, []);
+
+  const handleAutohideToggle = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_autohide", { enabled });
+      setAutohide(enabled);
+    } catch (error) {
+      console.error("Failed to toggle tab bar auto-hide:", error);
+    }
+  }, []);
+
+  const handleLegacyWindowEventsToggle = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_legacy_window_events", { enabled });
+      setLegacyWindowEvents(enabled);
+    } catch (error) {
+      console.error("Failed to toggle legacy window events:", error);
+    }
+  }, []);
+
+  const handleApiToggle = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_api_enabled", { enabled });
+      setApiEnabled(enabled);
+    } catch (error) {
+      console.error("Failed to toggle local API:", error);
+    }
+  }, []);
+
+  const handleUpdateCheckToggle = useCallback(async (enabled: boolean) => {
+    if (!updateCheck) return;
+    const next = { ...updateCheck, enabled };
+    try {
+      await invoke("set_update_check_settings", { settings: next });
+      setUpdateCheck(next);
+    } catch (error) {
+      console.error("Failed to save update check settings:", error);
+    }
+  }, [updateCheck]);
+
+  const handleNotificationPermission = useCallback(async () => {
+    try {
+      if (notificationPermission === "not_determined") {
+        setNotificationPermission(await invoke<NotificationPermission>("request_notification_permission"));
+      } else {
+        // macOS only shows the prompt once; afterwards it has to be changed in System Settings
+        await invoke("open_file_in_default_app", { path: NOTIFICATION_SETTINGS_URL });
+      }
+    } catch (error) {
+      console.error("Failed to request notification permission:", error);
+    }
+  }, [notificationPermission]);
+
+  // Re-read before saving: the enabled flag and mute list can change elsewhere
+  const updateNotificationSettings = useCallback(async (patch: Partial<NotificationSettings>) => {
+    try {
+      const current = await invoke<NotificationSettings>("get_notification_settings");
+      const next = { ...current, ...patch };
+      await invoke("set_notification_settings", { settings: next });
+      setNotificationSettings(next);
+    } catch (error) {
+      console.error("Failed to save notification settings:", error);
+    }
+  }, []);
+
+  const updateTrayTitleSettings = useCallback(async (next: TrayTitleSettings) => {
+    setTrayTitleSettings(next);
+    try {
+      await invoke("set_tray_title_settings", { settings: next });
+    } catch (error) {
+      console.error("Failed to save tray title settings:", error);
+    }
+  }, []);
+
+  const saveExcludedTitles = useCallback(async (draft: string) => {
+    const settings: WindowFilterSettings = {
+      excluded_titles: draft
+        .split("\n")
+        .map((line) => line.trim())
+        .filter((line) => line.length > 0),
+    };
+    try {
+      await invoke("set_window_filter_settings", { settings });
+    } catch (error) {
+      console.error("Failed to save window filter settings:", error);
+    }
+  }, []);
+
+  const updateEditorFilter = useCallback(async (next: EditorFilterSettings) => {
+    setEditorFilter(next);
+    try {
+      await invoke("set_editor_filter_settings", { settings: next });
+    } catch (error) {
+      console.error("Failed to save editor filter settings:", error);
+    }
+  }, []);
+
+  const updateWorkspaceScanLimit = useCallback(async (limit: number) => {
+    setWorkspaceScanLimit(limit);
+    try {
+      await invoke("set_workspace_scan_limit", { limit });
+    } catch (error) {
+      console.error("Failed to save workspace scan limit:", error);
+    }
+  }, []);
+
+  const updateDefaultTerminal = useCallback(async (terminal: TerminalApp) => {
+    setDefaultTerminal(terminal);
+    try {
+      await invoke("set_default_terminal", { terminal });
+    } catch (error) {
+      console.error("Failed to save default terminal:", error);
+    }
+  }, []);
+
+  const updateTabBarBehavior = useCallback(async (next: TabBarBehavior) => {
+    setTabBarBehavior(next);
+    try {
+      await invoke("configure_tab_bar_window", {
+        level: next.level,
+        collection_behavior: next.collection_behavior,
+      });
+    } catch (error) {
+      console.error("Failed to configure the tab bar window:", error);
+    }
+  }, []);
+
+  const handleTabBarPositionChange = useCallback(async (position: TabBarPosition) => {
+    setTabBarPosition(position);
+    try {
+      await invoke("set_tab_bar_position", { position });
+    } catch (error) {
+      console.error("Failed to move the tab bar:", error);
+    }
+  }, []);
+
+  const handleOpenLogs = useCallback(async () => {
+    try {
+      const path = await invoke<string>("get_log_file_path");
+      await invoke("open_file_in_default_app", { path });
+    } catch (error) {
+      console.error("Failed to open log file:", error);
+    }
+  }, []);
+
   const handleTabLayoutChange = useCallback(async (layout: TabLayout) => {
     setTabLayout(layout);
     await saveTabLayout(layout);
@@ -339,6 +604,105 @@ function Settings() {
             <p style={styles.note}>
               {t("settings.notificationNote")}
             </p>
+            {(notificationPermission === "not_determined" || notificationPermission === "denied") && (
+              <div style={styles.stepContent}>
+                <span style={styles.note}>
+                  {notificationPermission === "denied"
+                    ? t("settings.notificationPermissionDenied")
+                    : t("settings.notificationPermissionNotDetermined")}
+                </span>
+                <button
+                  style={{
+                    ...styles.openFileButton,
+                    ...(permissionHover ? { background: "#333333" } : {}),
+                  }}
+                  onClick={handleNotificationPermission}
+                  onMouseEnter={() => setPermissionHover(true)}
+                  onMouseLeave={() => setPermissionHover(false)}
+                >
+                  {notificationPermission === "denied"
+                    ? t("settings.openNotificationSettings")
+                    : t("settings.allowNotifications")}
+                </button>
+              </div>
+            )}
+            {notificationSettings && (
+              <>
+                <div style={{ ...styles.switchRow, marginTop: "12px" }}>
+                  <div style={styles.switchLabelGroup}>
+                    <span style={styles.switchLabel}>{t("settings.quietHoursLabel")}</span>
+                    <span style={styles.switchDescription}>
+                      {t("settings.quietHoursDescription")}
+                    </span>
+                  </div>
+                  <div
+                    style={{
+                      ...styles.switchTrack,
+                      ...(notificationSettings.quiet_hours ? styles.switchTrackActive : {}),
+                    }}
+                    onClick={() =>
+                      updateNotificationSettings({
+                        quiet_hours: notificationSettings.quiet_hours ? null : DEFAULT_QUIET_HOURS,
+                      })
+                    }
+                  >
+                    <div
+                      style={{
+                        ...styles.switchThumb,
+                        ...(notificationSettings.quiet_hours ? styles.switchThumbActive : {}),
+                      }}
+                    />
+                  </div>
+                </div>
+                {notificationSettings.quiet_hours && (
+                  <div style={styles.quietHoursRow}>
+                    <input
+                      type="time"
+                      style={styles.languageSelect}
+                      value={minutesToTime(notificationSettings.quiet_hours.start)}
+                      onChange={(e) =>
+                        updateNotificationSettings({
+                          quiet_hours: { ...notificationSettings.quiet_hours!, start: timeToMinutes(e.target.value) },
+                        })
+                      }
+                    />
+                    <span>–</span>
+                    <input
+                      type="time"
+                      style={styles.languageSelect}
+                      value={minutesToTime(notificationSettings.quiet_hours.end)}
+                      onChange={(e) =>
+                        updateNotificationSettings({
+                          quiet_hours: { ...notificationSettings.quiet_hours!, end: timeToMinutes(e.target.value) },
+                        })
+                      }
+                    />
+                  </div>
+                )}
+                {notificationSettings.muted_projects.length > 0 && (
+                  <div style={{ marginTop: "12px" }}>
+                    <span style={styles.switchLabel}>{t("settings.mutedProjectsLabel")}</span>
+                    {notificationSettings.muted_projects.map((path) => (
+                      <div key={path} style={styles.mutedProjectRow}>
+                        <span style={styles.switchDescription} title={path}>
+                          {path.split("/").pop() || path}
+                        </span>
+                        <button
+                          style={styles.openFileButton}
+                          onClick={() =>
+                            updateNotificationSettings({
+                              muted_projects: notificationSettings.muted_projects.filter((p) => p !== path),
+                            })
+                          }
+                        >
+                          {t("settings.unmuteProject")}
+                        </button>
+                      </div>
+                    ))}
+                  </div>
+                )}
+              </>
+            )}
           </div>
         </div>
 
@@ -354,18 +718,27 @@ function Settings() {
             <div
               style={{
                 ...styles.switchTrack,
-                ...(autostartEnabled ? styles.switchTrackActive : {}),
+                ...(launchAtLogin === "enabled" ? styles.switchTrackActive : {}),
               }}
-              onClick={() => handleAutostartToggle(!autostartEnabled)}
+              onClick={() => handleAutostartToggle(launchAtLogin === "disabled")}
             >
               <div
                 style={{
                   ...styles.switchThumb,
-                  ...(autostartEnabled ? styles.switchThumbActive : {}),
+                  ...(launchAtLogin === "enabled" ? styles.switchThumbActive : {}),
                 }}
               />
             </div>
           </div>
+          {/* macOS 13+ ではシステム設定での承認待ちになることがある */}
+          {launchAtLogin === "requires_approval" && (
+            <div style={{ marginTop: "8px" }}>
+              <p style={styles.note}>{t("settings.autostartRequiresApproval")}</p>
+              <button style={styles.openFileButton} onClick={handleOpenLoginItems}>
+                {t("settings.openLoginItems")}
+              </button>
+            </div>
+          )}
         </div>
 
         {/* Gitブランチ表示設定 */}
@@ -394,6 +767,184 @@ function Settings() {
           </div>
         </div>
 
+        {/* メニューバーのプロジェクト名表示 */}
+        {trayTitleSettings && (
+          <div style={styles.card}>
+            <div style={styles.switchRow}>
+              <div style={styles.switchLabelGroup}>
+                <span style={styles.switchLabel}>{t("settings.trayTitleLabel")}</span>
+                <span style={styles.switchDescription}>
+                  {t("settings.trayTitleDescription")}
+                </span>
+              </div>
+              <div
+                style={{
+                  ...styles.switchTrack,
+                  ...(trayTitleSettings.enabled ? styles.switchTrackActive : {}),
+                }}
+                onClick={() =>
+                  updateTrayTitleSettings({ ...trayTitleSettings, enabled: !trayTitleSettings.enabled })
+                }
+              >
+                <div
+                  style={{
+                    ...styles.switchThumb,
+                    ...(trayTitleSettings.enabled ? styles.switchThumbActive : {}),
+                  }}
+                />
+              </div>
+            </div>
+            {trayTitleSettings.enabled && (
+              <div style={styles.quietHoursRow}>
+                <span style={styles.switchDescription}>{t("settings.trayTitleMaxLength")}</span>
+                <input
+                  type="number"
+                  min={5}
+                  max={80}
+                  style={styles.languageSelect}
+                  value={trayTitleSettings.max_length}
+                  onChange={(e) => {
+                    const maxLength = Number(e.target.value);
+                    if (Number.isInteger(maxLength) && maxLength >= 5) {
+                      updateTrayTitleSettings({ ...trayTitleSettings, max_length: maxLength });
+                    }
+                  }}
+                />
+              </div>
+            )}
+          </div>
+        )}
+
+        {/* タブから除外するウィンドウ */}
+        {excludedTitlesDraft !== null && (
+          <div style={styles.card}>
+            <div style={styles.switchLabel}>{t("settings.excludedTitlesLabel")}</div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.excludedTitlesDescription")}
+            </p>
+            <textarea
+              aria-label={t("settings.excludedTitlesLabel")}
+              rows={3}
+              style={{ ...styles.languageSelect, ...styles.patternInput }}
+              placeholder={t("settings.excludedTitlesPlaceholder")}
+              value={excludedTitlesDraft}
+              onChange={(e) => setExcludedTitlesDraft(e.target.value)}
+              onBlur={(e) => saveExcludedTitles(e.target.value)}
+            />
+          </div>
+        )}
+
+        {/* 対象エディタと無視するフォルダ */}
+        {editorFilter && (
+          <div style={styles.card}>
+            <div style={styles.switchLabel}>{t("settings.trackedEditorsLabel")}</div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.trackedEditorsDescription")}
+            </p>
+            {Object.entries(EDITOR_IDS).map(([id, name]) => {
+              const enabled = !editorFilter.disabled_editors.includes(id);
+              return (
+                <div key={id} style={{ ...styles.switchRow, marginTop: "8px" }}>
+                  <span style={styles.switchLabel}>{name}</span>
+                  <div
+                    role="switch"
+                    aria-label={name}
+                    aria-checked={enabled}
+                    style={{
+                      ...styles.switchTrack,
+                      ...(enabled ? styles.switchTrackActive : {}),
+                    }}
+                    onClick={() =>
+                      updateEditorFilter({
+                        ...editorFilter,
+                        disabled_editors: enabled
+                          ? [...editorFilter.disabled_editors, id]
+                          : editorFilter.disabled_editors.filter((disabled) => disabled !== id),
+                      })
+                    }
+                  >
+                    <div
+                      style={{
+                        ...styles.switchThumb,
+                        ...(enabled ? styles.switchThumbActive : {}),
+                      }}
+                    />
+                  </div>
+                </div>
+              );
+            })}
+            <div style={{ ...styles.switchLabel, marginTop: "14px" }}>
+              {t("settings.ignoredPathsLabel")}
+            </div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.ignoredPathsDescription")}
+            </p>
+            <textarea
+              aria-label={t("settings.ignoredPathsLabel")}
+              rows={3}
+              style={{ ...styles.languageSelect, ...styles.patternInput }}
+              placeholder="~/scratch"
+              value={ignoredPathsDraft}
+              onChange={(e) => setIgnoredPathsDraft(e.target.value)}
+              onBlur={(e) =>
+                updateEditorFilter({
+                  ...editorFilter,
+                  ignored_paths: e.target.value
+                    .split("\n")
+                    .map((line) => line.trim())
+                    .filter((line) => line.length > 0),
+                })
+              }
+            />
+          </div>
+        )}
+
+        {/* 最近のプロジェクト履歴の読み込み件数 */}
+        {workspaceScanLimit !== null && (
+          <div style={styles.card}>
+            <div style={styles.switchLabel}>{t("settings.workspaceScanLimitLabel")}</div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.workspaceScanLimitDescription")}
+            </p>
+            <input
+              type="number"
+              min={0}
+              max={10000}
+              step={50}
+              aria-label={t("settings.workspaceScanLimitLabel")}
+              style={styles.languageSelect}
+              value={workspaceScanLimit}
+              onChange={(e) => {
+                const limit = Number(e.target.value);
+                if (Number.isInteger(limit) && limit >= 0) {
+                  updateWorkspaceScanLimit(limit);
+                }
+              }}
+            />
+          </div>
+        )}
+
+        {/* 「ターミナルで開く」に使うアプリ */}
+        {defaultTerminal !== null && (
+          <div style={styles.card}>
+            <div style={styles.switchLabel}>{t("settings.defaultTerminalLabel")}</div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.defaultTerminalDescription")}
+            </p>
+            <select
+              aria-label={t("settings.defaultTerminalLabel")}
+              value={defaultTerminal}
+              onChange={(e) => updateDefaultTerminal(e.target.value as TerminalApp)}
+              style={styles.languageSelect}
+            >
+              <option value="terminal">Terminal</option>
+              <option value="iterm2">iTerm2</option>
+              <option value="wezterm">WezTerm</option>
+              <option value="kitty">kitty</option>
+            </select>
+          </div>
+        )}
+
         {/* タブ表示形式 */}
         <div style={styles.card}>
           <div style={styles.switchLabel}>{t("settings.tabLayoutLabel")}</div>
@@ -428,6 +979,116 @@ function Settings() {
           </div>
         </div>
 
+        {/* 他のアプリが前面のときにタブバーを隠す */}
+        <div style={styles.card}>
+          <div style={styles.switchRow}>
+            <div style={styles.switchLabelGroup}>
+              <span style={styles.switchLabel}>{t("settings.autohideLabel")}</span>
+              <span style={styles.switchDescription}>
+                {t("settings.autohideDescription")}
+              </span>
+            </div>
+            <div
+              role="switch"
+              aria-label={t("settings.autohideLabel")}
+              aria-checked={autohide}
+              style={{
+                ...styles.switchTrack,
+                ...(autohide ? styles.switchTrackActive : {}),
+              }}
+              onClick={() => handleAutohideToggle(!autohide)}
+            >
+              <div
+                style={{
+                  ...styles.switchThumb,
+                  ...(autohide ? styles.switchThumbActive : {}),
+                }}
+              />
+            </div>
+          </div>
+        </div>
+
+        {/* タブバーを画面の上下どちらに置くか */}
+        {tabBarPosition && (
+          <div style={styles.card}>
+            <div style={styles.switchLabel}>{t("settings.tabBarPositionLabel")}</div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.tabBarPositionDescription")}
+            </p>
+            <select
+              aria-label={t("settings.tabBarPositionLabel")}
+              value={tabBarPosition}
+              onChange={(e) => handleTabBarPositionChange(e.target.value as TabBarPosition)}
+              style={styles.languageSelect}
+            >
+              {(["top", "bottom"] as const).map((position) => (
+                <option key={position} value={position}>
+                  {t(`settings.tabBarPosition.${position}`)}
+                </option>
+              ))}
+            </select>
+          </div>
+        )}
+
+        {/* タブバーのウィンドウレベルと Spaces での表示 */}
+        {tabBarBehavior && (
+          <div style={styles.card}>
+            <div style={styles.switchLabel}>{t("settings.tabBarLevelLabel")}</div>
+            <p style={{ ...styles.switchDescription, ...styles.layoutDescription }}>
+              {t("settings.tabBarLevelDescription")}
+            </p>
+            <select
+              aria-label={t("settings.tabBarLevelLabel")}
+              value={tabBarBehavior.level}
+              onChange={(e) =>
+                updateTabBarBehavior({ ...tabBarBehavior, level: e.target.value as TabBarLevel })
+              }
+              style={styles.languageSelect}
+            >
+              {(["above_modals", "normal", "floating", "status"] as const).map((level) => (
+                <option key={level} value={level}>
+                  {t(`settings.tabBarLevel.${level}`)}
+                </option>
+              ))}
+            </select>
+            {(["can_join_all_spaces", "full_screen_auxiliary"] as TabBarCollectionBehavior[]).map(
+              (behavior) => {
+                const enabled = tabBarBehavior.collection_behavior.includes(behavior);
+                const label = t(`settings.tabBarCollectionBehavior.${behavior}`);
+                return (
+                  <div key={behavior} style={{ ...styles.switchRow, marginTop: "8px" }}>
+                    <span style={styles.switchLabel}>{label}</span>
+                    <div
+                      role="switch"
+                      aria-label={label}
+                      aria-checked={enabled}
+                      style={{
+                        ...styles.switchTrack,
+                        ...(enabled ? styles.switchTrackActive : {}),
+                      }}
+                      onClick={() =>
+                        updateTabBarBehavior({
+                          ...tabBarBehavior,
+                          collection_behavior: enabled
+                            ? tabBarBehavior.collection_behavior.filter((b) => b !== behavior)
+                            : [...tabBarBehavior.collection_behavior, behavior],
+                        })
+                      }
+                    >
+                      <div
+                        style={{
+                          ...styles.switchThumb,
+                          ...(enabled ? styles.switchThumbActive : {}),
+                        }}
+                      />
+                    </div>
+                  </div>
+                );
+              },
+            )}
+          </div>
+        )}
+
         {/* 言語設定 */}
         <div style={styles.card}>
           <div style={styles.switchRow}>
@@ -448,10 +1109,137 @@ function Settings() {
           </div>
         </div>
 
+        {/* デバッグログ */}
+        <div style={styles.card}>
+          <div style={styles.switchRow}>
+            <div style={styles.switchLabelGroup}>
+              <span style={styles.switchLabel}>{t("settings.debugLoggingLabel")}</span>
+              <span style={styles.switchDescription}>
+                {t("settings.debugLoggingDescription")}
+              </span>
+            </div>
+            <div
+              style={{
+                ...styles.switchTrack,
+                ...(debugLoggingEnabled ? styles.switchTrackActive : {}),
+              }}
+              onClick={() => handleDebugLoggingToggle(!debugLoggingEnabled)}
+            >
+              <div
+                style={{
+                  ...styles.switchThumb,
+                  ...(debugLoggingEnabled ? styles.switchThumbActive : {}),
+                }}
+              />
+            </div>
+          </div>
+          {debugLoggingEnabled && (
+            <button
+              style={{
+                ...styles.openFileButton,
+                ...(openLogsHover ? { background: "#333333" } : {}),
+              }}
+              onClick={handleOpenLogs}
+              onMouseEnter={() => setOpenLogsHover(true)}
+              onMouseLeave={() => setOpenLogsHover(false)}
+            >
+              {t("settings.openLogs")}
+            </button>
+          )}
+        </div>
+
+        {/* 差分イベントに加えて従来のウィンドウ一覧イベントも送る */}
+        <div style={styles.card}>
+          <div style={styles.switchRow}>
+            <div style={styles.switchLabelGroup}>
+              <span style={styles.switchLabel}>{t("settings.legacyWindowEventsLabel")}</span>
+              <span style={styles.switchDescription}>
+                {t("settings.legacyWindowEventsDescription")}
+              </span>
+            </div>
+            <div
+              role="switch"
+              aria-label={t("settings.legacyWindowEventsLabel")}
+              aria-checked={legacyWindowEvents}
+              style={{
+                ...styles.switchTrack,
+                ...(legacyWindowEvents ? styles.switchTrackActive : {}),
+              }}
+              onClick={() => handleLegacyWindowEventsToggle(!legacyWindowEvents)}
+            >
+              <div
+                style={{
+                  ...styles.switchThumb,
+                  ...(legacyWindowEvents ? styles.switchThumbActive : {}),
+                }}
+              />
+            </div>
+          </div>
+        </div>
+
+        {/* スクリプト用ローカル API */}
+        <div style={styles.card}>
+          <div style={styles.switchRow}>
+            <div style={styles.switchLabelGroup}>
+              <span style={styles.switchLabel}>{t("settings.apiLabel")}</span>
+              <span style={styles.switchDescription}>
+                {t("settings.apiDescription")}
+              </span>
+            </div>
+            <div
+              style={{
+                ...styles.switchTrack,
+                ...(apiEnabled ? styles.switchTrackActive : {}),
+              }}
+              onClick={() => handleApiToggle(!apiEnabled)}
+            >
+              <div
+                style={{
+                  ...styles.switchThumb,
+                  ...(apiEnabled ? styles.switchThumbActive : {}),
+                }}
+              />
+            </div>
+          </div>
+          {apiEnabled && apiSocketPath && (
+            <p style={styles.note}>
+              {t("settings.apiSocketPath", { path: apiSocketPath })}
+            </p>
+          )}
+        </div>
+
         </>}
 
         {activeTab === "about" && <>
           <VersionInfo />
+
+          {/* GitHub リリースの定期チェック */}
+          {updateCheck && (
+            <div style={styles.card}>
+              <div style={styles.switchRow}>
+                <div style={styles.switchLabelGroup}>
+                  <span style={styles.switchLabel}>{t("settings.updateCheckLabel")}</span>
+                  <span style={styles.switchDescription}>
+                    {t("settings.updateCheckDescription")}
+                  </span>
+                </div>
+                <div
+                  style={{
+                    ...styles.switchTrack,
+                    ...(updateCheck.enabled ? styles.switchTrackActive : {}),
+                  }}
+                  onClick={() => handleUpdateCheckToggle(!updateCheck.enabled)}
+                >
+                  <div
+                    style={{
+                      ...styles.switchThumb,
+                      ...(updateCheck.enabled ? styles.switchThumbActive : {}),
+                    }}
+                  />
+                </div>
+              </div>
+            </div>
+          )}
         </>}
       </div>
     </div>
@@ -684,6 +1472,19 @@ const styles: Record<string, React.CSSProperties> = {
   switchThumbActive: {
     transform: "translateX(18px)",
   },
+  quietHoursRow: {
+    display: "flex",
+    alignItems: "center",
+    gap: "8px",
+    marginBottom: "8px",
+  },
+  mutedProjectRow: {
+    display: "flex",
+    justifyContent: "space-between",
+    alignItems: "center",
+    gap: "12px",
+    marginTop: "6px",
+  },
   languageSelect: {
     background: "#2d2d2d",
     color: "#ffffff",
@@ -697,6 +1498,13 @@ const styles: Record<string, React.CSSProperties> = {
   layoutDescription: {
     margin: "4px 0 12px",
   },
+  patternInput: {
+    width: "100%",
+    boxSizing: "border-box",
+    cursor: "text",
+    resize: "vertical",
+    fontFamily: "monospace",
+  },
   layoutOptions: {
     display: "grid",
     gridTemplateColumns: "repeat(2, minmax(0, 1fr))",
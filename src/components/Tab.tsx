@@ -21,9 +21,12 @@ interface TabProps {
   colorId?: string | null;
   onContextMenu?: (index: number, rect: DOMRect) => void;
   branch?: string;
+  hidden?: boolean;
+  remote?: string | null;
+  pathUnknown?: boolean;
 }
 
-const Tab = memo(function Tab({ name, isActive, isDragging, onClick, onClose, onDragStart, onDragEnd, onDragOver, onDrop, index, claudeStatus, colorId, onContextMenu, branch }: TabProps) {
+const Tab = memo(function Tab({ name, isActive, isDragging, onClick, onClose, onDragStart, onDragEnd, onDragOver, onDrop, index, claudeStatus, colorId, onContextMenu, branch, hidden, remote, pathUnknown }: TabProps) {
   const { t } = useTranslation();
   const [isHovered, setIsHovered] = useState(false);
 
@@ -54,6 +57,7 @@ const Tab = memo(function Tab({ name, isActive, isDragging, onClick, onClose, on
         ...(isActive ? styles.tabActive : {}),
         ...(isHovered ? styles.tabHover : {}),
         ...(isDragging ? styles.tabDragging : {}),
+        ...(hidden ? styles.tabHidden : {}),
         ...colorStyle,
       }}
       onClick={() => {
@@ -87,17 +91,24 @@ const Tab = memo(function Tab({ name, isActive, isDragging, onClick, onClose, on
         e.preventDefault();
         onDrop(index);
       }}
-      title={shortcutKey ? `${displayName} (${shortcutKey})` : displayName}
+      title={[
+        shortcutKey ? `${displayName} (${shortcutKey})` : displayName,
+        pathUnknown ? t("tabBar.pathUnknown") : null,
+      ].filter(Boolean).join("\n")}
       data-tab-index={index}
     >
       <div style={styles.tabTextContent}>
         <span style={styles.tabName}>{displayName}</span>
+        {remote && (
+          <span style={styles.branchName}>{"\u21C4"} {remote}</span>
+        )}
         {branch && (
           <span style={styles.branchName}>{"\u2387"} {branch}</span>
         )}
       </div>
       {claudeStatus === "waiting" && <div style={styles.badgeWaiting} />}
       {claudeStatus === "generating" && <div style={styles.badgeGenerating} className="pulse-animation" />}
+      {claudeStatus === "error" && <div style={styles.badgeError} />}
       <button
         style={{
           ...styles.closeButton,
@@ -141,6 +152,9 @@ const styles: Record<string, React.CSSProperties> = {
   tabDragging: {
     opacity: 0.5,
   },
+  tabHidden: {
+    opacity: 0.6,
+  },
   tabTextContent: {
     display: "flex",
     flexDirection: "column",
@@ -193,6 +207,13 @@ const styles: Record<string, React.CSSProperties> = {
     backgroundColor: "#ff3b30",
     flexShrink: 0,
   },
+  badgeError: {
+    width: "8px",
+    height: "8px",
+    borderRadius: "50%",
+    backgroundColor: "#ff9500",
+    flexShrink: 0,
+  },
 };
 
 export default Tab;
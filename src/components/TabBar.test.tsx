@@ -1,4 +1,5 @@
 import { fireEvent, render, screen, waitFor, within } from "@testing-library/react";
+import { invoke } from "@tauri-apps/api/core";
 import type { EditorWindow } from "../types/editor";
 import { repositoryColorKey, windowKey } from "../utils/store";
 import TabBar from "./TabBar";
@@ -233,4 +234,17 @@ describe("TabBar repository grouping", () => {
     expect(props.onWorktreeMenuClose).toHaveBeenCalledOnce();
     expect(screen.queryByRole("menu", { name: "group.tabList" })).not.toBeInTheDocument();
   });
+
+  it("reveals a tab's project folder from its context menu", async () => {
+    setup({}, "horizontal", [standaloneWindow], {});
+
+    fireEvent.contextMenu(screen.getByText("medii-e-consult-api"));
+    fireEvent.click(await screen.findByRole("button", { name: "tabBar.revealInFinder" }));
+
+    await waitFor(() => {
+      expect(invoke).toHaveBeenCalledWith("reveal_in_finder", {
+        path: standaloneWindow.path,
+      });
+    });
+  });
 });
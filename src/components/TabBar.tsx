@@ -1,5 +1,6 @@
 import { useState, useCallback, useRef, useMemo, useEffect } from "react";
 import { useTranslation } from "react-i18next";
+import { invoke } from "@tauri-apps/api/core";
 import Tab from "./Tab";
 import WorktreeTab from "./WorktreeTab";
 import GroupTabList from "./GroupTabList";
@@ -23,6 +24,7 @@ interface TabBarProps {
   onColorChange?: (windowKey: string, colorId: string | null) => void;
   showBranch?: boolean;
   tabLayout: TabLayout;
+  shortcutsPaused?: boolean;
   history: HistoryEntry[];
   showAddMenu: boolean;
   onAddMenuOpen: () => void;
@@ -76,7 +78,7 @@ const getListRowCount = (
 };
 
 function TabBar(props: TabBarProps) {
-  const { tabs, activeIndex, onTabClick, onNewTab, onCloseTab, onReorder, onReorderByVisual, claudeStatuses, tabColors, onColorChange, showBranch, tabLayout, history, showAddMenu, onAddMenuOpen, onAddMenuClose, onHistorySelect, onHistoryClear, onColorPickerOpen, onColorPickerClose, groups, groupAssignments, collapsedGroups, onAddGroup, onUpdateGroup, onDeleteGroup, onAssignTabsToGroup, onUnassignTabsFromGroup, onToggleGroupCollapse, onReorderGroups, groupColors, onSetGroupColor, onTabContextMenuOpen, onTabContextMenuClose, onWorktreeMenuOpen, onWorktreeMenuClose } = props;
+  const { tabs, activeIndex, onTabClick, onNewTab, onCloseTab, onReorder, onReorderByVisual, claudeStatuses, tabColors, onColorChange, showBranch, tabLayout, shortcutsPaused, history, showAddMenu, onAddMenuOpen, onAddMenuClose, onHistorySelect, onHistoryClear, onColorPickerOpen, onColorPickerClose, groups, groupAssignments, collapsedGroups, onAddGroup, onUpdateGroup, onDeleteGroup, onAssignTabsToGroup, onUnassignTabsFromGroup, onToggleGroupCollapse, onReorderGroups, groupColors, onSetGroupColor, onTabContextMenuOpen, onTabContextMenuClose, onWorktreeMenuOpen, onWorktreeMenuClose } = props;
   const { t } = useTranslation();
   const [draggedIndex, setDraggedIndex] = useState<number | null>(null);
   const [colorPickerTarget, setColorPickerTarget] = useState<{ key: string; currentColorId: string | null } | null>(null);
@@ -240,6 +242,28 @@ function TabBar(props: TabBarProps) {
     onColorPickerClose();
   }, [onColorPickerClose]);
 
+  const handleRetryPathResolution = useCallback(async () => {
+    setTabContextMenu(null);
+    onTabContextMenuClose();
+    try {
+      await invoke("clear_project_path_cache");
+    } catch (error) {
+      console.error("Failed to clear project path cache:", error);
+    }
+  }, [onTabContextMenuClose]);
+
+  const handlePathAction = useCallback(async (command: string) => {
+    const path = tabContextMenu ? tabs[tabContextMenu.index]?.path : undefined;
+    setTabContextMenu(null);
+    onTabContextMenuClose();
+    if (!path) return;
+    try {
+      await invoke(command, { path });
+    } catch (error) {
+      console.error(`Failed to run ${command}:`, error);
+    }
+  }, [tabContextMenu, tabs, onTabContextMenuClose]);
+
   const closeTabContextMenu = useCallback(() => {
     setTabContextMenu(null);
     setGroupSubmenuOpen(false);
@@ -449,6 +473,9 @@ function TabBar(props: TabBarProps) {
       colorId={tabColors ? getWindowScopedValue(tabColors, tab, tab.name) ?? null : null}
       onContextMenu={handleTabContextMenu}
       branch={showBranch !== false ? tab.branch : undefined}
+      hidden={tab.hidden}
+      remote={tab.remote}
+      pathUnknown={tab.path_source === "unresolved" && !tab.remote}
     />
   );
 
@@ -609,6 +636,12 @@ function TabBar(props: TabBarProps) {
         {/* Ungrouped tabs */}
         {renderRepositoryTabs(ungroupedTabs)}
 
+        {shortcutsPaused && (
+          <span style={styles.pausedIndicator} title={t("tabBar.shortcutsPaused")}>
+            ⏸
+          </span>
+        )}
+
         <button
           ref={addButtonRef}
           style={styles.addButton}
@@ -699,6 +732,42 @@ function TabBar(props: TabBarProps) {
             >
               {t("tabColor.title")}
             </button>
+            {tabs[tabContextMenu.index]?.path_source === "unresolved"
+              && !tabs[tabContextMenu.index]?.remote && (
+              <button
+                className="context-menu-item"
+                style={styles.contextMenuItem}
+                onClick={handleRetryPathResolution}
+              >
+                {t("tabBar.retryPath")}
+              </button>
+            )}
+            {tabs[tabContextMenu.index]?.path && (
+              <>
+                <div style={styles.contextMenuSeparator} />
+                <button
+                  className="context-menu-item"
+                  style={styles.contextMenuItem}
+                  onClick={() => handlePathAction("reveal_in_finder")}
+                >
+                  {t("tabBar.revealInFinder")}
+                </button>
+                <button
+                  className="context-menu-item"
+                  style={styles.contextMenuItem}
+                  onClick={() => handlePathAction("copy_path_to_clipboard")}
+                >
+                  {t("tabBar.copyPath")}
+                </button>
+                <button
+                  className="context-menu-item"
+                  style={styles.contextMenuItem}
+                  onClick={() => handlePathAction("open_terminal_at")}
+                >
+                  {t("tabBar.openInTerminal")}
+                </button>
+              </>
+            )}
             <div style={styles.contextMenuSeparator} />
             <div
               className="context-menu-item-with-submenu"
@@ -851,6 +920,12 @@ const styles: Record<string, React.CSSProperties> = {
     scrollbarWidth: "none" as const,
     msOverflowStyle: "none" as const,
   },
+  pausedIndicator: {
+    color: "rgba(255, 255, 255, 0.5)",
+    fontSize: "11px",
+    padding: "0 4px",
+    flexShrink: 0,
+  },
   addButton: {
     width: "28px",
     height: "28px",
@@ -2,7 +2,7 @@ import { useState, useEffect, useCallback } from "react";
 import { useTranslation } from "react-i18next";
 import { invoke } from "@tauri-apps/api/core";
 import { getVersion } from "@tauri-apps/api/app";
-import { fetchLatestVersion, compareVersions } from "../utils/version";
+import type { UpdateInfo } from "../types/editor";
 
 const GITHUB_REPO_URL = "https://github.com/884js/editor-tab-manager";
 
@@ -25,15 +25,13 @@ function VersionInfo() {
 
   useEffect(() => {
     getVersion().then(setAppVersion);
-    fetchLatestVersion()
-      .then((latest) => {
-        getVersion().then((current) => {
-          if (compareVersions(current, latest.version) < 0) {
-            setUpdateStatus({ state: "available", version: latest.version, url: latest.url });
-          } else {
-            setUpdateStatus({ state: "upToDate" });
-          }
-        });
+    invoke<UpdateInfo>("check_for_updates")
+      .then((info) => {
+        if (info.is_newer) {
+          setUpdateStatus({ state: "available", version: info.latest, url: info.url });
+        } else {
+          setUpdateStatus({ state: "upToDate" });
+        }
       })
       .catch(() => setUpdateStatus({ state: "error" }));
   }, []);
@@ -223,6 +223,47 @@ describe("useAppLifecycle", () => {
 
       expect(result.current.tabLayout).toBe("horizontal");
     });
+
+    it("forgets the current editor when it quits", async () => {
+      const { params, listeners } = setup();
+      params.currentBundleIdRef.current = "com.todesktop.230313mzl4w4u92";
+
+      await waitFor(() => {
+        expect(listeners.has("editor-quit")).toBe(true);
+      });
+
+      act(() => {
+        listeners.get("editor-quit")?.({ payload: { bundle_id: "com.microsoft.VSCode" } });
+      });
+      expect(params.currentBundleIdRef.current).toBe("com.todesktop.230313mzl4w4u92");
+
+      act(() => {
+        listeners.get("editor-quit")?.({
+          payload: { bundle_id: "com.todesktop.230313mzl4w4u92" },
+        });
+      });
+      expect(params.currentBundleIdRef.current).toBeNull();
+      expect(params.fetchWindowsRef.current).toHaveBeenCalled();
+    });
+
+    it("tracks shortcut pausing from the tray", async () => {
+      vi.mocked(invoke).mockImplementation(async (cmd: string) => {
+        if (cmd === "get_shortcuts_paused") return false;
+        return true;
+      });
+      const { result, listeners } = setup();
+
+      await waitFor(() => {
+        expect(listeners.has("shortcuts-paused-changed")).toBe(true);
+      });
+      expect(result.current.shortcutsPaused).toBe(false);
+
+      act(() => {
+        listeners.get("shortcuts-paused-changed")?.({ payload: true });
+      });
+
+      expect(result.current.shortcutsPaused).toBe(true);
+    });
   });
 
   describe("window resizing", () => {
@@ -292,6 +333,16 @@ describe("useAppLifecycle", () => {
       expect(appWindow.show).toHaveBeenCalled();
     });
 
+    it("reports ready once the listener is registered", async () => {
+      vi.mocked(invoke).mockResolvedValue(true);
+      const { listeners } = setup({ "onboarding:completed": true });
+
+      await waitFor(() => {
+        expect(invoke).toHaveBeenCalledWith("frontend_ready");
+      });
+      expect(listeners.has("app-activated")).toBe(true);
+    });
+
     it("resizes tab bar to the current monitor width on editor activation", async () => {
       vi.mocked(invoke).mockResolvedValue(true);
       const { result, listeners } = setup({ "onboarding:completed": true });
@@ -366,7 +417,7 @@ describe("useAppLifecycle", () => {
       expect(appWindow.setPosition).not.toHaveBeenCalled();
     });
 
-    it("hides window on other app activation", async () => {
+    it("leaves hiding to the backend on other app activation", async () => {
       vi.mocked(invoke).mockResolvedValue(true);
       const { result, listeners, params } = setup({ "onboarding:completed": true });
 
@@ -377,11 +428,13 @@ describe("useAppLifecycle", () => {
 
       await waitFor(() => {
         expect(listeners.has("app-activated")).toBe(true);
+        expect(listeners.has("tab-bar-visibility-changed")).toBe(true);
       });
 
       params.isVisibleRef.current = true;
 
       const appWindow = getCurrentWindow();
+      vi.mocked(appWindow.setPosition).mockClear();
       vi.mocked(invoke).mockResolvedValue(undefined);
 
       await act(async () => {
@@ -397,7 +450,15 @@ describe("useAppLifecycle", () => {
       });
 
       expect(params.isEditorActiveRef.current).toBe(false);
-      expect(appWindow.setPosition).toHaveBeenCalled();
+      expect(invoke).toHaveBeenCalledWith("restore_window_positions", {
+        bundle_id: "com.microsoft.VSCode",
+      });
+      expect(appWindow.setPosition).not.toHaveBeenCalled();
+
+      await act(async () => {
+        listeners.get("tab-bar-visibility-changed")!({ payload: false });
+      });
+      expect(params.isVisibleRef.current).toBe(false);
     });
 
     it("keeps tab bar visible for small window apps", async () => {
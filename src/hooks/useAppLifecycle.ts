@@ -1,12 +1,12 @@
 import { useEffect, useState, useCallback, useRef, type MutableRefObject } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { getCurrentWindow, LogicalSize, LogicalPosition, PhysicalPosition } from "@tauri-apps/api/window";
+import { getCurrentWindow, LogicalSize, LogicalPosition } from "@tauri-apps/api/window";
 import { currentMonitor, primaryMonitor } from "@tauri-apps/api/window";
 import { useTranslation } from "react-i18next";
 import { TAB_BAR_HEIGHT, ALL_EDITOR_BUNDLE_IDS } from "../types/editor";
 import type { AppActivationPayload } from "../types/editor";
-import type { TabLayout } from "../types/editor";
+import type { TabBarPosition, TabLayout } from "../types/editor";
 import { getStore, loadTabLayout } from "../utils/store";
 
 interface UseAppLifecycleParams {
@@ -28,6 +28,7 @@ interface UseAppLifecycleReturn {
   handleOnboardingComplete: (dontShowAgain: boolean) => Promise<void>;
   showBranch: boolean;
   tabLayout: TabLayout;
+  shortcutsPaused: boolean;
   resizeTabBar: () => Promise<void>;
   handleColorPickerOpen: () => Promise<void>;
   handleColorPickerClose: () => Promise<void>;
@@ -61,13 +62,15 @@ export function useAppLifecycle({
   currentBundleIdRef,
   notificationEnabledRef,
 }: UseAppLifecycleParams): UseAppLifecycleReturn {
-  const { t, i18n } = useTranslation();
+  const { i18n } = useTranslation();
   const [hasAccessibilityPermission, setHasAccessibilityPermission] = useState<boolean | null>(null);
   const [onboardingCompleted, setOnboardingCompleted] = useState<boolean | null>(null);
   const [showBranch, setShowBranch] = useState(true);
   const [tabLayout, setTabLayout] = useState<TabLayout>("horizontal");
+  const [shortcutsPaused, setShortcutsPaused] = useState(false);
   const isInitializedRef = useRef(false);
   const lastMonitorKeyRef = useRef<string | null>(null);
+  const tabBarPositionRef = useRef<TabBarPosition>("top");
 
   // Load notification + showBranch + language settings from store
   useEffect(() => {
@@ -94,6 +97,22 @@ export function useAppLifecycle({
     init();
   }, []);
 
+  // The backend places a bar at the bottom edge, where it has to clear the Dock
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    invoke<TabBarPosition>("get_tab_bar_position")
+      .then((position) => {
+        if (position) tabBarPositionRef.current = position;
+      })
+      .catch(() => {});
+    void listen<TabBarPosition>("tab-bar-position-changed", (event) => {
+      tabBarPositionRef.current = event.payload;
+    }).then((cleanup) => {
+      unlisten = cleanup;
+    });
+    return () => unlisten?.();
+  }, []);
+
   useEffect(() => {
     let unlisten: (() => void) | undefined;
     void listen<TabLayout>("tab-layout-changed", (event) => {
@@ -104,15 +123,62 @@ export function useAppLifecycle({
     return () => unlisten?.();
   }, []);
 
-  // Update tray menu when language changes
+  // Forget an editor that quit so new tabs don't target it
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    void listen<{ bundle_id: string }>("editor-quit", (event) => {
+      if (currentBundleIdRef.current === event.payload.bundle_id) {
+        currentBundleIdRef.current = null;
+      }
+      void fetchWindowsRef.current();
+    }).then((cleanup) => {
+      unlisten = cleanup;
+    });
+    return () => unlisten?.();
+  }, [currentBundleIdRef, fetchWindowsRef]);
+
+  // Shortcut interception can be paused from the tray menu
+  useEffect(() => {
+    const loadShortcutsPaused = async () => {
+      try {
+        setShortcutsPaused(await invoke<boolean>("get_shortcuts_paused"));
+      } catch {
+        // defaults: not paused
+      }
+    };
+    void loadShortcutsPaused();
+
+    let unlisten: (() => void) | undefined;
+    void listen<boolean>("shortcuts-paused-changed", (event) => {
+      setShortcutsPaused(event.payload === true);
+    }).then((cleanup) => {
+      unlisten = cleanup;
+    });
+    return () => unlisten?.();
+  }, []);
+
+  // Reload after a settings import so layout, language and filters are read again
   useEffect(() => {
-    invoke("update_tray_menu", {
-      settings_label: t("tray.settings"),
-      quit_label: t("tray.quit"),
-    }).catch((error) => {
-      console.error("Failed to update tray menu:", error);
+    let unlisten: (() => void) | undefined;
+    void listen<string[]>("settings-changed", () => {
+      window.location.reload();
+    }).then((cleanup) => {
+      unlisten = cleanup;
     });
-  }, [t]);
+    return () => unlisten?.();
+  }, []);
+
+  // Keep backend strings (tray menu, notification actions) in the UI language
+  useEffect(() => {
+    const applyLocale = async () => {
+      try {
+        await invoke("set_locale", { locale: i18n.language });
+      } catch (error) {
+        console.error("Failed to update backend locale:", error);
+      }
+    };
+    void applyLocale();
+  }, [i18n.language]);
 
   // Check accessibility permission and onboarding status on startup
   useEffect(() => {
@@ -282,6 +348,11 @@ export function useAppLifecycle({
     const appWindow = getCurrentWindow();
     const monitor = (await currentMonitor()) ?? (await primaryMonitor());
     if (!monitor) return;
+    if (tabBarPositionRef.current === "bottom") {
+      lastMonitorKeyRef.current = getMonitorKey(monitor);
+      await invoke("place_tab_bar", { extra_height: 0 });
+      return;
+    }
     const screenWidth = monitor.size.width / monitor.scaleFactor;
     const originX = monitor.position.x / monitor.scaleFactor;
     const originY = monitor.position.y / monitor.scaleFactor;
@@ -292,6 +363,11 @@ export function useAppLifecycle({
   }, []);
 
   const expandWindow = useCallback(async (extraHeight: number) => {
+    if (tabBarPositionRef.current === "bottom") {
+      // Grow upwards so the bar stays above the Dock
+      await invoke("place_tab_bar", { extra_height: extraHeight });
+      return;
+    }
     const appWindow = getCurrentWindow();
     const monitor = (await currentMonitor()) ?? (await primaryMonitor());
     if (monitor) {
@@ -350,7 +426,7 @@ export function useAppLifecycle({
       const unlisten = await listen<AppActivationPayload>("app-activated", async (event) => {
         if (!isMounted) return;
 
-        const { app_type, bundle_id, is_on_primary_screen, covers_editor } = event.payload;
+        const { app_type, bundle_id, is_on_primary_screen } = event.payload;
 
         if (app_type === "editor" || app_type === "tab_manager") {
           isEditorActiveRef.current = app_type === "editor";
@@ -382,32 +458,29 @@ export function useAppLifecycle({
           isEditorActiveRef.current = false;
           isTabManagerActiveRef.current = false;
 
+          // The backend hides the tab bar when the front window covers the editor
           if (is_on_primary_screen) {
             for (const bid of ALL_EDITOR_BUNDLE_IDS) {
               invoke("restore_window_positions", { bundle_id: bid }).catch(() => {});
             }
-            // Hide tab bar only when the front window covers the editor
-            if (covers_editor && isVisibleRef.current) {
-              await appWindow.setPosition(new PhysicalPosition(0, -10000));
-              isVisibleRef.current = false;
-            }
           }
         }
       });
       cleanupFns.push(unlisten);
+      // The backend holds the initial app-activated payload until the listener exists
+      invoke("frontend_ready").catch(() => {});
     };
     setupAppActivationListener();
 
-    const setupDisplayChangedListener = async () => {
-      const unlisten = await listen("display-changed", async () => {
+    // Shown and hidden by the backend as apps are activated (see autohide.rs)
+    const setupVisibilityListener = async () => {
+      const unlisten = await listen<boolean>("tab-bar-visibility-changed", (event) => {
         if (!isMounted) return;
-        if (showAddMenuRef.current) return;
-        if (!isVisibleRef.current) return;
-        await resizeTabBar();
+        isVisibleRef.current = event.payload === true;
       });
       cleanupFns.push(unlisten);
     };
-    setupDisplayChangedListener();
+    setupVisibilityListener();
 
     const setupMovedListener = async () => {
       const unlisten = await appWindow.onMoved(async () => {
@@ -448,6 +521,7 @@ export function useAppLifecycle({
     handleOnboardingComplete,
     showBranch,
     tabLayout,
+    shortcutsPaused,
     resizeTabBar,
     handleColorPickerOpen,
     handleColorPickerClose: handleOverlayClose,
@@ -24,6 +24,15 @@ interface UseClaudeStatusReturn {
   waitingTimersRef: MutableRefObject<Map<string, ReturnType<typeof setTimeout>>>;
 }
 
+// Fails when notification permission is denied; Settings shows how to fix that
+async function sendNotification(args: { title: string; subtitle: string; body: string; project_path: string }) {
+  try {
+    await invoke("send_notification", args);
+  } catch (error) {
+    console.warn("Notification not sent:", error);
+  }
+}
+
 export function useClaudeStatus({
   windowsRef,
   activeIndexRef,
@@ -110,13 +119,28 @@ export function useClaudeStatus({
     initNotification();
   }, []);
 
-  // notification-clicked listener
+  // notification-clicked / notification-focus / notification-dismiss listeners
   useEffect(() => {
     let isMounted = true;
-    let unlistenClick: (() => void) | null = null;
+    const unlisteners: (() => void)[] = [];
+
+    const dismissWaitingForPath = (projectPath: string) => {
+      if (claudeStatusesRef.current[projectPath] !== "waiting") return;
+      dismissedWaitingRef.current.add(projectPath);
+      setClaudeStatuses((prev) => {
+        const next = { ...prev };
+        delete next[projectPath];
+        return next;
+      });
+      const timer = waitingTimersRef.current.get(projectPath);
+      if (timer) {
+        clearTimeout(timer);
+        waitingTimersRef.current.delete(projectPath);
+      }
+    };
 
     const setupListener = async () => {
-      const unlisten = await listen<{ project_path: string }>("notification-clicked", async (event) => {
+      const focusProject = async (event: { payload: { project_path: string } }) => {
         if (!isMounted) return;
         const projectPath = event.payload.project_path;
         if (!projectPath) return;
@@ -124,19 +148,7 @@ export function useClaudeStatus({
         await appWindow.show();
         isVisibleRef.current = true;
 
-        if (claudeStatusesRef.current[projectPath] === "waiting") {
-          dismissedWaitingRef.current.add(projectPath);
-          setClaudeStatuses((prev) => {
-            const next = { ...prev };
-            delete next[projectPath];
-            return next;
-          });
-          const timer = waitingTimersRef.current.get(projectPath);
-          if (timer) {
-            clearTimeout(timer);
-            waitingTimersRef.current.delete(projectPath);
-          }
-        }
+        dismissWaitingForPath(projectPath);
 
         await new Promise((r) => setTimeout(r, 500));
 
@@ -147,18 +159,28 @@ export function useClaudeStatus({
           const first = windowsRef.current[0];
           await invoke("focus_editor_window", { bundle_id: first.bundle_id, window_id: first.id });
         }
-      });
+      };
+
+      const unlistenFns = await Promise.all([
+        // Clicking the notification body and the "Focus window" action behave the same
+        listen<{ project_path: string }>("notification-clicked", focusProject),
+        listen<{ project_path: string }>("notification-focus", focusProject),
+        listen<{ project_path: string }>("notification-dismiss", (event) => {
+          if (!isMounted) return;
+          dismissWaitingForPath(event.payload.project_path);
+        }),
+      ]);
       if (isMounted) {
-        unlistenClick = unlisten;
+        unlisteners.push(...unlistenFns);
       } else {
-        unlisten();
+        unlistenFns.forEach((unlisten) => unlisten());
       }
     };
     setupListener();
 
     return () => {
       isMounted = false;
-      unlistenClick?.();
+      unlisteners.forEach((unlisten) => unlisten());
     };
   }, [windowsRef, isVisibleRef]);
 
@@ -167,9 +189,9 @@ export function useClaudeStatus({
     let isMounted = true;
     let unlisten: (() => void) | null = null;
 
-    listen<ClaudeStatusPayload>("claude-status", (event) => {
+    const applyPayload = (payload: ClaudeStatusPayload) => {
       if (!isMounted) return;
-      const newStatuses = event.payload.statuses;
+      const newStatuses = payload.statuses;
       const prev = claudeStatusesRef.current;
 
       for (const path of dismissedWaitingRef.current) {
@@ -193,10 +215,13 @@ export function useClaudeStatus({
       if (completedPaths.length > 0 && notificationEnabledRef.current && !isEditorActiveRef.current) {
         for (const path of completedPaths) {
           const projectName = path.split("/").pop() || path;
-          invoke("send_notification", {
+          const message = payload.sessions?.find(
+            (session) => session.project_path === path && session.status === "waiting" && session.message
+          )?.message;
+          sendNotification({
             title: projectName,
             subtitle: "Claude Code",
-            body: i18n.t("app.notificationBody"),
+            body: message ?? i18n.t("app.notificationBody"),
             project_path: path,
           });
         }
@@ -226,10 +251,27 @@ export function useClaudeStatus({
       } else {
         setClaudeStatuses(filtered);
       }
+    };
+
+    let receivedEvent = false;
+    listen<ClaudeStatusPayload>("claude-status", (event) => {
+      receivedEvent = true;
+      applyPayload(event.payload);
     }).then((u) => {
       unlisten = u;
     });
 
+    // Events emitted before this webview (re)loaded are lost, so start from the watcher's last state
+    const loadInitialStatuses = async () => {
+      try {
+        const payload = await invoke<ClaudeStatusPayload>("get_claude_statuses");
+        if (payload && !receivedEvent) applyPayload(payload);
+      } catch (error) {
+        console.error("Failed to get Claude statuses:", error);
+      }
+    };
+    loadInitialStatuses();
+
     return () => {
       isMounted = false;
       unlisten?.();
@@ -1,7 +1,8 @@
 import { renderHook, act, waitFor } from "@testing-library/react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import type { EditorWindow, GroupAssignment, GroupDefinition, TabColorMap, WindowsSnapshot } from "../types/editor";
+import { message } from "@tauri-apps/plugin-dialog";
+import type { EditorWindow, GroupAssignment, GroupDefinition, TabColorMap, WindowsDiff, WindowsSnapshot } from "../types/editor";
 import { useEditorWindows } from "./useEditorWindows";
 
 // Mock store functions directly
@@ -395,6 +396,38 @@ describe("useEditorWindows", () => {
       });
     });
 
+    it("tells the user when macOS does not switch to the window", async () => {
+      const win1 = makeWindow({ id: 1, name: "alpha" });
+      const win2 = makeWindow({ id: 2, name: "beta" });
+
+      vi.mocked(invoke).mockResolvedValue([win1, win2]);
+      const { result } = setup();
+
+      await act(async () => {
+        await result.current.refreshWindows();
+      });
+
+      vi.mocked(invoke).mockImplementation(async (cmd) => {
+        if (cmd === "focus_editor_window") {
+          throw {
+            kind: "focus_unverified",
+            window_id: win2.id,
+            frontmost_bundle_id: "com.apple.finder",
+            focused_window_id: null,
+          };
+        }
+        return undefined;
+      });
+      await act(async () => {
+        result.current.handleTabClick(1);
+      });
+
+      await waitFor(() => {
+        expect(message).toHaveBeenCalledWith("app.focusUnverified", { kind: "warning" });
+      });
+      expect(invoke).not.toHaveBeenCalledWith("maximize_editor_window", expect.anything());
+    });
+
     it("does nothing when clicking the already active tab", async () => {
       const win1 = makeWindow({ id: 1, name: "alpha" });
       vi.mocked(invoke).mockResolvedValue([win1]);
@@ -623,6 +656,65 @@ describe("useEditorWindows", () => {
       expect(result.current.windows[0].name).toBe("current");
     });
 
+    it("applies a windows-diff on top of the last snapshot", async () => {
+      const api = makeWindow({ id: 1, name: "api", path: "/work/api" });
+      const web = makeWindow({ id: 2, name: "web", path: "/work/web" });
+      const snapshot: WindowsSnapshot = {
+        revision: 2,
+        windows: [api, web],
+        active_id: api.id,
+        source: "test",
+      };
+      vi.mocked(invoke).mockResolvedValue(snapshot);
+      const { result, listeners } = setup();
+
+      await act(async () => {
+        await result.current.fetchWindows();
+      });
+      await waitFor(() => expect(listeners.has("windows-diff")).toBe(true));
+
+      const diff: WindowsDiff = {
+        revision: 3,
+        added: [makeWindow({ id: 3, name: "cli", path: "/work/cli" })],
+        removed: [1],
+        updated: [{ ...web, branch: "main" }],
+        active_window_id: 3,
+      };
+      act(() => {
+        listeners.get("windows-diff")!({ payload: diff });
+      });
+
+      expect(result.current.windows.map((w) => w.name)).toEqual(["web", "cli"]);
+      expect(result.current.windows[0].branch).toBe("main");
+      expect(result.current.activeIndex).toBe(1);
+    });
+
+    it("pulls the full list when a windows-diff was missed", async () => {
+      const snapshot: WindowsSnapshot = {
+        revision: 2,
+        windows: [makeWindow()],
+        active_id: null,
+        source: "test",
+      };
+      vi.mocked(invoke).mockResolvedValue(snapshot);
+      const { result, listeners } = setup();
+
+      await act(async () => {
+        await result.current.fetchWindows();
+      });
+      await waitFor(() => expect(listeners.has("windows-diff")).toBe(true));
+      vi.mocked(invoke).mockClear();
+
+      act(() => {
+        listeners.get("windows-diff")!({
+          payload: { revision: 5, added: [], removed: [1], updated: [], active_window_id: null },
+        });
+      });
+
+      await waitFor(() => expect(invoke).toHaveBeenCalledWith("get_windows_snapshot"));
+      expect(result.current.windows).toHaveLength(1);
+    });
+
     it("cleans up listeners on unmount", async () => {
       const { unmount, listeners } = setup();
 
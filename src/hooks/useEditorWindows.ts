@@ -2,10 +2,10 @@ import { useEffect, useState, useCallback, useRef, type MutableRefObject } from
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow, PhysicalPosition } from "@tauri-apps/api/window";
-import { ask } from "@tauri-apps/plugin-dialog";
+import { ask, message } from "@tauri-apps/plugin-dialog";
 import type { TFunction } from "i18next";
 import { TAB_BAR_HEIGHT, ALL_EDITOR_BUNDLE_IDS } from "../types/editor";
-import type { EditorWindow, WindowsSnapshot, GroupDefinition, GroupAssignment, TabColorMap } from "../types/editor";
+import type { CloseWindowError, EditorWindow, FocusError, WindowsDiff, WindowsSnapshot, GroupDefinition, GroupAssignment, TabColorMap } from "../types/editor";
 import {
   loadTabOrder,
   loadTabColors,
@@ -91,6 +91,16 @@ function normalizeSnapshot(payload: WindowsSnapshot | EditorWindow[]): WindowsSn
   return payload;
 }
 
+// Apply a "windows-diff" to the registry's windows of the previous revision
+export function applyWindowsDiff(windows: EditorWindow[], diff: WindowsDiff): EditorWindow[] {
+  const removed = new Set(diff.removed);
+  const updated = new Map(diff.updated.map((window) => [window.id, window]));
+  return windows
+    .filter((window) => !removed.has(window.id))
+    .map((window) => updated.get(window.id) ?? window)
+    .concat(diff.added);
+}
+
 export function useEditorWindows({
   dismissWaitingForWindow,
   syncWaitingTimer,
@@ -114,6 +124,8 @@ export function useEditorWindows({
   const orderLoadedRef = useRef(false);
   const lastTabClickTimeRef = useRef<number>(0);
   const lastSnapshotRevisionRef = useRef<number>(-1);
+  // Registry windows of lastSnapshotRevisionRef, unsorted (base for windows-diff)
+  const registryWindowsRef = useRef<EditorWindow[]>([]);
 
   // Keep refs in sync with state
   useEffect(() => {
@@ -135,6 +147,9 @@ export function useEditorWindows({
         await invoke<WindowsSnapshot | EditorWindow[]>("get_windows_snapshot"),
       );
       void invoke("request_windows_refresh");
+      if (snapshot.revision >= lastSnapshotRevisionRef.current) {
+        registryWindowsRef.current = snapshot.windows;
+      }
       lastSnapshotRevisionRef.current = Math.max(
         lastSnapshotRevisionRef.current,
         snapshot.revision,
@@ -212,6 +227,22 @@ export function useEditorWindows({
     }
   }, [syncWaitingTimer]);
 
+  // The window never came to front (editor busy, or a fullscreen window on another
+  // Space): tell the user and move the highlight back to the real front window
+  const handleFocusError = useCallback(
+    (win: EditorWindow, error: unknown) => {
+      if ((error as FocusError | undefined)?.kind === "focus_unverified") {
+        void syncActiveTabRef.current();
+        void message(t("app.focusUnverified", { name: win.name || t("app.untitled") }), {
+          kind: "warning",
+        });
+        return;
+      }
+      console.error("Failed to focus/maximize window:", error);
+    },
+    [t]
+  );
+
   const handleTabClick = useCallback(
     (index: number) => {
       if (index === activeIndexRef.current) return;
@@ -230,12 +261,10 @@ export function useEditorWindows({
               tab_bar_height: TAB_BAR_HEIGHT,
             })
           )
-          .catch((error) => {
-            console.error("Failed to focus/maximize window:", error);
-          });
+          .catch((error) => handleFocusError(window, error));
       }
     },
-    [dismissWaitingForWindow]
+    [dismissWaitingForWindow, handleFocusError]
   );
 
   const handleNewTab = useCallback(async () => {
@@ -266,6 +295,8 @@ export function useEditorWindows({
           await invoke("close_editor_window", { bundle_id: win.bundle_id, window_id: win.id });
           setTimeout(() => refreshWindowsRef.current(), 500);
         } catch (error) {
+          // The editor is asking to save changes and is now in front; the tab stays
+          if ((error as CloseWindowError | undefined)?.kind === "blocked_by_dialog") return;
           console.error("Failed to close window:", error);
         }
       }
@@ -468,6 +499,9 @@ export function useEditorWindows({
       );
       void invoke("request_windows_refresh");
       const result = snapshot.windows;
+      if (snapshot.revision >= lastSnapshotRevisionRef.current) {
+        registryWindowsRef.current = result;
+      }
       lastSnapshotRevisionRef.current = Math.max(
         lastSnapshotRevisionRef.current,
         snapshot.revision,
@@ -515,6 +549,7 @@ export function useEditorWindows({
   const handleNewTabRef = useRef(handleNewTab);
   const syncActiveTabRef = useRef(syncActiveTab);
   const fetchWindowsRef = useRef(fetchWindows);
+  const handleFocusErrorRef = useRef(handleFocusError);
 
   useEffect(() => {
     refreshWindowsRef.current = refreshWindows;
@@ -531,6 +566,9 @@ export function useEditorWindows({
   useEffect(() => {
     fetchWindowsRef.current = fetchWindows;
   }, [fetchWindows]);
+  useEffect(() => {
+    handleFocusErrorRef.current = handleFocusError;
+  }, [handleFocusError]);
 
   // Setup event listeners - only once on mount
   useEffect(() => {
@@ -547,6 +585,14 @@ export function useEditorWindows({
       });
       cleanupFns.push(unlistenRefresh);
 
+      // Enabled editors and window filters may have changed in the settings window
+      const unlistenSettingsClosed = await listen("settings-closed", () => {
+        if (isMounted) {
+          void refreshWindowsRef.current();
+        }
+      });
+      cleanupFns.push(unlistenSettingsClosed);
+
       const unlistenNewTab = await listen("open-new-editor-tab", () => {
         if (isMounted) {
           handleNewTabRef.current();
@@ -573,14 +619,15 @@ export function useEditorWindows({
           syncWaitingTimer();
           const win = windowsRef.current[event.payload];
           if (win) {
-            invoke("focus_editor_window", { bundle_id: win.bundle_id, window_id: win.id }).then(
-              () =>
+            invoke("focus_editor_window", { bundle_id: win.bundle_id, window_id: win.id })
+              .then(() =>
                 invoke("maximize_editor_window", {
                   bundle_id: win.bundle_id,
                   window_id: win.id,
                   tab_bar_height: TAB_BAR_HEIGHT,
                 })
-            );
+              )
+              .catch((error) => handleFocusErrorRef.current(win, error));
           }
         }
       });
@@ -614,30 +661,20 @@ export function useEditorWindows({
       });
       cleanupFns.push(unlistenWindowFocus);
 
-      const unlistenSnapshot = await listen<WindowsSnapshot>("windows:snapshot", (event) => {
-        if (!isMounted) return;
-
-        if (!orderLoadedRef.current) {
-          // Initial load hasn't run yet — let fetchWindows handle first paint
-          // so colors/groups/etc. load atomically with the window list.
-          return;
-        }
-
-        if (event.payload.revision <= lastSnapshotRevisionRef.current) {
-          return;
-        }
-        lastSnapshotRevisionRef.current = event.payload.revision;
+      const applySnapshot = (snapshot: WindowsSnapshot) => {
+        lastSnapshotRevisionRef.current = snapshot.revision;
+        registryWindowsRef.current = snapshot.windows;
 
         const migratedOrder = migrateResolvedWindowKeys(
           tabOrderRef.current,
           windowsRef.current,
-          event.payload.windows,
+          snapshot.windows,
         );
         if (migratedOrder.some((key, index) => key !== tabOrderRef.current[index])) {
           void saveTabOrder(migratedOrder);
         }
         tabOrderRef.current = migratedOrder;
-        const sorted = sortWindowsByOrder(event.payload.windows, tabOrderRef.current);
+        const sorted = sortWindowsByOrder(snapshot.windows, tabOrderRef.current);
         const newOrder = sorted.map((w) => windowKey(w));
         const orderChanged =
           newOrder.length !== tabOrderRef.current.length ||
@@ -666,7 +703,7 @@ export function useEditorWindows({
 
         // Map active_id (CGWindowID) → activeIndex in the sorted list.
         // Runs even when windows didn't change: Registry also emits on active change.
-        const { active_id } = event.payload;
+        const { active_id } = snapshot;
         if (active_id !== null && active_id !== undefined) {
           const idx = sorted.findIndex((w) => w.id === active_id);
           if (idx >= 0 && idx !== activeIndexRef.current) {
@@ -675,8 +712,44 @@ export function useEditorWindows({
             syncWaitingTimer();
           }
         }
+      };
+
+      const unlistenSnapshot = await listen<WindowsSnapshot>("windows:snapshot", (event) => {
+        if (!isMounted) return;
+
+        if (!orderLoadedRef.current) {
+          // Initial load hasn't run yet — let fetchWindows handle first paint
+          // so colors/groups/etc. load atomically with the window list.
+          return;
+        }
+
+        if (event.payload.revision <= lastSnapshotRevisionRef.current) {
+          return;
+        }
+        applySnapshot(event.payload);
       });
       cleanupFns.push(unlistenSnapshot);
+
+      const unlistenDiff = await listen<WindowsDiff>("windows-diff", (event) => {
+        if (!isMounted || !orderLoadedRef.current) return;
+
+        const diff = event.payload;
+        if (diff.revision <= lastSnapshotRevisionRef.current) {
+          return;
+        }
+        if (diff.revision !== lastSnapshotRevisionRef.current + 1) {
+          // A diff was missed in between; pull the whole list again
+          void refreshWindowsRef.current().then(() => syncActiveTabRef.current());
+          return;
+        }
+        applySnapshot({
+          revision: diff.revision,
+          windows: applyWindowsDiff(registryWindowsRef.current, diff),
+          active_id: diff.active_window_id,
+          source: "diff",
+        });
+      });
+      cleanupFns.push(unlistenDiff);
     };
 
     setupListeners();
@@ -99,6 +99,7 @@ vi.mock("@tauri-apps/plugin-autostart", () => ({
 // ---- @tauri-apps/plugin-dialog ----
 vi.mock("@tauri-apps/plugin-dialog", () => ({
   ask: vi.fn().mockResolvedValue(true),
+  message: vi.fn().mockResolvedValue(undefined),
 }));
 
 // ---- react-i18next ----
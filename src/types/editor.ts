@@ -14,6 +14,26 @@ export interface EditorWindow {
   bundle_id: string;
   editor_name: string;
   resolution?: "exact" | "inferred" | "unresolved";
+  // The editor app is hidden (Cmd+H); focusing the tab unhides it
+  hidden?: boolean;
+  // Remote or container label such as "SSH: myhost"; path is empty for these
+  remote?: string | null;
+  // The window title shows the unsaved-changes dot
+  has_unsaved?: boolean;
+  // "multi_root": a .code-workspace is open and path is its first folder
+  workspace_kind?: "folder" | "multi_root" | "file" | null;
+  // Where path came from; "unresolved" when path is empty
+  path_source?: "session" | "workspace_storage" | "subdir_scan" | "ax_document" | "unresolved";
+  // Custom or path-derived tab color; null while path is empty
+  appearance?: ProjectAppearance | null;
+}
+
+export interface ProjectAppearance {
+  // Tab palette color id such as "blue", or "#rrggbb"
+  color: string;
+  emoji: string | null;
+  // Derived from the path because no color was picked
+  is_default: boolean;
 }
 
 export interface WindowsSnapshot {
@@ -23,6 +43,15 @@ export interface WindowsSnapshot {
   source: string;
 }
 
+// Payload of "windows-diff": changes since revision - 1, keyed on window id
+export interface WindowsDiff {
+  revision: number;
+  added: EditorWindow[];
+  removed: number[];
+  updated: EditorWindow[];
+  active_window_id: number | null;
+}
+
 export interface HistoryEntry {
   name: string;       // Project name
   path: string;       // File system path
@@ -41,12 +70,33 @@ export const EDITOR_DISPLAY_NAMES: Record<string, string> = {
 
 export const ALL_EDITOR_BUNDLE_IDS = Object.keys(EDITOR_DISPLAY_NAMES);
 
+// Backend editor IDs, as used by EditorFilterSettings.disabled_editors
+export const EDITOR_IDS: Record<string, string> = {
+  vscode: "VSCode",
+  cursor: "Cursor",
+  zed: "Zed",
+  codex: "Codex",
+  claude: "Claude",
+};
+
 export const MAX_HISTORY_ENTRIES = 20;
 
 export interface EditorState {
   is_active: boolean;
   windows: EditorWindow[];
   active_index: number | null;
+  // Running with zero windows is distinct from not running
+  is_running?: boolean;
+  pid?: number | null;
+}
+
+// Entry returned by get_running_editors
+export interface RunningEditor {
+  bundle_id: string;
+  editor_name: string;
+  is_running: boolean;
+  pid: number | null;
+  window_count: number;
 }
 
 // Payload from app-activated event
@@ -57,6 +107,16 @@ export interface AppActivationPayload {
   covers_editor: boolean;
 }
 
+// Returned by get_frontmost_app
+export interface FrontmostApp {
+  app_type: "editor" | "tab_manager" | "other";
+  bundle_id: string | null;
+  pid: number;
+  is_on_primary_screen: boolean;
+  // True for an editor or the tab manager itself
+  is_editor_context: boolean;
+}
+
 // Tab grouping
 export interface GroupDefinition {
   id: string;
@@ -71,10 +131,214 @@ export interface GroupAssignment {
 // null prevents a cleared path-specific color from falling back to a legacy name key
 export type TabColorMap = Record<string, string | null>;
 
+// Notification authorization status reported by get_notification_permission
+export type NotificationPermission =
+  | "not_determined"
+  | "denied"
+  | "authorized"
+  | "provisional"
+  | "ephemeral"
+  | "unavailable";
+
+// Returned by get_launch_at_login / set_launch_at_login
+export type LaunchAtLoginStatus = "enabled" | "disabled" | "requires_approval";
+
+// Persisted notification preferences (get_notification_settings)
+export interface NotificationSettings {
+  enabled: boolean;
+  // Local time, minutes since midnight; start > end spans midnight
+  quiet_hours: { start: number; end: number } | null;
+  muted_projects: string[];
+  // Project path -> "default", "none", or a sound name such as "Glass"
+  project_sounds: Record<string, string>;
+}
+
+// Project/branch text shown next to the menu bar icon
+export interface TrayTitleSettings {
+  enabled: boolean;
+  // Longer titles are truncated with an ellipsis (characters)
+  max_length: number;
+}
+
+// Windows left out of the tab list by title
+export interface WindowFilterSettings {
+  // One pattern per entry; "*" at either end matches any prefix/suffix
+  excluded_titles: string[];
+}
+
+// Editors turned off entirely and project folders hidden from the tabs
+export interface EditorFilterSettings {
+  // Editor IDs such as "zed"
+  disabled_editors: string[];
+  // A folder also hides every project under it
+  ignored_paths: string[];
+}
+
+// Terminal apps "Open in Terminal" can launch
+export type TerminalApp = "terminal" | "iterm2" | "wezterm" | "kitty";
+
+// Edge of the display the tab bars sit on (get_tab_bar_position / set_tab_bar_position)
+export type TabBarPosition = "top" | "bottom";
+
+// Tab bar window level and Spaces behavior (get_tab_bar_behavior / configure_tab_bar_window)
+export type TabBarLevel = "above_modals" | "normal" | "floating" | "status";
+export type TabBarCollectionBehavior = "can_join_all_spaces" | "full_screen_auxiliary";
+export interface TabBarBehavior {
+  level: TabBarLevel;
+  collection_behavior: TabBarCollectionBehavior[];
+}
+
+// Error from close_editor_window. A window that asks to save changes is brought
+// to front and stays open; `title` is the text of its sheet.
+export type CloseWindowError =
+  | { kind: "blocked_by_dialog"; title: string }
+  | { kind: "failed"; message: string };
+
+// Error from focus_editor_window. focus_unverified: the window never became focused
+// even after retrying (editor busy, or a fullscreen window on a Space macOS would not
+// switch to); says which app and window are in front instead.
+export type FocusError =
+  | {
+      kind: "focus_unverified";
+      window_id: number;
+      frontmost_bundle_id: string | null;
+      focused_window_id: number | null;
+    }
+  | { kind: "failed"; message: string };
+
+// Result of reopen_in_editor. `close_error` says why the original window is still
+// open when closing it was requested.
+export interface ReopenResult {
+  old_window_id: number | null;
+  new_window_id: number;
+  closed_original: boolean;
+  close_error: CloseWindowError | null;
+}
+
+export type ReopenError =
+  | { kind: "not_installed"; bundle_id: string }
+  | { kind: "failed"; message: string };
+
+// Entry of get_recently_closed, most recent first. closed_at is epoch millis.
+export interface ClosedWindow {
+  bundle_id: string;
+  path: string;
+  name: string;
+  closed_at: number;
+}
+
+// Result of close_all_windows / close_other_windows. `blocked` is the window that
+// asked something before closing; `remaining` were not tried.
+export interface CloseReport {
+  closed: number[];
+  failed: { window_id: number; error: string }[];
+  blocked: { window_id: number; title: string } | null;
+  remaining: number[];
+}
+
 // Claude Code status
-export type ClaudeStatus = "waiting" | "generating";
+export type ClaudeStatus = "waiting" | "generating" | "error";
+
+// Status of a single Claude Code session
+export interface ClaudeSessionStatus {
+  // Agent CLI that reported the session ("claude", "codex", or a custom name)
+  agent: string;
+  project_path: string;
+  session_id: string;
+  status: ClaudeStatus;
+  // What Claude is waiting for (only sent by JSON-format hooks)
+  message: string | null;
+  // Sub-agent (Task tool) session reported by a hook
+  is_subagent: boolean;
+  // Epoch millis when the session entered its current status
+  since: number;
+  last_update: number;
+}
 
 // Payload from claude-status event
 export interface ClaudeStatusPayload {
+  // Aggregated per project (a generating session wins over waiting ones)
   statuses: Record<string, ClaudeStatus>;
+  // Epoch millis when each project entered its aggregated status
+  since?: Record<string, number>;
+  // Agent that reported each project's aggregated status
+  agents?: Record<string, string>;
+  sessions?: ClaudeSessionStatus[];
+}
+
+// Returned by get_snapshot: everything a focus change needs in one call
+export interface AppSnapshot {
+  editor_state: EditorState;
+  claude_statuses: ClaudeStatusPayload;
+  // Last app-activated payload; null before the first activation
+  frontmost: AppActivationPayload | null;
+  has_accessibility_permission: boolean;
+}
+
+// A named set of open projects (save_session / list_sessions)
+export interface SavedSession {
+  name: string;
+  saved_at: number; // Epoch millis
+  entries: { bundle_id: string; path: string }[];
+}
+
+// Per-entry outcome from restore_session
+export type SessionRestoreResult = {
+  bundle_id: string;
+  path: string;
+} & (
+  | { status: "opened" }
+  | { status: "already_open" }
+  | { status: "failed"; message: string }
+);
+
+// Projects switched to together (create_group / list_groups)
+export interface ProjectGroup {
+  name: string;
+  // bundle_id is the editor used when the project isn't open
+  members: { path: string; bundle_id: string }[];
+}
+
+// Per-member outcome from activate_group
+export type GroupMemberResult = { path: string } & (
+  | { status: "focused" }
+  | { status: "opened" }
+  | { status: "failed"; message: string }
+);
+
+// "instance-message" event: what a second launch of the app asked for
+export type InstanceMessage =
+  | { command: "show" }
+  | { command: "focus"; path: string; editor?: string };
+
+// Document from export_settings, accepted by import_settings
+export interface SettingsExport {
+  version: number;
+  app_version: string;
+  // Epoch millis
+  exported_at: number;
+  // Every store key, including ones this version doesn't know
+  settings: Record<string, unknown>;
+}
+
+// check_for_updates result, also the "update-available" event payload
+export interface UpdateInfo {
+  current: string;
+  latest: string;
+  // Release page on GitHub
+  url: string;
+  is_newer: boolean;
+}
+
+// Error from get_editor_icon (the icon itself arrives as PNG bytes)
+export type EditorIconError =
+  | { kind: "not_installed"; bundle_id: string }
+  | { kind: "failed"; message: string };
+
+// Background update check (get_update_check_settings / set_update_check_settings)
+export interface UpdateCheckSettings {
+  enabled: boolean;
+  // Also post a notification when a newer release is found
+  notify: boolean;
+  interval_hours: number;
 }